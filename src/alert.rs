@@ -1,67 +1,582 @@
-use crate::model::SystemStats;
-use std::process::Command;
-use std::time::Instant;
+use crate::config::{AlertTiers, Config, ProcessWatch};
+use crate::format::format_bytes;
+use crate::health::unix_now;
+use crate::model::{HistoryBuffer, ProcessUsage, RecentHistory, Series, SystemStats};
+use crate::monitor::disk_fill::DiskFillPredictor;
+use crate::strings::t;
+use crate::ui::chart_window::render_alert_sparkline;
+use crate::ui::{sound, user_notifications};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
 
-const COOLDOWN_SECS: u64 = 60;
+pub const COOLDOWN_SECS: u64 = 60;
+/// Minimum time between two logged hook failures
+const HOOK_FAILURE_LOG_SECS: u64 = 300;
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+/// Difference between the newer and older half averages that counts as a trend
+const TREND_DELTA: f32 = 5.0;
+/// An alert clears once its value is this fraction below the threshold...
+const RECOVERY_MARGIN: f32 = 0.05;
+/// ...for this many polls in a row
+const RECOVERY_POLLS: u32 = 2;
+/// Anti-flap: never report a recovery sooner than this after the alert fired
+const RECOVERY_MIN_SECS: u64 = 10;
+/// Longest the sparkline may take to draw; past this the alert goes out
+/// with its text only
+const SPARKLINE_BUDGET: Duration = Duration::from_millis(300);
+
+/// Set for `--replay`: notifications are logged instead of shown, and no
+/// sounds or hooks run
+static SUPPRESSED: AtomicBool = AtomicBool::new(false);
+/// Numbers notifications and their images apart within a run
+static NEXT_NOTIFICATION: AtomicU64 = AtomicU64::new(0);
 
 pub struct AlertManager {
-    cpu_threshold: f32,
-    mem_threshold: f32,
-    temp_threshold: f32,
-    last_cpu_alert: Option<Instant>,
-    last_mem_alert: Option<Instant>,
-    last_temp_alert: Option<Instant>,
+    /// Last notification of each system-wide alert kind and tier
+    last_tier_alerts: HashMap<(&'static str, AlertTier), Instant>,
+    /// Tiers of the CPU, memory and temperature alerts that fired and have
+    /// not recovered; each tier recovers on its own
+    active_tiers: HashMap<(&'static str, AlertTier), ActiveAlert>,
+    /// Free space trend of each volume, for the disk filling up alert
+    disk_fill: DiskFillPredictor,
+    /// Mount points already alerted as filling up; cleared once the trend
+    /// no longer predicts a full disk within the horizon
+    filling: HashSet<String>,
+    /// Keyed by watch pattern and "cpu"/"memory"
+    last_process_alerts: HashMap<(String, &'static str), Instant>,
+    /// Alerts that fired and have not recovered yet, keyed like the hooks'
+    /// `kind` (plus the watch pattern for process alerts). Tracked even with
+    /// recovery notifications off, for the menu bar highlight.
+    active: HashMap<(String, &'static str), ActiveAlert>,
+    /// Unix time of the last logged hook failure, shared with hook threads
+    last_hook_failure_log: Arc<AtomicU64>,
+    /// Pause Alerts: nothing is checked, notified or run until resumed.
+    /// Lasts for the session only.
+    paused: bool,
+}
+
+struct ActiveAlert {
+    fired: Instant,
+    /// Consecutive polls spent below the recovery level
+    calm_polls: u32,
+}
+
+impl ActiveAlert {
+    fn new(fired: Instant) -> Self {
+        Self {
+            fired,
+            calm_polls: 0,
+        }
+    }
+
+    /// Count one poll toward recovery; true once `value` has stayed below
+    /// the recovery level for `RECOVERY_POLLS` polls
+    fn calmed(&mut self, value: f32, threshold: f32, now: Instant) -> bool {
+        if value >= threshold * (1.0 - RECOVERY_MARGIN) {
+            self.calm_polls = 0;
+            return false;
+        }
+        self.calm_polls += 1;
+        let settled = now.duration_since(self.fired).as_secs() >= RECOVERY_MIN_SECS;
+        self.calm_polls >= RECOVERY_POLLS && settled
+    }
+}
+
+/// Severity of a CPU, memory or temperature alert
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertTier {
+    Warning,
+    Critical,
+}
+
+impl AlertTier {
+    pub fn id(self) -> &'static str {
+        match self {
+            AlertTier::Warning => "warning",
+            AlertTier::Critical => "critical",
+        }
+    }
+
+    fn level(self, tiers: &AlertTiers) -> f32 {
+        match self {
+            AlertTier::Warning => tiers.warning,
+            AlertTier::Critical => tiers.critical,
+        }
+    }
+}
+
+/// A system-wide reading checked against its warning and critical levels
+struct TieredReading<'a> {
+    /// Hook kind: "cpu", "memory" or "temperature"
+    kind: &'static str,
+    /// "CPU" in "CPU at 97%"
+    name: &'static str,
+    unit: &'static str,
+    value: f32,
+    tiers: AlertTiers,
+    /// Appended to the notification, e.g. the trend
+    detail: String,
+    /// Drawn as a sparkline on the notification
+    history: Option<&'a Series<f32>>,
+}
+
+/// Combined usage of the processes matched by a watch
+pub struct WatchUsage {
+    /// Name of the heaviest matching process
+    pub label: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub count: u32,
+}
+
+/// Sent to `alert_webhook_url` as JSON and to `alert_command` as env vars
+#[derive(Serialize)]
+struct AlertPayload {
+    kind: &'static str,
+    value: f32,
+    threshold: f32,
+    /// "warning" or "critical" for the CPU, memory and temperature alerts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tier: Option<&'static str>,
+    timestamp: u64,
+    hostname: String,
 }
 
 impl AlertManager {
     pub fn new() -> Self {
         Self {
-            cpu_threshold: 90.0,
-            mem_threshold: 90.0,
-            temp_threshold: 95.0,
-            last_cpu_alert: None,
-            last_mem_alert: None,
-            last_temp_alert: None,
+            last_tier_alerts: HashMap::new(),
+            active_tiers: HashMap::new(),
+            disk_fill: DiskFillPredictor::new(),
+            filling: HashSet::new(),
+            last_process_alerts: HashMap::new(),
+            active: HashMap::new(),
+            last_hook_failure_log: Arc::new(AtomicU64::new(0)),
+            paused: false,
         }
     }
 
-    pub fn check(&mut self, stats: &SystemStats) {
-        let now = Instant::now();
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
 
-        if stats.cpu.global_usage >= self.cpu_threshold && self.can_alert(&self.last_cpu_alert, now)
-        {
-            notify(
-                "CPU Usage High",
-                &format!("CPU at {:.0}%", stats.cpu.global_usage),
-            );
-            self.last_cpu_alert = Some(now);
-        }
+    /// Pause or resume alerts; returns whether they are paused now
+    pub fn toggle_paused(&mut self) -> bool {
+        self.paused = !self.paused;
+        self.paused
+    }
 
-        if stats.memory.usage_percent >= self.mem_threshold
-            && self.can_alert(&self.last_mem_alert, now)
-        {
-            notify(
-                "Memory Usage High",
-                &format!("Memory at {:.0}%", stats.memory.usage_percent),
-            );
-            self.last_mem_alert = Some(now);
+    pub fn check(&mut self, stats: &SystemStats, history: &HistoryBuffer, config: &Config) {
+        self.check_at(stats, history, config, Instant::now());
+    }
+
+    /// `check` as of `now`, so tests can step through cooldowns
+    fn check_at(
+        &mut self,
+        stats: &SystemStats,
+        history: &HistoryBuffer,
+        config: &Config,
+        now: Instant,
+    ) {
+        if self.paused {
+            return;
         }
+        let poll_interval_secs = config.poll_interval().as_secs_f64();
 
-        let max_temp = stats
+        let levels = &config.alert_thresholds;
+        self.check_tiers(
+            config,
+            TieredReading {
+                kind: "cpu",
+                name: "CPU",
+                unit: "%",
+                value: stats.cpu.global_usage,
+                tiers: levels.cpu,
+                detail: trend_summary(&history.recent.cpu_usage, poll_interval_secs, "%"),
+                history: Some(&history.recent.cpu_usage),
+            },
+            now,
+        );
+        self.check_tiers(
+            config,
+            TieredReading {
+                kind: "memory",
+                name: "Memory",
+                unit: "%",
+                value: stats.memory.usage_percent,
+                tiers: levels.memory,
+                detail: trend_summary(&history.recent.mem_usage, poll_interval_secs, "%"),
+                history: Some(&history.recent.mem_usage),
+            },
+            now,
+        );
+
+        let hottest = stats
             .temperature
             .readings
             .iter()
-            .map(|r| r.temp_c)
-            .fold(0.0_f32, f32::max);
-        if max_temp >= self.temp_threshold && self.can_alert(&self.last_temp_alert, now) {
+            .max_by(|a, b| a.temp_c.total_cmp(&b.temp_c));
+        let max_temp = hottest.map(|r| r.temp_c).unwrap_or(0.0).max(0.0);
+        let hottest_history = hottest.and_then(|r| history.recent.temps.get(&r.label));
+        let trend = hottest_history
+            .map(|data| trend_summary(data, poll_interval_secs, "C"))
+            .unwrap_or_default();
+        self.check_tiers(
+            config,
+            TieredReading {
+                kind: "temperature",
+                name: "Temperature",
+                unit: "C",
+                value: max_temp,
+                tiers: levels.temperature,
+                detail: trend,
+                history: hottest_history,
+            },
+            now,
+        );
+
+        self.check_drive_health(stats, config, now);
+        self.check_disk_fill(stats, config, now);
+
+        for watch in &config.process_watches {
+            self.check_watch(watch, &stats.processes, config, now);
+        }
+        // Forget watches that were removed while their alert was active
+        self.active.retain(|(pattern, _), _| {
+            pattern.is_empty()
+                || config
+                    .process_watches
+                    .iter()
+                    .any(|w| &w.name_pattern == pattern)
+        });
+    }
+
+    /// Notify at the highest tier `reading` has reached, each tier on its
+    /// own cooldown: going from warning to critical fires right away. Only
+    /// critical alerts play a sound. Each tier recovers separately, and
+    /// the recovery notification waits for the warning tier to clear.
+    fn check_tiers(&mut self, config: &Config, reading: TieredReading, now: Instant) {
+        let TieredReading {
+            kind,
+            name,
+            unit,
+            value,
+            tiers,
+            detail,
+            history,
+        } = reading;
+        let reached = [AlertTier::Critical, AlertTier::Warning]
+            .into_iter()
+            .find(|tier| value >= tier.level(&tiers));
+        if let Some(tier) = reached {
+            let key = (kind, tier);
+            if self.can_alert(&self.last_tier_alerts.get(&key).copied(), now) {
+                let level = tier.level(&tiers);
+                let recent = history
+                    .map(|data| last_minutes(data, config.poll_interval().as_secs_f64()))
+                    .unwrap_or_default();
+                notify_alert(
+                    kind,
+                    tier,
+                    tier_title(kind, tier),
+                    format!(
+                        "{} at {:.0}{} ({} at {:.0}{}){}",
+                        name,
+                        value,
+                        unit,
+                        tier.id(),
+                        level,
+                        unit,
+                        detail
+                    ),
+                    recent,
+                );
+                self.run_hooks(config, kind, Some(tier), value, level);
+                if tier == AlertTier::Critical {
+                    play_sound(config, kind);
+                    // The critical alert stands in for the warning on the way up
+                    let warning = (kind, AlertTier::Warning);
+                    self.last_tier_alerts.insert(warning, now);
+                    self.active_tiers
+                        .entry(warning)
+                        .or_insert_with(|| ActiveAlert::new(now));
+                }
+                self.last_tier_alerts.insert(key, now);
+                self.active_tiers.insert(key, ActiveAlert::new(now));
+            }
+        }
+
+        for tier in [AlertTier::Critical, AlertTier::Warning] {
+            let key = (kind, tier);
+            let Some(alert) = self.active_tiers.get_mut(&key) else {
+                continue;
+            };
+            if !alert.calmed(value, tier.level(&tiers), now) {
+                continue;
+            }
+            self.active_tiers.remove(&key);
+            if tier == AlertTier::Warning && config.alert_recovery_notifications {
+                notify_recovery(
+                    kind,
+                    recovered_title(kind),
+                    &format!("{} back to normal ({:.0}{})", name, value, unit),
+                );
+            }
+        }
+    }
+
+    /// Remember that an alert fired; it stays active until it recovers.
+    fn fired(&mut self, pattern: String, kind: &'static str, now: Instant) {
+        self.active.insert((pattern, kind), ActiveAlert::new(now));
+    }
+
+    /// Kinds of the system-wide alerts ("cpu", "memory", "temperature",
+    /// "disk_health") that fired and have not recovered yet
+    pub fn active_kinds(&self) -> HashSet<&'static str> {
+        self.active
+            .keys()
+            .filter(|(pattern, _)| pattern.is_empty())
+            .map(|&(_, kind)| kind)
+            .chain(self.active_tiers.keys().map(|&(kind, _)| kind))
+            .collect()
+    }
+
+    /// Whether an active alert just cleared: `value` has stayed below the
+    /// recovery level for `RECOVERY_POLLS` polls. Not subject to the cooldown.
+    fn recovered(
+        &mut self,
+        pattern: String,
+        kind: &'static str,
+        value: f32,
+        threshold: f32,
+        now: Instant,
+    ) -> bool {
+        let key = (pattern, kind);
+        let Some(alert) = self.active.get_mut(&key) else {
+            return false;
+        };
+        if !alert.calmed(value, threshold, now) {
+            return false;
+        }
+        self.active.remove(&key);
+        true
+    }
+
+    /// SMART verdicts and wear only change over days, so each problem is
+    /// reported once when it appears rather than every cooldown
+    fn check_drive_health(&mut self, stats: &SystemStats, config: &Config, now: Instant) {
+        let Some(health) = stats.disks.iter().find_map(|d| d.health.as_ref()) else {
+            return;
+        };
+        let key = (String::new(), "disk_health");
+        let threshold = config.disk_wear_alert_percent;
+        let worn = health.wear_percent.filter(|&wear| wear >= threshold);
+        if health.passed && worn.is_none() {
+            if self.active.remove(&key).is_some() && config.alert_recovery_notifications {
+                notify(t("Disk Health Recovered"), "The internal drive reports OK");
+            }
+            return;
+        }
+        if self.active.contains_key(&key) {
+            return;
+        }
+        let (body, value) = match worn {
+            Some(wear) if health.passed => (format!("Internal drive wear at {}%", wear), wear),
+            _ => (
+                "The internal drive failed its SMART health check".to_string(),
+                health.wear_percent.unwrap_or(0),
+            ),
+        };
+        notify(t("Disk Health Warning"), &body);
+        self.run_hooks(config, "disk_health", None, value as f32, threshold as f32);
+        play_sound(config, "disk_health");
+        self.fired(String::new(), "disk_health", now);
+    }
+
+    /// Alert once per episode when a volume is filling up fast enough to be
+    /// full within `disk_fill_alert_mins`
+    fn check_disk_fill(&mut self, stats: &SystemStats, config: &Config, now: Instant) {
+        let mounts: Vec<&str> = stats.disks.iter().map(|d| d.mount_point.as_str()).collect();
+        self.disk_fill.retain(&mounts);
+        self.filling
+            .retain(|mount| mounts.contains(&mount.as_str()));
+        let horizon_mins = config.disk_fill_alert_mins;
+        for disk in stats.disks.iter().filter(|d| !d.is_read_only) {
+            let until_full = self
+                .disk_fill
+                .observe(&disk.mount_point, disk.available_bytes, now)
+                .filter(|_| horizon_mins > 0);
+            let mins = match until_full {
+                Some(left) if left.as_secs() < u64::from(horizon_mins) * 60 => {
+                    (left.as_secs() / 60).max(1)
+                }
+                _ => {
+                    self.filling.remove(&disk.mount_point);
+                    continue;
+                }
+            };
+            if !self.filling.insert(disk.mount_point.clone()) {
+                continue;
+            }
             notify(
-                "Temperature High",
-                &format!("Temperature at {:.0}C", max_temp),
+                t("Disk Filling Up"),
+                &format!(
+                    "Disk '{}' will be full in ~{} minutes at the current rate ({} free)",
+                    disk.name,
+                    mins,
+                    format_bytes(disk.available_bytes)
+                ),
             );
-            self.last_temp_alert = Some(now);
+            self.run_hooks(config, "disk_fill", None, mins as f32, horizon_mins as f32);
+            play_sound(config, "disk_fill");
         }
     }
 
+    fn check_watch(
+        &mut self,
+        watch: &ProcessWatch,
+        processes: &[ProcessUsage],
+        config: &Config,
+        now: Instant,
+    ) {
+        // A watch with no matching processes left counts as idle
+        let usage = watch_usage(watch, processes).unwrap_or(WatchUsage {
+            label: watch.name_pattern.clone(),
+            cpu_percent: 0.0,
+            memory_bytes: 0,
+            count: 0,
+        });
+        let recovery = config.alert_recovery_notifications;
+        if let Some(threshold) = watch.cpu_threshold {
+            let key = (watch.name_pattern.clone(), "cpu");
+            if usage.count > 0
+                && usage.cpu_percent >= threshold
+                && self.can_alert(&self.last_process_alerts.get(&key).copied(), now)
+            {
+                notify(
+                    t("Process CPU High"),
+                    &format!("{} using {:.0}% CPU", usage.label, usage.cpu_percent),
+                );
+                self.run_hooks(config, "process_cpu", None, usage.cpu_percent, threshold);
+                play_sound(config, "process_cpu");
+                self.last_process_alerts.insert(key, now);
+                self.fired(watch.name_pattern.clone(), "process_cpu", now);
+            } else if self.recovered(
+                watch.name_pattern.clone(),
+                "process_cpu",
+                usage.cpu_percent,
+                threshold,
+                now,
+            ) && recovery
+            {
+                notify(
+                    t("Process CPU Recovered"),
+                    &format!(
+                        "{} CPU back to normal ({:.0}%)",
+                        usage.label, usage.cpu_percent
+                    ),
+                );
+            }
+        }
+        if let Some(threshold) = watch.mem_bytes_threshold {
+            let key = (watch.name_pattern.clone(), "memory");
+            if usage.count > 0
+                && usage.memory_bytes >= threshold
+                && self.can_alert(&self.last_process_alerts.get(&key).copied(), now)
+            {
+                notify(
+                    t("Process Memory High"),
+                    &format!("{} using {}", usage.label, format_bytes(usage.memory_bytes)),
+                );
+                self.run_hooks(
+                    config,
+                    "process_memory",
+                    None,
+                    usage.memory_bytes as f32,
+                    threshold as f32,
+                );
+                play_sound(config, "process_memory");
+                self.last_process_alerts.insert(key, now);
+                self.fired(watch.name_pattern.clone(), "process_memory", now);
+            } else if self.recovered(
+                watch.name_pattern.clone(),
+                "process_memory",
+                usage.memory_bytes as f32,
+                threshold as f32,
+                now,
+            ) && recovery
+            {
+                notify(
+                    t("Process Memory Recovered"),
+                    &format!(
+                        "{} memory back to normal ({})",
+                        usage.label,
+                        format_bytes(usage.memory_bytes)
+                    ),
+                );
+            }
+        }
+    }
+
+    /// "Test Alert": send a synthetic payload through the configured hooks.
+    pub fn send_test(&self, config: &Config) {
+        notify(t("Test Alert"), "Alert hooks triggered");
+        self.run_hooks(config, "test", None, 0.0, 0.0);
+        play_sound(config, "test");
+    }
+
+    /// Fire the webhook and/or command on a background thread.
+    fn run_hooks(
+        &self,
+        config: &Config,
+        kind: &'static str,
+        tier: Option<AlertTier>,
+        value: f32,
+        threshold: f32,
+    ) {
+        let webhook_url = config
+            .alert_webhook_url
+            .clone()
+            .filter(|url| !url.trim().is_empty());
+        let command = config
+            .alert_command
+            .clone()
+            .filter(|cmd| !cmd.trim().is_empty());
+        if webhook_url.is_none() && command.is_none() || SUPPRESSED.load(Ordering::Relaxed) {
+            return;
+        }
+        let payload = AlertPayload {
+            kind,
+            value,
+            threshold,
+            tier: tier.map(AlertTier::id),
+            timestamp: unix_now(),
+            hostname: sysinfo::System::host_name().unwrap_or_default(),
+        };
+        let last_failure_log = Arc::clone(&self.last_hook_failure_log);
+        let _ = thread::Builder::new()
+            .name("alert-hook".to_string())
+            .spawn(move || {
+                if let Some(url) = webhook_url {
+                    if let Err(e) = post_webhook(&url, &payload) {
+                        log_hook_failure(&last_failure_log, "webhook", &e);
+                    }
+                }
+                if let Some(command) = command {
+                    if let Err(e) = run_command(&command, &payload) {
+                        log_hook_failure(&last_failure_log, "command", &e);
+                    }
+                }
+            });
+    }
+
     fn can_alert(&self, last: &Option<Instant>, now: Instant) -> bool {
         match last {
             None => true,
@@ -70,10 +585,484 @@ impl AlertManager {
     }
 }
 
-fn notify(title: &str, message: &str) {
-    let script = format!(
-        "display notification \"{}\" with title \"{}\"",
-        message, title
+/// "CPU Critical", "Memory Warning", ...
+fn tier_title(kind: &str, tier: AlertTier) -> &'static str {
+    match (kind, tier) {
+        ("cpu", AlertTier::Warning) => t("CPU Warning"),
+        ("cpu", AlertTier::Critical) => t("CPU Critical"),
+        ("memory", AlertTier::Warning) => t("Memory Warning"),
+        ("memory", AlertTier::Critical) => t("Memory Critical"),
+        (_, AlertTier::Warning) => t("Temperature Warning"),
+        (_, AlertTier::Critical) => t("Temperature Critical"),
+    }
+}
+
+fn recovered_title(kind: &str) -> &'static str {
+    match kind {
+        "cpu" => t("CPU Usage Recovered"),
+        "memory" => t("Memory Usage Recovered"),
+        _ => t("Temperature Recovered"),
+    }
+}
+
+/// Sum the processes whose name contains the watch pattern, ignoring case.
+pub fn watch_usage(watch: &ProcessWatch, processes: &[ProcessUsage]) -> Option<WatchUsage> {
+    let pattern = watch.name_pattern.trim().to_lowercase();
+    if pattern.is_empty() {
+        return None;
+    }
+    let matches: Vec<&ProcessUsage> = processes
+        .iter()
+        .filter(|p| p.name.to_lowercase().contains(&pattern))
+        .collect();
+    let heaviest = matches.iter().max_by_key(|p| p.memory_bytes)?;
+    Some(WatchUsage {
+        label: heaviest.name.clone(),
+        cpu_percent: matches.iter().map(|p| p.cpu_percent).sum(),
+        memory_bytes: matches.iter().map(|p| p.memory_bytes).sum(),
+        count: matches.iter().map(|p| p.count).sum(),
+    })
+}
+
+/// " — rising, avg 84% over 5 min" for the samples in the last
+/// `RECENT_MINUTES`, or an empty string when there is too little history to
+/// say anything.
+fn trend_summary(data: &Series<f32>, poll_interval_secs: f64, unit: &str) -> String {
+    let window = data.len().min(RecentHistory::window(poll_interval_secs));
+    if window < 4 {
+        return String::new();
+    }
+    let half = window / 2;
+    let start = data.len() - window;
+    let (Some(all), Some(older), Some(newer)) = (
+        data.aggregates(window),
+        data.aggregates_in(start..start + half),
+        data.aggregates(window - half),
+    ) else {
+        return String::new();
+    };
+    let delta = (newer.avg - older.avg) as f32;
+    let direction = if delta >= TREND_DELTA {
+        "rising"
+    } else if -delta >= TREND_DELTA {
+        "falling"
+    } else {
+        "steady"
+    };
+    let window_secs = (window as f64 * poll_interval_secs).round() as u64;
+    format!(
+        " — {}, avg {:.0}{} over {}",
+        direction,
+        all.avg,
+        unit,
+        format_window(window_secs)
+    )
+}
+
+/// The readings in the last `RECENT_MINUTES` of `data`
+fn last_minutes(data: &Series<f32>, poll_interval_secs: f64) -> Vec<f32> {
+    let start = data
+        .len()
+        .saturating_sub(RecentHistory::window(poll_interval_secs));
+    (start..data.len())
+        .filter_map(|i| data.reading(i))
+        .copied()
+        .collect()
+}
+
+fn format_window(secs: u64) -> String {
+    if secs >= 120 {
+        format!("{} min", secs / 60)
+    } else if secs >= 60 {
+        "1 min".to_string()
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn post_webhook(url: &str, payload: &AlertPayload) -> Result<(), String> {
+    let json = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+    let status = Command::new("curl")
+        .args(["-sS", "--fail", "-m", &WEBHOOK_TIMEOUT_SECS.to_string()])
+        .args(["-X", "POST", "-H", "Content-Type: application/json"])
+        .args(["--data-binary", &json, url])
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("curl exited with {}", status))
+    }
+}
+
+/// Run `alert_command` through `sh -c` with the payload in `MSM_ALERT_*` env vars.
+fn run_command(command: &str, payload: &AlertPayload) -> Result<(), String> {
+    let json = serde_json::to_string(payload).map_err(|e| e.to_string())?;
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("MSM_ALERT_KIND", payload.kind)
+        .env("MSM_ALERT_VALUE", format!("{:.1}", payload.value))
+        .env("MSM_ALERT_THRESHOLD", format!("{:.1}", payload.threshold))
+        .env("MSM_ALERT_TIER", payload.tier.unwrap_or_default())
+        .env("MSM_ALERT_TIMESTAMP", payload.timestamp.to_string())
+        .env("MSM_ALERT_HOSTNAME", &payload.hostname)
+        .env("MSM_ALERT_JSON", json)
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("exited with {}", status))
+    }
+}
+
+/// Log a hook failure, at most once per `HOOK_FAILURE_LOG_SECS`.
+fn log_hook_failure(last_logged: &AtomicU64, hook: &str, error: &str) {
+    let now = unix_now();
+    let last = last_logged.load(Ordering::Relaxed);
+    if now.saturating_sub(last) < HOOK_FAILURE_LOG_SECS {
+        return;
+    }
+    last_logged.store(now, Ordering::Relaxed);
+    log::warn!("alert {} failed: {}", hook, error);
+}
+
+/// The sound for alerts of `kind`: its override if it has one, else
+/// `alert_sound`. NSSound plays in the background, so the poll isn't held up.
+fn play_sound(config: &Config, kind: &str) {
+    if SUPPRESSED.load(Ordering::Relaxed) {
+        return;
+    }
+    let sound = match config.alert_sounds.get(kind) {
+        Some(sound) => sound.as_deref(),
+        None => config.alert_sound.as_deref(),
+    };
+    if let Some(sound) = sound.filter(|s| !s.trim().is_empty()) {
+        sound::play(sound);
+    }
+}
+
+pub fn notify(title: &str, message: &str) {
+    if SUPPRESSED.load(Ordering::Relaxed) {
+        log::info!("notification suppressed: {}: {}", title, message);
+        return;
+    }
+    let id = notification_id("notice");
+    deliver(&id, &id, title, message, None);
+}
+
+/// `notify` for a CPU, memory or temperature alert, with `recent` values
+/// of its metric attached as a sparkline. The sparkline is drawn off the
+/// main thread, and left out if it takes longer than `SPARKLINE_BUDGET`.
+fn notify_alert(
+    kind: &'static str,
+    tier: AlertTier,
+    title: &str,
+    message: String,
+    recent: Vec<f32>,
+) {
+    if SUPPRESSED.load(Ordering::Relaxed) {
+        log::info!("notification suppressed: {}: {}", title, message);
+        return;
+    }
+    let title = title.to_string();
+    thread::spawn(move || {
+        let image = sparkline_png(kind, &notification_id(kind), recent);
+        let posted = deliver(
+            &alert_id(kind, tier),
+            &alert_thread(kind),
+            &title,
+            &message,
+            image.as_deref(),
+        );
+        // Notification Center takes the image; osascript leaves it behind
+        if let (false, Some(path)) = (posted, image) {
+            let _ = fs::remove_file(path);
+        }
+    });
+}
+
+/// `notify` for an alert that cleared. It replaces the warning it ends in
+/// Notification Center and takes the critical one away.
+fn notify_recovery(kind: &'static str, title: &str, message: &str) {
+    if SUPPRESSED.load(Ordering::Relaxed) {
+        log::info!("notification suppressed: {}: {}", title, message);
+        return;
+    }
+    user_notifications::remove(&alert_id(kind, AlertTier::Critical));
+    deliver(
+        &alert_id(kind, AlertTier::Warning),
+        &alert_thread(kind),
+        title,
+        message,
+        None,
     );
-    let _ = Command::new("osascript").arg("-e").arg(&script).spawn();
+}
+
+/// Unique within the run, for notifications and their images
+fn notification_id(prefix: &str) -> String {
+    let n = NEXT_NOTIFICATION.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}-{}", prefix, std::process::id(), n)
+}
+
+/// Notification id of an alert tier, the same key `check_tiers` tracks it
+/// by: the next alert or recovery at that tier replaces it
+fn alert_id(kind: &str, tier: AlertTier) -> String {
+    format!("alert-{}-{}", kind, tier.id())
+}
+
+/// Groups the tiers and recovery of one kind of alert
+fn alert_thread(kind: &str) -> String {
+    format!("alert-{}", kind)
+}
+
+/// Post through Notification Center, or with `osascript` where that isn't
+/// available. True if Notification Center took it.
+fn deliver(id: &str, thread: &str, title: &str, message: &str, image: Option<&Path>) -> bool {
+    if user_notifications::post(id, thread, title, message, image) {
+        return true;
+    }
+    let _ = notify_command(title, message).spawn();
+    false
+}
+
+/// `recent` drawn to a PNG in the temp folder, or None if that fails or
+/// takes longer than `SPARKLINE_BUDGET`
+fn sparkline_png(kind: &'static str, id: &str, recent: Vec<f32>) -> Option<PathBuf> {
+    if recent.len() < 2 {
+        return None;
+    }
+    let path = std::env::temp_dir().join(format!("mac-state-monitor-{}.png", id));
+    let (tx, rx) = mpsc::channel();
+    let target = path.clone();
+    thread::spawn(move || {
+        let result = render_alert_sparkline(kind, &recent, &target);
+        // Too late: the alert went out without it
+        if tx.send(result).is_err() {
+            let _ = fs::remove_file(&target);
+        }
+    });
+    match rx.recv_timeout(SPARKLINE_BUDGET) {
+        Ok(Ok(())) => Some(path),
+        Ok(Err(e)) => {
+            log::warn!("could not draw the alert sparkline: {}", e);
+            None
+        }
+        Err(_) => {
+            log::info!(
+                "alert sparkline took over {} ms; sent without it",
+                SPARKLINE_BUDGET.as_millis()
+            );
+            None
+        }
+    }
+}
+
+/// Keep alerts and other notifications from reaching the user for the rest
+/// of the run
+pub fn suppress_notifications() {
+    SUPPRESSED.store(true, Ordering::Relaxed);
+}
+
+/// Title and message are passed as script arguments, never spliced into the
+/// script, so quotes in volume or process names can't break out of it.
+fn notify_command(title: &str, message: &str) -> Command {
+    let mut command = Command::new("osascript");
+    for line in [
+        "on run argv",
+        "display notification (item 2 of argv) with title (item 1 of argv)",
+        "end run",
+    ] {
+        command.arg("-e").arg(line);
+    }
+    command.arg(title).arg(message);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::mock::sample;
+    use std::time::Duration;
+
+    fn at(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn notification_text_stays_out_of_the_script() {
+        let label = r#"evil" & (do shell script "touch /tmp/pwned") & "\"#;
+        let message = format!("{} using 95% CPU", label);
+        let command = notify_command("Process CPU High", &message);
+        let args: Vec<&str> = command
+            .get_args()
+            .map(|arg| arg.to_str().unwrap())
+            .collect();
+        let (script, text) = args.split_at(args.len() - 2);
+        assert_eq!(text, ["Process CPU High", message.as_str()]);
+        assert!(script.iter().all(|line| !line.contains("pwned")));
+    }
+
+    #[test]
+    fn a_tier_waits_out_its_cooldown() {
+        suppress_notifications();
+        let config = Config::default();
+        let history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+        let start = Instant::now();
+        let warning = ("cpu", AlertTier::Warning);
+        let busy = sample(85.0, 10.0, 40.0);
+
+        alerts.check_at(&busy, &history, &config, start);
+        assert_eq!(alerts.last_tier_alerts.get(&warning), Some(&start));
+        alerts.check_at(&busy, &history, &config, at(start, COOLDOWN_SECS - 1));
+        assert_eq!(alerts.last_tier_alerts.get(&warning), Some(&start));
+        let again = at(start, COOLDOWN_SECS);
+        alerts.check_at(&busy, &history, &config, again);
+        assert_eq!(alerts.last_tier_alerts.get(&warning), Some(&again));
+    }
+
+    #[test]
+    fn critical_fires_during_the_warning_cooldown() {
+        suppress_notifications();
+        let config = Config::default();
+        let history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+        let start = Instant::now();
+        let warning = ("cpu", AlertTier::Warning);
+        let critical = ("cpu", AlertTier::Critical);
+
+        alerts.check_at(&sample(85.0, 10.0, 40.0), &history, &config, start);
+        assert_eq!(alerts.last_tier_alerts.get(&warning), Some(&start));
+        assert!(!alerts.last_tier_alerts.contains_key(&critical));
+
+        let escalated = at(start, 10);
+        alerts.check_at(&sample(97.0, 10.0, 40.0), &history, &config, escalated);
+        assert_eq!(alerts.last_tier_alerts.get(&critical), Some(&escalated));
+        // The critical alert stands in for the warning, cooldown included
+        assert_eq!(alerts.last_tier_alerts.get(&warning), Some(&escalated));
+        alerts.check_at(&sample(97.0, 10.0, 40.0), &history, &config, at(start, 20));
+        assert_eq!(alerts.last_tier_alerts.get(&critical), Some(&escalated));
+
+        // Back under critical but still over the warning level: only the
+        // critical tier recovers
+        let warm = sample(85.0, 10.0, 40.0);
+        alerts.check_at(&warm, &history, &config, at(start, 30));
+        alerts.check_at(&warm, &history, &config, at(start, 31));
+        assert!(!alerts.active_tiers.contains_key(&critical));
+        assert!(alerts.active_tiers.contains_key(&warning));
+        assert_eq!(tier_title("cpu", AlertTier::Critical), t("CPU Critical"));
+    }
+
+    #[test]
+    fn recovery_needs_the_margin_for_several_polls() {
+        suppress_notifications();
+        let config = Config::default();
+        let history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+        let start = Instant::now();
+
+        alerts.check_at(&sample(85.0, 10.0, 40.0), &history, &config, start);
+        assert!(alerts.active_kinds().contains("cpu"));
+        // Under the 80% warning level, but not by the margin
+        alerts.check_at(&sample(79.0, 10.0, 40.0), &history, &config, at(start, 20));
+        alerts.check_at(&sample(79.0, 10.0, 40.0), &history, &config, at(start, 21));
+        assert!(alerts.active_kinds().contains("cpu"));
+        alerts.check_at(&sample(50.0, 10.0, 40.0), &history, &config, at(start, 22));
+        assert!(alerts.active_kinds().contains("cpu"));
+        alerts.check_at(&sample(50.0, 10.0, 40.0), &history, &config, at(start, 23));
+        assert!(!alerts.active_kinds().contains("cpu"));
+    }
+
+    #[test]
+    fn recovery_waits_for_the_minimum_time() {
+        suppress_notifications();
+        let config = Config::default();
+        let history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+        let start = Instant::now();
+        let calm = sample(20.0, 10.0, 40.0);
+
+        alerts.check_at(&sample(85.0, 10.0, 40.0), &history, &config, start);
+        for secs in 1..RECOVERY_MIN_SECS {
+            alerts.check_at(&calm, &history, &config, at(start, secs));
+            assert!(alerts.active_kinds().contains("cpu"));
+        }
+        alerts.check_at(&calm, &history, &config, at(start, RECOVERY_MIN_SECS));
+        assert!(alerts.active_kinds().is_empty());
+    }
+
+    #[test]
+    fn nothing_fires_while_paused() {
+        suppress_notifications();
+        let config = Config::default();
+        let history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+        assert!(alerts.toggle_paused());
+        alerts.check_at(
+            &sample(99.0, 99.0, 110.0),
+            &history,
+            &config,
+            Instant::now(),
+        );
+        assert!(alerts.active_kinds().is_empty());
+    }
+
+    #[test]
+    fn sparklines_cover_the_last_five_minutes() {
+        let mut history = HistoryBuffer::new(60);
+        for i in 0..1000 {
+            history.push(&sample(i as f32 % 100.0, 50.0, 40.0));
+        }
+        // The charts hold a minute, the alerts five
+        assert_eq!(history.cpu_usage.len(), 60);
+        let recent = last_minutes(&history.recent.cpu_usage, 2.0);
+        assert_eq!(recent.len(), 150);
+        assert_eq!(recent.last(), Some(&99.0));
+        assert_eq!(last_minutes(&history.recent.temps["CPU"], 1.0).len(), 300);
+        // One poll a minute: five samples
+        assert_eq!(last_minutes(&history.recent.cpu_usage, 60.0).len(), 5);
+        // Less history than five minutes: all of it
+        let mut short = HistoryBuffer::new(60);
+        for cpu in [1.0, 2.0, 3.0] {
+            short.push(&sample(cpu, 50.0, 40.0));
+        }
+        assert_eq!(last_minutes(&short.recent.cpu_usage, 2.0), [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn trends_span_the_last_five_minutes() {
+        let mut history = HistoryBuffer::new(60);
+        for i in 0..300 {
+            let cpu = if i < 150 { 20.0 } else { 60.0 };
+            history.push(&sample(cpu, 50.0, 40.0));
+        }
+        let cpu = &history.recent.cpu_usage;
+        assert_eq!(
+            trend_summary(cpu, 1.0, "%"),
+            " — rising, avg 40% over 5 min"
+        );
+        let mem = &history.recent.mem_usage;
+        assert_eq!(
+            trend_summary(mem, 1.0, "%"),
+            " — steady, avg 50% over 5 min"
+        );
+        assert_eq!(
+            trend_summary(&HistoryBuffer::new(60).recent.cpu_usage, 1.0, "%"),
+            ""
+        );
+    }
+
+    #[test]
+    fn alert_tiers_group_by_kind_and_replace_by_tier() {
+        let warning = alert_id("cpu", AlertTier::Warning);
+        // Stable, so the recovery replaces the warning it ends
+        assert_eq!(warning, alert_id("cpu", AlertTier::Warning));
+        assert_ne!(warning, alert_id("cpu", AlertTier::Critical));
+        assert_ne!(warning, alert_id("memory", AlertTier::Warning));
+        assert_ne!(alert_thread("cpu"), alert_thread("memory"));
+    }
 }
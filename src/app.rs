@@ -1,57 +1,485 @@
-use crate::alert::AlertManager;
-use crate::config::Config;
+use crate::alert::{self, notify, AlertManager};
+use crate::config::{
+    config_dir, AlertPreset, ChartSmoothing, ChartThemeChoice, ColorScheme, Config, CpuStatusStyle,
+    Hotkey, Language, LowPowerBehavior, Module, NetChartStyle, NetDisplay, OpenMenuUpdates,
+    RunnerIdleBehavior, TempLabelStyle, DIGEST_MODULE, MAX_PINNED_SENSORS, MAX_POLL_INTERVAL_SECS,
+    MIN_POLL_INTERVAL_SECS,
+};
+use crate::diagnostics::{self, TickStats};
+use crate::format::format_bytes;
+use crate::issues;
 use crate::launch_agent;
-use crate::model::HistoryBuffer;
-use crate::monitor::SystemMonitor;
+use crate::logging;
+use crate::model::{HistoryBuffer, SystemStats};
+use crate::monitor::daily_traffic;
+use crate::monitor::volumes::{self, VolumeChange, VolumeWatcher};
+use crate::monitor::{disk, power, StatsSource, SystemMonitor};
+use crate::replay::{self, ReplaySource};
+use crate::report;
+use crate::settings_transfer;
+use crate::strings::{self, t, tf};
+use crate::summary::{self, DailyLoad};
+use crate::ui::appearance;
 use crate::ui::chart_window::{ChartMode, ChartWindow};
-use crate::ui::tray::TrayManager;
-use std::time::Instant;
+use crate::ui::hotkey::HotkeyRegistration;
+use crate::ui::pasteboard;
+use crate::ui::prompt;
+use crate::ui::session;
+use crate::ui::sound;
+#[cfg(feature = "touch-bar")]
+use crate::ui::touch_bar::{self, TouchBar};
+use crate::ui::tray::{self, custom_frames_root_dir, TrayManager};
+use crate::ui::user_notifications;
+use crate::uninstall;
+use objc2::MainThreadMarker;
+use rfd::{FileDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tao::dpi::PhysicalPosition;
 use tao::event_loop::EventLoopWindowTarget;
+use tao::window::WindowId;
+
+/// Runner and status item animation step, and its slower battery variant
+const ANIMATION_INTERVAL: Duration = Duration::from_millis(40);
+const BATTERY_ANIMATION_INTERVAL: Duration = Duration::from_millis(200);
+/// While a menu is open or a chart window is dragged; the runner holds still
+const INTERACTION_ANIMATION_INTERVAL: Duration = Duration::from_millis(200);
+/// A chart window that moved or resized this recently is still being dragged
+const CHART_DRAG_HOLD: Duration = Duration::from_millis(300);
+/// Poll interval multiplier on battery with `LowPowerBehavior::Polling`
+const BATTERY_POLL_MULTIPLIER: u32 = 3;
+/// NET refresh between slower full polls
+const FAST_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Shortest gap between two writes of the stats file
+const STATS_FILE_INTERVAL: Duration = Duration::from_secs(1);
+/// A poll this many intervals late follows a gap in the history
+const HISTORY_GAP_INTERVALS: u32 = 3;
 
 pub struct App {
     config: Config,
-    monitor: SystemMonitor,
+    monitor: Box<dyn StatsSource>,
     tray: TrayManager,
     alert: AlertManager,
     pub history: HistoryBuffer,
+    /// Stats from the most recent tick
+    latest: SystemStats,
     pub chart_window: ChartWindow,
+    /// Single charts popped out next to the full window
+    pop_outs: HashMap<ChartMode, ChartWindow>,
+    /// None while `config.touch_bar` is off
+    #[cfg(feature = "touch-bar")]
+    touch_bar: Option<TouchBar>,
+    tick_stats: TickStats,
+    hotkey: HotkeyRegistration,
+    /// Power source as of the last tick
+    on_battery: bool,
+    /// App CPU was above the warning level at the last tick
+    app_cpu_high: bool,
+    /// The mini chart window or a pop-out moved or was resized since the
+    /// config was last saved
+    chart_frame_moved: bool,
+    /// shutdown() already ran
+    shut_down: bool,
+    /// Mount points across polls, for the mount/unmount notifications
+    volumes: VolumeWatcher,
+    /// Last write of `stats_output_path`
+    stats_file_written: Option<Instant>,
+    /// The left mouse button is down in a chart window
+    chart_pressed: bool,
+    /// Last time a chart window moved or resized
+    chart_moved_at: Option<Instant>,
+    /// CPU, memory and temperature since local midnight
+    daily_load: DailyLoad,
+    /// Local day and hour of the previous poll
+    summary_clock: Option<(i32, u8)>,
+    /// Wall-clock time of the previous poll and the interval it ran at.
+    /// `Instant` stands still while the Mac sleeps, so it can't see a gap.
+    last_poll: Option<(SystemTime, Duration)>,
+    /// Poll interval of a `--replay`, set by the recording and the speed;
+    /// None for live stats
+    replay_interval: Option<Duration>,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(mtm: MainThreadMarker) -> Self {
+        user_notifications::request_authorization();
+        Self::with_source(mtm, Box::new(SystemMonitor::new()), None)
+    }
+
+    /// Feed a recording through the app instead of live stats, with
+    /// notifications, sounds and alert hooks off
+    pub fn replay(mtm: MainThreadMarker, source: ReplaySource, speed: f64) -> Self {
+        alert::suppress_notifications();
+        replay::report_panics();
+        let interval = source.interval(speed);
+        Self::with_source(mtm, Box::new(source), Some(interval))
+    }
+
+    fn with_source(
+        mtm: MainThreadMarker,
+        monitor: Box<dyn StatsSource>,
+        replay_interval: Option<Duration>,
+    ) -> Self {
         let mut config = Config::load();
         config.launch_at_login = launch_agent::is_enabled();
-        Self {
+        strings::set_language(config.language);
+        let mut chart_window = ChartWindow::new();
+        configure_chart(&mut chart_window, &config);
+        chart_window.set_mini(config.chart_mini_mode, config.chart_mini_position);
+        let mut hotkey = HotkeyRegistration::new();
+        hotkey.sync(config.chart_hotkey);
+        hotkey.set_action(&config.chart_hotkey_action);
+        let mut app = Self {
             config,
-            monitor: SystemMonitor::new(),
-            tray: TrayManager::new(),
+            monitor,
+            tray: TrayManager::new(mtm),
             alert: AlertManager::new(),
             history: HistoryBuffer::new(60),
-            chart_window: ChartWindow::new(),
-        }
+            latest: SystemStats::default(),
+            chart_window,
+            pop_outs: HashMap::new(),
+            #[cfg(feature = "touch-bar")]
+            touch_bar: None,
+            tick_stats: TickStats::default(),
+            hotkey,
+            on_battery: power::on_battery(),
+            app_cpu_high: false,
+            chart_frame_moved: false,
+            shut_down: false,
+            volumes: VolumeWatcher::new(),
+            stats_file_written: None,
+            chart_pressed: false,
+            chart_moved_at: None,
+            daily_load: DailyLoad::default(),
+            summary_clock: None,
+            last_poll: None,
+            replay_interval,
+        };
+        app.sync_touch_bar();
+        app
     }
 
     pub fn tick(&mut self) {
-        let stats = self.monitor.poll();
-        self.history.push(&stats);
-        self.tray.update(&stats, &self.config);
-        self.alert.check(&stats);
-        self.chart_window.render(&self.history);
+        // A finished replay holds its last sample on screen
+        if self.monitor.finished() {
+            return;
+        }
+        let started = Instant::now();
+        let interval = self.poll_interval();
+        self.monitor
+            .set_process_collection(!self.config.process_watches.is_empty());
+        self.monitor.set_public_ip_url(
+            Some(self.config.public_ip_url.as_str()).filter(|_| self.config.show_public_ip),
+        );
+        self.monitor
+            .set_ping_target(self.config.ping_target.as_deref());
+        self.monitor.set_disk_filter(
+            &self.config.disk_include_mounts,
+            &self.config.disk_exclude_mounts,
+        );
+        self.mark_history_gap();
+        let mut stats = record_poll(
+            self.monitor.as_mut(),
+            &self.config,
+            &mut self.history,
+            &mut self.alert,
+            started,
+        );
+        stats.app_usage.render_ms = self.chart_render_time().map(as_millis);
+        self.check_app_cpu(stats.app_usage.cpu_percent);
+        self.check_volumes(&stats);
+        self.check_daily_summary(&stats);
+        self.tray.update(
+            &stats,
+            &self.history,
+            &self.config,
+            &self.alert.active_kinds(),
+        );
+        #[cfg(feature = "touch-bar")]
+        if let Some(touch_bar) = &mut self.touch_bar {
+            touch_bar.update(&stats, &self.config, self.alert.is_paused());
+        }
+        let settings = diagnostics::effective_settings(&self.config, &self.tray.runner_settings());
+        self.tray.set_effective_settings(settings);
+        self.tray.set_issues(issues::recent());
+        self.render_charts();
+        self.write_stats_file(&stats);
+        self.latest = stats;
+        self.on_battery = power::on_battery();
+        self.sync_sampler_suspension();
+        self.tick_stats.record(started, started.elapsed(), interval);
+        if std::mem::take(&mut self.chart_frame_moved) {
+            self.config.save();
+        }
+    }
+
+    /// Before exiting (Quit, SIGTERM or the event loop ending): save the
+    /// config and today's traffic, then let go of the status items, chart window and hotkey.
+    /// History lives in memory only, so there is nothing to write for it.
+    pub fn shutdown(&mut self) {
+        if self.shut_down {
+            return;
+        }
+        self.config.save();
+        self.monitor.save_daily_traffic();
+        self.release();
+    }
+
+    fn release(&mut self) {
+        self.shut_down = true;
+        self.hotkey.sync(None);
+        for window in self.chart_windows_mut() {
+            window.handle_close();
+        }
+        self.tray.remove_items();
+    }
+
+    /// Between full polls: fresh NET rates on top of the last full stats.
+    /// Nothing goes into the history, so charts keep one point per poll.
+    pub fn tick_fast(&mut self) {
+        let fast = self.monitor.poll_fast();
+        let net = &mut self.latest.network;
+        net.total_received_bytes = fast.total_received_bytes;
+        net.total_transmitted_bytes = fast.total_transmitted_bytes;
+        net.session_received_bytes = fast.session_received_bytes;
+        net.session_transmitted_bytes = fast.session_transmitted_bytes;
+        net.today_received_bytes = fast.today_received_bytes;
+        net.today_transmitted_bytes = fast.today_transmitted_bytes;
+        net.received_per_sec = fast.received_per_sec;
+        net.transmitted_per_sec = fast.transmitted_per_sec;
+        self.tray.update_net(&self.latest);
+    }
+
+    /// Break the charts' lines before this poll if it came late (the Mac
+    /// slept, or the event loop was held up) or at a new interval, where
+    /// one sample no longer stands for the same span of time
+    fn mark_history_gap(&mut self) {
+        let now = SystemTime::now();
+        let interval = self.poll_interval();
+        if let Some((last, last_interval)) = self.last_poll {
+            let elapsed = now.duration_since(last).unwrap_or_default();
+            if interval != last_interval || elapsed > last_interval * HISTORY_GAP_INTERVALS {
+                self.history.push_gap();
+            }
+        }
+        self.last_poll = Some((now, interval));
+    }
+
+    /// Log when the app's own CPU use crosses `app_cpu_warn_percent`, once per
+    /// crossing.
+    fn check_app_cpu(&mut self, cpu_percent: f32) {
+        let high = cpu_percent > self.config.app_cpu_warn_percent;
+        if high && !self.app_cpu_high {
+            log::warn!(
+                "app CPU at {:.1}% (warning above {:.1}%)",
+                cpu_percent,
+                self.config.app_cpu_warn_percent
+            );
+        }
+        self.app_cpu_high = high;
+    }
+
+    /// Time between polls, stretched on battery when configured; set by
+    /// the recording during a replay
+    pub fn poll_interval(&self) -> Duration {
+        if let Some(interval) = self.replay_interval {
+            return interval;
+        }
+        let interval = self.config.poll_interval();
+        if self.on_battery && self.config.low_power == LowPowerBehavior::Polling {
+            interval * BATTERY_POLL_MULTIPLIER
+        } else {
+            interval
+        }
+    }
+
+    /// None when full polls are frequent enough, on battery when polling
+    /// is reduced, and during a replay
+    pub fn fast_poll_interval(&self) -> Option<Duration> {
+        if self.replay_interval.is_some() {
+            return None;
+        }
+        let reduced = self.on_battery && self.config.low_power == LowPowerBehavior::Polling;
+        (self.poll_interval() > FAST_POLL_INTERVAL && !reduced).then_some(FAST_POLL_INTERVAL)
+    }
+
+    pub fn animation_interval(&self) -> Duration {
+        if self.interacting(Instant::now()) {
+            INTERACTION_ANIMATION_INTERVAL
+        } else if self.on_battery && self.config.low_power != LowPowerBehavior::Off {
+            BATTERY_ANIMATION_INTERVAL
+        } else {
+            ANIMATION_INTERVAL
+        }
     }
 
     pub fn config(&self) -> &Config {
         &self.config
     }
 
-    pub fn set_poll_interval(&mut self, secs: u64) {
-        self.config.poll_interval_secs = secs;
+    pub fn set_poll_interval(&mut self, secs: f64) {
+        self.config.poll_interval_secs = secs.clamp(MIN_POLL_INTERVAL_SECS, MAX_POLL_INTERVAL_SECS);
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// "Custom…": ask for an interval in seconds; invalid input is ignored.
+    pub fn prompt_poll_interval(&mut self) {
+        let message = format!(
+            "Refresh interval in seconds ({} to {}):",
+            MIN_POLL_INTERVAL_SECS, MAX_POLL_INTERVAL_SECS
+        );
+        let current = self.config.poll_interval().as_secs_f64().to_string();
+        let Some(answer) = prompt::ask_text(&message, &current) else {
+            return;
+        };
+        match answer.trim().trim_end_matches('s').trim().parse::<f64>() {
+            Ok(secs) if secs.is_finite() => self.set_poll_interval(secs),
+            _ => log::warn!("ignoring refresh interval {:?}", answer),
+        }
+    }
+
+    pub fn toggle_auto_hide(&mut self, module: Module) {
+        let rules = &mut self.config.auto_hide;
+        match module {
+            Module::Net => rules.net = !rules.net,
+            Module::Temp => rules.temp = !rules.temp,
+            _ => return,
+        }
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn toggle_digest(&mut self) {
+        let visible = self.config.module_visible(DIGEST_MODULE);
+        self.config.set_module_visible(DIGEST_MODULE, !visible);
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) {
+        self.config.color_scheme = scheme;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_cpu_status_style(&mut self, style: CpuStatusStyle) {
+        self.config.cpu_status_style = style;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn toggle_cpu_cluster_label(&mut self) {
+        self.config.cpu_cluster_label = !self.config.cpu_cluster_label;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_chart_hotkey(&mut self, hotkey: Option<Hotkey>) {
+        self.config.chart_hotkey = hotkey;
+        self.hotkey.sync(hotkey);
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_module_width(&mut self, width: Option<f64>) {
+        self.config.module_width = width;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Module Order: the status items are created again in this order on
+    /// the next update
+    pub fn set_module_order(&mut self, order: &[Module]) {
+        self.config.module_order = order.iter().map(|m| m.id().to_string()).collect();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Move Left / Move Right in a module's menu
+    pub fn move_module(&mut self, module: Module, left: bool) {
+        let mut order = self.config.module_order();
+        let Some(index) = order.iter().position(|&m| m == module) else {
+            return;
+        };
+        let other = if left {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1).filter(|&i| i < order.len())
+        };
+        if let Some(other) = other {
+            order.swap(index, other);
+            self.set_module_order(&order);
+        }
+    }
+
+    pub fn set_open_menu_updates(&mut self, mode: OpenMenuUpdates) {
+        self.config.open_menu_updates = mode;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_low_power_behavior(&mut self, behavior: LowPowerBehavior) {
+        self.config.low_power = behavior;
         self.tray.invalidate_cpu_menu();
+        self.sync_sampler_suspension();
         self.config.save();
     }
 
+    /// Pause the background samplers while the Mac sleeps or the screen is
+    /// locked, and on battery when polling is reduced
+    pub fn sync_sampler_suspension(&mut self) {
+        let reduced = self.on_battery && self.config.low_power == LowPowerBehavior::Polling;
+        self.monitor
+            .set_samplers_suspended(session::inactive() || reduced);
+    }
+
+    pub fn set_language(&mut self, language: Language) {
+        self.config.language = language;
+        strings::set_language(language);
+        self.tray.invalidate_cpu_menu();
+        self.tray.invalidate_temp_menu();
+        self.render_charts();
+        self.config.save();
+    }
+
+    /// A dropdown closed; apply the readings it held back if so configured.
+    pub fn menu_closed(&mut self) {
+        if self.config.open_menu_updates == OpenMenuUpdates::Deferred {
+            self.tray.update_menus(&self.latest, &self.config);
+        }
+    }
+
     pub fn set_temp_component(&mut self, label: String) {
         self.config.menubar_temp_component = label;
-        self.tray.invalidate_cpu_menu();
+        self.tray.invalidate_temp_menu();
+        self.config.save();
+    }
+
+    pub fn set_temp_label_style(&mut self, style: TempLabelStyle) {
+        self.config.temp_label_style = style;
+        self.tray.invalidate_temp_menu();
+        self.config.save();
+    }
+
+    /// Unpin a pinned sensor, or pin it while fewer than the maximum are
+    pub fn toggle_pinned_sensor(&mut self, label: String) {
+        let pinned = &mut self.config.pinned_sensors;
+        if let Some(i) = pinned.iter().position(|l| *l == label) {
+            pinned.remove(i);
+        } else if pinned.len() < MAX_PINNED_SENSORS {
+            pinned.push(label);
+        } else {
+            return;
+        }
+        for window in std::iter::once(&mut self.chart_window).chain(self.pop_outs.values_mut()) {
+            window.set_pinned_sensors(pinned);
+        }
+        self.tray.invalidate_temp_menu();
         self.config.save();
     }
 
@@ -61,6 +489,40 @@ impl App {
         self.config.save();
     }
 
+    /// "Custom…": ask for a display time in seconds; invalid input is ignored.
+    pub fn prompt_runner_display_secs(&mut self) {
+        let current = self.config.runner_display_secs.to_string();
+        let Some(answer) =
+            prompt::ask_text(t("Runner display time in seconds (1 to 3600):"), &current)
+        else {
+            return;
+        };
+        match answer.trim().trim_end_matches('s').trim().parse::<u64>() {
+            Ok(secs) => self.set_runner_display_secs(secs),
+            _ => log::warn!("ignoring runner display time {:?}", answer),
+        }
+    }
+
+    /// Frame Speed of the runner now playing
+    pub fn set_runner_frame_override(&mut self, ms: u64) {
+        self.tray.set_runner_frame_ms(&mut self.config, ms);
+        self.config.save();
+    }
+
+    /// Animation Speed, for every runner without a Frame Speed of its own
+    pub fn set_runner_frame_ms(&mut self, ms: u64) {
+        self.config.runner_frame_ms = ms.clamp(40, 200);
+        self.tray.sync_runner_config(&self.config);
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_runner_idle_behavior(&mut self, behavior: RunnerIdleBehavior) {
+        self.config.runner_idle_behavior = behavior;
+        self.tray.sync_runner_config(&self.config);
+        self.config.save();
+    }
+
     pub fn toggle_runner_in_rotation(&mut self, runner_id: String) {
         if self
             .tray
@@ -75,31 +537,881 @@ impl App {
         self.config.save();
     }
 
+    /// Next/Previous Runner: switch now and restart the display timer.
+    pub fn step_runner(&mut self, forward: bool) {
+        self.tray.step_runner(forward);
+    }
+
+    pub fn toggle_runner_shuffle(&mut self) {
+        self.config.runner_rotation_shuffle = !self.config.runner_rotation_shuffle;
+        self.tray.sync_runner_config(&self.config);
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
     pub fn select_runner_category(&mut self, category: String) {
         self.tray.select_runner_category(&mut self.config, &category);
         self.config.save();
     }
 
+    pub fn select_only_runner_category(&mut self, category: String) {
+        self.tray
+            .select_only_runner_category(&mut self.config, &category);
+        self.config.save();
+    }
+
     pub fn import_custom_runner(&mut self) {
-        if self.tray.import_custom_runner_frames(&mut self.config) {
+        let imported = self.tray.import_custom_runner_frames(&mut self.config);
+        self.custom_runner_imported(imported);
+    }
+
+    pub fn import_custom_runner_folder(&mut self) {
+        let imported = self.tray.import_custom_runner_folder(&mut self.config);
+        self.custom_runner_imported(imported);
+    }
+
+    fn custom_runner_imported(&mut self, frame_count: Option<usize>) {
+        if let Some(count) = frame_count {
             self.config.save();
+            notify(
+                t("Custom Runner Imported"),
+                &tf("{} frames", &[&count.to_string()]),
+            );
+        }
+    }
+
+    /// Write config.json and the custom runner frames to a folder for another Mac.
+    pub fn export_settings(&self) {
+        let Some(dir) = FileDialog::new()
+            .set_title("Export Settings")
+            .set_file_name("Mac State Monitor Settings")
+            .save_file()
+        else {
+            return;
+        };
+        match settings_transfer::export(&self.config, &dir) {
+            Ok(()) => notify(t("Settings Exported"), &dir.display().to_string()),
+            Err(e) => {
+                log::error!("could not export settings to {}: {}", dir.display(), e);
+                notify(t("Export Failed"), &e.to_string());
+            }
+        }
+    }
+
+    /// Replace this Mac's settings with an export, or add its custom runners
+    /// and process watches to them.
+    pub fn import_settings(&mut self) {
+        let Some(dir) = FileDialog::new().set_title("Import Settings").pick_folder() else {
+            return;
+        };
+        let mut imported = match settings_transfer::read(&dir) {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!("could not import settings from {}: {}", dir.display(), e);
+                notify(t("Import Failed"), &e);
+                return;
+            }
+        };
+        let choice = MessageDialog::new()
+            .set_title("Import Settings")
+            .set_description(
+                "Replace all settings with the imported ones, or merge: keep these \
+                 settings and add the imported custom runners and process watches.",
+            )
+            .set_buttons(MessageButtons::YesNoCancelCustom(
+                "Replace".to_string(),
+                "Merge".to_string(),
+                "Cancel".to_string(),
+            ))
+            .show();
+        let replace = match choice {
+            MessageDialogResult::Yes => true,
+            MessageDialogResult::No => false,
+            MessageDialogResult::Custom(label) if label == "Replace" => true,
+            MessageDialogResult::Custom(label) if label == "Merge" => false,
+            _ => return,
+        };
+        if let Err(e) = settings_transfer::install_runners(&mut imported, &dir) {
+            log::error!("could not copy imported runner frames: {}", e);
+            notify(t("Import Failed"), &e.to_string());
+            return;
+        }
+
+        if replace {
+            // Login items belong to this Mac, not to the export
+            imported.launch_at_login = self.config.launch_at_login;
+            self.config = imported.with_active_profile();
+        } else {
+            self.config
+                .custom_runner_sets
+                .extend(imported.custom_runner_sets);
+            for watch in imported.process_watches {
+                if !self
+                    .config
+                    .process_watches
+                    .iter()
+                    .any(|w| w.name_pattern == watch.name_pattern)
+                {
+                    self.config.process_watches.push(watch);
+                }
+            }
+        }
+
+        self.apply_config();
+        notify(t("Settings Imported"), &dir.display().to_string());
+    }
+
+    /// Lay profile `name` over the base settings and apply the result.
+    /// Edits so far are saved first, to the base settings or to the profile
+    /// they were made in.
+    pub fn switch_profile(&mut self, name: &str) {
+        self.config.save();
+        let Some(config) = Config::load_base().with_profile(name) else {
+            return;
+        };
+        log::info!("switched to profile {:?}", name);
+        self.config = config;
+        self.apply_config();
+    }
+
+    /// "Save Current as Profile…": store the live settings under a new or
+    /// existing name and make it the active profile.
+    pub fn prompt_save_profile(&mut self) {
+        let current = self.config.active_profile.clone().unwrap_or_default();
+        let Some(name) = prompt::ask_text(t("Profile name:"), &current) else {
+            return;
+        };
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let snapshot = self.config.profile_snapshot();
+        self.config.save();
+        let mut base = Config::load_base();
+        base.profiles.insert(name.clone(), snapshot);
+        let Some(config) = base.with_profile(&name) else {
+            return;
+        };
+        self.config = config;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Push a wholesale config change (import, profile switch) out to the
+    /// tray, chart window, hotkey and strings, then save it.
+    fn apply_config(&mut self) {
+        self.set_poll_interval(self.config.poll_interval_secs);
+        self.tray.sync_runner_config(&self.config);
+        self.hotkey.sync(self.config.chart_hotkey);
+        self.hotkey.set_action(&self.config.chart_hotkey_action);
+        strings::set_language(self.config.language);
+        for window in std::iter::once(&mut self.chart_window).chain(self.pop_outs.values_mut()) {
+            configure_chart(window, &self.config);
+        }
+        // A window in the other style closes; it reopens in the new one
+        self.chart_window
+            .set_mini(self.config.chart_mini_mode, self.config.chart_mini_position);
+        self.sync_touch_bar();
+        self.tray.invalidate_cpu_menu();
+        self.tray.invalidate_temp_menu();
+        self.config.save();
+    }
+
+    /// Create or remove the Touch Bar to match `config.touch_bar`, and put
+    /// it on chart windows opened since
+    #[cfg(feature = "touch-bar")]
+    fn sync_touch_bar(&mut self) {
+        let windows: Vec<_> = std::iter::once(&self.chart_window)
+            .chain(self.pop_outs.values())
+            .filter_map(ChartWindow::ns_window)
+            .collect();
+        match (&self.touch_bar, self.config.touch_bar) {
+            (None, true) => {
+                self.touch_bar = TouchBar::new();
+                match &self.touch_bar {
+                    Some(touch_bar) => touch_bar.attach(&windows),
+                    None => log::warn!("could not create the Touch Bar"),
+                }
+            }
+            (Some(touch_bar), true) => touch_bar.attach(&windows),
+            (Some(_), false) => {
+                touch_bar::detach(&windows);
+                self.touch_bar = None;
+            }
+            (None, false) => {}
+        }
+    }
+
+    #[cfg(not(feature = "touch-bar"))]
+    fn sync_touch_bar(&mut self) {}
+
+    /// Put the latest stats on the pasteboard as plain text or JSON.
+    pub fn copy_stats(&self, as_json: bool) {
+        let text = if as_json {
+            report::json(&self.latest)
+        } else {
+            let trends = tray::module_trends(&self.history, &self.config);
+            report::plain_text(&self.latest, &trends)
+        };
+        pasteboard::copy_text(&text);
+    }
+
+    /// "Export History…": the last hour, a row per minute, as CSV, or as
+    /// JSON when the file name ends in .json.
+    pub fn export_history(&self) {
+        let Some(path) = FileDialog::new()
+            .set_title(t("Export History"))
+            .set_file_name("Mac State Monitor History.csv")
+            .add_filter("CSV", &["csv"])
+            .add_filter("JSON", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        let as_json = path.extension().is_some_and(|ext| ext == "json");
+        let text = if as_json {
+            report::history_json(&self.history.rollup).map_err(|e| e.to_string())
+        } else {
+            Ok(report::history_csv(&self.history.rollup))
+        };
+        let result = text.and_then(|text| fs::write(&path, text).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => notify(t("History Exported"), &path.display().to_string()),
+            Err(e) => {
+                log::error!("could not export history to {}: {}", path.display(), e);
+                notify(t("Export Failed"), &e);
+            }
+        }
+    }
+
+    /// Replace `stats_output_path` with the latest stats, at most once a
+    /// second; readers never see a half-written file.
+    fn write_stats_file(&mut self, stats: &SystemStats) {
+        // Scripts reading the file would take a replay for this Mac
+        if self.replay_interval.is_some() {
+            return;
+        }
+        let Some(path) = &self.config.stats_output_path else {
+            return;
+        };
+        let now = Instant::now();
+        if self
+            .stats_file_written
+            .is_some_and(|last| now.duration_since(last) < STATS_FILE_INTERVAL)
+        {
+            return;
+        }
+        let json = match report::stats_file_json(stats) {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("could not serialize stats: {}", e);
+                return;
+            }
+        };
+        self.stats_file_written = Some(now);
+        let tmp = path.with_extension("json.tmp");
+        if let Err(e) = fs::write(&tmp, json).and_then(|_| fs::rename(&tmp, path)) {
+            log::warn!("could not write {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn send_test_alert(&self) {
+        self.alert.send_test(&self.config);
+    }
+
+    /// Volumes that mounted or unmounted since the last polls; the DISK
+    /// menu rebuilds on its own when the mounts change.
+    fn check_volumes(&mut self, stats: &SystemStats) {
+        let changes = self.volumes.update(&stats.disk_mounts);
+        if !self.config.notify_volume_changes {
+            return;
+        }
+        for change in changes {
+            match change {
+                VolumeChange::Mounted(mount_point) => {
+                    let name = volumes::mount_name(&mount_point);
+                    let body = match stats.disks.iter().find(|d| d.mount_point == mount_point) {
+                        Some(disk) => {
+                            tf("{} — {} free", &[name, &format_bytes(disk.available_bytes)])
+                        }
+                        None => name.to_string(),
+                    };
+                    notify(t("Volume Mounted"), &body);
+                }
+                VolumeChange::Unmounted(mount_point) => {
+                    notify(t("Volume Unmounted"), volumes::mount_name(&mount_point));
+                }
+            }
+        }
+    }
+
+    /// Count the poll toward today's totals and send the summary once the
+    /// configured hour passes while the app runs
+    fn check_daily_summary(&mut self, stats: &SystemStats) {
+        let (day, hour) = daily_traffic::local_day_hour();
+        self.daily_load.add(stats, day);
+        let previous = self.summary_clock.replace((day, hour));
+        let Some(summary_hour) = self.config.daily_summary else {
+            return;
+        };
+        if !summary::crossed(previous, (day, hour), summary_hour)
+            || self.config.daily_summary_sent_day == Some(day)
+        {
+            return;
+        }
+        notify(t("Today's Summary"), &self.daily_load.body(stats));
+        self.config.daily_summary_sent_day = Some(day);
+        self.config.save();
+    }
+
+    pub fn toggle_volume_notifications(&mut self) {
+        self.config.notify_volume_changes = !self.config.notify_volume_changes;
+        self.tray.invalidate_disk_menu();
+        self.config.save();
+    }
+
+    /// Pause Alerts on the Touch Bar: nothing fires until it is pressed
+    /// again or the app restarts
+    pub fn toggle_alert_pause(&mut self) {
+        let paused = self.alert.toggle_paused();
+        log::info!("alerts {}", if paused { "paused" } else { "resumed" });
+        #[cfg(feature = "touch-bar")]
+        if let Some(touch_bar) = &mut self.touch_bar {
+            touch_bar.update(&self.latest, &self.config, paused);
+        }
+    }
+
+    pub fn toggle_alert_recovery(&mut self) {
+        self.config.alert_recovery_notifications = !self.config.alert_recovery_notifications;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Picking a sound plays it once as a preview.
+    pub fn set_alert_sound(&mut self, sound: Option<String>) {
+        if let Some(sound) = &sound {
+            sound::play(sound);
+        }
+        self.config.alert_sound = sound;
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Alert Thresholds: replace the warning and critical levels with a preset
+    pub fn set_alert_preset(&mut self, preset: AlertPreset) {
+        self.config.alert_thresholds = preset.thresholds();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Profile App (30 s): `sample` output plus internal counters.
+    pub fn profile_app(&self) {
+        if !self.config.diagnostics_enabled {
+            return;
+        }
+        let mut lines =
+            diagnostics::instrumentation(&self.tick_stats, &self.monitor.sampler_status());
+        lines.push(String::new());
+        lines.extend(diagnostics::effective_settings(
+            &self.config,
+            &self.tray.runner_settings(),
+        ));
+        diagnostics::start_profile(lines);
+    }
+
+    /// Reveal the log folder in Finder so logs can be attached to bug reports.
+    pub fn open_log_folder(&self) {
+        open_folder(&logging::log_dir(), false);
+    }
+
+    pub fn clear_issues(&mut self) {
+        issues::clear();
+        self.tray.set_issues(Vec::new());
+    }
+
+    pub fn open_config_folder(&self) {
+        open_folder(&config_dir(), false);
+    }
+
+    pub fn reveal_custom_frames(&self) {
+        open_folder(&custom_frames_root_dir(), true);
+    }
+
+    /// The NET menu picks up the zeroed totals on the next poll.
+    pub fn reset_session_counters(&mut self) {
+        self.monitor.reset_session_counters();
+    }
+
+    /// Also zeroes the saved total, so it stays reset across a restart.
+    pub fn reset_daily_traffic(&mut self) {
+        self.monitor.reset_daily_traffic();
+    }
+
+    pub fn set_net_display(&mut self, display: NetDisplay) {
+        self.config.net_display = display;
+        self.tray.invalidate_net_menu();
+        self.config.save();
+    }
+
+    /// Flip a volume between shown and hidden with the smallest pattern
+    /// change: drop an exact entry that decides it, else add one.
+    pub fn toggle_disk_mount(&mut self, mount_point: &str) {
+        let config = &mut self.config;
+        let shown = |config: &Config| {
+            disk::is_shown(
+                mount_point,
+                &config.disk_include_mounts,
+                &config.disk_exclude_mounts,
+            )
+        };
+        if shown(config) {
+            config.disk_include_mounts.retain(|p| p != mount_point);
+            if shown(config) {
+                config.disk_exclude_mounts.push(mount_point.to_string());
+            }
+        } else {
+            config.disk_exclude_mounts.retain(|p| p != mount_point);
+            if !shown(config) {
+                config.disk_include_mounts.push(mount_point.to_string());
+            }
+        }
+        self.tray.invalidate_disk_menu();
+        self.config.save();
+    }
+
+    /// Eject a removable volume on a background thread; diskutil can take a
+    /// while, or fail when files on it are still open.
+    pub fn eject_disk(&self, mount_point: String) {
+        let spawned = thread::Builder::new()
+            .name("eject".to_string())
+            .spawn(move || {
+                let error = match Command::new("diskutil")
+                    .arg("eject")
+                    .arg(&mount_point)
+                    .output()
+                {
+                    Ok(output) if output.status.success() => return,
+                    Ok(output) => {
+                        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                        if stderr.is_empty() {
+                            String::from_utf8_lossy(&output.stdout).trim().to_string()
+                        } else {
+                            stderr
+                        }
+                    }
+                    Err(e) => e.to_string(),
+                };
+                log::warn!("could not eject {}: {}", mount_point, error);
+                // The log keeps diskutil's full explanation; its first line
+                // says what failed
+                let summary = error.lines().map(str::trim).find(|line| !line.is_empty());
+                notify(t("Eject Failed"), summary.unwrap_or(&mount_point));
+            });
+        if let Err(e) = spawned {
+            log::error!("could not start eject thread: {}", e);
         }
     }
 
     pub fn toggle_launch_at_login(&mut self) {
         self.config.launch_at_login = !self.config.launch_at_login;
-        launch_agent::set_enabled(self.config.launch_at_login);
+        if let Err(e) = launch_agent::set_enabled(self.config.launch_at_login) {
+            log::error!("{}", e);
+        }
         self.config.save();
     }
 
+    /// Uninstall…: confirm, remove the login item and, if asked, the config
+    /// folder. True when the app should quit.
+    pub fn uninstall(&mut self) -> bool {
+        let choice = MessageDialog::new()
+            .set_title(t("Uninstall Mac State Monitor"))
+            .set_description(t(
+                "Remove the login item and quit? Afterwards, move the app to the Trash.",
+            ))
+            .set_buttons(MessageButtons::OkCancel)
+            .show();
+        if !matches!(choice, MessageDialogResult::Ok) {
+            return false;
+        }
+        let choice = MessageDialog::new()
+            .set_title(t("Uninstall Mac State Monitor"))
+            .set_description(tf(
+                "Also delete settings, logs and custom runner frames in {}?",
+                &[&config_dir().display().to_string()],
+            ))
+            .set_buttons(MessageButtons::YesNo)
+            .show();
+        let delete_data = matches!(choice, MessageDialogResult::Yes);
+
+        let outcome = uninstall::run(delete_data);
+        for path in &outcome.removed {
+            log::info!("uninstall removed {}", path.display());
+        }
+        if !outcome.failed.is_empty() {
+            for error in &outcome.failed {
+                log::error!("uninstall: {}", error);
+            }
+            MessageDialog::new()
+                .set_level(MessageLevel::Error)
+                .set_title(t("Uninstall Incomplete"))
+                .set_description(outcome.failed.join("\n"))
+                .show();
+        }
+        // Saving now would bring back the config folder we may have deleted
+        self.release();
+        true
+    }
+
     pub fn toggle_charts(&mut self, event_loop: &EventLoopWindowTarget<()>, mode: ChartMode) {
+        if mode == ChartMode::Cores {
+            self.history.track_cores = true;
+        }
         self.chart_window.toggle(event_loop, mode);
         if self.chart_window.is_visible() {
+            self.sync_touch_bar();
+            self.chart_window.render(&self.history);
+        }
+    }
+
+    pub fn save_chart_image(&mut self) {
+        let Some(path) = FileDialog::new()
+            .set_title("Save Chart as Image")
+            .set_file_name("mac-state-monitor-charts.png")
+            .add_filter("PNG", &["png"])
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = self.chart_window.export_png(&self.history, &path) {
+            log::error!("could not save chart to {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn toggle_chart_auto_zoom(&mut self) {
+        self.config.chart_auto_zoom = !self.config.chart_auto_zoom;
+        let enabled = self.config.chart_auto_zoom;
+        for window in self.chart_windows_mut() {
+            window.set_auto_zoom(enabled);
+        }
+        self.render_charts();
+        self.config.save();
+    }
+
+    pub fn toggle_chart_pageouts(&mut self) {
+        self.config.chart_show_pageouts = !self.config.chart_show_pageouts;
+        let enabled = self.config.chart_show_pageouts;
+        for window in self.chart_windows_mut() {
+            window.set_show_pageouts(enabled);
+        }
+        self.render_charts();
+        self.config.save();
+    }
+
+    pub fn toggle_chart_stats(&mut self) {
+        self.config.chart_show_stats = !self.config.chart_show_stats;
+        let enabled = self.config.chart_show_stats;
+        for window in self.chart_windows_mut() {
+            window.set_show_stats(enabled);
+        }
+        self.render_charts();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn toggle_chart_temp_range(&mut self) {
+        self.config.chart_temp_fixed_range = !self.config.chart_temp_fixed_range;
+        let fixed = self.config.chart_temp_fixed_range;
+        for window in self.chart_windows_mut() {
+            window.set_temp_fixed_range(fixed);
+        }
+        self.render_charts();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_chart_smoothing(&mut self, smoothing: ChartSmoothing) {
+        self.config.chart_smoothing = smoothing;
+        for window in self.chart_windows_mut() {
+            window.set_smoothing(smoothing.window());
+        }
+        self.render_charts();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_net_chart_style(&mut self, style: NetChartStyle) {
+        self.config.net_chart_style = style;
+        for window in self.chart_windows_mut() {
+            window.set_net_style(style);
+        }
+        self.render_charts();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    pub fn set_chart_theme(&mut self, theme: ChartThemeChoice) {
+        self.config.chart_theme = theme;
+        for window in self.chart_windows_mut() {
+            window.set_theme(theme);
+        }
+        self.render_charts();
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Open a window with just one chart, or bring it to the front. It
+    /// opens where it was last left.
+    pub fn pop_out_chart(&mut self, event_loop: &EventLoopWindowTarget<()>, mode: ChartMode) {
+        let config = &self.config;
+        let window = self.pop_outs.entry(mode).or_insert_with(|| {
+            let frame = config.chart_pop_out_frames.get(mode.id()).copied();
+            let mut window = ChartWindow::pop_out(mode, frame);
+            configure_chart(&mut window, config);
+            window
+        });
+        window.show(event_loop);
+        window.render(&self.history);
+        self.sync_touch_bar();
+    }
+
+    /// Remember where a pop-out was moved or resized to; saved on the next
+    /// tick since a drag reports many moves.
+    pub fn pop_out_moved(&mut self, window_id: WindowId) {
+        let Some((mode, window)) = self
+            .pop_outs
+            .iter_mut()
+            .find(|(_, window)| window.window_id() == Some(window_id))
+        else {
+            return;
+        };
+        if let Some(frame) = window.frame() {
+            self.config
+                .chart_pop_out_frames
+                .insert(mode.id().to_string(), frame);
+            self.chart_frame_moved = true;
+        }
+    }
+
+    /// The chart window, full or popped out, that owns `window_id`, along
+    /// with the history to draw
+    pub fn chart_window_for(
+        &mut self,
+        window_id: WindowId,
+    ) -> Option<(&mut ChartWindow, &HistoryBuffer)> {
+        let window = std::iter::once(&mut self.chart_window)
+            .chain(self.pop_outs.values_mut())
+            .find(|window| window.window_id() == Some(window_id))?;
+        Some((window, &self.history))
+    }
+
+    fn chart_windows_mut(&mut self) -> impl Iterator<Item = &mut ChartWindow> {
+        std::iter::once(&mut self.chart_window).chain(self.pop_outs.values_mut())
+    }
+
+    /// Redraw every chart window; hidden ones return right away.
+    fn render_charts(&mut self) {
+        self.chart_window.render(&self.history);
+        for window in self.pop_outs.values_mut() {
+            window.render(&self.history);
+        }
+    }
+
+    /// Time the visible chart windows took for their latest renders, together
+    fn chart_render_time(&self) -> Option<Duration> {
+        std::iter::once(&self.chart_window)
+            .chain(self.pop_outs.values())
+            .filter(|window| window.is_visible())
+            .filter_map(ChartWindow::last_render)
+            .reduce(|total, render| total + render)
+    }
+
+    /// Turning mini mode on always shows the window; turning it off reopens
+    /// the normal window only if the mini one was showing.
+    pub fn toggle_chart_mini_mode(&mut self, event_loop: &EventLoopWindowTarget<()>) {
+        self.config.chart_mini_mode = !self.config.chart_mini_mode;
+        let reopen = self
+            .chart_window
+            .set_mini(self.config.chart_mini_mode, self.config.chart_mini_position);
+        if reopen || self.config.chart_mini_mode {
+            self.chart_window.show(event_loop);
             self.chart_window.render(&self.history);
         }
+        self.tray.invalidate_cpu_menu();
+        self.config.save();
+    }
+
+    /// Remember where the mini window was dragged; saved on the next tick
+    /// since a drag reports many moves.
+    pub fn chart_window_moved(&mut self, position: PhysicalPosition<i32>) {
+        if let Some(position) = self.chart_window.moved(position) {
+            self.config.chart_mini_position = Some(position);
+            self.chart_frame_moved = true;
+        }
+    }
+
+    pub fn toggle_chart_pause(&mut self) {
+        self.chart_window.toggle_pause(&self.history);
+        self.chart_window.render(&self.history);
     }
 
     pub fn animate(&mut self, now: Instant) {
-        self.tray.animate(now);
+        // Titles colored for the old appearance are redrawn right away
+        // rather than at the next poll
+        if appearance::take_changed() {
+            self.tray.update(
+                &self.latest,
+                &self.history,
+                &self.config,
+                &self.alert.active_kinds(),
+            );
+            self.render_charts();
+        }
+        let interacting = self.interacting(now);
+        self.tray.animate(now, interacting);
+    }
+
+    /// A status item menu is open, or a chart window is pressed or being
+    /// dragged or resized
+    fn interacting(&self, now: Instant) -> bool {
+        tray::any_menu_open()
+            || self.chart_pressed
+            || self
+                .chart_moved_at
+                .is_some_and(|moved| now.duration_since(moved) < CHART_DRAG_HOLD)
+    }
+
+    /// Left button down (true) or up in `window_id`, if it is a chart window
+    pub fn chart_pressed(&mut self, window_id: WindowId, pressed: bool) {
+        if self.chart_window_for(window_id).is_some() {
+            self.chart_pressed = pressed;
+        }
+    }
+
+    /// A chart window moved or resized
+    pub fn chart_touched(&mut self) {
+        self.chart_moved_at = Some(Instant::now());
+    }
+}
+
+/// Settings every chart window shares; mini mode is the full window's own
+fn configure_chart(window: &mut ChartWindow, config: &Config) {
+    window.set_auto_zoom(config.chart_auto_zoom);
+    window.set_show_pageouts(config.chart_show_pageouts);
+    window.set_show_stats(config.chart_show_stats);
+    window.set_ignore_reduce_motion(config.ignore_reduce_motion);
+    window.set_temp_fixed_range(config.chart_temp_fixed_range);
+    window.set_smoothing(config.chart_smoothing.window());
+    window.set_net_style(config.net_chart_style);
+    window.set_theme(config.chart_theme);
+    window.set_latency(config.ping_target.is_some(), config.latency_warn_ms);
+    window.set_pinned_sensors(&config.pinned_sensors);
+}
+
+/// The part of a poll that doesn't touch AppKit: sample `source`, add the
+/// sample to the history and check alerts on it. `started` is when the
+/// poll began, for its timing.
+fn record_poll(
+    source: &mut dyn StatsSource,
+    config: &Config,
+    history: &mut HistoryBuffer,
+    alert: &mut AlertManager,
+    started: Instant,
+) -> SystemStats {
+    let mut stats = source.poll();
+    stats.temperature.pin(&config.pinned_sensors);
+    stats.app_usage.poll_ms = as_millis(started.elapsed());
+    history.push(&stats);
+    // Alerts before the tray update, so it highlights the modules they cover
+    alert.check(&stats, history, config);
+    stats
+}
+
+fn as_millis(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Open `dir` in Finder, creating it first; `reveal` selects it in its
+/// parent window instead.
+fn open_folder(dir: &Path, reveal: bool) {
+    if let Err(e) = fs::create_dir_all(dir) {
+        log::error!("could not create {}: {}", dir.display(), e);
+    }
+    let mut open = Command::new("open");
+    if reveal {
+        open.arg("-R");
+    }
+    if let Err(e) = open.arg(dir).spawn() {
+        log::error!("could not open {}: {}", dir.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TemperatureReading;
+    use crate::monitor::mock::{sample, MockStatsSource};
+
+    #[test]
+    fn polls_flow_into_the_history_and_alerts() {
+        alert::suppress_notifications();
+        let config = Config::default();
+        let mut source = MockStatsSource::new([
+            sample(30.0, 40.0, 50.0),
+            sample(97.0, 40.0, 50.0),
+            sample(35.0, 92.0, 50.0),
+        ]);
+        let mut history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+        let mut poll = || {
+            record_poll(
+                &mut source,
+                &config,
+                &mut history,
+                &mut alerts,
+                Instant::now(),
+            )
+        };
+
+        assert_eq!(poll().cpu.global_usage, 30.0);
+        poll();
+        let stats = poll();
+        assert_eq!(stats.memory.usage_percent, 92.0);
+
+        // CPU fired on the second poll and needs more calm polls to recover;
+        // memory reached its warning level on the third
+        let kinds = alerts.active_kinds();
+        assert!(kinds.contains("cpu") && kinds.contains("memory"));
+        assert!(!kinds.contains("temperature"));
+        assert_eq!(
+            history.cpu_usage.iter().copied().collect::<Vec<_>>(),
+            [30.0, 97.0, 35.0]
+        );
+        assert_eq!(history.temps["CPU"].len(), 3);
+        assert_eq!(source.polls, 3);
+        assert!(source.finished());
+    }
+
+    #[test]
+    fn pinned_sensors_reach_the_history() {
+        let mut config = Config::default();
+        config.pinned_sensors = vec!["SSD".to_string()];
+        let mut stats = sample(10.0, 10.0, 40.0);
+        stats.temperature.raw.push(TemperatureReading {
+            label: "SSD".to_string(),
+            temp_c: 38.0,
+        });
+        let mut source = MockStatsSource::new([stats]);
+        let mut history = HistoryBuffer::new(60);
+        let mut alerts = AlertManager::new();
+
+        let stats = record_poll(
+            &mut source,
+            &config,
+            &mut history,
+            &mut alerts,
+            Instant::now(),
+        );
+        assert_eq!(stats.temperature.readings.len(), 2);
+        assert_eq!(history.temps["SSD"][0], 38.0);
     }
 }
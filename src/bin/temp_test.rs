@@ -1,3 +1,6 @@
+#[path = "../monitor/smc.rs"]
+mod smc;
+
 use sysinfo::Components;
 
 fn main() {
@@ -8,4 +11,30 @@ fn main() {
     if components.iter().count() == 0 {
         println!("No temperature sensors found via sysinfo");
     }
+
+    // Every temperature key (T*) the SMC exposes, for bug reports
+    let Some(smc) = smc::Smc::open() else {
+        println!("Could not open the SMC");
+        return;
+    };
+    let keys: Vec<smc::SmcKey> = smc
+        .key_names()
+        .into_iter()
+        .filter(|name| name.starts_with('T'))
+        .filter_map(|name| smc.key(&name))
+        .collect();
+    for key in &keys {
+        let bytes = smc.read_bytes(key).unwrap_or_default();
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        println!(
+            "smc: {:?}, type: {:?}, value: {:?}, bytes: {}",
+            key.name,
+            key.data_type,
+            smc.read_value(key),
+            hex.join(" ")
+        );
+    }
+    if keys.is_empty() {
+        println!("No temperature keys found via the SMC");
+    }
 }
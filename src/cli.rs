@@ -0,0 +1,77 @@
+use crate::config::{Config, MAX_POLL_INTERVAL_SECS, MIN_POLL_INTERVAL_SECS};
+use crate::model::Trends;
+use crate::monitor::{StatsSource, SystemMonitor};
+use crate::report;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+/// CPU usage is a delta between two refreshes, so the first poll waits
+const CPU_SETTLE: Duration = Duration::from_secs(1);
+
+enum Output {
+    Text,
+    Json,
+    /// JSON lines every so many seconds; None follows `poll_interval_secs`
+    Watch(Option<f64>),
+}
+
+/// `--once` (text), `--json` or `--watch [secs]`: print stats to stdout
+/// without AppKit. None when no CLI flag was given, else the exit code.
+pub fn run(args: &[String]) -> Option<i32> {
+    let output = if let Some(i) = args.iter().position(|arg| arg == "--watch") {
+        let secs = args.get(i + 1).and_then(|arg| arg.parse::<f64>().ok());
+        Output::Watch(secs.filter(|secs| secs.is_finite()))
+    } else if args.iter().any(|arg| arg == "--json") {
+        Output::Json
+    } else if args.iter().any(|arg| arg == "--once") {
+        Output::Text
+    } else {
+        return None;
+    };
+
+    let config = Config::load();
+    let mut monitor = SystemMonitor::headless();
+    monitor.set_process_collection(!config.process_watches.is_empty());
+    monitor.set_disk_filter(&config.disk_include_mounts, &config.disk_exclude_mounts);
+    thread::sleep(CPU_SETTLE);
+
+    let mut stdout = io::stdout().lock();
+    let written = match output {
+        // A single poll has no history to trend over
+        Output::Text => writeln!(
+            stdout,
+            "{}",
+            report::plain_text(&monitor.poll(), &Trends::default())
+        ),
+        Output::Json => writeln!(stdout, "{}", report::json(&monitor.poll())),
+        Output::Watch(secs) => {
+            let interval = match secs {
+                Some(secs) => Duration::from_secs_f64(
+                    secs.clamp(MIN_POLL_INTERVAL_SECS, MAX_POLL_INTERVAL_SECS),
+                ),
+                None => config.poll_interval(),
+            };
+            watch(&mut monitor, interval, &mut stdout)
+        }
+    };
+    // A closed pipe (`| head`) ends the output; anything else is an error
+    match written {
+        Ok(()) => Some(0),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Some(0),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            Some(1)
+        }
+    }
+}
+
+/// One JSON line per poll until Ctrl-C or the reader goes away; the
+/// output is a recording `--replay` can play back
+fn watch(monitor: &mut SystemMonitor, interval: Duration, out: &mut impl Write) -> io::Result<()> {
+    loop {
+        writeln!(out, "{}", report::json_line(&monitor.poll()))?;
+        out.flush()?;
+        thread::sleep(interval);
+    }
+}
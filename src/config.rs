@@ -1,16 +1,49 @@
+use crate::issues;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-pub const LAUNCH_AT_LOGIN_ID: &str = "launch_at_login";
+/// `menubar_temp_component` value that shows the hottest sensor
+pub const HOTTEST_TEMP_COMPONENT: &str = "*";
+/// `visible_modules` id of the item showing the metric closest to alert
+pub const DIGEST_MODULE: &str = "digest";
+/// Upgrades from each config version to the next, oldest first: entry `n`
+/// turns a version `n` config into version `n + 1`
+const MIGRATIONS: [fn(&mut Map<String, Value>); 1] = [migrate_v0_to_v1];
+/// Version `Config::default()` and migrated configs are written as
+pub const CONFIG_VERSION: u32 = MIGRATIONS.len() as u32;
+/// Raw sensors that can be pinned as readings of their own at once
+pub const MAX_PINNED_SENSORS: usize = 3;
+/// Bounds for `poll_interval_secs`; sysinfo needs ~200 ms between CPU samples
+pub const MIN_POLL_INTERVAL_SECS: f64 = 0.5;
+pub const MAX_POLL_INTERVAL_SECS: f64 = 600.0;
+/// Settings "Save Current as Profile…" captures. Hand-written profiles may
+/// overlay any other top-level field as well.
+const PROFILE_FIELDS: [&str; 13] = [
+    "poll_interval_secs",
+    "auto_hide",
+    "visible_modules",
+    "alert_thresholds",
+    "runner_id",
+    "runner_rotation_ids",
+    "runner_rotation_shuffle",
+    "runner_display_secs",
+    "runner_frame_ms",
+    "runner_idle_behavior",
+    "runner_idle_threshold",
+    "process_watches",
+    "alert_recovery_notifications",
+];
 
 pub fn config_dir() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_default();
     PathBuf::from(home).join("Library/Application Support/mac-state-monitor")
 }
 
-fn config_path() -> PathBuf {
+pub fn config_path() -> PathBuf {
     config_dir().join("config.json")
 }
 
@@ -26,6 +59,14 @@ fn default_runner_display_secs() -> u64 {
     600
 }
 
+fn default_runner_idle_threshold() -> f32 {
+    5.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
 fn default_runner_icon_mode() -> RunnerIconMode {
     RunnerIconMode::White
 }
@@ -37,11 +78,719 @@ pub enum RunnerIconMode {
     White,
 }
 
+/// What the runner does while the CPU is idle
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnerIdleBehavior {
+    /// Keep animating at the slowest speed
+    #[default]
+    Animate,
+    /// Hold the set's sleep frame (or its first frame)
+    Sleep,
+}
+
+impl RunnerIdleBehavior {
+    pub fn id(self) -> &'static str {
+        match self {
+            RunnerIdleBehavior::Animate => "animate",
+            RunnerIdleBehavior::Sleep => "sleep",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "animate" => Some(RunnerIdleBehavior::Animate),
+            "sleep" => Some(RunnerIdleBehavior::Sleep),
+            _ => None,
+        }
+    }
+}
+
+/// How the CPU status item shows its value
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuStatusStyle {
+    #[default]
+    Text,
+    Sparkline,
+    Both,
+}
+
+impl CpuStatusStyle {
+    pub fn id(self) -> &'static str {
+        match self {
+            CpuStatusStyle::Text => "text",
+            CpuStatusStyle::Sparkline => "sparkline",
+            CpuStatusStyle::Both => "both",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "text" => Some(CpuStatusStyle::Text),
+            "sparkline" => Some(CpuStatusStyle::Sparkline),
+            "both" => Some(CpuStatusStyle::Both),
+            _ => None,
+        }
+    }
+}
+
+/// What to slow down while running on battery power
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LowPowerBehavior {
+    #[default]
+    Off,
+    /// Animate the runner and status items less often
+    Animation,
+    /// Reduce animation and poll less often as well
+    Polling,
+}
+
+impl LowPowerBehavior {
+    pub fn id(self) -> &'static str {
+        match self {
+            LowPowerBehavior::Off => "off",
+            LowPowerBehavior::Animation => "animation",
+            LowPowerBehavior::Polling => "polling",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "off" => Some(LowPowerBehavior::Off),
+            "animation" => Some(LowPowerBehavior::Animation),
+            "polling" => Some(LowPowerBehavior::Polling),
+            _ => None,
+        }
+    }
+}
+
+/// What happens to a dropdown's readings while it is open
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenMenuUpdates {
+    /// Keep updating every poll
+    Live,
+    /// Freeze until the next poll after the menu closes
+    Paused,
+    /// Freeze, then catch up as soon as the menu closes
+    #[default]
+    Deferred,
+}
+
+impl OpenMenuUpdates {
+    pub fn id(self) -> &'static str {
+        match self {
+            OpenMenuUpdates::Live => "live",
+            OpenMenuUpdates::Paused => "paused",
+            OpenMenuUpdates::Deferred => "deferred",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "live" => Some(OpenMenuUpdates::Live),
+            "paused" => Some(OpenMenuUpdates::Paused),
+            "deferred" => Some(OpenMenuUpdates::Deferred),
+            _ => None,
+        }
+    }
+}
+
+/// Moving average drawn over the CPU, percent and rate charts
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartSmoothing {
+    #[default]
+    Off,
+    Avg5,
+    Avg15,
+}
+
+impl ChartSmoothing {
+    pub fn id(self) -> &'static str {
+        match self {
+            ChartSmoothing::Off => "off",
+            ChartSmoothing::Avg5 => "avg5",
+            ChartSmoothing::Avg15 => "avg15",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "off" => Some(ChartSmoothing::Off),
+            "avg5" => Some(ChartSmoothing::Avg5),
+            "avg15" => Some(ChartSmoothing::Avg15),
+            _ => None,
+        }
+    }
+
+    /// Samples averaged per point; 1 draws the raw series
+    pub fn window(self) -> usize {
+        match self {
+            ChartSmoothing::Off => 1,
+            ChartSmoothing::Avg5 => 5,
+            ChartSmoothing::Avg15 => 15,
+        }
+    }
+}
+
+/// Position and size of a window, in points
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct WindowFrame {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// What line two of the TEMP status item says
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TempLabelStyle {
+    /// Always "TEMP"
+    Static,
+    /// The displayed sensor, e.g. "GPU"
+    #[default]
+    SensorName,
+    /// Show the hottest reading and name it, whatever
+    /// `menubar_temp_component` says
+    Hottest,
+}
+
+impl TempLabelStyle {
+    pub fn id(self) -> &'static str {
+        match self {
+            TempLabelStyle::Static => "static",
+            TempLabelStyle::SensorName => "sensor_name",
+            TempLabelStyle::Hottest => "hottest",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "static" => Some(TempLabelStyle::Static),
+            "sensor_name" => Some(TempLabelStyle::SensorName),
+            "hottest" => Some(TempLabelStyle::Hottest),
+            _ => None,
+        }
+    }
+}
+
+/// What the NET status item shows
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NetDisplay {
+    /// Current download and upload rates
+    #[default]
+    Speed,
+    /// Traffic since local midnight, for metered connections
+    DailyTotal,
+}
+
+impl NetDisplay {
+    pub fn id(self) -> &'static str {
+        match self {
+            NetDisplay::Speed => "speed",
+            NetDisplay::DailyTotal => "daily_total",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "speed" => Some(NetDisplay::Speed),
+            "daily_total" => Some(NetDisplay::DailyTotal),
+            _ => None,
+        }
+    }
+}
+
+/// How the chart window lays out download and upload
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NetChartStyle {
+    /// One pane per direction
+    #[default]
+    Separate,
+    /// One pane, upload drawn below zero
+    Mirrored,
+    /// One pane, both directions drawn from zero
+    Overlaid,
+}
+
+impl NetChartStyle {
+    pub fn id(self) -> &'static str {
+        match self {
+            NetChartStyle::Separate => "separate",
+            NetChartStyle::Mirrored => "mirrored",
+            NetChartStyle::Overlaid => "overlaid",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "separate" => Some(NetChartStyle::Separate),
+            "mirrored" => Some(NetChartStyle::Mirrored),
+            "overlaid" => Some(NetChartStyle::Overlaid),
+            _ => None,
+        }
+    }
+}
+
+/// Colors of the chart windows
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartThemeChoice {
+    /// Dark or Light, following the system appearance
+    Auto,
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ChartThemeChoice {
+    pub fn id(self) -> &'static str {
+        match self {
+            ChartThemeChoice::Auto => "auto",
+            ChartThemeChoice::Dark => "dark",
+            ChartThemeChoice::Light => "light",
+            ChartThemeChoice::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "auto" => Some(ChartThemeChoice::Auto),
+            "dark" => Some(ChartThemeChoice::Dark),
+            "light" => Some(ChartThemeChoice::Light),
+            "high_contrast" => Some(ChartThemeChoice::HighContrast),
+            _ => None,
+        }
+    }
+}
+
+/// Language of menus, notification titles and chart captions
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    /// First supported entry of the macOS preferred languages
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "zh-Hans")]
+    ChineseSimplified,
+    #[serde(rename = "ja")]
+    Japanese,
+}
+
+impl Language {
+    pub fn id(self) -> &'static str {
+        match self {
+            Language::Auto => "auto",
+            Language::English => "en",
+            Language::ChineseSimplified => "zh-Hans",
+            Language::Japanese => "ja",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "auto" => Some(Language::Auto),
+            "en" => Some(Language::English),
+            "zh-Hans" => Some(Language::ChineseSimplified),
+            "ja" => Some(Language::Japanese),
+            _ => None,
+        }
+    }
+}
+
+/// A status bar module
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Module {
+    Cpu,
+    Mem,
+    Disk,
+    Net,
+    Temp,
+}
+
+impl Module {
+    /// Default left-to-right order in the menu bar
+    pub const ALL: [Module; 5] = [
+        Module::Cpu,
+        Module::Mem,
+        Module::Disk,
+        Module::Net,
+        Module::Temp,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Module::Cpu => "cpu",
+            Module::Mem => "mem",
+            Module::Disk => "disk",
+            Module::Net => "net",
+            Module::Temp => "temp",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "cpu" => Some(Module::Cpu),
+            "mem" => Some(Module::Mem),
+            "disk" => Some(Module::Disk),
+            "net" => Some(Module::Net),
+            "temp" => Some(Module::Temp),
+            _ => None,
+        }
+    }
+}
+
+fn default_module_order() -> Vec<String> {
+    Module::ALL.iter().map(|m| m.id().to_string()).collect()
+}
+
+fn default_net_idle_kbps() -> u64 {
+    50
+}
+
+fn default_temp_cool_c() -> f32 {
+    60.0
+}
+
+fn default_auto_hide_polls() -> u32 {
+    5
+}
+
+/// Alert when processes whose name contains `name_pattern` (case-insensitive)
+/// together exceed a CPU or memory budget
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProcessWatch {
+    pub name_pattern: String,
+    /// Summed CPU percent (100 = one full core)
+    #[serde(default)]
+    pub cpu_threshold: Option<f32>,
+    #[serde(default)]
+    pub mem_bytes_threshold: Option<u64>,
+}
+
+/// Levels of one alert: the warning tier notifies quietly, the critical
+/// tier also plays the alert sound
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct AlertTiers {
+    pub warning: f32,
+    pub critical: f32,
+}
+
+/// Warning and critical levels of the CPU, memory and temperature alerts
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct AlertThresholds {
+    /// Percent
+    pub cpu: AlertTiers,
+    /// Percent
+    pub memory: AlertTiers,
+    /// Hottest sensor, in C
+    pub temperature: AlertTiers,
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        AlertPreset::Standard.thresholds()
+    }
+}
+
+/// Alert Thresholds presets in the menu
+#[derive(Clone, Copy, PartialEq)]
+pub enum AlertPreset {
+    Relaxed,
+    Standard,
+    Strict,
+}
+
+impl AlertPreset {
+    pub const ALL: [AlertPreset; 3] = [
+        AlertPreset::Relaxed,
+        AlertPreset::Standard,
+        AlertPreset::Strict,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            AlertPreset::Relaxed => "relaxed",
+            AlertPreset::Standard => "standard",
+            AlertPreset::Strict => "strict",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.id() == id)
+    }
+
+    pub fn thresholds(self) -> AlertThresholds {
+        let tiers = |warning, critical| AlertTiers { warning, critical };
+        match self {
+            AlertPreset::Relaxed => AlertThresholds {
+                cpu: tiers(90.0, 98.0),
+                memory: tiers(90.0, 97.0),
+                temperature: tiers(95.0, 105.0),
+            },
+            AlertPreset::Standard => AlertThresholds {
+                cpu: tiers(80.0, 95.0),
+                memory: tiers(85.0, 95.0),
+                temperature: tiers(90.0, 100.0),
+            },
+            AlertPreset::Strict => AlertThresholds {
+                cpu: tiers(70.0, 90.0),
+                memory: tiers(75.0, 90.0),
+                temperature: tiers(80.0, 95.0),
+            },
+        }
+    }
+}
+
+/// Global shortcut: a Carbon virtual key code plus modifier mask
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub key_code: u32,
+    pub modifiers: u32,
+}
+
+impl Hotkey {
+    pub const fn new(key_code: u32, modifiers: u32) -> Self {
+        Self {
+            key_code,
+            modifiers,
+        }
+    }
+}
+
+fn default_public_ip_url() -> String {
+    "https://api.ipify.org".to_string()
+}
+
+fn default_daily_summary() -> Option<u8> {
+    Some(18)
+}
+
+fn default_latency_warn_ms() -> u32 {
+    150
+}
+
+/// The APFS system, data and VM volumes duplicate what `/` shows
+fn default_disk_exclude_mounts() -> Vec<String> {
+    vec!["/System/Volumes".to_string()]
+}
+
+fn default_app_cpu_warn_percent() -> f32 {
+    5.0
+}
+
+fn default_disk_wear_alert_percent() -> u32 {
+    80
+}
+
+fn default_disk_fill_alert_mins() -> u32 {
+    60
+}
+
+/// ⌃⌥M
+fn default_chart_hotkey() -> Option<Hotkey> {
+    Some(Hotkey::new(46, (1 << 12) | (1 << 11)))
+}
+
+fn default_chart_hotkey_action() -> String {
+    "show_charts".to_string()
+}
+
+/// Hide NET/TEMP while nothing interesting is happening
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AutoHideConfig {
+    #[serde(default)]
+    pub net: bool,
+    /// NET is idle while both directions are below this rate
+    #[serde(default = "default_net_idle_kbps")]
+    pub net_idle_kbps: u64,
+    #[serde(default)]
+    pub temp: bool,
+    /// TEMP is cool while the displayed temperature is below this
+    #[serde(default = "default_temp_cool_c")]
+    pub temp_cool_c: f32,
+    /// Consecutive polls the condition must hold before hiding
+    #[serde(default = "default_auto_hide_polls")]
+    pub after_polls: u32,
+}
+
+impl Default for AutoHideConfig {
+    fn default() -> Self {
+        Self {
+            net: false,
+            net_idle_kbps: default_net_idle_kbps(),
+            temp: false,
+            temp_cool_c: default_temp_cool_c(),
+            after_polls: default_auto_hide_polls(),
+        }
+    }
+}
+
+/// One color breakpoint: values at or above `at` are drawn in `color`
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColorStop {
+    pub at: f32,
+    /// System color name: green, yellow, orange, red, purple, blue, ... or label
+    pub color: String,
+}
+
+impl ColorStop {
+    fn new(at: f32, color: &str) -> Self {
+        Self {
+            at,
+            color: color.to_string(),
+        }
+    }
+}
+
+fn default_color_stops() -> Vec<ColorStop> {
+    vec![
+        ColorStop::new(0.0, "green"),
+        ColorStop::new(30.0, "yellow"),
+        ColorStop::new(60.0, "purple"),
+        ColorStop::new(80.0, "red"),
+    ]
+}
+
+/// Per-metric color tables for the status item values
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColorThresholds {
+    #[serde(default = "default_color_stops")]
+    pub cpu: Vec<ColorStop>,
+    #[serde(default = "default_color_stops")]
+    pub mem: Vec<ColorStop>,
+    #[serde(default = "default_color_stops")]
+    pub disk: Vec<ColorStop>,
+    #[serde(default = "default_color_stops")]
+    pub temp: Vec<ColorStop>,
+}
+
+impl Default for ColorThresholds {
+    fn default() -> Self {
+        Self {
+            cpu: default_color_stops(),
+            mem: default_color_stops(),
+            disk: default_color_stops(),
+            temp: default_color_stops(),
+        }
+    }
+}
+
+impl ColorThresholds {
+    /// Built-in preset that only colors values that are actually high
+    pub fn conservative() -> Self {
+        let stops = |yellow: f32, purple: f32, red: f32| {
+            vec![
+                ColorStop::new(0.0, "green"),
+                ColorStop::new(yellow, "yellow"),
+                ColorStop::new(purple, "purple"),
+                ColorStop::new(red, "red"),
+            ]
+        };
+        Self {
+            cpu: stops(50.0, 75.0, 90.0),
+            mem: stops(70.0, 85.0, 95.0),
+            disk: stops(70.0, 85.0, 95.0),
+            temp: stops(60.0, 75.0, 90.0),
+        }
+    }
+
+    /// Table for `module`; NET has none (its color reflects Wi-Fi signal).
+    pub fn stops(&self, module: Module) -> Option<&[ColorStop]> {
+        match module {
+            Module::Cpu => Some(&self.cpu),
+            Module::Mem => Some(&self.mem),
+            Module::Disk => Some(&self.disk),
+            Module::Temp => Some(&self.temp),
+            Module::Net => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    /// `Config::color_thresholds`
+    #[default]
+    Default,
+    Conservative,
+    /// Always label color
+    Off,
+}
+
+impl ColorScheme {
+    pub fn id(self) -> &'static str {
+        match self {
+            ColorScheme::Default => "default",
+            ColorScheme::Conservative => "conservative",
+            ColorScheme::Off => "off",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "default" => Some(ColorScheme::Default),
+            "conservative" => Some(ColorScheme::Conservative),
+            "off" => Some(ColorScheme::Off),
+            _ => None,
+        }
+    }
+}
+
+/// A named group of runners, toggled together from the runner menu
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RunnerCategory {
+    pub name: String,
+    pub runner_ids: Vec<String>,
+}
+
+/// Added after the configured categories: every custom set
+pub const CUSTOM_RUNNER_CATEGORY: &str = "Custom";
+/// Added last: runners no category lists, e.g. newly exported RunCat sets
+pub const OTHER_RUNNER_CATEGORY: &str = "Other";
+
+/// RunCat sets by kind, the default `runner_categories`
+const DEFAULT_RUNNER_CATEGORIES: &[(&str, &[&str])] = &[
+    ("Cats", &["runcat:cat", "runcat:cat-b", "runcat:cat-c", "runcat:cat-tail", "runcat:flash-cat", "runcat:golden-cat", "runcat:metal-cluster-cat", "runcat:mock-nyan-cat", "runcat:maneki-neko"]),
+    ("Dogs", &["runcat:dog", "runcat:puppy", "runcat:terrier", "runcat:welsh-corgi", "runcat:greyhound"]),
+    ("Animals", &["runcat:bird", "runcat:butterfly", "runcat:chameleon", "runcat:cheetah", "runcat:chicken", "runcat:dinosaur", "runcat:dolphin", "runcat:dragon", "runcat:fishman", "runcat:fox", "runcat:frog", "runcat:hamster-wheel", "runcat:hedgehog", "runcat:horse", "runcat:mouse", "runcat:octopus", "runcat:otter", "runcat:owl", "runcat:parrot", "runcat:penguin", "runcat:penguin2", "runcat:pig", "runcat:rabbit", "runcat:reindeer-sleigh", "runcat:sheep", "runcat:squirrel", "runcat:uhooi", "runcat:whale"]),
+    ("Food", &["runcat:coffee", "runcat:frypan", "runcat:mochi", "runcat:rotating-sushi", "runcat:rubber-duck", "runcat:sausage", "runcat:sushi", "runcat:tapioca-drink"]),
+    ("People", &["runcat:dogeza", "runcat:human", "runcat:party-people", "runcat:push-up", "runcat:sit-up"]),
+    ("Machines", &["runcat:cogwheel", "runcat:engine", "runcat:factory", "runcat:reactor", "runcat:rocket", "runcat:steam-locomotive"]),
+    ("Nature", &["runcat:bonfire", "runcat:drop", "runcat:earth", "runcat:slime", "runcat:snowman", "runcat:sparkler", "runcat:wind-chime"]),
+    ("Fantasy", &["runcat:ghost", "runcat:jack-o-lantern", "runcat:triforce"]),
+    ("Abstract", &["runcat:city", "runcat:cradle", "runcat:dots", "runcat:entaku", "runcat:pendulum", "runcat:pulse", "runcat:sine-curve"]),
+];
+
+fn default_runner_categories() -> Vec<RunnerCategory> {
+    DEFAULT_RUNNER_CATEGORIES
+        .iter()
+        .map(|(name, ids)| RunnerCategory {
+            name: name.to_string(),
+            runner_ids: ids.iter().map(|id| id.to_string()).collect(),
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct CustomRunnerSet {
     pub id: String,
     pub name: String,
     pub frame_paths: Vec<String>,
+    /// Per-set frame duration, overriding `Config::runner_frame_ms`
+    #[serde(default)]
+    pub frame_ms: Option<u64>,
 }
 
 impl CustomRunnerSet {
@@ -58,61 +807,810 @@ impl CustomRunnerSet {
             id,
             name,
             frame_paths,
+            frame_ms: None,
         }
     }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Config {
-    pub poll_interval_secs: u64,
+    /// Schema version; configs written before versioning count as 0
+    #[serde(default)]
+    pub config_version: u32,
+    /// Seconds between polls; older configs stored whole seconds, which
+    /// still parse
+    pub poll_interval_secs: f64,
     pub menubar_temp_component: String,
     #[serde(default)]
+    pub temp_label_style: TempLabelStyle,
+    /// Rotate the TEMP item through CPU/GPU/SSD this often; None shows one sensor
+    #[serde(default)]
+    pub temp_cycle_secs: Option<u64>,
+    /// Raw sensor names shown alongside CPU/GPU/SSD, at most
+    /// `MAX_PINNED_SENSORS`
+    #[serde(default)]
+    pub pinned_sensors: Vec<String>,
+    #[serde(default)]
     pub launch_at_login: bool,
     #[serde(default = "default_runner_id")]
     pub runner_id: String,
     #[serde(default = "default_runner_frame_ms")]
     pub runner_frame_ms: u64,
+    /// Frame duration overrides for built-in RunCat sets, keyed by runner id
+    #[serde(default)]
+    pub runner_frame_overrides: BTreeMap<String, u64>,
     #[serde(default = "default_runner_display_secs")]
     pub runner_display_secs: u64,
     #[serde(default)]
     pub runner_rotation_ids: Vec<String>,
     #[serde(default)]
     pub custom_runner_sets: Vec<CustomRunnerSet>,
+    /// Groups for the runner menu; edit the JSON to make your own
+    #[serde(default = "default_runner_categories")]
+    pub runner_categories: Vec<RunnerCategory>,
     #[serde(default = "default_runner_icon_mode")]
     pub runner_icon_mode: RunnerIconMode,
+    #[serde(default)]
+    pub runner_idle_behavior: RunnerIdleBehavior,
+    /// CPU percent below which the runner counts as idle
+    #[serde(default = "default_runner_idle_threshold")]
+    pub runner_idle_threshold: f32,
+    /// Rotate to a random runner instead of the next one in order
+    #[serde(default)]
+    pub runner_rotation_shuffle: bool,
+    /// Keep animating the runner and redrawing charts under Reduce Motion
+    #[serde(default)]
+    pub ignore_reduce_motion: bool,
+    #[serde(default)]
+    pub cpu_status_style: CpuStatusStyle,
+    /// Replace the CPU item's "CPU" label with P/E cluster usage (Apple Silicon)
+    #[serde(default)]
+    pub cpu_cluster_label: bool,
+    #[serde(default)]
+    pub open_menu_updates: OpenMenuUpdates,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub low_power: LowPowerBehavior,
+    /// Fixed width of the two-line status items in points; None sizes them to fit
+    #[serde(default)]
+    pub module_width: Option<f64>,
+    /// Module ids from left to right; modules left out keep their default
+    /// place after the listed ones
+    #[serde(default = "default_module_order")]
+    pub module_order: Vec<String>,
+    /// Append a 1-minute trend arrow to each module title, its tooltip and
+    /// the copied stats
+    #[serde(default = "default_true")]
+    pub show_trend_arrows: bool,
+    #[serde(default)]
+    pub auto_hide: AutoHideConfig,
+    /// Status items to show, by `Module` id, plus `DIGEST_MODULE` for the
+    /// metric closest to its alert threshold. CPU always shows: its item
+    /// carries the app menu.
+    #[serde(default = "default_module_order")]
+    pub visible_modules: Vec<String>,
+    /// Fit the percent charts' y-axis to the visible data
+    #[serde(default)]
+    pub chart_auto_zoom: bool,
+    /// Show a pageouts/sec panel in the chart window
+    #[serde(default)]
+    pub chart_show_pageouts: bool,
+    #[serde(default)]
+    pub chart_smoothing: ChartSmoothing,
+    /// Min/avg/max of the visible window in each chart caption
+    #[serde(default)]
+    pub chart_show_stats: bool,
+    /// Lock the TEMP chart to 0..100 instead of fitting it to the readings
+    #[serde(default)]
+    pub chart_temp_fixed_range: bool,
+    #[serde(default)]
+    pub net_chart_style: NetChartStyle,
+    #[serde(default)]
+    pub chart_theme: ChartThemeChoice,
+    #[serde(default)]
+    pub net_display: NetDisplay,
+    /// Chart window as a small borderless, always-on-top strip
+    #[serde(default)]
+    pub chart_mini_mode: bool,
+    /// Where the mini chart window was last dragged to, in points
+    #[serde(default)]
+    pub chart_mini_position: Option<(f64, f64)>,
+    /// Where each popped-out chart was left, keyed by chart mode id
+    #[serde(default)]
+    pub chart_pop_out_frames: BTreeMap<String, WindowFrame>,
+    /// POST a JSON payload here when an alert fires (ntfy, Slack, ...)
+    #[serde(default)]
+    pub alert_webhook_url: Option<String>,
+    /// Shell command run when an alert fires, payload in `MSM_ALERT_*` env vars
+    #[serde(default)]
+    pub alert_command: Option<String>,
+    /// Rewritten with the latest stats as JSON every poll (at most once a
+    /// second), for tools that read a file; null disables it
+    #[serde(default)]
+    pub stats_output_path: Option<PathBuf>,
+    /// Notify again once an alert's metric is back below its threshold
+    #[serde(default)]
+    pub alert_recovery_notifications: bool,
+    #[serde(default)]
+    pub alert_thresholds: AlertThresholds,
+    /// Notify when a volume mounts or unmounts
+    #[serde(default)]
+    pub notify_volume_changes: bool,
+    /// Local hour (0-23) of the end-of-day summary notification; null
+    /// turns it off
+    #[serde(default = "default_daily_summary")]
+    pub daily_summary: Option<u8>,
+    /// `local_day` the last summary went out, so a restart doesn't send
+    /// it again
+    #[serde(default)]
+    pub daily_summary_sent_day: Option<i32>,
+    /// Played when an alert fires: a system sound name ("Sosumi") or a
+    /// file path; None is silent
+    #[serde(default)]
+    pub alert_sound: Option<String>,
+    /// Per-kind overrides of `alert_sound`, keyed like the hooks' `kind`
+    /// ("cpu", "temperature", ...); null silences that kind
+    #[serde(default)]
+    pub alert_sounds: BTreeMap<String, Option<String>>,
+    /// Log a warning when the app's own CPU use goes above this
+    #[serde(default = "default_app_cpu_warn_percent")]
+    pub app_cpu_warn_percent: f32,
+    /// Show developer diagnostics actions (Profile App)
+    #[serde(default)]
+    pub diagnostics_enabled: bool,
+    #[serde(default)]
+    pub color_scheme: ColorScheme,
+    /// Color tables used by the Default scheme
+    #[serde(default)]
+    pub color_thresholds: ColorThresholds,
+    #[serde(default)]
+    pub process_watches: Vec<ProcessWatch>,
+    /// Show the public IP and active VPN interfaces in the NET menu
+    #[serde(default)]
+    pub show_public_ip: bool,
+    /// HTTPS endpoint that answers with the caller's IP as plain text
+    #[serde(default = "default_public_ip_url")]
+    pub public_ip_url: String,
+    /// Host (optionally `host:port`, default port 443) whose connect time
+    /// the NET menu and chart show; null turns the latency probe off
+    #[serde(default)]
+    pub ping_target: Option<String>,
+    /// Latency above this many ms is shown in red
+    #[serde(default = "default_latency_warn_ms")]
+    pub latency_warn_ms: u32,
+    /// Runs `chart_hotkey_action` from any app; null disables it
+    #[serde(default = "default_chart_hotkey")]
+    pub chart_hotkey: Option<Hotkey>,
+    /// Menu action id the shortcut triggers, e.g. "show_core_charts" or
+    /// "copy_stats"; the chart window by default
+    #[serde(default = "default_chart_hotkey_action")]
+    pub chart_hotkey_action: String,
+    /// CPU/MEM/TEMP and the Pause Alerts and Show Charts buttons in the
+    /// Touch Bar, on Macs that have one
+    #[serde(default = "default_true")]
+    pub touch_bar: bool,
+    /// Mounts shown even when an exclude pattern matches them; globs
+    /// (`*`, `?`) or path prefixes
+    #[serde(default)]
+    pub disk_include_mounts: Vec<String>,
+    /// Mounts left out of the DISK menu and chart
+    #[serde(default = "default_disk_exclude_mounts")]
+    pub disk_exclude_mounts: Vec<String>,
+    /// Alert once the internal drive's SMART wear level reaches this percent
+    #[serde(default = "default_disk_wear_alert_percent")]
+    pub disk_wear_alert_percent: u32,
+    /// Alert when a volume is steadily filling up and would be full within
+    /// this many minutes at the current rate; 0 disables it
+    #[serde(default = "default_disk_fill_alert_mins")]
+    pub disk_fill_alert_mins: u32,
+    /// Named overlays of top-level fields, laid over the rest on switching
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Map<String, Value>>,
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Base values of the fields the active profile overlays, written back
+    /// in their place on saving
+    #[serde(skip)]
+    profile_base: Map<String, Value>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            poll_interval_secs: 1,
+            config_version: CONFIG_VERSION,
+            poll_interval_secs: 1.0,
             menubar_temp_component: "CPU".to_string(),
+            temp_label_style: TempLabelStyle::default(),
+            temp_cycle_secs: None,
+            pinned_sensors: Vec::new(),
             launch_at_login: false,
             runner_id: default_runner_id(),
             runner_frame_ms: default_runner_frame_ms(),
+            runner_frame_overrides: BTreeMap::new(),
             runner_display_secs: default_runner_display_secs(),
             runner_rotation_ids: vec![default_runner_id()],
             custom_runner_sets: Vec::new(),
+            runner_categories: default_runner_categories(),
             runner_icon_mode: default_runner_icon_mode(),
+            runner_idle_behavior: RunnerIdleBehavior::Animate,
+            runner_idle_threshold: default_runner_idle_threshold(),
+            runner_rotation_shuffle: false,
+            ignore_reduce_motion: false,
+            cpu_status_style: CpuStatusStyle::Text,
+            cpu_cluster_label: false,
+            open_menu_updates: OpenMenuUpdates::default(),
+            language: Language::default(),
+            low_power: LowPowerBehavior::default(),
+            module_width: None,
+            module_order: default_module_order(),
+            show_trend_arrows: true,
+            auto_hide: AutoHideConfig::default(),
+            visible_modules: default_module_order(),
+            chart_auto_zoom: false,
+            chart_show_pageouts: false,
+            chart_smoothing: ChartSmoothing::Off,
+            chart_show_stats: false,
+            chart_temp_fixed_range: false,
+            net_chart_style: NetChartStyle::Separate,
+            chart_theme: ChartThemeChoice::Dark,
+            net_display: NetDisplay::Speed,
+            chart_mini_mode: false,
+            chart_mini_position: None,
+            chart_pop_out_frames: BTreeMap::new(),
+            alert_webhook_url: None,
+            alert_command: None,
+            stats_output_path: None,
+            alert_recovery_notifications: false,
+            alert_thresholds: AlertThresholds::default(),
+            notify_volume_changes: false,
+            daily_summary: default_daily_summary(),
+            daily_summary_sent_day: None,
+            alert_sound: None,
+            alert_sounds: BTreeMap::new(),
+            app_cpu_warn_percent: default_app_cpu_warn_percent(),
+            diagnostics_enabled: false,
+            color_scheme: ColorScheme::Default,
+            color_thresholds: ColorThresholds::default(),
+            process_watches: Vec::new(),
+            show_public_ip: false,
+            public_ip_url: default_public_ip_url(),
+            ping_target: None,
+            latency_warn_ms: default_latency_warn_ms(),
+            chart_hotkey: default_chart_hotkey(),
+            chart_hotkey_action: default_chart_hotkey_action(),
+            touch_bar: true,
+            disk_include_mounts: Vec::new(),
+            disk_exclude_mounts: default_disk_exclude_mounts(),
+            disk_wear_alert_percent: default_disk_wear_alert_percent(),
+            disk_fill_alert_mins: default_disk_fill_alert_mins(),
+            profiles: BTreeMap::new(),
+            active_profile: None,
+            profile_base: Map::new(),
         }
     }
 }
 
 impl Config {
+    /// Time between polls, clamped to the supported range
+    pub fn poll_interval(&self) -> Duration {
+        let secs = if self.poll_interval_secs.is_finite() {
+            self.poll_interval_secs
+                .clamp(MIN_POLL_INTERVAL_SECS, MAX_POLL_INTERVAL_SECS)
+        } else {
+            1.0
+        };
+        Duration::from_secs_f64(secs)
+    }
+
+    /// Every module once, left to right: `module_order` without unknown
+    /// or repeated ids, then the modules it leaves out
+    pub fn module_order(&self) -> Vec<Module> {
+        let mut order: Vec<Module> = Vec::new();
+        let listed = self
+            .module_order
+            .iter()
+            .filter_map(|id| Module::from_id(id));
+        for module in listed.chain(Module::ALL) {
+            if !order.contains(&module) {
+                order.push(module);
+            }
+        }
+        order
+    }
+
+    /// Whether the status item `id` (a `Module` id or `DIGEST_MODULE`) shows
+    pub fn module_visible(&self, id: &str) -> bool {
+        id == Module::Cpu.id() || self.visible_modules.iter().any(|m| m == id)
+    }
+
+    /// Show or hide the status item `id`
+    pub fn set_module_visible(&mut self, id: &str, visible: bool) {
+        self.visible_modules.retain(|m| m != id);
+        if visible {
+            self.visible_modules.push(id.to_string());
+        }
+    }
+
+    /// Color tables in effect, or None when the scheme is Off
+    pub fn effective_color_thresholds(&self) -> Option<ColorThresholds> {
+        match self.color_scheme {
+            ColorScheme::Default => Some(self.color_thresholds.clone()),
+            ColorScheme::Conservative => Some(ColorThresholds::conservative()),
+            ColorScheme::Off => None,
+        }
+    }
+
+    /// Load `config.json` with the active profile laid over it
     pub fn load() -> Self {
+        Self::load_base().with_active_profile()
+    }
+
+    /// Base config `self` with its active profile laid over it, if that
+    /// profile exists and applies
+    pub fn with_active_profile(self) -> Self {
+        match self.active_profile.clone() {
+            Some(name) => self.with_profile(&name).unwrap_or(self),
+            None => self,
+        }
+    }
+
+    /// The live values of `PROFILE_FIELDS`, to store as a profile
+    pub fn profile_snapshot(&self) -> Map<String, Value> {
+        let Ok(Value::Object(fields)) = serde_json::to_value(self) else {
+            return Map::new();
+        };
+        fields
+            .into_iter()
+            .filter(|(key, _)| PROFILE_FIELDS.contains(&key.as_str()))
+            .collect()
+    }
+
+    /// Base config `self` with profile `name` laid over it; None when there
+    /// is no such profile or the result doesn't parse. Fields the config
+    /// doesn't have are ignored.
+    pub fn with_profile(&self, name: &str) -> Option<Self> {
+        let Some(overlay) = self.profiles.get(name) else {
+            log::warn!("no profile named {:?}", name);
+            return None;
+        };
+        let mut merged = serde_json::to_value(self).ok()?;
+        let mut base = Map::new();
+        for (key, value) in overlay {
+            let fixed = ["profiles", "active_profile", "config_version"].contains(&key.as_str());
+            if fixed || merged.get(key).is_none() {
+                continue;
+            }
+            let base_value = std::mem::replace(&mut merged[key], value.clone());
+            base.insert(key.clone(), base_value);
+        }
+        match serde_json::from_value::<Self>(merged) {
+            Ok(mut config) => {
+                config.active_profile = Some(name.to_string());
+                config.profile_base = base;
+                Some(config)
+            }
+            Err(e) => {
+                log::warn!("profile {:?} is invalid: {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// `config.json` as saved, without the active profile laid over it
+    pub fn load_base() -> Self {
         let path = config_path();
-        if let Ok(data) = fs::read_to_string(&path) {
-            serde_json::from_str(&data).unwrap_or_default()
-        } else {
-            Self::default()
+        let Ok(data) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        let (config, changed) = Self::parse(&path, &data);
+        if changed {
+            config.save();
+        }
+        config
+    }
+
+    /// Read `data`, the contents of `path`, migrating an older config or
+    /// salvaging a broken one. True when the result should be saved back.
+    fn parse(path: &Path, data: &str) -> (Self, bool) {
+        let mut fields = match serde_json::from_str(data) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(_) => return (Self::recover(path, data, "not a JSON object"), true),
+            Err(e) => return (Self::recover(path, data, e), true),
+        };
+        let from = migrate(&mut fields);
+        if from > CONFIG_VERSION {
+            log::warn!(
+                "config.json is version {}, newer than this app's {}; unknown settings are ignored",
+                from,
+                CONFIG_VERSION
+            );
+        }
+        match serde_json::from_value::<Self>(Value::Object(fields)) {
+            Ok(config) => {
+                let migrated = from < CONFIG_VERSION;
+                if migrated {
+                    back_up_before_migration(path, from);
+                }
+                (config, migrated)
+            }
+            Err(e) => (Self::recover(path, data, e), true),
+        }
+    }
+
+    /// Move an unreadable config aside and keep whatever of it still parses
+    fn recover(path: &Path, data: &str, error: impl std::fmt::Display) -> Self {
+        log::warn!("config.json is invalid ({}), salvaging what parses", error);
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let backup = path.with_extension(format!("json.bad-{}", ts));
+        if let Err(e) = fs::rename(path, &backup) {
+            log::warn!(
+                "could not back up invalid config to {}: {}",
+                backup.display(),
+                e
+            );
         }
+        Self::salvage(data).unwrap_or_default()
     }
 
+    /// Write to a temp file and rename it over `config.json`, so a crash
+    /// mid-write never leaves a half-written config behind.
     pub fn save(&self) {
         let dir = config_dir();
-        let _ = fs::create_dir_all(&dir);
-        if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = fs::write(config_path(), json);
+        if let Err(e) = fs::create_dir_all(&dir) {
+            issues::report(
+                "config",
+                format!("could not create {}: {}", dir.display(), e),
+            );
+        }
+        let json = match self
+            .stored_value()
+            .and_then(|value| serde_json::to_string_pretty(&value))
+        {
+            Ok(json) => json,
+            Err(e) => {
+                issues::report("config", format!("could not serialize config: {}", e));
+                return;
+            }
+        };
+        let path = config_path();
+        let tmp = path.with_extension("json.tmp");
+        let result = fs::write(&tmp, json).and_then(|()| fs::rename(&tmp, &path));
+        if let Err(e) = result {
+            issues::report(
+                "config",
+                format!("could not save {}: {}", path.display(), e),
+            );
+        }
+    }
+
+    /// The config as saved and exported: fields the active profile overlays
+    /// get their base values back, and their live values, edits included,
+    /// go to the profile, so a profile never leaks into the base settings
+    pub fn stored_value(&self) -> serde_json::Result<Value> {
+        let mut value = serde_json::to_value(self)?;
+        let Some(name) = self.active_profile.as_deref() else {
+            return Ok(value);
+        };
+        if !self.profiles.contains_key(name) {
+            return Ok(value);
+        }
+        for (key, base) in &self.profile_base {
+            let live = std::mem::replace(&mut value[key], base.clone());
+            value["profiles"][name][key] = live;
+        }
+        Ok(value)
+    }
+
+    /// Start from defaults and keep every top-level field of a damaged config
+    /// that still deserializes. Arrays keep their valid elements.
+    fn salvage(data: &str) -> Option<Self> {
+        let mut fields = match serde_json::from_str(data) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(_) => return None,
+            Err(_) => complete_prefix(data)?,
+        };
+        migrate(&mut fields);
+        let mut merged = serde_json::to_value(Self::default()).ok()?;
+        for (key, value) in fields {
+            if try_field(&mut merged, &key, value.clone()) {
+                continue;
+            }
+            if let Value::Array(items) = value {
+                let mut kept = Vec::new();
+                for item in items {
+                    kept.push(item);
+                    if !try_field(&mut merged, &key, Value::Array(kept.clone())) {
+                        kept.pop();
+                    }
+                }
+            }
+        }
+        serde_json::from_value(merged).ok()
+    }
+}
+
+/// Run the migrations a config's version still needs, in order, and stamp
+/// it with the current version. Returns the version it was read as; newer
+/// versions are left untouched.
+fn migrate(fields: &mut Map<String, Value>) -> u32 {
+    let from = fields
+        .get("config_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v.min(u32::MAX as u64) as u32);
+    if from >= CONFIG_VERSION {
+        return from;
+    }
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        step(fields);
+        log::info!(
+            "migrated config from version {} to {}",
+            version,
+            version + 1
+        );
+    }
+    fields.insert("config_version".to_string(), CONFIG_VERSION.into());
+    from
+}
+
+/// Keep the file as it was before its first migration, as
+/// `config.json.v<from>.bak`; an existing backup is never overwritten.
+fn back_up_before_migration(path: &Path, from: u32) {
+    let backup = path.with_extension(format!("json.v{}.bak", from));
+    if backup.exists() {
+        return;
+    }
+    if let Err(e) = fs::copy(path, &backup) {
+        log::warn!(
+            "could not back up config to {} before migrating: {}",
+            backup.display(),
+            e
+        );
+    }
+}
+
+/// v1 rotates through `runner_rotation_ids`; v0 configs may only name a
+/// single `runner_id`.
+fn migrate_v0_to_v1(fields: &mut Map<String, Value>) {
+    let rotation_empty = match fields.get("runner_rotation_ids") {
+        Some(Value::Array(ids)) => ids.is_empty(),
+        Some(_) | None => true,
+    };
+    if let (true, Some(Value::String(id))) = (rotation_empty, fields.get("runner_id")) {
+        let ids = vec![Value::String(id.clone())];
+        fields.insert("runner_rotation_ids".to_string(), Value::Array(ids));
+    }
+}
+
+/// Set `merged[key] = value` if the result is still a valid config.
+fn try_field(merged: &mut Value, key: &str, value: Value) -> bool {
+    let mut candidate = merged.clone();
+    candidate[key] = value;
+    if serde_json::from_value::<Config>(candidate.clone()).is_err() {
+        return false;
+    }
+    *merged = candidate;
+    true
+}
+
+/// Cut a truncated config (as written by `save`, one top-level field per
+/// `\n  "key"` line) back to its last complete field.
+fn complete_prefix(data: &str) -> Option<Map<String, Value>> {
+    let mut end = data.len();
+    while let Some(pos) = data[..end].rfind("\n  \"") {
+        let prefix = data[..pos].trim_end().trim_end_matches(',');
+        if let Ok(Value::Object(fields)) = serde_json::from_str(&format!("{}\n}}", prefix)) {
+            return Some(fields);
         }
+        end = pos;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn with_profiles(profiles: Value) -> Config {
+        let mut config = Config::default();
+        config.profiles = serde_json::from_value(profiles).unwrap();
+        config
+    }
+
+    #[test]
+    fn a_profile_never_leaks_into_the_base() {
+        let base = with_profiles(json!({"work": {"poll_interval_secs": 5.0, "no_such_field": 1}}));
+        let mut config = base.with_profile("work").unwrap();
+        assert_eq!(config.poll_interval_secs, 5.0);
+
+        // Edits to a profile field go to the profile, others to the base
+        config.poll_interval_secs = 7.0;
+        config.show_trend_arrows = false;
+        let stored = config.stored_value().unwrap();
+        assert_eq!(stored["poll_interval_secs"], json!(base.poll_interval_secs));
+        assert_eq!(stored["profiles"]["work"]["poll_interval_secs"], json!(7.0));
+        assert_eq!(stored["show_trend_arrows"], json!(false));
+        assert_eq!(stored["active_profile"], json!("work"));
+    }
+
+    #[test]
+    fn switching_starts_from_the_base() {
+        let base = with_profiles(json!({
+            "fast": {"poll_interval_secs": 1.0},
+            "digest": {"visible_modules": ["cpu", "digest"]},
+        }));
+        let fast = base.with_profile("fast").unwrap();
+        let saved: Config = serde_json::from_value(fast.stored_value().unwrap()).unwrap();
+        assert_eq!(saved.poll_interval_secs, base.poll_interval_secs);
+
+        let digest = saved.with_profile("digest").unwrap();
+        assert!(digest.module_visible(DIGEST_MODULE));
+        assert!(!digest.module_visible("mem"));
+        assert_eq!(digest.poll_interval_secs, base.poll_interval_secs);
+        assert_eq!(digest.active_profile.as_deref(), Some("digest"));
+        assert!(base.with_profile("missing").is_none());
+    }
+
+    #[test]
+    fn snapshots_capture_alert_levels_and_module_visibility() {
+        let snapshot = Config::default().profile_snapshot();
+        assert!(snapshot.contains_key("alert_thresholds"));
+        assert!(snapshot.contains_key("visible_modules"));
+        assert!(snapshot.contains_key("auto_hide"));
+        assert!(!snapshot.contains_key("language"));
+        assert_eq!(snapshot.len(), PROFILE_FIELDS.len());
+    }
+
+    // Config files as earlier releases wrote them
+    const V0: &str = include_str!("../tests/fixtures/config/v0.json");
+    const V0_EMPTY_ROTATION: &str = include_str!("../tests/fixtures/config/v0-empty-rotation.json");
+    const V0_SINGLE_RUNNER: &str = include_str!("../tests/fixtures/config/v0-single-runner.json");
+    const V1: &str = include_str!("../tests/fixtures/config/v1.json");
+
+    /// A fresh directory under the temp dir for one test's config files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("msm-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn a_truncated_config_keeps_its_complete_fields() {
+        let mut config = Config::default();
+        config.poll_interval_secs = 3.0;
+        config.show_trend_arrows = false;
+        let json = serde_json::to_string_pretty(&config.stored_value().unwrap()).unwrap();
+        // Cut mid-way through the last field, as a crash during a write would
+        let cut = json.rfind("\n  \"").unwrap() + 6;
+        let salvaged = Config::salvage(&json[..cut]).unwrap();
+        assert_eq!(salvaged.poll_interval_secs, 3.0);
+        assert!(!salvaged.show_trend_arrows);
+    }
+
+    #[test]
+    fn a_wrong_typed_field_falls_back_to_its_default() {
+        let salvaged = Config::salvage(
+            r#"{
+                "poll_interval_secs": "fast",
+                "show_trend_arrows": false,
+                "runner_rotation_ids": ["cat", 5, "dog"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            salvaged.poll_interval_secs,
+            Config::default().poll_interval_secs
+        );
+        assert!(!salvaged.show_trend_arrows);
+        assert_eq!(salvaged.runner_rotation_ids, ["cat", "dog"]);
+    }
+
+    #[test]
+    fn an_empty_file_is_moved_aside_and_replaced_by_defaults() {
+        let dir = scratch_dir("empty");
+        let path = dir.join("config.json");
+        fs::write(&path, "").unwrap();
+        let (config, changed) = Config::parse(&path, "");
+        assert!(changed);
+        assert_eq!(
+            config.poll_interval_secs,
+            Config::default().poll_interval_secs
+        );
+        assert!(!path.exists());
+        let backups: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with("config.json.bad-")
+            })
+            .collect();
+        assert_eq!(backups.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_v0_config_migrates_and_keeps_a_backup() {
+        let dir = scratch_dir("v0");
+        let path = dir.join("config.json");
+        fs::write(&path, V0).unwrap();
+        let (config, changed) = Config::parse(&path, V0);
+        assert!(changed);
+        assert_eq!(config.config_version, CONFIG_VERSION);
+        assert_eq!(config.runner_rotation_ids, ["runcat:dog", "runcat:cat"]);
+        assert_eq!(config.poll_interval_secs, 2.0);
+        assert!(config.launch_at_login);
+        let backup = dir.join("config.json.v0.bak");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), V0);
+
+        // A later migration never overwrites the first backup
+        fs::write(&path, V0_SINGLE_RUNNER).unwrap();
+        let (config, changed) = Config::parse(&path, V0_SINGLE_RUNNER);
+        assert!(changed);
+        assert_eq!(config.runner_rotation_ids, ["runcat:horse"]);
+        assert_eq!(fs::read_to_string(&backup).unwrap(), V0);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn migrating_keeps_an_existing_rotation() {
+        let mut fields = serde_json::from_str::<Map<String, Value>>(V0).unwrap();
+        assert_eq!(migrate(&mut fields), 0);
+        assert_eq!(
+            fields["runner_rotation_ids"],
+            json!(["runcat:dog", "runcat:cat"])
+        );
+        assert_eq!(fields["config_version"], json!(CONFIG_VERSION));
+
+        // An empty rotation falls back to the single runner
+        let mut fields = serde_json::from_str::<Map<String, Value>>(V0_EMPTY_ROTATION).unwrap();
+        assert_eq!(migrate(&mut fields), 0);
+        assert_eq!(fields["runner_rotation_ids"], json!(["runcat:dog"]));
+    }
+
+    #[test]
+    fn cpu_always_shows() {
+        let mut config = Config::default();
+        assert!(!config.module_visible(DIGEST_MODULE));
+        config.set_module_visible(DIGEST_MODULE, true);
+        config.set_module_visible("mem", false);
+        config.set_module_visible(Module::Cpu.id(), false);
+        assert!(config.module_visible(DIGEST_MODULE));
+        assert!(!config.module_visible("mem"));
+        assert!(config.module_visible("cpu"));
+    }
+
+    #[test]
+    fn a_current_config_is_left_alone() {
+        let dir = scratch_dir("current");
+        let path = dir.join("config.json");
+        let data = serde_json::to_string(&Config::default().stored_value().unwrap()).unwrap();
+        fs::write(&path, &data).unwrap();
+        let (_, changed) = Config::parse(&path, &data);
+        assert!(!changed);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::write(&path, V1).unwrap();
+        let (config, changed) = Config::parse(&path, V1);
+        assert!(!changed);
+        assert_eq!(config.poll_interval_secs, 1.5);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // A newer version is read as it is, unknown fields ignored
+        let mut fields = json!({"config_version": CONFIG_VERSION + 1, "from_the_future": true})
+            .as_object()
+            .unwrap()
+            .clone();
+        assert_eq!(migrate(&mut fields), CONFIG_VERSION + 1);
+        assert!(!fields.contains_key("runner_rotation_ids"));
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,355 @@
+use crate::alert::{notify, COOLDOWN_SECS};
+use crate::config::{config_dir, Config, HOTTEST_TEMP_COMPONENT};
+use crate::format::format_bytes;
+use crate::health::unix_now;
+use crate::scheduler::TaskStatus;
+use crate::strings::t;
+use crate::ui::motion;
+use crate::ui::tray::{self, RunnerSettings};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Rotations longer than this are summarized as a count
+const MAX_LISTED_RUNNERS: usize = 3;
+const PROFILE_SECS: u64 = 30;
+/// A tick this many poll intervals after the one before it came late
+const OVERRUN_INTERVALS: u32 = 2;
+
+/// Set while a `sample` run is in progress
+static PROFILING: AtomicBool = AtomicBool::new(false);
+/// `TickStats::overruns`, for the heartbeat
+static OVERRUNS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+/// Bytes allocated and not yet freed
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting calls for the instrumentation lines
+struct CountingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    /// A move, not a new allocation; only the live size changes
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed);
+            LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+/// Timing of `App::tick`
+#[derive(Default)]
+pub struct TickStats {
+    pub count: u64,
+    pub total: Duration,
+    pub last: Duration,
+    pub slowest: Duration,
+    /// Ticks that came late or took longer than the poll interval
+    pub overruns: u64,
+    /// Start of the last tick and the interval it was polled at
+    last_started: Option<(Instant, Duration)>,
+}
+
+impl TickStats {
+    /// A tick that began at `started` and took `elapsed`, polling every
+    /// `interval`. Time asleep doesn't count as late: `Instant` stops with
+    /// the Mac.
+    pub fn record(&mut self, started: Instant, elapsed: Duration, interval: Duration) {
+        let late = self.last_started.is_some_and(|(last, last_interval)| {
+            started.duration_since(last) >= last_interval * OVERRUN_INTERVALS
+        });
+        if late || elapsed > interval {
+            self.overruns += 1;
+            OVERRUNS.store(self.overruns, Ordering::Relaxed);
+        }
+        self.last_started = Some((started, interval));
+        self.count += 1;
+        self.total += elapsed;
+        self.last = elapsed;
+        self.slowest = self.slowest.max(elapsed);
+    }
+}
+
+/// Late or slow ticks since launch
+pub fn overrun_count() -> u64 {
+    OVERRUNS.load(Ordering::Relaxed)
+}
+
+/// The settings the app is actually running with, one line each.
+///
+/// Built from the live structs (after clamping and filtering), not from the
+/// config file.
+pub fn effective_settings(config: &Config, runner: &RunnerSettings) -> Vec<String> {
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" };
+    let rotation = if runner.rotation_ids.len() <= MAX_LISTED_RUNNERS {
+        runner.rotation_ids.join(", ")
+    } else {
+        format!("{} runners", runner.rotation_ids.len())
+    };
+    let rules = &config.auto_hide;
+    let temp_component = if config.menubar_temp_component == HOTTEST_TEMP_COMPONENT {
+        "hottest sensor"
+    } else {
+        config.menubar_temp_component.as_str()
+    };
+
+    let mut lines = vec![
+        format!("Poll interval: {}s", config.poll_interval_secs),
+        format!(
+            "Menu bar temperature: {} (line two: {})",
+            temp_component,
+            config.temp_label_style.id()
+        ),
+        format!(
+            "Runner: {} ({} ms/frame)",
+            runner.selected_id, runner.frame_ms
+        ),
+        format!("Rotation: {}, every {}s", rotation, runner.display_secs),
+        format!(
+            "Runner when idle: {} below {:.0}%",
+            config.runner_idle_behavior.id(),
+            runner.idle_threshold
+        ),
+        format!("Trend arrows: {}", on_off(config.show_trend_arrows)),
+    ];
+    let reduce_motion = match (motion::reduce_motion(), config.ignore_reduce_motion) {
+        (false, _) => "off",
+        (true, false) => "on (runner still, charts redraw on data)",
+        (true, true) => "on (ignored)",
+    };
+    lines.push(format!("Reduce Motion: {}", reduce_motion));
+    lines.push(if rules.net {
+        format!(
+            "Auto-hide NET: below {} KB/s for {} polls",
+            rules.net_idle_kbps, rules.after_polls
+        )
+    } else {
+        "Auto-hide NET: off".to_string()
+    });
+    lines.push(if rules.temp {
+        format!(
+            "Auto-hide TEMP: below {:.0}C for {} polls",
+            rules.temp_cool_c, rules.after_polls
+        )
+    } else {
+        "Auto-hide TEMP: off".to_string()
+    });
+    lines.push(format!(
+        "Status items: {}",
+        config.visible_modules.join(", ")
+    ));
+    lines.push(format!("Color scheme: {}", config.color_scheme.id()));
+    lines.push(format!(
+        "Chart auto-zoom: {}",
+        on_off(config.chart_auto_zoom)
+    ));
+    lines.push(format!(
+        "Chart temperature axis: {}",
+        if config.chart_temp_fixed_range {
+            "fixed"
+        } else {
+            "auto"
+        }
+    ));
+    lines.push(format!("Chart smoothing: {}", config.chart_smoothing.id()));
+    lines.push(format!("Network chart: {}", config.net_chart_style.id()));
+    lines.push(format!("Chart theme: {}", config.chart_theme.id()));
+    lines.push(format!("Network display: {}", config.net_display.id()));
+    lines.push(match config.ping_target.as_deref() {
+        Some(target) => format!(
+            "Latency probe: {} (red above {} ms)",
+            target, config.latency_warn_ms
+        ),
+        None => "Latency probe: off".to_string(),
+    });
+    let order: Vec<&str> = config.module_order().iter().map(|m| m.id()).collect();
+    lines.push(format!("Module order: {}", order.join(", ")));
+    let levels = &config.alert_thresholds;
+    lines.push(format!(
+        "Alerts: CPU {:.0}/{:.0}%, MEM {:.0}/{:.0}%, TEMP {:.0}/{:.0}C, cooldown {}s",
+        levels.cpu.warning,
+        levels.cpu.critical,
+        levels.memory.warning,
+        levels.memory.critical,
+        levels.temperature.warning,
+        levels.temperature.critical,
+        COOLDOWN_SECS
+    ));
+    let is_set = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+    lines.push(format!(
+        "Alert webhook: {}",
+        on_off(is_set(&config.alert_webhook_url))
+    ));
+    lines.push(format!(
+        "Alert command: {}",
+        on_off(is_set(&config.alert_command))
+    ));
+    lines.push(match config.disk_fill_alert_mins {
+        0 => "Disk filling alert: off".to_string(),
+        mins => format!("Disk filling alert: full within {} min", mins),
+    });
+    lines.push(match config.daily_summary {
+        Some(hour) => format!("Daily summary: at {:02}:00", hour),
+        None => "Daily summary: off".to_string(),
+    });
+    lines.push(format!(
+        "Stats file: {}",
+        config
+            .stats_output_path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "off".to_string())
+    ));
+    lines.push(format!(
+        "Launch at login: {}",
+        on_off(config.launch_at_login)
+    ));
+    lines
+}
+
+/// Internal counters: tick timings, allocations and per-task sampler status.
+pub fn instrumentation(ticks: &TickStats, sampler: &[TaskStatus]) -> Vec<String> {
+    let average = ticks
+        .total
+        .checked_div(ticks.count.max(1) as u32)
+        .unwrap_or_default();
+    let mut lines = vec![format!(
+        "Ticks: {} (last {:?}, avg {:?}, slowest {:?}, {} overruns)",
+        ticks.count, ticks.last, average, ticks.slowest, ticks.overruns
+    )];
+    lines.push(format!(
+        "Allocations: {} ({} freed, {} live)",
+        ALLOCATIONS.load(Ordering::Relaxed),
+        DEALLOCATIONS.load(Ordering::Relaxed),
+        format_bytes(LIVE_BYTES.load(Ordering::Relaxed) as u64)
+    ));
+    let (applied, skipped) = tray::title_update_counts();
+    lines.push(format!(
+        "Title updates: {} applied, {} skipped (unchanged)",
+        applied, skipped
+    ));
+    for task in sampler {
+        let last = task
+            .last_duration
+            .map(|d| format!("{:?}", d))
+            .unwrap_or_else(|| "never ran".to_string());
+        lines.push(format!(
+            "Sampler {}: last {}, {} failures",
+            task.name, last, task.failures
+        ));
+    }
+    lines
+}
+
+fn diagnostics_dir() -> PathBuf {
+    config_dir().join("diagnostics")
+}
+
+/// Sample the app's own process for 30 s on a background thread.
+///
+/// Writes `sample.txt` and `internal.txt` (the given lines) into a new
+/// `diagnostics/profile-<time>` directory, then reveals it in Finder.
+pub fn start_profile(internal: Vec<String>) {
+    if PROFILING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let dir = diagnostics_dir().join(format!("profile-{}", unix_now()));
+    let pid = std::process::id();
+    let spawned = thread::Builder::new()
+        .name("profile".to_string())
+        .spawn(move || {
+            match run_profile(&dir, pid, &internal) {
+                Ok(()) => {
+                    notify(
+                        t("Profile Complete"),
+                        &format!("Saved to {}", dir.display()),
+                    );
+                    let _ = Command::new("open").arg("-R").arg(&dir).spawn();
+                }
+                Err(e) => notify(t("Profile Failed"), &e),
+            }
+            PROFILING.store(false, Ordering::SeqCst);
+        });
+    if spawned.is_err() {
+        PROFILING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn run_profile(dir: &Path, pid: u32, internal: &[String]) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    fs::write(dir.join("internal.txt"), internal.join("\n") + "\n").map_err(|e| e.to_string())?;
+    let output = Command::new("sample")
+        .arg(pid.to_string())
+        .arg(PROFILE_SECS.to_string())
+        .arg("-file")
+        .arg(dir.join("sample.txt"))
+        .output()
+        .map_err(|e| format!("sample is not available: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("sample exited with {}", output.status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn late_and_slow_ticks_are_overruns() {
+        let interval = Duration::from_secs(2);
+        let start = Instant::now();
+        let mut ticks = TickStats::default();
+        ticks.record(start, Duration::from_millis(20), interval);
+        ticks.record(start + interval, Duration::from_millis(20), interval);
+        assert_eq!(ticks.overruns, 0);
+        // Held up for a whole extra interval
+        ticks.record(start + interval * 3, Duration::from_millis(20), interval);
+        assert_eq!(ticks.overruns, 1);
+        // Took longer than the interval itself
+        ticks.record(start + interval * 4, Duration::from_secs(3), interval);
+        assert_eq!(ticks.overruns, 2);
+        // Judged by the interval the last tick was waiting for
+        let faster = interval / 4;
+        let quick = Duration::from_millis(20);
+        ticks.record(start + interval * 5, quick, faster);
+        ticks.record(start + interval * 5 + faster, quick, faster);
+        assert_eq!(ticks.overruns, 2);
+        assert_eq!(ticks.count, 6);
+    }
+
+    #[test]
+    fn allocations_are_counted() {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let buffer = Vec::<u8>::with_capacity(64);
+        assert!(ALLOCATIONS.load(Ordering::Relaxed) > before);
+        drop(buffer);
+        let lines = instrumentation(&TickStats::default(), &[]);
+        assert!(lines.iter().any(|line| line.starts_with("Allocations: ")));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::config::config_dir;
+use crate::diagnostics;
+use crate::scheduler::{Scheduler, TaskPriority};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+/// A heartbeat older than this is stale
+const STALE_AFTER_SECS: u64 = 180;
+
+pub const HEALTH_OK: i32 = 0;
+pub const HEALTH_STALE: i32 = 1;
+pub const HEALTH_MISSING: i32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct Heartbeat {
+    written_at: u64,
+    last_sample_at: u64,
+    version: String,
+    /// Wall-clock time the machine spent asleep between the last two heartbeats
+    last_sleep_gap_secs: u64,
+    /// Polls that came late or ran longer than the poll interval since launch
+    #[serde(default)]
+    overruns: u64,
+}
+
+fn heartbeat_path() -> PathBuf {
+    config_dir().join("heartbeat.json")
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Write `heartbeat.json` every minute. `last_sample_at` is updated by the
+/// poll loop with the unix time of the latest sample.
+pub fn register_heartbeat(scheduler: &Scheduler, last_sample_at: Arc<AtomicU64>) {
+    let mut previous: Option<(Instant, u64)> = None;
+    let mut last_sleep_gap_secs = 0;
+    scheduler.register(
+        "heartbeat",
+        TaskPriority::High,
+        HEARTBEAT_INTERVAL,
+        Duration::from_secs(5),
+        move || {
+            let now = Instant::now();
+            let wall = unix_now();
+            // Instant stops while asleep, wall-clock time does not
+            if let Some((prev_instant, prev_wall)) = previous {
+                let awake = now.duration_since(prev_instant).as_secs();
+                let gap = wall.saturating_sub(prev_wall).saturating_sub(awake);
+                if gap > 0 {
+                    last_sleep_gap_secs = gap;
+                }
+            }
+            previous = Some((now, wall));
+
+            let heartbeat = Heartbeat {
+                written_at: wall,
+                last_sample_at: last_sample_at.load(Ordering::Relaxed),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                last_sleep_gap_secs,
+                overruns: diagnostics::overrun_count(),
+            };
+            let json = serde_json::to_string_pretty(&heartbeat).map_err(|e| e.to_string())?;
+            let path = heartbeat_path();
+            let tmp = path.with_extension("json.tmp");
+            fs::create_dir_all(config_dir()).map_err(|e| e.to_string())?;
+            fs::write(&tmp, json).map_err(|e| e.to_string())?;
+            fs::rename(&tmp, &path).map_err(|e| e.to_string())
+        },
+    );
+}
+
+/// `--check-health`: report heartbeat freshness and return the exit code.
+pub fn check() -> i32 {
+    let Some(heartbeat) = fs::read_to_string(heartbeat_path())
+        .ok()
+        .and_then(|data| serde_json::from_str::<Heartbeat>(&data).ok())
+    else {
+        println!(
+            "missing: no readable heartbeat at {}",
+            heartbeat_path().display()
+        );
+        return HEALTH_MISSING;
+    };
+
+    let now = unix_now();
+    let age = now.saturating_sub(heartbeat.written_at);
+    let sample_age = now.saturating_sub(heartbeat.last_sample_at);
+    if age <= STALE_AFTER_SECS && sample_age <= STALE_AFTER_SECS {
+        println!(
+            "ok: heartbeat {}s ago, last sample {}s ago, {} overruns, v{}",
+            age, sample_age, heartbeat.overruns, heartbeat.version
+        );
+        return HEALTH_OK;
+    }
+
+    // Heartbeat went stale while the machine slept: not a failure if we
+    // only woke up recently.
+    if let Some(wake) = last_wake_time() {
+        if wake > heartbeat.written_at && now.saturating_sub(wake) <= STALE_AFTER_SECS {
+            println!(
+                "ok: woke {}s ago, heartbeat {}s ago (last sleep gap {}s)",
+                now.saturating_sub(wake),
+                age,
+                heartbeat.last_sleep_gap_secs
+            );
+            return HEALTH_OK;
+        }
+    }
+
+    println!(
+        "stale: heartbeat {}s ago, last sample {}s ago (last sleep gap {}s, {} overruns)",
+        age, sample_age, heartbeat.last_sleep_gap_secs, heartbeat.overruns
+    );
+    HEALTH_STALE
+}
+
+/// Unix time of the last wake from sleep (`kern.waketime`).
+fn last_wake_time() -> Option<u64> {
+    let output = Command::new("sysctl")
+        .arg("-n")
+        .arg("kern.waketime")
+        .output()
+        .ok()?;
+    // "{ sec = 1700000000, usec = 123456 } Tue Nov ..."
+    let text = String::from_utf8_lossy(&output.stdout);
+    let rest = text.split("sec = ").nth(1)?;
+    let secs: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    secs.parse().ok().filter(|&s| s > 0)
+}
@@ -0,0 +1,70 @@
+//! Problems the app runs into itself: settings that could not be saved,
+//! runner frames that would not load, sensors that went quiet. Reported from
+//! anywhere through `report`, logged, and kept for the Issues submenu and
+//! the badge on the runner.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Older issues drop off once this many are kept
+const MAX_ISSUES: usize = 20;
+
+static ISSUES: Mutex<VecDeque<Issue>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, PartialEq)]
+pub struct Issue {
+    /// Part of the app it came from, e.g. "config"
+    pub source: &'static str,
+    pub message: String,
+    /// Times reported in a row
+    pub count: u32,
+}
+
+impl Issue {
+    /// "config: could not save … (×3)"
+    pub fn line(&self) -> String {
+        match self.count {
+            1 => format!("{}: {}", self.source, self.message),
+            n => format!("{}: {} (×{})", self.source, self.message, n),
+        }
+    }
+}
+
+/// Log an issue and keep it; the same one reported again counts up instead
+/// of adding a line
+pub fn report(source: &'static str, message: impl Into<String>) {
+    let message = message.into();
+    log::error!("{}: {}", source, message);
+    let Ok(mut issues) = ISSUES.lock() else {
+        return;
+    };
+    if let Some(last) = issues
+        .back_mut()
+        .filter(|last| last.source == source && last.message == message)
+    {
+        last.count += 1;
+        return;
+    }
+    if issues.len() >= MAX_ISSUES {
+        issues.pop_front();
+    }
+    issues.push_back(Issue {
+        source,
+        message,
+        count: 1,
+    });
+}
+
+/// Newest first
+pub fn recent() -> Vec<Issue> {
+    ISSUES
+        .lock()
+        .map(|issues| issues.iter().rev().cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn clear() {
+    if let Ok(mut issues) = ISSUES.lock() {
+        issues.clear();
+    }
+}
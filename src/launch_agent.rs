@@ -3,7 +3,7 @@ use std::path::PathBuf;
 
 const PLIST_LABEL: &str = "com.mac-state-monitor.app";
 
-fn plist_path() -> PathBuf {
+pub fn plist_path() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_default();
     PathBuf::from(home)
         .join("Library/LaunchAgents")
@@ -14,7 +14,9 @@ pub fn is_enabled() -> bool {
     plist_path().exists()
 }
 
-pub fn set_enabled(enabled: bool) {
+/// Write or remove the LaunchAgent plist; a plist that is already gone
+/// counts as disabled.
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
     let path = plist_path();
     if enabled {
         let exe = std::env::current_exe()
@@ -38,9 +40,15 @@ pub fn set_enabled(enabled: bool) {
 </plist>"#,
             PLIST_LABEL, exe
         );
-        let _ = fs::create_dir_all(path.parent().unwrap());
-        let _ = fs::write(&path, plist);
+        fs::create_dir_all(path.parent().unwrap())
+            .and_then(|()| fs::write(&path, plist))
+            .map_err(|e| format!("could not write {}: {}", path.display(), e))
     } else {
-        let _ = fs::remove_file(&path);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("could not remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
     }
 }
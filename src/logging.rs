@@ -0,0 +1,101 @@
+use crate::config::config_dir;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Rotate msm.log once it grows past this
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// msm.log plus msm.log.1 and msm.log.2
+const KEPT_LOG_FILES: usize = 3;
+
+pub fn log_dir() -> PathBuf {
+    config_dir().join("logs")
+}
+
+fn log_path(index: usize) -> PathBuf {
+    match index {
+        0 => log_dir().join("msm.log"),
+        n => log_dir().join(format!("msm.log.{}", n)),
+    }
+}
+
+/// Writes to stderr and to a size-rotated file in `log_dir()`.
+struct FileLogger {
+    file: Mutex<Option<File>>,
+}
+
+impl FileLogger {
+    /// Shift msm.log → msm.log.1 → msm.log.2, dropping the oldest.
+    fn rotate(file: &mut Option<File>) {
+        *file = None;
+        for index in (1..KEPT_LOG_FILES).rev() {
+            let _ = fs::rename(log_path(index - 1), log_path(index));
+        }
+        *file = open_log();
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let line = format!(
+            "{} {:<5} {}: {}",
+            ts,
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        eprintln!("{}", line);
+
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        let Some(f) = file.as_mut() else {
+            return;
+        };
+        let _ = writeln!(f, "{}", line);
+        if f.metadata().map(|m| m.len()).unwrap_or(0) > MAX_LOG_BYTES {
+            Self::rotate(&mut file);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(f) = file.as_mut() {
+                let _ = f.flush();
+            }
+        }
+    }
+}
+
+fn open_log() -> Option<File> {
+    fs::create_dir_all(log_dir()).ok()?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(0))
+        .ok()
+}
+
+/// Install the logger. Without a writable log folder it still logs to stderr.
+pub fn init() {
+    let logger = FileLogger {
+        file: Mutex::new(open_log()),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
@@ -1,31 +1,80 @@
 mod alert;
 mod app;
+mod cli;
 mod config;
+mod diagnostics;
+mod format;
+mod health;
+mod issues;
 mod launch_agent;
+mod logging;
 mod model;
 mod monitor;
+mod replay;
+mod report;
+mod scheduler;
+mod settings_transfer;
+mod signals;
+mod strings;
+mod summary;
 mod ui;
+mod uninstall;
 
 use app::App;
-use config::LAUNCH_AT_LOGIN_ID;
-use std::time::{Duration, Instant};
-use tao::event::{Event, WindowEvent};
+use objc2::MainThreadMarker;
+use std::time::Instant;
+use tao::event::{ElementState, Event, MouseButton, WindowEvent};
 use tao::event_loop::{ControlFlow, EventLoopBuilder};
-use ui::chart_window::ChartMode;
-use ui::tray::{
-    take_pending_event, QUIT_ID, RUNNER_ALL_ID, RUNNER_CATEGORY_PREFIX, RUNNER_DISPLAY_PREFIX,
-    RUNNER_IMPORT_ID, RUNNER_TOGGLE_PREFIX, SHOW_CHARTS_ID, SHOW_TEMP_CHARTS_ID, TEMP_PREFIX,
-};
+use tao::keyboard::KeyCode;
+use ui::action::TrayAction;
+use ui::tray::{take_menu_closed, take_pending_event};
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--check-health") {
+        std::process::exit(health::check());
+    }
+    // 无界面命令；需在初始化日志前运行，否则会重新创建配置目录
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--uninstall") {
+        std::process::exit(uninstall::cli(&args));
+    }
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    let replay = match replay::from_args(&args) {
+        Some(Ok(replay)) => Some(replay),
+        Some(Err(e)) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+        None => None,
+    };
+
+    logging::init();
+
     let event_loop = EventLoopBuilder::<()>::with_user_event().build();
 
-    let mut app = App::new();
+    // AppKit 状态栏项只能在主线程创建
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::error!("not running on the main thread; exiting");
+        std::process::exit(1);
+    };
+    ui::motion::watch_reduce_motion();
+    ui::appearance::watch_appearance();
+    ui::session::watch_session();
+    let mut app = match replay {
+        Some((source, speed)) => App::replay(mtm, source, speed),
+        None => App::new(mtm),
+    };
     app.tick();
+    signals::quit_on_sigterm();
+    ui::url_scheme::install();
 
-    let mut poll_interval = Duration::from_secs(app.config().poll_interval_secs);
+    let mut poll_interval = app.poll_interval();
     let mut last_tick = Instant::now();
-    let animation_interval = Duration::from_millis(40);
+    let mut fast_poll_interval = app.fast_poll_interval();
+    let mut last_fast_tick = Instant::now();
+    let mut animation_interval = app.animation_interval();
     let mut last_animation = Instant::now();
 
     event_loop.run(move |event, event_loop, control_flow| {
@@ -36,20 +85,33 @@ fn main() {
             // 检查是否需要 tick
             if now.duration_since(last_tick) >= poll_interval {
                 app.tick();
-                poll_interval = Duration::from_secs(app.config().poll_interval_secs);
+                poll_interval = app.poll_interval();
+                fast_poll_interval = app.fast_poll_interval();
+                animation_interval = app.animation_interval();
                 last_tick = now;
+                last_fast_tick = now;
+            } else if fast_poll_interval.is_some_and(|i| now.duration_since(last_fast_tick) >= i) {
+                // 轮询间隔较长时，网络速率每秒刷新
+                app.tick_fast();
+                last_fast_tick = now;
             }
 
             // 检查是否需要动画更新
             if now.duration_since(last_animation) >= animation_interval {
                 app.animate(now);
+                // 菜单或图表窗口使用中时放慢动画
+                animation_interval = app.animation_interval();
                 last_animation = now;
             }
 
             // 计算下次唤醒时间
             let next_poll = last_tick + poll_interval;
             let next_animation = last_animation + animation_interval;
-            *control_flow = ControlFlow::WaitUntil(next_poll.min(next_animation));
+            let mut next_wake = next_poll.min(next_animation);
+            if let Some(interval) = fast_poll_interval {
+                next_wake = next_wake.min(last_fast_tick + interval);
+            }
+            *control_flow = ControlFlow::WaitUntil(next_wake);
         }
 
         match event {
@@ -58,53 +120,223 @@ fn main() {
                 window_id,
                 ..
             } => {
+                if let Some((chart, _)) = app.chart_window_for(window_id) {
+                    chart.handle_close();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { event, .. },
+                window_id,
+                ..
+            } if event.state == ElementState::Pressed => {
                 if app.chart_window.window_id() == Some(window_id) {
-                    app.chart_window.handle_close();
+                    match event.physical_key {
+                        KeyCode::KeyS => app.save_chart_image(),
+                        KeyCode::KeyZ => app.toggle_chart_auto_zoom(),
+                        KeyCode::KeyP => app.toggle_chart_pageouts(),
+                        KeyCode::Space => app.toggle_chart_pause(),
+                        KeyCode::KeyM => app.toggle_chart_mini_mode(event_loop),
+                        key => app.chart_window.handle_key(key, &app.history),
+                    }
+                } else if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.handle_key(event.physical_key, history);
                 }
             }
-            Event::RedrawRequested(window_id) => {
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                window_id,
+                ..
+            } => {
+                if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.set_cursor(Some((position.x, position.y)), history);
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                window_id,
+                ..
+            } => {
+                if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.set_cursor(None, history);
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                window_id,
+                ..
+            } => {
+                let dragged = app
+                    .chart_window_for(window_id)
+                    .is_some_and(|(chart, _)| chart.drag());
+                // 拖动窗口时收不到松开事件，改由移动事件保持 runner 暂停
+                app.chart_pressed(window_id, !dragged);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Released,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                window_id,
+                ..
+            } => app.chart_pressed(window_id, false),
+            Event::WindowEvent {
+                event: WindowEvent::Focused(false),
+                window_id,
+                ..
+            } => {
+                app.chart_pressed(window_id, false);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Moved(position),
+                window_id,
+                ..
+            } => {
+                app.chart_touched();
                 if app.chart_window.window_id() == Some(window_id) {
-                    app.chart_window.render(&app.history);
+                    app.chart_window_moved(position);
+                } else {
+                    app.pop_out_moved(window_id);
                 }
             }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(size),
+                window_id,
+                ..
+            } => {
+                app.chart_touched();
+                if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.handle_resize(size, history);
+                }
+                app.pop_out_moved(window_id);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Focused(true),
+                window_id,
+                ..
+            } => {
+                // 从 Dock 或其他桌面切回：被遮挡的窗口会跳过渲染
+                if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.render(history);
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ScaleFactorChanged { .. },
+                window_id,
+                ..
+            } => {
+                // 移动到像素密度不同的显示器
+                if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.render(history);
+                }
+            }
+            Event::RedrawRequested(window_id) => {
+                if let Some((chart, history)) = app.chart_window_for(window_id) {
+                    chart.render(history);
+                }
+            }
+            Event::LoopDestroyed => app.shutdown(),
             _ => {}
         }
 
+        // 睡眠、唤醒或锁屏时暂停或恢复后台采样
+        if ui::session::take_changed() {
+            app.sync_sampler_suspension();
+        }
+
+        // 补上菜单打开期间暂缓的读数
+        if take_menu_closed() {
+            app.menu_closed();
+        }
+
         // Handle native menu events
         if let Some(action) = take_pending_event() {
-            match action.as_str() {
-                QUIT_ID => *control_flow = ControlFlow::Exit,
-                SHOW_CHARTS_ID => app.toggle_charts(event_loop, ChartMode::All),
-                SHOW_TEMP_CHARTS_ID => app.toggle_charts(event_loop, ChartMode::TempOnly),
-                LAUNCH_AT_LOGIN_ID => app.toggle_launch_at_login(),
-                RUNNER_ALL_ID => app.select_all_runners(),
-                RUNNER_IMPORT_ID => app.import_custom_runner(),
-                _ if action.starts_with("interval_") => {
-                    if let Ok(secs) = action.trim_start_matches("interval_").parse::<u64>() {
-                        app.set_poll_interval(secs);
-                    }
+            match action {
+                TrayAction::Quit => {
+                    app.shutdown();
+                    *control_flow = ControlFlow::Exit;
                 }
-                _ if action.starts_with(RUNNER_DISPLAY_PREFIX) => {
-                    if let Ok(secs) = action
-                        .trim_start_matches(RUNNER_DISPLAY_PREFIX)
-                        .parse::<u64>()
-                    {
-                        app.set_runner_display_secs(secs);
+                TrayAction::Uninstall => {
+                    if app.uninstall() {
+                        *control_flow = ControlFlow::Exit;
                     }
                 }
-                _ if action.starts_with(RUNNER_CATEGORY_PREFIX) => {
-                    let category = action.trim_start_matches(RUNNER_CATEGORY_PREFIX).to_string();
-                    app.select_runner_category(category);
-                }
-                _ if action.starts_with(RUNNER_TOGGLE_PREFIX) => {
-                    let runner_id = action.trim_start_matches(RUNNER_TOGGLE_PREFIX).to_string();
-                    app.toggle_runner_in_rotation(runner_id);
+                TrayAction::ShowCharts(mode) => app.toggle_charts(event_loop, mode),
+                TrayAction::PopOutChart(mode) => app.pop_out_chart(event_loop, mode),
+                TrayAction::ToggleChartPause => app.toggle_chart_pause(),
+                TrayAction::SaveChartImage => app.save_chart_image(),
+                TrayAction::ToggleChartMiniMode => app.toggle_chart_mini_mode(event_loop),
+                TrayAction::ToggleChartStats => app.toggle_chart_stats(),
+                TrayAction::ToggleChartTempRange => app.toggle_chart_temp_range(),
+                TrayAction::SetChartSmoothing(smoothing) => app.set_chart_smoothing(smoothing),
+                TrayAction::SetNetChartStyle(style) => app.set_net_chart_style(style),
+                TrayAction::SetChartTheme(theme) => app.set_chart_theme(theme),
+                TrayAction::CopyStats => app.copy_stats(false),
+                TrayAction::CopyStatsJson => app.copy_stats(true),
+                TrayAction::ExportHistory => app.export_history(),
+                TrayAction::TestAlert => app.send_test_alert(),
+                TrayAction::ToggleAlertRecovery => app.toggle_alert_recovery(),
+                TrayAction::ToggleAlertPause => app.toggle_alert_pause(),
+                TrayAction::ToggleVolumeNotifications => app.toggle_volume_notifications(),
+                TrayAction::SetAlertSound(sound) => app.set_alert_sound(sound),
+                TrayAction::SetAlertPreset(preset) => app.set_alert_preset(preset),
+                TrayAction::ProfileApp => app.profile_app(),
+                TrayAction::OpenLogFolder => app.open_log_folder(),
+                TrayAction::ClearIssues => app.clear_issues(),
+                TrayAction::OpenConfigFolder => app.open_config_folder(),
+                TrayAction::RevealCustomFrames => app.reveal_custom_frames(),
+                TrayAction::ToggleLaunchAtLogin => app.toggle_launch_at_login(),
+                TrayAction::ExportSettings => app.export_settings(),
+                TrayAction::ImportSettings => app.import_settings(),
+                TrayAction::ResetSessionCounters => app.reset_session_counters(),
+                TrayAction::ResetDailyTraffic => app.reset_daily_traffic(),
+                TrayAction::SetNetDisplay(display) => app.set_net_display(display),
+                TrayAction::EjectDisk(mount_point) => app.eject_disk(mount_point),
+                TrayAction::ToggleDiskMount(mount_point) => app.toggle_disk_mount(&mount_point),
+                TrayAction::SwitchProfile(name) => app.switch_profile(&name),
+                TrayAction::SaveProfile => app.prompt_save_profile(),
+                TrayAction::SetPollInterval(secs) => app.set_poll_interval(secs),
+                TrayAction::CustomPollInterval => app.prompt_poll_interval(),
+                TrayAction::ToggleAutoHide(module) => app.toggle_auto_hide(module),
+                TrayAction::ToggleDigest => app.toggle_digest(),
+                TrayAction::SetColorScheme(scheme) => app.set_color_scheme(scheme),
+                TrayAction::SetCpuStatusStyle(style) => app.set_cpu_status_style(style),
+                TrayAction::ToggleCpuClusterLabel => app.toggle_cpu_cluster_label(),
+                TrayAction::SetChartHotkey(hotkey) => app.set_chart_hotkey(hotkey),
+                TrayAction::SetModuleWidth(width) => app.set_module_width(width),
+                TrayAction::SetModuleOrder(order) => app.set_module_order(&order),
+                TrayAction::MoveModuleLeft(module) => app.move_module(module, true),
+                TrayAction::MoveModuleRight(module) => app.move_module(module, false),
+                TrayAction::SetOpenMenuUpdates(mode) => app.set_open_menu_updates(mode),
+                TrayAction::SetLowPowerBehavior(behavior) => app.set_low_power_behavior(behavior),
+                TrayAction::SetLanguage(language) => app.set_language(language),
+                TrayAction::SetTempComponent(component) => app.set_temp_component(component),
+                TrayAction::SetTempLabelStyle(style) => app.set_temp_label_style(style),
+                TrayAction::TogglePinnedSensor(label) => app.toggle_pinned_sensor(label),
+                TrayAction::SetRunnerDisplaySecs(secs) => app.set_runner_display_secs(secs),
+                TrayAction::CustomRunnerDisplaySecs => app.prompt_runner_display_secs(),
+                TrayAction::SetRunnerFrameMs(ms) => app.set_runner_frame_override(ms),
+                TrayAction::SetRunnerAnimationSpeed(ms) => app.set_runner_frame_ms(ms),
+                TrayAction::SetRunnerIdleBehavior(behavior) => {
+                    app.set_runner_idle_behavior(behavior)
                 }
-                _ if action.starts_with(TEMP_PREFIX) => {
-                    let component = action.trim_start_matches(TEMP_PREFIX).to_string();
-                    app.set_temp_component(component);
+                TrayAction::ToggleRunner(runner_id) => app.toggle_runner_in_rotation(runner_id),
+                TrayAction::SelectRunnerCategory(category) => app.select_runner_category(category),
+                TrayAction::OnlyRunnerCategory(category) => {
+                    app.select_only_runner_category(category)
                 }
-                _ => {}
+                TrayAction::SelectAllRunners => app.select_all_runners(),
+                TrayAction::NextRunner => app.step_runner(true),
+                TrayAction::PreviousRunner => app.step_runner(false),
+                TrayAction::ToggleRunnerShuffle => app.toggle_runner_shuffle(),
+                TrayAction::ImportCustomRunner => app.import_custom_runner(),
+                TrayAction::ImportCustomRunnerFolder => app.import_custom_runner_folder(),
             }
         }
     });
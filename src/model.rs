@@ -1,109 +1,829 @@
+use crate::health::unix_now;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::collections::VecDeque;
+use std::ops::{Deref, Range};
 use std::time::Instant;
 
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
 pub struct SystemStats {
     pub cpu: CpuStats,
     pub memory: MemoryStats,
     pub disks: Vec<DiskStats>,
+    /// Every mounted volume, including the ones filtered out of `disks`
+    pub disk_mounts: Vec<String>,
     pub network: NetworkStats,
     pub temperature: TemperatureStats,
+    /// Refreshed every few polls; None until the first sample
+    pub system_load: Option<SystemLoadStats>,
+    /// Per-name process usage; empty unless process collection is enabled
+    pub processes: Vec<ProcessUsage>,
+    /// What the monitor itself costs
+    pub app_usage: AppUsage,
+    #[serde(skip)]
     pub timestamp: Instant,
 }
 
-#[derive(Clone, Debug)]
+/// This app's own CPU and memory use, plus how long its last poll and chart
+/// render took
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppUsage {
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub poll_ms: f64,
+    /// None until the chart window has rendered once
+    pub render_ms: Option<f64>,
+}
+
+/// System-wide process, thread and open file counts
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SystemLoadStats {
+    pub processes: u32,
+    pub threads: u32,
+    pub open_files: Option<u32>,
+}
+
+/// Usage of all processes sharing a name
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProcessUsage {
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct TemperatureReading {
     pub label: String,
     pub temp_c: f32,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct TemperatureStats {
     pub readings: Vec<TemperatureReading>,
+    /// Every valid sensor by its own name, before grouping into readings
+    pub raw: Vec<TemperatureReading>,
 }
 
 impl TemperatureStats {
-    pub fn find_temp(&self, label_fragment: &str) -> Option<f32> {
+    /// First reading whose label contains `label_fragment` (case-insensitive)
+    pub fn find(&self, label_fragment: &str) -> Option<&TemperatureReading> {
         let frag = label_fragment.to_lowercase();
         self.readings
             .iter()
             .find(|r| r.label.to_lowercase().contains(&frag))
-            .map(|r| r.temp_c)
+    }
+
+    pub fn hottest(&self) -> Option<&TemperatureReading> {
+        self.readings
+            .iter()
+            .max_by(|a, b| a.temp_c.total_cmp(&b.temp_c))
+    }
+
+    /// Add the raw sensors named in `pinned` as readings of their own, in
+    /// pin order, unless a reading already has that label.
+    pub fn pin(&mut self, pinned: &[String]) {
+        for label in pinned {
+            if self.readings.iter().any(|r| &r.label == label) {
+                continue;
+            }
+            if let Some(reading) = self.raw.iter().find(|r| &r.label == label) {
+                self.readings.push(reading.clone());
+            }
+        }
+    }
+}
+
+/// Least-squares slope of the readings among the last `window` polls, in
+/// units per poll; None with fewer than two readings.
+pub fn window_slope<T: Copy + Into<f64>>(data: &Series<T>, window: usize) -> Option<f64> {
+    let start = data.len().saturating_sub(window);
+    let points: Vec<(f64, f64)> = (start..data.len())
+        .filter_map(|i| Some((i as f64, (*data.reading(i)?).into())))
+        .collect();
+    let n = points.len();
+    if n < 2 {
+        return None;
+    }
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n as f64;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n as f64;
+    let mut num = 0.0;
+    let mut den = 0.0;
+    for (x, y) in points {
+        let dx = x - mean_x;
+        num += dx * (y - mean_y);
+        den += dx * dx;
+    }
+    Some(num / den)
+}
+
+/// Per-minute change below which a percent metric reads as flat
+const PERCENT_TREND_THRESHOLD: f64 = 5.0;
+/// Per-minute change in disk usage (%) below which it reads as flat; a
+/// disk filling this fast is writing gigabytes a minute
+const DISK_TREND_THRESHOLD: f64 = 0.5;
+/// Per-minute change (KB/s) in download plus upload below which the
+/// network reads as flat
+const NET_TREND_THRESHOLD: f64 = 100.0;
+/// Per-minute change (C) below which a temperature reads as flat
+const TEMP_TREND_THRESHOLD: f64 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Flat,
+}
+
+impl Trend {
+    /// Trend over the last minute of `data`, sampled `per_minute` times a minute.
+    pub fn over_window<T: Copy + Into<f64>>(
+        data: &Series<T>,
+        per_minute: f64,
+        threshold: f64,
+    ) -> Option<Self> {
+        let window = (per_minute.round() as usize).max(2);
+        let change = window_slope(data, window)? * per_minute;
+        Some(if change >= threshold {
+            Trend::Rising
+        } else if change <= -threshold {
+            Trend::Falling
+        } else {
+            Trend::Flat
+        })
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Trend::Rising => "↑",
+            Trend::Falling => "↓",
+            Trend::Flat => "→",
+        }
     }
 }
 
+/// 1-minute trends of the menu bar modules. The titles, their tooltips and
+/// the clipboard snapshot all read them, so they agree on direction. None
+/// until there are two samples.
+#[derive(Clone, Default)]
+pub struct Trends {
+    pub cpu: Option<Trend>,
+    pub mem: Option<Trend>,
+    /// The first disk, the one DISK shows
+    pub disk: Option<Trend>,
+    /// Download and upload rates combined
+    pub net: Option<Trend>,
+    /// By sensor label
+    pub temps: BTreeMap<String, Trend>,
+}
+
+impl Trends {
+    pub fn of(history: &HistoryBuffer, poll_interval_secs: f64) -> Self {
+        let per_minute = 60.0 / poll_interval_secs;
+        let window = (per_minute.round() as usize).max(2);
+        let skip = history.net_down.len().saturating_sub(window);
+        let mut net = Series::new(window);
+        let totals = history.net_down.iter().zip(history.net_up.iter());
+        for (down, up) in totals.skip(skip) {
+            net.push(down + up);
+        }
+        Self {
+            cpu: Trend::over_window(&history.cpu_usage, per_minute, PERCENT_TREND_THRESHOLD),
+            mem: Trend::over_window(&history.mem_usage, per_minute, PERCENT_TREND_THRESHOLD),
+            disk: Trend::over_window(&history.disk_usage, per_minute, DISK_TREND_THRESHOLD),
+            net: Trend::over_window(&net, per_minute, NET_TREND_THRESHOLD),
+            temps: history
+                .temps
+                .iter()
+                .filter_map(|(label, data)| {
+                    let trend = Trend::over_window(data, per_minute, TEMP_TREND_THRESHOLD)?;
+                    Some((label.clone(), trend))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn temp(&self, label: &str) -> Option<Trend> {
+        self.temps.get(label).copied()
+    }
+}
+
+/// Samples of one metric, oldest first; pushing past `cap` drops the oldest.
+/// Reads go through the underlying `VecDeque`.
+#[derive(Clone, Default)]
+pub struct Series<T> {
+    values: VecDeque<T>,
+    /// Parallel to `values`: the sample follows a gap in sampling
+    after_gap: VecDeque<bool>,
+    /// Parallel to `values`: the poll had no reading, and the value is
+    /// only a placeholder
+    missing: VecDeque<bool>,
+    /// `mark_gap` was called since the last push
+    gap_pending: bool,
+    cap: usize,
+}
+
+impl<T> Series<T> {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(cap),
+            after_gap: VecDeque::with_capacity(cap),
+            missing: VecDeque::with_capacity(cap),
+            gap_pending: false,
+            cap,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.push_slot(value, false);
+    }
+
+    /// A poll without a reading, such as a ping timeout: the series stays
+    /// in step with the others, charts leave a gap and aggregates skip it
+    pub fn push_missing(&mut self)
+    where
+        T: Default,
+    {
+        self.push_slot(T::default(), true);
+    }
+
+    fn push_slot(&mut self, value: T, missing: bool) {
+        if self.values.len() >= self.cap {
+            self.values.pop_front();
+            self.after_gap.pop_front();
+            self.missing.pop_front();
+        }
+        self.values.push_back(value);
+        let after_gap = std::mem::take(&mut self.gap_pending);
+        self.after_gap.push_back(after_gap);
+        self.missing.push_back(missing);
+    }
+
+    /// The reading at `index`; None past the end and for polls without one
+    pub fn reading(&self, index: usize) -> Option<&T> {
+        if *self.missing.get(index)? {
+            return None;
+        }
+        self.values.get(index)
+    }
+
+    /// Every reading, oldest first, leaving out polls without one
+    pub fn readings(&self) -> impl Iterator<Item = &T> {
+        self.values
+            .iter()
+            .zip(&self.missing)
+            .filter(|(_, &missing)| !missing)
+            .map(|(value, _)| value)
+    }
+
+    /// Samples were missed: the next one is not joined to the one before
+    pub fn mark_gap(&mut self) {
+        self.gap_pending = !self.values.is_empty();
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.after_gap.clear();
+        self.missing.clear();
+        self.gap_pending = false;
+    }
+
+    /// Index ranges of the runs between gaps, oldest first; empty while
+    /// the series is. Polls without a reading are left out of every run. A
+    /// gap before the oldest sample (its neighbour already dropped) or after
+    /// the newest (nothing pushed yet) splits nothing.
+    pub fn segments(&self) -> Vec<Range<usize>> {
+        let mut segments = Vec::new();
+        let mut start = None;
+        for (i, (&gap, &missing)) in self.after_gap.iter().zip(&self.missing).enumerate() {
+            match start {
+                Some(run) if missing || gap => {
+                    segments.push(run..i);
+                    start = (!missing).then_some(i);
+                }
+                Some(_) => {}
+                None => start = (!missing).then_some(i),
+            }
+        }
+        if let Some(run) = start {
+            segments.push(run..self.values.len());
+        }
+        segments
+    }
+}
+
+impl<T: Copy + Into<f64>> Series<T> {
+    /// Aggregates of the newest `window` polls, or of all of them when
+    /// there are fewer; None without a reading
+    pub fn aggregates(&self, window: usize) -> Option<Aggregates> {
+        let skip = self.values.len().saturating_sub(window);
+        self.aggregates_in(skip..self.values.len())
+    }
+
+    /// Aggregates of the polls in `range`; None without a reading
+    pub fn aggregates_in(&self, range: Range<usize>) -> Option<Aggregates> {
+        let readings = range.filter_map(|i| self.reading(i));
+        Aggregates::of(readings.map(|&v| v.into()))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Series<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<T> Deref for Series<T> {
+    type Target = VecDeque<T>;
+
+    fn deref(&self) -> &VecDeque<T> {
+        &self.values
+    }
+}
+
+/// Min, max, mean and 95th percentile of a run of samples
+#[derive(Clone, Copy)]
+pub struct Aggregates {
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    pub p95: f64,
+}
+
+impl Aggregates {
+    /// None without any finite sample; non-finite ones are skipped
+    pub fn of(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut sorted: Vec<f64> = values.into_iter().filter(|v| v.is_finite()).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        // Nearest-rank percentile
+        let p95_rank = ((n as f64 * 0.95).ceil() as usize).clamp(1, n);
+        Some(Self {
+            min: sorted[0],
+            max: sorted[n - 1],
+            avg: sorted.iter().sum::<f64>() / n as f64,
+            p95: sorted[p95_rank - 1],
+        })
+    }
+}
+
+/// Aggregates of the main history series over one trailing window
+pub struct HistoryAggregates {
+    pub cpu: Option<Aggregates>,
+    pub mem: Option<Aggregates>,
+    pub disk: Option<Aggregates>,
+    /// KB/s, like the series
+    pub net_down: Option<Aggregates>,
+    pub net_up: Option<Aggregates>,
+    pub pageouts: Option<Aggregates>,
+}
+
+#[derive(Clone)]
 pub struct HistoryBuffer {
-    pub temps: BTreeMap<String, VecDeque<f32>>,
-    pub cpu_usage: VecDeque<f32>,
-    pub mem_usage: VecDeque<f32>,
-    pub net_down: VecDeque<f64>,
-    pub net_up: VecDeque<f64>,
+    pub temps: BTreeMap<String, Series<f32>>,
+    pub cpu_usage: Series<f32>,
+    pub mem_usage: Series<f32>,
+    /// Usage of the volume the SSD item shows (the first disk)
+    pub disk_usage: Series<f32>,
+    pub net_down: Series<f64>,
+    pub net_up: Series<f64>,
+    /// Pages per second; 0 while no rate is known yet
+    pub pageouts: Series<f64>,
+    /// Milliseconds; a timeout is a poll without a reading, so charts
+    /// leave a gap
+    pub latency: Series<f64>,
+    /// Memory breakdown in bytes; empty while the breakdown is unavailable
+    pub mem_app: Series<f64>,
+    pub mem_wired: Series<f64>,
+    pub mem_compressed: Series<f64>,
+    /// Physical memory as of the last sample, in bytes
+    pub mem_total: f64,
+    /// One buffer per core; only filled once `track_cores` is set
+    pub cores: Vec<Series<f32>>,
+    pub track_cores: bool,
     pub max_points: usize,
+    /// The last hour of the main series, a row per minute
+    pub rollup: HourRollup,
+    /// CPU, memory and temperatures over the last few minutes, for alerts
+    pub recent: RecentHistory,
 }
 
 impl HistoryBuffer {
     pub fn new(max_points: usize) -> Self {
         Self {
             temps: BTreeMap::new(),
-            cpu_usage: VecDeque::with_capacity(max_points),
-            mem_usage: VecDeque::with_capacity(max_points),
-            net_down: VecDeque::with_capacity(max_points),
-            net_up: VecDeque::with_capacity(max_points),
+            cpu_usage: Series::new(max_points),
+            mem_usage: Series::new(max_points),
+            disk_usage: Series::new(max_points),
+            net_down: Series::new(max_points),
+            net_up: Series::new(max_points),
+            pageouts: Series::new(max_points),
+            latency: Series::new(max_points),
+            mem_app: Series::new(max_points),
+            mem_wired: Series::new(max_points),
+            mem_compressed: Series::new(max_points),
+            mem_total: 0.0,
+            cores: Vec::new(),
+            track_cores: false,
             max_points,
+            rollup: HourRollup::default(),
+            recent: RecentHistory::default(),
         }
     }
 
-    fn push_val_f32(buf: &mut VecDeque<f32>, val: f32, max: usize) {
-        if buf.len() >= max {
-            buf.pop_front();
+    /// Sampling stopped for a while (the Mac slept, or the poll interval
+    /// changed): charts break their lines here instead of bridging the gap
+    pub fn push_gap(&mut self) {
+        let percents = [
+            &mut self.cpu_usage,
+            &mut self.mem_usage,
+            &mut self.disk_usage,
+        ];
+        let temps = self.temps.values_mut();
+        for series in percents.into_iter().chain(temps).chain(&mut self.cores) {
+            series.mark_gap();
         }
-        buf.push_back(val);
+        for series in [
+            &mut self.net_down,
+            &mut self.net_up,
+            &mut self.pageouts,
+            &mut self.latency,
+            &mut self.mem_app,
+            &mut self.mem_wired,
+            &mut self.mem_compressed,
+        ] {
+            series.mark_gap();
+        }
+        self.recent.mark_gap();
     }
 
-    fn push_val_f64(buf: &mut VecDeque<f64>, val: f64, max: usize) {
-        if buf.len() >= max {
-            buf.pop_front();
+    /// Aggregates over the newest `window` samples of each series
+    pub fn aggregates(&self, window: usize) -> HistoryAggregates {
+        HistoryAggregates {
+            cpu: self.cpu_usage.aggregates(window),
+            mem: self.mem_usage.aggregates(window),
+            disk: self.disk_usage.aggregates(window),
+            net_down: self.net_down.aggregates(window),
+            net_up: self.net_up.aggregates(window),
+            pageouts: self.pageouts.aggregates(window),
         }
-        buf.push_back(val);
+    }
+
+    /// Aggregates of one temperature sensor; None when it has no history
+    pub fn temp_aggregates(&self, label: &str, window: usize) -> Option<Aggregates> {
+        self.temps.get(label)?.aggregates(window)
     }
 
     pub fn push(&mut self, stats: &super::model::SystemStats) {
         // Temperatures
-        for reading in &stats.temperature.readings {
-            let buf = self
-                .temps
-                .entry(reading.label.clone())
-                .or_insert_with(|| VecDeque::with_capacity(self.max_points));
-            if buf.len() >= self.max_points {
-                buf.pop_front();
-            }
-            buf.push_back(reading.temp_c);
-        }
+        let polls = self.cpu_usage.len();
+        push_temps(
+            &mut self.temps,
+            &stats.temperature.readings,
+            self.max_points,
+            polls,
+        );
 
         // CPU
-        Self::push_val_f32(&mut self.cpu_usage, stats.cpu.global_usage, self.max_points);
+        self.cpu_usage.push(stats.cpu.global_usage);
 
         // Memory
-        Self::push_val_f32(
-            &mut self.mem_usage,
-            stats.memory.usage_percent,
-            self.max_points,
-        );
+        self.mem_usage.push(stats.memory.usage_percent);
+        self.mem_total = stats.memory.total_bytes as f64;
+        match stats.memory.breakdown {
+            Some(breakdown) => {
+                for (buf, bytes) in [
+                    (&mut self.mem_app, breakdown.app_bytes),
+                    (&mut self.mem_wired, breakdown.wired_bytes),
+                    (&mut self.mem_compressed, breakdown.compressed_bytes),
+                ] {
+                    buf.push(bytes as f64);
+                }
+            }
+            // Keep the three series aligned: start over once data returns
+            None => {
+                self.mem_app.clear();
+                self.mem_wired.clear();
+                self.mem_compressed.clear();
+            }
+        }
+
+        // Per-core CPU; start over if the core count ever changes
+        if self.track_cores {
+            let per_core = &stats.cpu.per_core_usage;
+            if self.cores.len() != per_core.len() {
+                self.cores = vec![Series::new(self.max_points); per_core.len()];
+            }
+            for (buf, &usage) in self.cores.iter_mut().zip(per_core) {
+                buf.push(usage);
+            }
+        }
+
+        // Disk
+        match stats.disks.first() {
+            Some(disk) => self.disk_usage.push(disk.usage_percent),
+            None => self.disk_usage.push_missing(),
+        }
 
         // Network (convert to KB/s for readability)
         let down_kb = stats.network.received_per_sec as f64 / 1024.0;
         let up_kb = stats.network.transmitted_per_sec as f64 / 1024.0;
-        Self::push_val_f64(&mut self.net_down, down_kb, self.max_points);
-        Self::push_val_f64(&mut self.net_up, up_kb, self.max_points);
+        self.net_down.push(down_kb);
+        self.net_up.push(up_kb);
+
+        // Swap activity
+        let pageouts = stats
+            .memory
+            .swap_activity
+            .map(|a| a.pageouts_per_sec)
+            .unwrap_or(0.0);
+        self.pageouts.push(pageouts);
+
+        // Latency
+        match stats.network.latency_ms {
+            Some(ms) => self.latency.push(ms),
+            None => self.latency.push_missing(),
+        }
+
+        self.rollup.push(stats, unix_now());
+        self.recent.push(stats);
+    }
+}
+
+/// One poll's temperatures. Sensors that didn't answer get a poll without
+/// a reading, and a new sensor starts with `polls` of them, so every series
+/// stays in step with the others.
+fn push_temps(
+    temps: &mut BTreeMap<String, Series<f32>>,
+    readings: &[TemperatureReading],
+    cap: usize,
+    polls: usize,
+) {
+    for reading in readings {
+        temps
+            .entry(reading.label.clone())
+            .or_insert_with(|| {
+                let mut series = Series::new(cap);
+                for _ in 0..polls {
+                    series.push_missing();
+                }
+                series
+            })
+            .push(reading.temp_c);
+    }
+    for (label, series) in temps.iter_mut() {
+        if !readings.iter().any(|r| &r.label == label) {
+            series.push_missing();
+        }
+    }
+}
+
+/// Minutes of full-resolution history kept for alerts
+pub const RECENT_MINUTES: f64 = 5.0;
+/// `RECENT_MINUTES` at the shortest poll interval, half a second
+const RECENT_POINTS: usize = 600;
+
+/// CPU, memory and each temperature sensor over the last `RECENT_MINUTES`,
+/// for the trend and sparkline on alerts. The chart series only hold
+/// `max_points` polls, a minute at the default interval.
+#[derive(Clone)]
+pub struct RecentHistory {
+    pub cpu_usage: Series<f32>,
+    pub mem_usage: Series<f32>,
+    pub temps: BTreeMap<String, Series<f32>>,
+}
+
+impl Default for RecentHistory {
+    fn default() -> Self {
+        Self {
+            cpu_usage: Series::new(RECENT_POINTS),
+            mem_usage: Series::new(RECENT_POINTS),
+            temps: BTreeMap::new(),
+        }
+    }
+}
+
+impl RecentHistory {
+    /// Polls spanning `RECENT_MINUTES` at `poll_interval_secs`
+    pub fn window(poll_interval_secs: f64) -> usize {
+        (RECENT_MINUTES * 60.0 / poll_interval_secs).ceil() as usize
+    }
+
+    fn push(&mut self, stats: &SystemStats) {
+        let polls = self.cpu_usage.len();
+        push_temps(
+            &mut self.temps,
+            &stats.temperature.readings,
+            RECENT_POINTS,
+            polls,
+        );
+        self.cpu_usage.push(stats.cpu.global_usage);
+        self.mem_usage.push(stats.memory.usage_percent);
+    }
+
+    fn mark_gap(&mut self) {
+        let percents = [&mut self.cpu_usage, &mut self.mem_usage];
+        for series in percents.into_iter().chain(self.temps.values_mut()) {
+            series.mark_gap();
+        }
+    }
+}
+
+/// Minutes the hour rollup keeps, the current one included
+pub const ROLLUP_MINUTES: u64 = 60;
+
+/// Average and peak of one metric over a run of samples
+#[derive(Clone, Copy, Serialize)]
+pub struct AvgMax {
+    pub avg: f64,
+    pub max: f64,
+    #[serde(skip)]
+    samples: u32,
+}
+
+impl AvgMax {
+    /// Count `value` into `span`; non-finite values are skipped
+    fn add(span: &mut Option<Self>, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        match span {
+            Some(span) => {
+                span.samples += 1;
+                span.avg += (value - span.avg) / span.samples as f64;
+                span.max = span.max.max(value);
+            }
+            None => {
+                *span = Some(Self {
+                    avg: value,
+                    max: value,
+                    samples: 1,
+                })
+            }
+        }
+    }
+
+    /// Both runs as one
+    fn merge(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                let samples = a.samples + b.samples;
+                let total = a.avg * a.samples as f64 + b.avg * b.samples as f64;
+                Some(Self {
+                    avg: total / samples as f64,
+                    max: a.max.max(b.max),
+                    samples,
+                })
+            }
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+/// One minute of the main series; None where a metric had no reading
+#[derive(Clone, Default, Serialize)]
+pub struct MinuteSummary {
+    /// Unix time the minute starts at
+    pub start: u64,
+    /// Polls taken in the minute
+    pub samples: u32,
+    pub cpu: Option<AvgMax>,
+    pub mem: Option<AvgMax>,
+    pub disk: Option<AvgMax>,
+    /// KB/s, like the series
+    pub net_down: Option<AvgMax>,
+    pub net_up: Option<AvgMax>,
+    pub pageouts: Option<AvgMax>,
+}
+
+impl MinuteSummary {
+    fn merge(&mut self, other: &Self) {
+        self.samples += other.samples;
+        for (mine, theirs) in [
+            (&mut self.cpu, other.cpu),
+            (&mut self.mem, other.mem),
+            (&mut self.disk, other.disk),
+            (&mut self.net_down, other.net_down),
+            (&mut self.net_up, other.net_up),
+            (&mut self.pageouts, other.pageouts),
+        ] {
+            *mine = AvgMax::merge(*mine, theirs);
+        }
+    }
+}
+
+/// The main series over the last hour at one-minute resolution, for the
+/// menu's summary line and history exports. The series themselves only
+/// span the charts; an hour of raw polls would be thousands of points.
+#[derive(Clone, Default)]
+pub struct HourRollup {
+    /// Oldest first; the newest is still filling up
+    minutes: VecDeque<MinuteSummary>,
+}
+
+impl HourRollup {
+    /// Count a poll taken at `unix_secs` into its minute. A clock that
+    /// went back counts into the newest minute.
+    pub fn push(&mut self, stats: &SystemStats, unix_secs: u64) {
+        let start = unix_secs - unix_secs % 60;
+        if self
+            .minutes
+            .back()
+            .is_none_or(|minute| minute.start < start)
+        {
+            self.minutes.push_back(MinuteSummary {
+                start,
+                ..MinuteSummary::default()
+            });
+        }
+        let newest = self.minutes.back().map_or(start, |minute| minute.start);
+        while self
+            .minutes
+            .front()
+            .is_some_and(|minute| minute.start + ROLLUP_MINUTES * 60 <= newest)
+        {
+            self.minutes.pop_front();
+        }
+        let Some(minute) = self.minutes.back_mut() else {
+            return;
+        };
+        minute.samples += 1;
+        AvgMax::add(&mut minute.cpu, stats.cpu.global_usage as f64);
+        AvgMax::add(&mut minute.mem, stats.memory.usage_percent as f64);
+        if let Some(disk) = stats.disks.first() {
+            AvgMax::add(&mut minute.disk, disk.usage_percent as f64);
+        }
+        AvgMax::add(
+            &mut minute.net_down,
+            stats.network.received_per_sec as f64 / 1024.0,
+        );
+        AvgMax::add(
+            &mut minute.net_up,
+            stats.network.transmitted_per_sec as f64 / 1024.0,
+        );
+        if let Some(activity) = stats.memory.swap_activity {
+            AvgMax::add(&mut minute.pageouts, activity.pageouts_per_sec);
+        }
+    }
+
+    /// Oldest first
+    pub fn minutes(&self) -> &VecDeque<MinuteSummary> {
+        &self.minutes
+    }
+
+    /// Every minute as one; None while empty
+    pub fn overall(&self) -> Option<MinuteSummary> {
+        let (first, rest) = (self.minutes.front()?, self.minutes.range(1..));
+        let mut overall = first.clone();
+        for minute in rest {
+            overall.merge(minute);
+        }
+        Some(overall)
     }
 }
 
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct CpuStats {
     pub global_usage: f32,
     pub per_core_usage: Vec<f32>,
     pub core_count: usize,
+    /// Cluster averages on Apple Silicon; None on Intel
+    pub p_core_usage: Option<f32>,
+    pub e_core_usage: Option<f32>,
+    /// Average cluster frequency over the last poll, when IOReport has it
+    pub p_core_mhz: Option<u32>,
+    pub e_core_mhz: Option<u32>,
+    /// 1, 5 and 15 minute load averages
+    pub load_average: [f64; 3],
+    pub uptime_secs: u64,
 }
 
+impl CpuStats {
+    /// 1-minute load relative to core count, as a percentage
+    pub fn load_percent(&self) -> f32 {
+        if self.core_count == 0 {
+            return 0.0;
+        }
+        (self.load_average[0] / self.core_count as f64 * 100.0) as f32
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct MemoryStats {
     pub total_bytes: u64,
     pub used_bytes: u64,
@@ -111,48 +831,360 @@ pub struct MemoryStats {
     pub swap_total_bytes: u64,
     pub swap_used_bytes: u64,
     pub usage_percent: f32,
+    /// None on the first poll, before there is a delta
+    pub swap_activity: Option<SwapActivity>,
+    /// None when the VM statistics can't be read
+    pub breakdown: Option<MemoryBreakdown>,
 }
 
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MemoryBreakdown {
+    pub app_bytes: u64,
+    pub wired_bytes: u64,
+    pub compressed_bytes: u64,
+}
+
+impl MemoryBreakdown {
+    pub fn used_bytes(&self) -> u64 {
+        self.app_bytes + self.wired_bytes + self.compressed_bytes
+    }
+}
+
+/// Paging rates in pages per second
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SwapActivity {
+    pub pageins_per_sec: f64,
+    pub pageouts_per_sec: f64,
+    pub compressions_per_sec: f64,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct DiskStats {
     pub name: String,
     pub mount_point: String,
     pub total_bytes: u64,
     pub available_bytes: u64,
     pub usage_percent: f32,
+    /// e.g. "apfs", "exfat"
+    pub file_system: String,
+    pub is_removable: bool,
+    pub is_read_only: bool,
+    /// SMART data of the internal drive, on its volumes; None without smartctl
+    pub health: Option<DriveHealth>,
 }
 
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DriveHealth {
+    /// SMART overall-health self-assessment
+    pub passed: bool,
+    /// NVMe "percentage used" of the rated endurance; can exceed 100
+    pub wear_percent: Option<u32>,
+    pub temp_c: Option<f32>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct NetworkStats {
     pub total_received_bytes: u64,
     pub total_transmitted_bytes: u64,
+    /// Traffic since launch or the last reset of the session counters
+    pub session_received_bytes: u64,
+    pub session_transmitted_bytes: u64,
+    /// Traffic since local midnight; zero when not counted (CLI modes)
+    pub today_received_bytes: u64,
+    pub today_transmitted_bytes: u64,
     pub received_per_sec: u64,
     pub transmitted_per_sec: u64,
+    pub wifi: Option<WifiInfo>,
+    /// None unless `show_public_ip` is on
+    pub public_ip: Option<PublicIpInfo>,
+    /// Connect time to `ping_target` in ms; None while off or after a
+    /// timeout
+    pub latency_ms: Option<f64>,
+    /// None until lsof has answered, or when it can't run
+    pub connections: Option<ConnectionStats>,
+}
+
+/// Sockets of the processes lsof can see
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ConnectionStats {
+    pub established: u32,
+    /// Open TCP and UDP sockets in any state
+    pub sockets: u32,
+    /// Sorted by port
+    pub listening: Vec<ListeningPort>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ListeningPort {
+    pub port: u16,
+    /// "*", "127.0.0.1", "[::1]", ...
+    pub address: String,
+    pub process: String,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PublicIpInfo {
+    /// None before the first lookup succeeds or after one fails
+    pub address: Option<String>,
+    /// Active VPN interfaces, e.g. "utun3"
+    pub vpn_interfaces: Vec<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WifiInfo {
+    /// None when macOS withholds the SSID (missing location permission)
+    pub ssid: Option<String>,
+    pub rssi_dbm: i32,
+    pub noise_dbm: i32,
+    pub channel: String,
+    pub tx_rate_mbps: u32,
 }
 
 impl Default for SystemStats {
     fn default() -> Self {
         Self {
-            cpu: CpuStats {
-                global_usage: 0.0,
-                per_core_usage: vec![],
-                core_count: 0,
-            },
-            memory: MemoryStats {
-                total_bytes: 0,
-                used_bytes: 0,
-                available_bytes: 0,
-                swap_total_bytes: 0,
-                swap_used_bytes: 0,
-                usage_percent: 0.0,
-            },
+            cpu: CpuStats::default(),
+            memory: MemoryStats::default(),
             disks: vec![],
-            network: NetworkStats {
-                total_received_bytes: 0,
-                total_transmitted_bytes: 0,
-                received_per_sec: 0,
-                transmitted_per_sec: 0,
-            },
+            disk_mounts: vec![],
+            network: NetworkStats::default(),
             temperature: TemperatureStats::default(),
+            system_load: None,
+            processes: vec![],
+            app_usage: AppUsage::default(),
             timestamp: Instant::now(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::mock::sample;
+
+    #[test]
+    fn push_keeps_the_newest_max_points() {
+        let mut history = HistoryBuffer::new(3);
+        for cpu in [10.0, 20.0, 30.0, 40.0] {
+            history.push(&sample(cpu, 50.0, 45.0));
+        }
+        assert_eq!(
+            history.cpu_usage.iter().copied().collect::<Vec<_>>(),
+            [20.0, 30.0, 40.0]
+        );
+        assert_eq!(history.mem_usage.len(), 3);
+        assert_eq!(history.temps["CPU"].len(), 3);
+        // No disks in the sample: polls without a reading, in step
+        assert_eq!(history.disk_usage.len(), 3);
+        assert_eq!(history.disk_usage.readings().count(), 0);
+    }
+
+    #[test]
+    fn push_converts_network_rates_to_kb() {
+        let mut history = HistoryBuffer::new(10);
+        let mut stats = sample(0.0, 0.0, 0.0);
+        stats.network.received_per_sec = 2048;
+        stats.network.transmitted_per_sec = 512;
+        history.push(&stats);
+        assert_eq!(history.net_down[0], 2.0);
+        assert_eq!(history.net_up[0], 0.5);
+    }
+
+    #[test]
+    fn a_missing_memory_breakdown_clears_its_series() {
+        let mut history = HistoryBuffer::new(10);
+        let mut stats = sample(0.0, 0.0, 0.0);
+        stats.memory.breakdown = Some(MemoryBreakdown::default());
+        history.push(&stats);
+        history.push(&stats);
+        assert_eq!(history.mem_app.len(), 2);
+        stats.memory.breakdown = None;
+        history.push(&stats);
+        assert!(history.mem_app.is_empty());
+        assert!(history.mem_wired.is_empty());
+        assert!(history.mem_compressed.is_empty());
+    }
+
+    #[test]
+    fn an_empty_history_has_no_aggregates() {
+        let history = HistoryBuffer::new(10);
+        let aggregates = history.aggregates(60);
+        assert!(aggregates.cpu.is_none() && aggregates.net_down.is_none());
+        assert!(history.temp_aggregates("CPU", 60).is_none());
+        assert!(history.rollup.overall().is_none());
+    }
+
+    #[test]
+    fn a_window_longer_than_the_data_covers_all_of_it() {
+        let mut history = HistoryBuffer::new(10);
+        for cpu in [10.0, 20.0, 60.0] {
+            history.push(&sample(cpu, 0.0, 0.0));
+        }
+        let cpu = history.aggregates(3600).cpu.unwrap();
+        assert_eq!((cpu.min, cpu.max, cpu.avg), (10.0, 60.0, 30.0));
+        let newest = history.aggregates(1).cpu.unwrap();
+        assert_eq!(newest.avg, 60.0);
+    }
+
+    #[test]
+    fn the_rollup_keeps_an_hour_of_minutes() {
+        let mut rollup = HourRollup::default();
+        let start = 1_700_000_000 - 1_700_000_000 % 60;
+        // Two polls a minute for 90 minutes, the second one busier
+        for minute in 0..90 {
+            let at = start + minute * 60;
+            rollup.push(&sample(10.0, 50.0, 0.0), at);
+            rollup.push(&sample(30.0, 50.0, 0.0), at + 30);
+        }
+        assert_eq!(rollup.minutes().len(), ROLLUP_MINUTES as usize);
+        assert_eq!(rollup.minutes()[0].start, start + 30 * 60);
+        let hour = rollup.overall().unwrap();
+        assert_eq!(hour.samples, 120);
+        let cpu = hour.cpu.unwrap();
+        assert_eq!((cpu.avg, cpu.max), (20.0, 30.0));
+        // No disks and no swap activity in the samples
+        assert!(hour.disk.is_none() && hour.pageouts.is_none());
+    }
+
+    #[test]
+    fn a_clock_going_back_counts_into_the_newest_minute() {
+        let mut rollup = HourRollup::default();
+        rollup.push(&sample(10.0, 0.0, 0.0), 6000);
+        rollup.push(&sample(20.0, 0.0, 0.0), 5000);
+        assert_eq!(rollup.minutes().len(), 1);
+        assert_eq!(rollup.minutes()[0].samples, 2);
+    }
+
+    #[test]
+    fn cores_start_over_when_the_count_changes() {
+        let mut history = HistoryBuffer::new(10);
+        let mut stats = sample(0.0, 0.0, 0.0);
+        stats.cpu.per_core_usage = vec![1.0, 2.0];
+        history.push(&stats);
+        assert!(history.cores.is_empty());
+
+        history.track_cores = true;
+        history.push(&stats);
+        history.push(&stats);
+        assert_eq!(history.cores.len(), 2);
+        assert_eq!(history.cores[1].len(), 2);
+        stats.cpu.per_core_usage = vec![1.0, 2.0, 3.0, 4.0];
+        history.push(&stats);
+        assert_eq!(history.cores.len(), 4);
+        assert_eq!(history.cores[0].len(), 1);
+    }
+
+    #[test]
+    fn segments_split_at_gaps() {
+        let mut series = Series::new(10);
+        assert!(series.segments().is_empty());
+        // A gap before the first sample splits nothing
+        series.mark_gap();
+        series.push(1.0);
+        series.push(2.0);
+        series.mark_gap();
+        series.push(3.0);
+        series.mark_gap();
+        series.push(4.0);
+        series.push(5.0);
+        // Nor does one after the newest, until something follows it
+        series.mark_gap();
+        assert_eq!(series.segments(), [0..2, 2..3, 3..5]);
+    }
+
+    #[test]
+    fn a_gap_at_the_oldest_sample_splits_nothing() {
+        let mut series = Series::new(3);
+        series.push(1.0);
+        series.mark_gap();
+        series.push(2.0);
+        series.push(3.0);
+        series.push(4.0);
+        // The sample before the gap is gone, so the gap leads the series
+        assert_eq!(series.segments(), [0..3]);
+    }
+
+    #[test]
+    fn missing_readings_are_left_out() {
+        let mut series = Series::new(10);
+        series.push_missing();
+        series.push(10.0);
+        series.push(20.0);
+        series.push_missing();
+        series.push_missing();
+        series.push(30.0);
+        series.push_missing();
+        assert_eq!(series.len(), 7);
+        assert_eq!(series.segments(), [1..3, 5..6]);
+        assert_eq!(series.reading(0), None);
+        assert_eq!(series.reading(1), Some(&10.0));
+        assert_eq!(series.reading(7), None);
+        assert_eq!(
+            series.readings().copied().collect::<Vec<_>>(),
+            [10.0, 20.0, 30.0]
+        );
+        let aggregates = series.aggregates(10).unwrap();
+        assert_eq!((aggregates.min, aggregates.avg), (10.0, 20.0));
+        // Only timeouts in the window
+        assert!(series.aggregates(1).is_none());
+    }
+
+    #[test]
+    fn ping_timeouts_keep_latency_in_step() {
+        let mut history = HistoryBuffer::new(10);
+        let mut stats = sample(0.0, 0.0, 0.0);
+        stats.network.latency_ms = Some(12.0);
+        history.push(&stats);
+        stats.network.latency_ms = None;
+        history.push(&stats);
+        assert_eq!(history.latency.len(), history.cpu_usage.len());
+        assert_eq!(history.latency.reading(1), None);
+        assert!(history.latency.readings().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn absent_readings_keep_the_series_in_step() {
+        let mut history = HistoryBuffer::new(10);
+        history.push(&sample(10.0, 50.0, 40.0));
+        // The CPU sensor stops answering and a GPU one shows up
+        let mut stats = sample(20.0, 50.0, 41.0);
+        stats.temperature.readings[0].label = "GPU".to_string();
+        history.push(&stats);
+        for temps in [&history.temps, &history.recent.temps] {
+            assert_eq!(temps["CPU"].len(), 2);
+            assert_eq!(temps["CPU"].reading(1), None);
+            assert_eq!(temps["GPU"].len(), 2);
+            assert_eq!(temps["GPU"].reading(0), None);
+            assert_eq!(temps["GPU"].reading(1), Some(&41.0));
+        }
+        assert_eq!(history.disk_usage.len(), history.cpu_usage.len());
+    }
+
+    #[test]
+    fn trends_follow_the_last_minute() {
+        let mut history = HistoryBuffer::new(100);
+        for i in 0..30 {
+            let mut stats = sample(i as f32, 50.0, 60.0);
+            stats.network.received_per_sec = (1000 - i * 10) * 1024;
+            history.push(&stats);
+        }
+        // Two-second polls: 30 samples make the minute
+        let trends = Trends::of(&history, 2.0);
+        assert_eq!(trends.cpu, Some(Trend::Rising));
+        assert_eq!(trends.mem, Some(Trend::Flat));
+        assert_eq!(trends.net, Some(Trend::Falling));
+        assert_eq!(trends.temp("CPU"), Some(Trend::Flat));
+        // No disks in the samples
+        assert_eq!(trends.disk, None);
+        assert_eq!(Trends::of(&HistoryBuffer::new(10), 2.0).cpu, None);
+    }
+}
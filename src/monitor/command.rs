@@ -0,0 +1,50 @@
+//! Command line tools run by the background samplers, stopped when they
+//! take too long so a wedged tool can't pile up behind the scheduler.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WAIT_STEP: Duration = Duration::from_millis(50);
+
+/// Run `command` and return its stdout, whatever its exit status. It is
+/// killed once it has run for `timeout`.
+pub fn output_within(command: &mut Command, timeout: Duration) -> Result<Vec<u8>, String> {
+    let name = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not run {}: {}", name, e))?;
+    // Drain the pipe while waiting so a long listing can't stall the tool
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| format!("no output from {}", name))?;
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).map(|_| output)
+    });
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => thread::sleep(WAIT_STEP),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} took longer than {:?}, stopped it",
+                    name, timeout
+                ));
+            }
+            Err(e) => return Err(format!("could not wait for {}: {}", name, e)),
+        }
+    }
+    reader
+        .join()
+        .map_err(|_| format!("could not read the output of {}", name))?
+        .map_err(|e| format!("could not read the output of {}: {}", name, e))
+}
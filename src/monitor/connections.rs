@@ -0,0 +1,147 @@
+//! Socket counts and listening ports from `lsof`, read every 20 seconds by
+//! the scheduler. Without root, lsof only sees this user's processes; when
+//! it is missing, denied or too slow there is no summary to show.
+
+use super::command;
+use crate::model::{ConnectionStats, ListeningPort};
+use crate::scheduler::{Scheduler, TaskPriority};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(20);
+/// lsof is killed after this long
+const LSOF_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct ConnectionLookup {
+    stats: Arc<Mutex<Option<ConnectionStats>>>,
+}
+
+/// One open internet socket in lsof's field output
+#[derive(Default)]
+struct Socket {
+    protocol: String,
+    /// "127.0.0.1:631" or "10.0.0.2:50123->1.2.3.4:443"
+    name: String,
+    /// TCP state, e.g. "LISTEN"; empty for UDP
+    state: String,
+}
+
+impl ConnectionLookup {
+    /// Read the sockets every 20 seconds on `scheduler`
+    pub fn register(scheduler: &Scheduler) -> Self {
+        let stats = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&stats);
+        scheduler.register(
+            "connections",
+            TaskPriority::Low,
+            REFRESH_INTERVAL,
+            LSOF_TIMEOUT + Duration::from_secs(1),
+            move || {
+                let result = run_lsof().map(|output| parse(&output));
+                *slot.lock().unwrap() = result.as_ref().ok().cloned();
+                result.map(|_| ())
+            },
+        );
+        Self { stats }
+    }
+
+    /// The last summary, if any
+    pub fn latest(&self) -> Option<ConnectionStats> {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+/// lsof's field output for every internet socket; an error when it can't
+/// run, times out or prints nothing
+fn run_lsof() -> Result<String, String> {
+    let mut lsof = Command::new("/usr/sbin/lsof");
+    lsof.args(["-w", "-n", "-P", "-i", "-F", "cPnT"]);
+    // lsof exits non-zero when it finds nothing, so go by the output
+    let output = String::from_utf8(command::output_within(&mut lsof, LSOF_TIMEOUT)?)
+        .map_err(|e| format!("unexpected lsof output: {}", e))?;
+    if output.trim().is_empty() {
+        return Err("no sockets in lsof output".to_string());
+    }
+    Ok(output)
+}
+
+/// Fields come one per line, tagged by their first character: `p` starts a
+/// process, `f` a file of it, then its `P`rotocol, `n`ame and `T`CP info.
+fn parse(output: &str) -> ConnectionStats {
+    let mut stats = ConnectionStats {
+        established: 0,
+        sockets: 0,
+        listening: Vec::new(),
+    };
+    let mut command = "";
+    let mut socket: Option<Socket> = None;
+    for line in output.lines() {
+        let Some(tag) = line.chars().next() else {
+            continue;
+        };
+        let value = &line[tag.len_utf8()..];
+        match tag {
+            'p' | 'f' => {
+                if let Some(done) = socket.take() {
+                    count(&mut stats, done, command);
+                }
+                if tag == 'f' {
+                    socket = Some(Socket::default());
+                }
+            }
+            'c' => command = value,
+            'P' => {
+                if let Some(s) = socket.as_mut() {
+                    s.protocol = value.to_string();
+                }
+            }
+            'n' => {
+                if let Some(s) = socket.as_mut() {
+                    s.name = value.to_string();
+                }
+            }
+            'T' => {
+                if let (Some(s), Some(state)) = (socket.as_mut(), value.strip_prefix("ST=")) {
+                    s.state = state.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(done) = socket.take() {
+        count(&mut stats, done, command);
+    }
+    stats
+        .listening
+        .sort_by(|a, b| a.port.cmp(&b.port).then_with(|| a.process.cmp(&b.process)));
+    // IPv4 and IPv6 listeners of one process show up as two sockets
+    stats
+        .listening
+        .dedup_by(|a, b| a.port == b.port && a.process == b.process);
+    stats
+}
+
+fn count(stats: &mut ConnectionStats, socket: Socket, command: &str) {
+    stats.sockets += 1;
+    if socket.protocol != "TCP" {
+        return;
+    }
+    match socket.state.as_str() {
+        "ESTABLISHED" => stats.established += 1,
+        "LISTEN" => {
+            let Some((address, port)) = socket.name.rsplit_once(':') else {
+                return;
+            };
+            let Ok(port) = port.parse() else {
+                return;
+            };
+            stats.listening.push(ListeningPort {
+                port,
+                address: address.to_string(),
+                process: command.to_string(),
+            });
+        }
+        _ => {}
+    }
+}
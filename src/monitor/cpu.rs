@@ -1,11 +1,62 @@
 use crate::model::CpuStats;
+use std::process::Command;
 use sysinfo::System;
 
-pub fn collect(sys: &System) -> CpuStats {
+/// Logical CPUs per cluster on Apple Silicon. The kernel numbers the
+/// efficiency cores first, so they are the start of `System::cpus`.
+pub struct CoreClusters {
+    pub performance: usize,
+    pub efficiency: usize,
+}
+
+impl CoreClusters {
+    /// None on Intel Macs, which have a single performance level
+    pub fn detect() -> Option<Self> {
+        let output = Command::new("sysctl")
+            .arg("-n")
+            .arg("hw.nperflevels")
+            .arg("hw.perflevel0.logicalcpu")
+            .arg("hw.perflevel1.logicalcpu")
+            .output()
+            .ok()?;
+        let values: Vec<usize> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect();
+        match values[..] {
+            [2, performance, efficiency] => Some(Self {
+                performance,
+                efficiency,
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub fn collect(sys: &System, clusters: Option<&CoreClusters>) -> CpuStats {
     let cpus = sys.cpus();
+    let load = System::load_average();
+    let per_core_usage: Vec<f32> = cpus.iter().map(|c| c.cpu_usage()).collect();
+    // Skip the split if the counts don't add up to what sysinfo sees
+    let (e_cores, p_cores) = match clusters {
+        Some(c) if c.performance + c.efficiency == per_core_usage.len() => {
+            per_core_usage.split_at(c.efficiency)
+        }
+        _ => (&[][..], &[][..]),
+    };
     CpuStats {
         global_usage: sys.global_cpu_usage(),
-        per_core_usage: cpus.iter().map(|c| c.cpu_usage()).collect(),
         core_count: cpus.len(),
+        p_core_usage: mean(p_cores),
+        e_core_usage: mean(e_cores),
+        p_core_mhz: None,
+        e_core_mhz: None,
+        per_core_usage,
+        load_average: [load.one, load.five, load.fifteen],
+        uptime_secs: System::uptime(),
     }
 }
+
+fn mean(values: &[f32]) -> Option<f32> {
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
@@ -0,0 +1,150 @@
+//! Traffic since local midnight, kept in `traffic.json` in the config folder
+//! so quitting or restarting the app doesn't zero it.
+
+use crate::config::config_dir;
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_char, c_long};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How often the totals are written while counting
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize)]
+struct SavedTraffic {
+    /// See `local_day`
+    day: i32,
+    received_bytes: u64,
+    transmitted_bytes: u64,
+}
+
+pub struct DailyTraffic {
+    day: i32,
+    received: u64,
+    transmitted: u64,
+    last_save: Instant,
+    /// Counted since the last save
+    dirty: bool,
+}
+
+/// struct tm as laid out on macOS
+#[repr(C)]
+struct Tm {
+    tm_sec: i32,
+    tm_min: i32,
+    tm_hour: i32,
+    tm_mday: i32,
+    tm_mon: i32,
+    tm_year: i32,
+    tm_wday: i32,
+    tm_yday: i32,
+    tm_isdst: i32,
+    tm_gmtoff: c_long,
+    tm_zone: *const c_char,
+}
+
+extern "C" {
+    fn time(out: *mut i64) -> i64;
+    fn tzset();
+    fn localtime_r(clock: *const i64, result: *mut Tm) -> *mut Tm;
+}
+
+fn traffic_path() -> PathBuf {
+    config_dir().join("traffic.json")
+}
+
+/// Today on the local calendar as year * 1000 + day of the year. tzset
+/// picks up a changed time zone; DST is already part of local time.
+fn local_day() -> i32 {
+    local_day_hour().0
+}
+
+/// `local_day` and the hour of the local time, 0-23
+pub fn local_day_hour() -> (i32, u8) {
+    unsafe {
+        tzset();
+        let now = time(std::ptr::null_mut());
+        let mut tm: Tm = std::mem::zeroed();
+        if localtime_r(&now, &mut tm).is_null() {
+            return (0, 0);
+        }
+        ((tm.tm_year + 1900) * 1000 + tm.tm_yday, tm.tm_hour as u8)
+    }
+}
+
+impl DailyTraffic {
+    /// The saved totals when they are from today, else zero
+    pub fn load() -> Self {
+        let day = local_day();
+        let saved = fs::read_to_string(traffic_path())
+            .ok()
+            .and_then(|data| serde_json::from_str::<SavedTraffic>(&data).ok())
+            .filter(|saved| saved.day == day);
+        Self {
+            day,
+            received: saved.as_ref().map_or(0, |s| s.received_bytes),
+            transmitted: saved.as_ref().map_or(0, |s| s.transmitted_bytes),
+            last_save: Instant::now(),
+            dirty: false,
+        }
+    }
+
+    /// Count bytes moved since the last refresh, starting over on a new day.
+    pub fn add(&mut self, received: u64, transmitted: u64) {
+        let day = local_day();
+        if day != self.day {
+            self.day = day;
+            self.received = 0;
+            self.transmitted = 0;
+            self.dirty = true;
+        }
+        self.received += received;
+        self.transmitted += transmitted;
+        self.dirty |= received > 0 || transmitted > 0;
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save();
+        }
+    }
+
+    /// (received, transmitted) bytes today
+    pub fn totals(&self) -> (u64, u64) {
+        (self.received, self.transmitted)
+    }
+
+    pub fn reset(&mut self) {
+        self.received = 0;
+        self.transmitted = 0;
+        self.dirty = true;
+        self.save();
+    }
+
+    /// Write the totals if anything changed since the last save.
+    pub fn save(&mut self) {
+        self.last_save = Instant::now();
+        if !self.dirty {
+            return;
+        }
+        let saved = SavedTraffic {
+            day: self.day,
+            received_bytes: self.received,
+            transmitted_bytes: self.transmitted,
+        };
+        let json = match serde_json::to_string_pretty(&saved) {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("could not serialize today's traffic: {}", e);
+                return;
+            }
+        };
+        let path = traffic_path();
+        let tmp = path.with_extension("json.tmp");
+        let written = fs::create_dir_all(config_dir())
+            .and_then(|_| fs::write(&tmp, json))
+            .and_then(|_| fs::rename(&tmp, &path));
+        match written {
+            Ok(()) => self.dirty = false,
+            Err(e) => log::warn!("could not save {}: {}", path.display(), e),
+        }
+    }
+}
@@ -1,16 +1,28 @@
 use crate::model::DiskStats;
 use std::collections::HashSet;
+use std::path::Path;
 use sysinfo::Disks;
 
-pub fn collect(disks: &Disks) -> Vec<DiskStats> {
-    let mut seen_names = HashSet::new();
-    disks
+/// Volumes that pass the include/exclude patterns, plus the mount point of
+/// every volume found (filtered out or not)
+pub fn collect(
+    disks: &Disks,
+    include: &[String],
+    exclude: &[String],
+) -> (Vec<DiskStats>, Vec<String>) {
+    let mut seen_mounts = HashSet::new();
+    let mut mounts = Vec::new();
+    let stats = disks
         .iter()
         .filter(|d| {
             let mp = d.mount_point().to_string_lossy().to_string();
-            let name = d.name().to_string_lossy().to_string();
-            // Skip zero-size, /System/Volumes mounts, and duplicate disk names
-            d.total_space() > 0 && !mp.starts_with("/System/Volumes") && seen_names.insert(name)
+            // Skip zero-size and duplicate mounts
+            if d.total_space() == 0 || !seen_mounts.insert(mp.clone()) {
+                return false;
+            }
+            let shown = is_shown(&mp, include, exclude);
+            mounts.push(mp);
+            shown
         })
         .map(|d| {
             let total = d.total_space();
@@ -27,7 +39,60 @@ pub fn collect(disks: &Disks) -> Vec<DiskStats> {
                 total_bytes: total,
                 available_bytes: available,
                 usage_percent,
+                file_system: d.file_system().to_string_lossy().to_string(),
+                is_removable: d.is_removable(),
+                is_read_only: d.is_read_only(),
+                health: None,
             }
         })
-        .collect()
+        .collect();
+    (stats, mounts)
+}
+
+/// A mount is shown when an include pattern matches it or no exclude
+/// pattern does
+pub fn is_shown(mount_point: &str, include: &[String], exclude: &[String]) -> bool {
+    include.iter().any(|p| mount_matches(p, mount_point))
+        || !exclude.iter().any(|p| mount_matches(p, mount_point))
+}
+
+/// Patterns with `*` or `?` are globs over the whole path; anything else is
+/// a prefix matching that mount and everything under it. `/` only matches
+/// itself, so it doesn't swallow every volume.
+pub fn mount_matches(pattern: &str, mount_point: &str) -> bool {
+    if pattern.contains(['*', '?']) {
+        return glob_matches(pattern.as_bytes(), mount_point.as_bytes());
+    }
+    if pattern == "/" {
+        return mount_point == "/";
+    }
+    Path::new(mount_point).starts_with(pattern)
+}
+
+/// `*` matches any run of characters, `/` included; `?` matches one
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and how much text it has taken so far
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
 }
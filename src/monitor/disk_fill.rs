@@ -0,0 +1,93 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Free space samples older than this don't count toward the trend
+const FILL_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// The samples have to span at least this long before predicting
+const MIN_SPAN: Duration = Duration::from_secs(2 * 60);
+const MIN_SAMPLES: usize = 4;
+/// Free space has to have dropped by this much over the window
+const MIN_DROP_BYTES: u64 = 256 * 1024 * 1024;
+/// At least this share of the poll-to-poll changes has to be a drop
+const MIN_FALLING_SHARE: f64 = 0.75;
+/// Free space growing by more than this between polls starts over
+const RECOVERY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Estimates when volumes run out of space from the recent free space
+/// trend. Only a sustained drop predicts anything: small fluctuations and
+/// one-off writes don't, and freeing space forgets the trend.
+#[derive(Default)]
+pub struct DiskFillPredictor {
+    /// Recent (time, available bytes) by mount point, oldest first
+    samples: HashMap<String, VecDeque<(Instant, u64)>>,
+}
+
+impl DiskFillPredictor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a poll's free space; returns the time until the volume is
+    /// full at the current rate, when it is steadily filling up
+    pub fn observe(&mut self, mount_point: &str, available: u64, now: Instant) -> Option<Duration> {
+        let samples = self.samples.entry(mount_point.to_string()).or_default();
+        if samples
+            .back()
+            .is_some_and(|&(_, last)| available > last.saturating_add(RECOVERY_BYTES))
+        {
+            samples.clear();
+        }
+        samples.push_back((now, available));
+        while samples
+            .front()
+            .is_some_and(|&(at, _)| now.duration_since(at) > FILL_WINDOW)
+        {
+            samples.pop_front();
+        }
+        time_to_full(samples)
+    }
+
+    /// Forget volumes that are no longer mounted
+    pub fn retain(&mut self, mount_points: &[&str]) {
+        self.samples
+            .retain(|mount, _| mount_points.contains(&mount.as_str()));
+    }
+}
+
+fn time_to_full(samples: &VecDeque<(Instant, u64)>) -> Option<Duration> {
+    let (&(first_at, first), &(last_at, last)) = (samples.front()?, samples.back()?);
+    if samples.len() < MIN_SAMPLES
+        || last_at.duration_since(first_at) < MIN_SPAN
+        || first.saturating_sub(last) < MIN_DROP_BYTES
+    {
+        return None;
+    }
+    let steps = samples.len() - 1;
+    let falling = samples
+        .iter()
+        .zip(samples.iter().skip(1))
+        .filter(|((_, before), (_, after))| after <= before)
+        .count();
+    if (falling as f64) < steps as f64 * MIN_FALLING_SHARE {
+        return None;
+    }
+
+    // Least-squares bytes per second over the window
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|&(at, bytes)| (at.duration_since(first_at).as_secs_f64(), bytes as f64))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for &(x, y) in &points {
+        num += (x - mean_x) * (y - mean_y);
+        den += (x - mean_x) * (x - mean_x);
+    }
+    let rate = num / den;
+    if !rate.is_finite() || rate >= 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(last as f64 / -rate))
+}
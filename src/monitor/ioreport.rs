@@ -0,0 +1,296 @@
+//! Apple Silicon cluster frequencies from IOReport, which unprivileged
+//! processes may read: time spent in each CPU performance state, weighted
+//! by the frequency tables `pmgr` publishes in the IORegistry.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::ptr;
+
+type CFTypeRef = *const c_void;
+
+const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+const KERN_SUCCESS: i32 = 0;
+/// kIOMainPortDefault
+const MAIN_PORT_DEFAULT: u32 = 0;
+/// Residency states that aren't a frequency step
+const INACTIVE_STATES: [&str; 3] = ["IDLE", "DOWN", "OFF"];
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFStringCreateWithCString(
+        alloc: CFTypeRef,
+        c_str: *const c_char,
+        encoding: u32,
+    ) -> CFTypeRef;
+    fn CFStringGetCString(s: CFTypeRef, buffer: *mut c_char, size: isize, encoding: u32) -> bool;
+    fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+    fn CFArrayGetCount(array: CFTypeRef) -> isize;
+    fn CFArrayGetValueAtIndex(array: CFTypeRef, index: isize) -> CFTypeRef;
+    fn CFDataGetLength(data: CFTypeRef) -> isize;
+    fn CFDataGetBytePtr(data: CFTypeRef) -> *const u8;
+    fn CFRelease(cf: CFTypeRef);
+}
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOServiceMatching(name: *const c_char) -> *mut c_void;
+    /// Consumes the `matching` dictionary
+    fn IOServiceGetMatchingServices(main_port: u32, matching: *mut c_void, iter: *mut u32) -> i32;
+    fn IOIteratorNext(iterator: u32) -> u32;
+    /// `name` must hold 128 bytes (`io_name_t`)
+    fn IORegistryEntryGetName(entry: u32, name: *mut c_char) -> i32;
+    fn IORegistryEntryCreateCFProperty(
+        entry: u32,
+        key: CFTypeRef,
+        allocator: CFTypeRef,
+        options: u32,
+    ) -> CFTypeRef;
+    fn IOObjectRelease(object: u32) -> i32;
+}
+
+#[link(name = "IOReport")]
+extern "C" {
+    fn IOReportCopyChannelsInGroup(
+        group: CFTypeRef,
+        subgroup: CFTypeRef,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> *mut c_void;
+    fn IOReportCreateSubscription(
+        a: CFTypeRef,
+        desired: *mut c_void,
+        subscribed: *mut *mut c_void,
+        channel_id: u64,
+        b: CFTypeRef,
+    ) -> CFTypeRef;
+    fn IOReportCreateSamples(
+        subscription: CFTypeRef,
+        channels: *mut c_void,
+        a: CFTypeRef,
+    ) -> CFTypeRef;
+    fn IOReportCreateSamplesDelta(
+        previous: CFTypeRef,
+        current: CFTypeRef,
+        a: CFTypeRef,
+    ) -> CFTypeRef;
+    fn IOReportChannelGetChannelName(channel: CFTypeRef) -> CFTypeRef;
+    fn IOReportStateGetCount(channel: CFTypeRef) -> i32;
+    fn IOReportStateGetNameForIndex(channel: CFTypeRef, index: i32) -> CFTypeRef;
+    fn IOReportStateGetResidency(channel: CFTypeRef, index: i32) -> i64;
+}
+
+/// Average frequency of each cluster while it was running
+pub struct ClusterMhz {
+    pub performance: Option<u32>,
+    pub efficiency: Option<u32>,
+}
+
+pub struct ClusterFrequencies {
+    subscription: CFTypeRef,
+    channels: *mut c_void,
+    previous: Option<CFTypeRef>,
+    /// MHz of each active performance state, slowest first
+    performance_steps: Vec<u32>,
+    efficiency_steps: Vec<u32>,
+}
+
+impl ClusterFrequencies {
+    /// None when IOReport or the `pmgr` frequency tables are unavailable
+    /// (Intel Macs, or a sandbox that blocks them)
+    pub fn open() -> Option<Self> {
+        let (efficiency_steps, performance_steps) = dvfs_tables()?;
+        unsafe {
+            let group = cf_string(c"CPU Stats");
+            let subgroup = cf_string(c"CPU Core Performance States");
+            let desired = IOReportCopyChannelsInGroup(group, subgroup, 0, 0, 0);
+            CFRelease(group);
+            CFRelease(subgroup);
+            if desired.is_null() {
+                return None;
+            }
+            let mut channels = ptr::null_mut();
+            let subscription =
+                IOReportCreateSubscription(ptr::null(), desired, &mut channels, 0, ptr::null());
+            CFRelease(desired);
+            if subscription.is_null() || channels.is_null() {
+                return None;
+            }
+            Some(Self {
+                subscription,
+                channels,
+                previous: None,
+                performance_steps,
+                efficiency_steps,
+            })
+        }
+    }
+
+    /// Frequencies since the previous call; None on the first one
+    pub fn sample(&mut self) -> Option<ClusterMhz> {
+        let current =
+            unsafe { IOReportCreateSamples(self.subscription, self.channels, ptr::null()) };
+        if current.is_null() {
+            return None;
+        }
+        let previous = self.previous.replace(current)?;
+        unsafe {
+            let delta = IOReportCreateSamplesDelta(previous, current, ptr::null());
+            CFRelease(previous);
+            if delta.is_null() {
+                return None;
+            }
+            let mhz = self.cluster_mhz(delta);
+            CFRelease(delta);
+            Some(mhz)
+        }
+    }
+
+    /// Sums each cluster's per-core residencies, then weights the steps
+    unsafe fn cluster_mhz(&self, delta: CFTypeRef) -> ClusterMhz {
+        let mut performance = Vec::new();
+        let mut efficiency = Vec::new();
+        let key = cf_string(c"IOReportChannels");
+        let list = CFDictionaryGetValue(delta, key);
+        CFRelease(key);
+        let count = if list.is_null() {
+            0
+        } else {
+            CFArrayGetCount(list)
+        };
+        for i in 0..count {
+            let channel = CFArrayGetValueAtIndex(list, i);
+            let Some(name) = string_of(IOReportChannelGetChannelName(channel)) else {
+                continue;
+            };
+            let residencies = if name.starts_with("PCPU") {
+                &mut performance
+            } else if name.starts_with("ECPU") {
+                &mut efficiency
+            } else {
+                continue;
+            };
+            let active = (0..IOReportStateGetCount(channel)).filter(|&state| {
+                string_of(IOReportStateGetNameForIndex(channel, state))
+                    .is_some_and(|s| !INACTIVE_STATES.contains(&s.as_str()))
+            });
+            for (step, state) in active.enumerate() {
+                if residencies.len() <= step {
+                    residencies.resize(step + 1, 0i64);
+                }
+                residencies[step] += IOReportStateGetResidency(channel, state);
+            }
+        }
+        ClusterMhz {
+            performance: weighted_mhz(&performance, &self.performance_steps),
+            efficiency: weighted_mhz(&efficiency, &self.efficiency_steps),
+        }
+    }
+}
+
+impl Drop for ClusterFrequencies {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(previous) = self.previous.take() {
+                CFRelease(previous);
+            }
+            CFRelease(self.channels);
+            CFRelease(self.subscription);
+        }
+    }
+}
+
+/// None when the cluster never left idle
+fn weighted_mhz(residencies: &[i64], steps: &[u32]) -> Option<u32> {
+    let pairs = residencies.iter().zip(steps);
+    let total: i64 = pairs.clone().map(|(&r, _)| r).sum();
+    if total <= 0 {
+        return None;
+    }
+    let weighted: f64 = pairs.map(|(&r, &mhz)| r as f64 * mhz as f64).sum();
+    Some((weighted / total as f64).round() as u32)
+}
+
+/// (efficiency, performance) frequency steps in MHz from the `pmgr` node
+fn dvfs_tables() -> Option<(Vec<u32>, Vec<u32>)> {
+    unsafe {
+        let matching = IOServiceMatching(c"AppleARMIODevice".as_ptr());
+        if matching.is_null() {
+            return None;
+        }
+        let mut iter = 0;
+        if IOServiceGetMatchingServices(MAIN_PORT_DEFAULT, matching, &mut iter) != KERN_SUCCESS {
+            return None;
+        }
+        let mut tables = None;
+        loop {
+            let entry = IOIteratorNext(iter);
+            if entry == 0 {
+                break;
+            }
+            let mut name = [0 as c_char; 128];
+            let is_pmgr = IORegistryEntryGetName(entry, name.as_mut_ptr()) == KERN_SUCCESS
+                && CStr::from_ptr(name.as_ptr()) == c"pmgr";
+            if is_pmgr && tables.is_none() {
+                let efficiency = frequency_steps(entry, c"voltage-states1-sram");
+                let performance = frequency_steps(entry, c"voltage-states5-sram");
+                tables = efficiency.zip(performance);
+            }
+            IOObjectRelease(entry);
+        }
+        IOObjectRelease(iter);
+        tables
+    }
+}
+
+/// The property holds (frequency, voltage) pairs of little-endian u32s.
+/// Older chips give the frequency in Hz, newer ones in kHz.
+unsafe fn frequency_steps(entry: u32, key: &CStr) -> Option<Vec<u32>> {
+    let key = cf_string(key);
+    let data = IORegistryEntryCreateCFProperty(entry, key, ptr::null(), 0);
+    CFRelease(key);
+    if data.is_null() {
+        return None;
+    }
+    let len = CFDataGetLength(data).max(0) as usize;
+    let bytes = std::slice::from_raw_parts(CFDataGetBytePtr(data), len);
+    let steps: Vec<u32> = bytes
+        .chunks_exact(8)
+        .map(|pair| u32::from_le_bytes([pair[0], pair[1], pair[2], pair[3]]))
+        .filter(|&freq| freq > 0)
+        .map(|freq| {
+            if freq >= 10_000_000 {
+                freq / 1_000_000
+            } else {
+                freq / 1_000
+            }
+        })
+        .collect();
+    CFRelease(data);
+    (!steps.is_empty()).then_some(steps)
+}
+
+/// Caller releases the result
+unsafe fn cf_string(s: &CStr) -> CFTypeRef {
+    CFStringCreateWithCString(ptr::null(), s.as_ptr(), CF_STRING_ENCODING_UTF8)
+}
+
+/// Copies a borrowed CFString
+unsafe fn string_of(s: CFTypeRef) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    let mut buffer = [0 as c_char; 64];
+    if !CFStringGetCString(
+        s,
+        buffer.as_mut_ptr(),
+        buffer.len() as isize,
+        CF_STRING_ENCODING_UTF8,
+    ) {
+        return None;
+    }
+    Some(
+        CStr::from_ptr(buffer.as_ptr())
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
@@ -0,0 +1,85 @@
+use crate::scheduler::{Scheduler, TaskPriority};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(2);
+/// A probe slower than this counts as a timeout
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Name lookups can't be cut short; give up on a probe stuck in one
+const TASK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Port used when the target doesn't name one
+const DEFAULT_PORT: u16 = 443;
+
+/// Round trip to a host, measured as the time a TCP connect takes (no
+/// privileges needed, unlike ICMP), probed every few seconds by the
+/// scheduler.
+pub struct LatencyProbe {
+    /// None while the feature is off
+    target: Arc<Mutex<Option<String>>>,
+    /// Milliseconds of the last finished probe; None after a timeout
+    latest: Arc<Mutex<Option<f64>>>,
+}
+
+impl LatencyProbe {
+    pub fn register(scheduler: &Scheduler) -> Self {
+        let target: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+        let latest = Arc::new(Mutex::new(None));
+        let (task_target, slot) = (Arc::clone(&target), Arc::clone(&latest));
+        scheduler.register(
+            "latency",
+            TaskPriority::Normal,
+            PROBE_INTERVAL,
+            TASK_TIMEOUT,
+            move || {
+                let Some(probed) = task_target.lock().unwrap().clone() else {
+                    return Ok(());
+                };
+                let result = probe(&probed);
+                // Drop the answer if the target changed while probing
+                if task_target.lock().unwrap().as_deref() == Some(probed.as_str()) {
+                    *slot.lock().unwrap() = result.as_ref().ok().copied().flatten();
+                }
+                result.map(|_| ())
+            },
+        );
+        Self { target, latest }
+    }
+
+    pub fn set_target(&mut self, target: Option<&str>) {
+        let target = target.map(str::trim).filter(|t| !t.is_empty());
+        let mut current = self.target.lock().unwrap();
+        if current.as_deref() != target {
+            *current = target.map(str::to_string);
+            *self.latest.lock().unwrap() = None;
+        }
+    }
+
+    /// The last finished probe, in milliseconds; None while off, before the
+    /// first answer, or after a timeout
+    pub fn latest(&self) -> Option<f64> {
+        *self.latest.lock().unwrap()
+    }
+}
+
+/// Milliseconds to connect, None on a timeout
+fn probe(target: &str) -> Result<Option<f64>, String> {
+    let address = resolve(target)?;
+    let started = Instant::now();
+    // A refused connection still took a round trip, but can't be told
+    // apart from an unreachable host; both count as a timeout
+    Ok(TcpStream::connect_timeout(&address, PROBE_TIMEOUT)
+        .ok()
+        .map(|_| started.elapsed().as_secs_f64() * 1000.0))
+}
+
+/// `host:port`, or a bare host or address with the default port
+fn resolve(target: &str) -> Result<SocketAddr, String> {
+    let host = target.trim_matches(['[', ']']);
+    target
+        .to_socket_addrs()
+        .or_else(|_| (host, DEFAULT_PORT).to_socket_addrs())
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| format!("could not resolve latency target {}", target))
+}
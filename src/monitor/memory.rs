@@ -1,7 +1,94 @@
-use crate::model::MemoryStats;
+use crate::model::{MemoryBreakdown, MemoryStats, SwapActivity};
+use std::sync::OnceLock;
 use sysinfo::System;
 
-pub fn collect(sys: &System) -> MemoryStats {
+const HOST_VM_INFO64: i32 = 4;
+/// sizeof(vm_statistics64_data_t) / sizeof(integer_t)
+const HOST_VM_INFO64_COUNT: u32 = 38;
+
+/// Word offsets of the 64-bit counters in `vm_statistics64`
+const PAGEINS_WORD: usize = 8;
+const PAGEOUTS_WORD: usize = 10;
+const COMPRESSIONS_WORD: usize = 26;
+/// Word offsets of the 32-bit page counts in `vm_statistics64`
+const WIRE_COUNT_WORD: usize = 3;
+const PURGEABLE_COUNT_WORD: usize = 22;
+const COMPRESSOR_PAGE_COUNT_WORD: usize = 32;
+const INTERNAL_PAGE_COUNT_WORD: usize = 35;
+
+extern "C" {
+    fn mach_host_self() -> u32;
+    fn host_statistics64(host: u32, flavor: i32, info: *mut i32, count: *mut u32) -> i32;
+    fn host_page_size(host: u32, page_size: *mut usize) -> i32;
+}
+
+type VmStatistics = [u32; HOST_VM_INFO64_COUNT as usize];
+
+/// Cumulative paging counters since boot
+#[derive(Clone, Copy)]
+pub struct PagingCounters {
+    pageins: u64,
+    pageouts: u64,
+    compressions: u64,
+}
+
+fn host() -> u32 {
+    // Each mach_host_self call adds a port reference, so take it once
+    static HOST: OnceLock<u32> = OnceLock::new();
+    *HOST.get_or_init(|| unsafe { mach_host_self() })
+}
+
+/// Raw `vm_statistics64` words via `host_statistics64`.
+fn vm_statistics() -> Option<VmStatistics> {
+    let host = host();
+    let mut words = [0u32; HOST_VM_INFO64_COUNT as usize];
+    let mut count = HOST_VM_INFO64_COUNT;
+    let status = unsafe {
+        host_statistics64(
+            host,
+            HOST_VM_INFO64,
+            words.as_mut_ptr() as *mut i32,
+            &mut count,
+        )
+    };
+    if status != 0 {
+        return None;
+    }
+    Some(words)
+}
+
+fn paging_counters(words: &VmStatistics) -> PagingCounters {
+    // Native (little-endian) u64 split across two words
+    let counter = |word: usize| words[word] as u64 | ((words[word + 1] as u64) << 32);
+    PagingCounters {
+        pageins: counter(PAGEINS_WORD),
+        pageouts: counter(PAGEOUTS_WORD),
+        compressions: counter(COMPRESSIONS_WORD),
+    }
+}
+
+/// App, wired and compressed memory the way Activity Monitor counts them.
+fn breakdown(words: &VmStatistics) -> Option<MemoryBreakdown> {
+    let mut page_size = 0usize;
+    if unsafe { host_page_size(host(), &mut page_size) } != 0 || page_size == 0 {
+        return None;
+    }
+    let bytes = |pages: u32| pages as u64 * page_size as u64;
+    let app_pages = words[INTERNAL_PAGE_COUNT_WORD].saturating_sub(words[PURGEABLE_COUNT_WORD]);
+    Some(MemoryBreakdown {
+        app_bytes: bytes(app_pages),
+        wired_bytes: bytes(words[WIRE_COUNT_WORD]),
+        compressed_bytes: bytes(words[COMPRESSOR_PAGE_COUNT_WORD]),
+    })
+}
+
+/// `prev` is None on the first poll, which then reports no activity rather
+/// than everything since boot.
+pub fn collect(
+    sys: &System,
+    prev: Option<PagingCounters>,
+    elapsed_secs: f64,
+) -> (MemoryStats, Option<PagingCounters>) {
     let total = sys.total_memory();
     let used = sys.used_memory();
     let available = sys.available_memory();
@@ -11,12 +98,30 @@ pub fn collect(sys: &System) -> MemoryStats {
         0.0
     };
 
-    MemoryStats {
+    let vm = vm_statistics();
+    let counters = vm.as_ref().map(paging_counters);
+    let swap_activity = match (prev, counters) {
+        (Some(prev), Some(now)) => {
+            let rate = |now: u64, prev: u64| now.saturating_sub(prev) as f64 / elapsed_secs;
+            Some(SwapActivity {
+                pageins_per_sec: rate(now.pageins, prev.pageins),
+                pageouts_per_sec: rate(now.pageouts, prev.pageouts),
+                compressions_per_sec: rate(now.compressions, prev.compressions),
+            })
+        }
+        _ => None,
+    };
+
+    let stats = MemoryStats {
         total_bytes: total,
         used_bytes: used,
         available_bytes: available,
         swap_total_bytes: sys.total_swap(),
         swap_used_bytes: sys.used_swap(),
         usage_percent,
-    }
+        swap_activity,
+        breakdown: vm.as_ref().and_then(breakdown),
+    };
+
+    (stats, counters)
 }
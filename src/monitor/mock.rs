@@ -0,0 +1,50 @@
+//! Scripted samples in place of the live system, for tests of the poll
+//! pipeline.
+
+use super::StatsSource;
+use crate::model::{NetworkStats, SystemStats, TemperatureReading};
+use std::collections::VecDeque;
+
+/// Hands out its samples in order, then empty stats once they run out
+pub struct MockStatsSource {
+    samples: VecDeque<SystemStats>,
+    /// Full polls taken so far
+    pub polls: usize,
+}
+
+impl MockStatsSource {
+    pub fn new(samples: impl IntoIterator<Item = SystemStats>) -> Self {
+        Self {
+            samples: samples.into_iter().collect(),
+            polls: 0,
+        }
+    }
+}
+
+impl StatsSource for MockStatsSource {
+    fn poll(&mut self) -> SystemStats {
+        self.polls += 1;
+        self.samples.pop_front().unwrap_or_default()
+    }
+
+    fn poll_fast(&mut self) -> NetworkStats {
+        NetworkStats::default()
+    }
+
+    fn finished(&mut self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// A sample with the given CPU and memory usage and one "CPU" sensor at
+/// `temp_c`
+pub fn sample(cpu: f32, mem: f32, temp_c: f32) -> SystemStats {
+    let mut stats = SystemStats::default();
+    stats.cpu.global_usage = cpu;
+    stats.memory.usage_percent = mem;
+    stats.temperature.readings.push(TemperatureReading {
+        label: "CPU".to_string(),
+        temp_c,
+    });
+    stats
+}
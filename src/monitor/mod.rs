@@ -1,25 +1,136 @@
+mod command;
+mod connections;
 mod cpu;
-mod disk;
+pub mod daily_traffic;
+pub mod disk;
+pub mod disk_fill;
+mod ioreport;
+mod latency;
 mod memory;
+#[cfg(test)]
+pub mod mock;
 mod network;
+pub mod power;
+mod processes;
+mod public_ip;
+mod smart;
+mod smc;
 pub mod temperature;
+pub mod volumes;
+mod wifi;
 
+use crate::health;
+use crate::issues;
 use crate::model::*;
-use std::time::Instant;
-use sysinfo::{Components, Disks, Networks, System};
+use crate::scheduler::{Scheduler, TaskPriority, TaskStatus};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{Components, Disks, Networks, Pid, ProcessesToUpdate, System};
+
+/// Process enumeration is comparatively expensive: not every poll
+const SYSTEM_LOAD_INTERVAL: Duration = Duration::from_secs(5);
+/// Shortest time span rates are computed over
+const MIN_RATE_WINDOW_SECS: f64 = 0.25;
+
+/// Where the app's readings come from: the live system, or anything else
+/// that produces `SystemStats` (a recording, a script)
+pub trait StatsSource {
+    /// Take a full sample
+    fn poll(&mut self) -> SystemStats;
+
+    /// Network only, for the seconds between full polls
+    fn poll_fast(&mut self) -> NetworkStats;
+
+    /// Refresh the process table each poll
+    fn set_process_collection(&mut self, _enabled: bool) {}
+
+    fn reset_session_counters(&mut self) {}
+
+    fn reset_daily_traffic(&mut self) {}
+
+    fn save_daily_traffic(&mut self) {}
+
+    /// Mount patterns for the DISK menu
+    fn set_disk_filter(&mut self, _include: &[String], _exclude: &[String]) {}
+
+    /// Endpoint for public IP lookups; None turns them off
+    fn set_public_ip_url(&mut self, _url: Option<&str>) {}
+
+    /// Host probed for the latency readout; None turns it off
+    fn set_ping_target(&mut self, _target: Option<&str>) {}
+
+    /// Pause the background samplers, all but the heartbeat
+    fn set_samplers_suspended(&mut self, _suspended: bool) {}
+
+    /// Run history of the background samplers, for diagnostics
+    fn sampler_status(&self) -> Vec<TaskStatus> {
+        Vec::new()
+    }
+
+    /// A replay that has played every sample; it reports how it went the
+    /// first time this is true. Live sources never finish.
+    fn finished(&mut self) -> bool {
+        false
+    }
+}
 
 pub struct SystemMonitor {
     sys: System,
     networks: Networks,
     disks: Disks,
     components: Components,
+    /// P/E core counts; None on Intel
+    core_clusters: Option<cpu::CoreClusters>,
+    /// None when IOReport can't be read
+    cluster_frequencies: Option<ioreport::ClusterFrequencies>,
+    /// None when the SMC can't be read; sysinfo's Components are used then
+    smc_sensors: Option<temperature::SmcSensors>,
     last_poll: Instant,
+    /// Counters at the last full poll, for the rates the history records
     prev_net_rx: u64,
     prev_net_tx: u64,
+    /// Counters and time of the last refresh, full or fast, for the session
+    /// totals and the `poll_fast` rates
+    fast_net_rx: u64,
+    fast_net_tx: u64,
+    last_net_refresh: Instant,
+    /// Bytes moved since launch or the last reset
+    session_net_rx: u64,
+    session_net_tx: u64,
+    /// Bytes moved today; None for the CLI modes, which must not write it
+    daily_traffic: Option<daily_traffic::DailyTraffic>,
+    prev_paging: Option<memory::PagingCounters>,
+    /// Mount patterns from the config, see `disk::is_shown`
+    disk_include: Vec<String>,
+    disk_exclude: Vec<String>,
+    scheduler: Scheduler,
+    wifi: Arc<Mutex<Option<WifiInfo>>>,
+    last_sample_at: Arc<AtomicU64>,
+    /// The last poll read at least one temperature
+    had_temps: bool,
+    system_load: Arc<Mutex<Option<SystemLoadStats>>>,
+    /// Refresh the process table each poll (only needed for process watches)
+    collect_processes: bool,
+    public_ip: public_ip::PublicIpLookup,
+    latency: latency::LatencyProbe,
+    connections: connections::ConnectionLookup,
+    smart: smart::SmartLookup,
+    /// This process, for the self-usage readout
+    own_pid: Option<Pid>,
 }
 
 impl SystemMonitor {
     pub fn new() -> Self {
+        let mut monitor = Self::headless();
+        health::register_heartbeat(&monitor.scheduler, Arc::clone(&monitor.last_sample_at));
+        monitor.daily_traffic = Some(daily_traffic::DailyTraffic::load());
+        monitor
+    }
+
+    /// Without the heartbeat, for the CLI modes: a one-off run must not look
+    /// like a live menu bar app to `--check-health`.
+    pub fn headless() -> Self {
         let mut sys = System::new_all();
         sys.refresh_cpu_usage();
         let networks = Networks::new_with_refreshed_list();
@@ -27,47 +138,276 @@ impl SystemMonitor {
         let components = Components::new_with_refreshed_list();
 
         let (rx, tx) = network::total_bytes(&networks);
+        let core_clusters = cpu::CoreClusters::detect();
+        let cluster_frequencies = match core_clusters {
+            Some(_) => ioreport::ClusterFrequencies::open(),
+            None => None,
+        };
+
+        let scheduler = Scheduler::new();
+        let wifi = Arc::new(Mutex::new(None));
+        let wifi_slot = Arc::clone(&wifi);
+        scheduler.register(
+            "wifi",
+            TaskPriority::Low,
+            Duration::from_secs(30),
+            Duration::from_secs(10),
+            move || match wifi::sample() {
+                Ok(info) => {
+                    *wifi_slot.lock().unwrap() = info;
+                    Ok(())
+                }
+                Err(e) => {
+                    *wifi_slot.lock().unwrap() = None;
+                    Err(e)
+                }
+            },
+        );
+        let smart = smart::SmartLookup::register(&scheduler);
+        let latency = latency::LatencyProbe::register(&scheduler);
+        let connections = connections::ConnectionLookup::register(&scheduler);
+        let public_ip = public_ip::PublicIpLookup::register(&scheduler);
+        let system_load = Arc::new(Mutex::new(None));
+        let system_load_slot = Arc::clone(&system_load);
+        scheduler.register(
+            "system-load",
+            TaskPriority::Low,
+            SYSTEM_LOAD_INTERVAL,
+            processes::TOP_TIMEOUT + Duration::from_secs(1),
+            move || {
+                let load = processes::sample()?;
+                *system_load_slot.lock().unwrap() = Some(load);
+                Ok(())
+            },
+        );
+        let last_sample_at = Arc::new(AtomicU64::new(health::unix_now()));
 
         Self {
             sys,
             networks,
             disks,
             components,
+            core_clusters,
+            cluster_frequencies,
+            smc_sensors: temperature::SmcSensors::open(),
             last_poll: Instant::now(),
             prev_net_rx: rx,
             prev_net_tx: tx,
+            fast_net_rx: rx,
+            fast_net_tx: tx,
+            last_net_refresh: Instant::now(),
+            session_net_rx: 0,
+            session_net_tx: 0,
+            daily_traffic: None,
+            prev_paging: None,
+            disk_include: Vec::new(),
+            disk_exclude: Vec::new(),
+            scheduler,
+            wifi,
+            last_sample_at,
+            had_temps: false,
+            system_load,
+            collect_processes: false,
+            public_ip,
+            latency,
+            connections,
+            smart,
+            own_pid: sysinfo::get_current_pid().ok(),
+        }
+    }
+
+    /// Add the traffic since the last refresh to the session and daily totals.
+    fn count_session(&mut self, net: &mut NetworkStats, rx: u64, tx: u64, now: Instant) {
+        // A counter that went backwards (interface reset) counts as no traffic
+        let delta_rx = rx.saturating_sub(self.fast_net_rx);
+        let delta_tx = tx.saturating_sub(self.fast_net_tx);
+        self.session_net_rx += delta_rx;
+        self.session_net_tx += delta_tx;
+        self.fast_net_rx = rx;
+        self.fast_net_tx = tx;
+        self.last_net_refresh = now;
+        net.session_received_bytes = self.session_net_rx;
+        net.session_transmitted_bytes = self.session_net_tx;
+        if let Some(daily) = self.daily_traffic.as_mut() {
+            daily.add(delta_rx, delta_tx);
+            (net.today_received_bytes, net.today_transmitted_bytes) = daily.totals();
         }
     }
 
-    pub fn poll(&mut self) -> SystemStats {
+    /// CPU and memory of this process; the timings are filled in by the caller.
+    fn app_usage(&mut self) -> AppUsage {
+        let Some(pid) = self.own_pid else {
+            return AppUsage::default();
+        };
+        if !self.collect_processes {
+            self.sys
+                .refresh_processes(ProcessesToUpdate::Some(&[pid]), false);
+        }
+        match self.sys.process(pid) {
+            Some(process) => AppUsage {
+                cpu_percent: process.cpu_usage(),
+                memory_bytes: process.memory(),
+                ..AppUsage::default()
+            },
+            None => AppUsage::default(),
+        }
+    }
+}
+
+impl StatsSource for SystemMonitor {
+    fn poll(&mut self) -> SystemStats {
         let now = Instant::now();
-        let elapsed = now.duration_since(self.last_poll).as_secs_f64().max(0.1);
+        // Sub-second polls are fine, but a tick right after another one would
+        // divide counter deltas by a near-zero time and report spikes
+        let elapsed = now
+            .duration_since(self.last_poll)
+            .as_secs_f64()
+            .max(MIN_RATE_WINDOW_SECS);
 
         self.sys.refresh_cpu_usage();
         self.sys.refresh_memory();
         self.disks.refresh(true);
         self.networks.refresh(true);
-        self.components.refresh(true);
 
-        let cpu = cpu::collect(&self.sys);
-        let memory = memory::collect(&self.sys);
-        let disks = disk::collect(&self.disks);
-        let (net, new_rx, new_tx) =
+        let mut cpu = cpu::collect(&self.sys, self.core_clusters.as_ref());
+        if let Some(mhz) = self.cluster_frequencies.as_mut().and_then(|f| f.sample()) {
+            cpu.p_core_mhz = mhz.performance;
+            cpu.e_core_mhz = mhz.efficiency;
+        }
+        let (memory, paging) = memory::collect(&self.sys, self.prev_paging, elapsed);
+        let (mut disks, disk_mounts) =
+            disk::collect(&self.disks, &self.disk_include, &self.disk_exclude);
+        if let Some(health) = self.smart.latest() {
+            for disk in disks.iter_mut().filter(|d| !d.is_removable) {
+                disk.health = Some(health.clone());
+            }
+        }
+        let (mut net, new_rx, new_tx) =
             network::collect(&self.networks, self.prev_net_rx, self.prev_net_tx, elapsed);
+        self.count_session(&mut net, new_rx, new_tx, now);
+        net.wifi = self.wifi.lock().unwrap().clone();
+        let vpn_interfaces = public_ip::vpn_interfaces(&self.networks);
+        // A different interface set, VPN or Wi-Fi network prompts a new lookup
+        let mut network_key: Vec<&str> = self.networks.keys().map(String::as_str).collect();
+        network_key.sort();
+        network_key.extend(vpn_interfaces.iter().map(String::as_str));
+        network_key.extend(net.wifi.as_ref().and_then(|w| w.ssid.as_deref()));
+        let network_key = network_key.join(",");
+        net.public_ip = self
+            .public_ip
+            .latest(network_key)
+            .map(|address| PublicIpInfo {
+                address,
+                vpn_interfaces,
+            });
+        net.latency_ms = self.latency.latest();
+        net.connections = self.connections.latest();
 
         self.prev_net_rx = new_rx;
         self.prev_net_tx = new_tx;
+        self.prev_paging = paging;
         self.last_poll = now;
+        self.last_sample_at
+            .store(health::unix_now(), Ordering::Relaxed);
 
-        let temp = temperature::collect_from(&self.components);
+        let temp = match self.smc_sensors.as_ref().map(|smc| smc.collect()) {
+            Some(temp) if !temp.readings.is_empty() => temp,
+            _ => {
+                self.components.refresh(true);
+                temperature::collect_from(&self.components)
+            }
+        };
+        if temp.readings.is_empty() && self.had_temps {
+            issues::report("monitor", "temperature sensors stopped answering");
+        }
+        self.had_temps = !temp.readings.is_empty();
+
+        let processes = if self.collect_processes {
+            self.sys.refresh_processes(ProcessesToUpdate::All, true);
+            processes::collect_usage(&self.sys)
+        } else {
+            Vec::new()
+        };
+        let app_usage = self.app_usage();
 
         SystemStats {
             cpu,
             memory,
             disks,
+            disk_mounts,
             network: net,
             temperature: temp,
+            system_load: self.system_load.lock().unwrap().clone(),
+            processes,
+            app_usage,
             timestamp: now,
         }
     }
+
+    /// Network only, for the seconds between full polls: rates since the
+    /// previous refresh of either kind. Wi-Fi and public IP are left None for
+    /// the caller to keep from the last full poll.
+    fn poll_fast(&mut self) -> NetworkStats {
+        let now = Instant::now();
+        let elapsed = now
+            .duration_since(self.last_net_refresh)
+            .as_secs_f64()
+            .max(MIN_RATE_WINDOW_SECS);
+        self.networks.refresh(true);
+        let (mut net, rx, tx) =
+            network::collect(&self.networks, self.fast_net_rx, self.fast_net_tx, elapsed);
+        self.count_session(&mut net, rx, tx, now);
+        net
+    }
+
+    fn set_process_collection(&mut self, enabled: bool) {
+        self.collect_processes = enabled;
+    }
+
+    fn reset_session_counters(&mut self) {
+        self.session_net_rx = 0;
+        self.session_net_tx = 0;
+    }
+
+    fn reset_daily_traffic(&mut self) {
+        if let Some(daily) = self.daily_traffic.as_mut() {
+            daily.reset();
+        }
+    }
+
+    /// Write today's traffic now rather than at the next periodic save
+    fn save_daily_traffic(&mut self) {
+        if let Some(daily) = self.daily_traffic.as_mut() {
+            daily.save();
+        }
+    }
+
+    /// Mount patterns for the DISK menu; applied from the next poll.
+    fn set_disk_filter(&mut self, include: &[String], exclude: &[String]) {
+        if self.disk_include != include {
+            self.disk_include = include.to_vec();
+        }
+        if self.disk_exclude != exclude {
+            self.disk_exclude = exclude.to_vec();
+        }
+    }
+
+    /// Endpoint for public IP lookups; None turns them off.
+    fn set_public_ip_url(&mut self, url: Option<&str>) {
+        self.public_ip.set_url(url);
+    }
+
+    /// Host probed for the latency readout; None turns it off.
+    fn set_ping_target(&mut self, target: Option<&str>) {
+        self.latency.set_target(target);
+    }
+
+    fn set_samplers_suspended(&mut self, suspended: bool) {
+        self.scheduler.set_suspended(suspended);
+    }
+
+    /// Run history of the background samplers, for diagnostics
+    fn sampler_status(&self) -> Vec<TaskStatus> {
+        self.scheduler.task_status()
+    }
 }
@@ -24,8 +24,17 @@ pub fn collect(
     let stats = NetworkStats {
         total_received_bytes: rx,
         total_transmitted_bytes: tx,
+        // Filled in by the monitor, which keeps the running totals
+        session_received_bytes: 0,
+        session_transmitted_bytes: 0,
+        today_received_bytes: 0,
+        today_transmitted_bytes: 0,
         received_per_sec: (delta_rx as f64 / elapsed_secs) as u64,
         transmitted_per_sec: (delta_tx as f64 / elapsed_secs) as u64,
+        wifi: None,
+        public_ip: None,
+        latency_ms: None,
+        connections: None,
     };
 
     (stats, rx, tx)
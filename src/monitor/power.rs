@@ -0,0 +1,45 @@
+use std::ffi::{c_char, c_void, CStr};
+
+const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+/// kIOPMBatteryPowerKey
+const BATTERY_POWER: &str = "Battery Power";
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOPSCopyPowerSourcesInfo() -> *const c_void;
+    /// Follows the Get rule: the returned string belongs to the snapshot
+    fn IOPSGetProvidingPowerSourceType(snapshot: *const c_void) -> *const c_void;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFRelease(cf: *const c_void);
+    fn CFStringGetCString(
+        string: *const c_void,
+        buffer: *mut c_char,
+        size: isize,
+        encoding: u32,
+    ) -> bool;
+}
+
+/// True while the Mac runs from its battery; false on AC, UPS or when unknown.
+pub fn on_battery() -> bool {
+    unsafe {
+        let snapshot = IOPSCopyPowerSourcesInfo();
+        if snapshot.is_null() {
+            return false;
+        }
+        let source = IOPSGetProvidingPowerSourceType(snapshot);
+        let mut buf = [0 as c_char; 64];
+        let ok = !source.is_null()
+            && CFStringGetCString(
+                source,
+                buf.as_mut_ptr(),
+                buf.len() as isize,
+                K_CF_STRING_ENCODING_UTF8,
+            );
+        let battery = ok && CStr::from_ptr(buf.as_ptr()).to_bytes() == BATTERY_POWER.as_bytes();
+        CFRelease(snapshot);
+        battery
+    }
+}
@@ -0,0 +1,77 @@
+use super::command;
+use crate::model::{ProcessUsage, SystemLoadStats};
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::Duration;
+use sysinfo::System;
+
+/// top is killed after this long
+pub const TOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Process and thread totals from `top`'s header, plus open files from sysctl.
+pub fn sample() -> Result<SystemLoadStats, String> {
+    let mut top = Command::new("top");
+    top.args(["-l", "1", "-n", "0", "-s", "0"]);
+    let output = command::output_within(&mut top, TOP_TIMEOUT)?;
+    let text = String::from_utf8_lossy(&output);
+    let (processes, threads) = text
+        .lines()
+        .find_map(parse_processes_line)
+        .ok_or_else(|| "no Processes line in top output".to_string())?;
+    Ok(SystemLoadStats {
+        processes,
+        threads,
+        open_files: open_files(),
+    })
+}
+
+/// "Processes: 612 total, 3 running, 609 sleeping, 3420 threads" → (612, 3420)
+fn parse_processes_line(line: &str) -> Option<(u32, u32)> {
+    let rest = line.trim().strip_prefix("Processes:")?;
+    let mut processes = None;
+    let mut threads = None;
+    for part in rest.split(',') {
+        let mut words = part.split_whitespace();
+        let (Some(count), Some(kind)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let Ok(count) = count.parse() else {
+            continue;
+        };
+        match kind {
+            "total" => processes = Some(count),
+            "threads" => threads = Some(count),
+            _ => {}
+        }
+    }
+    Some((processes?, threads?))
+}
+
+/// Open file descriptors system-wide (`kern.num_files`).
+fn open_files() -> Option<u32> {
+    let output = Command::new("sysctl")
+        .arg("-n")
+        .arg("kern.num_files")
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Per-process CPU and memory, summed over processes with the same name.
+/// `sys` must have had its processes refreshed.
+pub fn collect_usage(sys: &System) -> Vec<ProcessUsage> {
+    let mut by_name: HashMap<String, ProcessUsage> = HashMap::new();
+    for process in sys.processes().values() {
+        let name = process.name().to_string_lossy().into_owned();
+        let usage = by_name.entry(name.clone()).or_insert_with(|| ProcessUsage {
+            name,
+            cpu_percent: 0.0,
+            memory_bytes: 0,
+            count: 0,
+        });
+        usage.cpu_percent += process.cpu_usage();
+        usage.memory_bytes += process.memory();
+        usage.count += 1;
+    }
+    by_name.into_values().collect()
+}
@@ -0,0 +1,110 @@
+use crate::scheduler::{Scheduler, TaskPriority};
+use std::net::IpAddr;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::Networks;
+
+/// How often the scheduler checks whether a lookup is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Look the address up again this often even if the network looks unchanged
+const REFRESH_INTERVAL: Duration = Duration::from_secs(600);
+const FETCH_TIMEOUT_SECS: &str = "5";
+/// Interface name prefixes used by VPN clients
+const VPN_PREFIXES: [&str; 3] = ["utun", "ppp", "ipsec"];
+
+/// What the next lookup should use, as of the latest poll
+#[derive(Clone, Default, PartialEq)]
+struct Request {
+    /// None while the feature is off
+    url: Option<String>,
+    /// Interfaces and SSID
+    network_key: String,
+}
+
+/// Public IP lookups by the scheduler, redone every 10 minutes or when the
+/// network changes.
+pub struct PublicIpLookup {
+    request: Arc<Mutex<Request>>,
+    address: Arc<Mutex<Option<String>>>,
+}
+
+impl PublicIpLookup {
+    pub fn register(scheduler: &Scheduler) -> Self {
+        let request = Arc::new(Mutex::new(Request::default()));
+        let address = Arc::new(Mutex::new(None));
+        let (task_request, slot) = (Arc::clone(&request), Arc::clone(&address));
+        // The request and time of the last lookup
+        let mut last: Option<(Request, Instant)> = None;
+        scheduler.register(
+            "public-ip",
+            TaskPriority::Low,
+            CHECK_INTERVAL,
+            // curl gives up on its own after FETCH_TIMEOUT_SECS
+            Duration::from_secs(7),
+            move || {
+                let request = task_request.lock().unwrap().clone();
+                let Some(url) = request.url.clone() else {
+                    return Ok(());
+                };
+                let due = match &last {
+                    Some((previous, fetched)) => {
+                        *previous != request || fetched.elapsed() >= REFRESH_INTERVAL
+                    }
+                    None => true,
+                };
+                if !due {
+                    return Ok(());
+                }
+                last = Some((request, Instant::now()));
+                let result = fetch(&url);
+                *slot.lock().unwrap() = result.as_ref().ok().cloned();
+                result.map(|_| ())
+            },
+        );
+        Self { request, address }
+    }
+
+    pub fn set_url(&mut self, url: Option<&str>) {
+        self.request.lock().unwrap().url = url.map(str::to_string);
+    }
+
+    /// None while disabled; otherwise the last address found, if any. A
+    /// different `network_key` than last time prompts a new lookup.
+    pub fn latest(&self, network_key: String) -> Option<Option<String>> {
+        let mut request = self.request.lock().unwrap();
+        if request.url.is_none() {
+            return None;
+        }
+        request.network_key = network_key;
+        Some(self.address.lock().unwrap().clone())
+    }
+}
+
+fn fetch(url: &str) -> Result<String, String> {
+    let output = Command::new("curl")
+        .args(["-fsS", "--max-time", FETCH_TIMEOUT_SECS, url])
+        .output()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("public IP lookup via {} failed", url));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let address: IpAddr = text
+        .trim()
+        .parse()
+        .map_err(|_| format!("{} did not answer with an address", url))?;
+    Ok(address.to_string())
+}
+
+/// VPN-style interfaces that carried traffic since the last refresh
+pub fn vpn_interfaces(networks: &Networks) -> Vec<String> {
+    let mut names: Vec<String> = networks
+        .iter()
+        .filter(|(name, _)| VPN_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .filter(|(_, data)| data.received() > 0 || data.transmitted() > 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
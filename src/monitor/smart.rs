@@ -0,0 +1,100 @@
+//! SMART data of the internal drive from `smartctl` (smartmontools), read
+//! every few minutes by the scheduler. Without smartctl, or when it can't
+//! open the drive, there is simply no health to show.
+
+use super::command;
+use crate::model::DriveHealth;
+use crate::scheduler::{Scheduler, TaskPriority};
+use serde::Deserialize;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+/// smartctl is killed after this long
+const SMARTCTL_TIMEOUT: Duration = Duration::from_secs(10);
+/// The internal drive on every Mac
+const INTERNAL_DEVICE: &str = "disk0";
+/// GUI apps don't get the shell's PATH, so try Homebrew's locations first
+const SMARTCTL_PATHS: [&str; 3] = [
+    "/opt/homebrew/bin/smartctl",
+    "/usr/local/bin/smartctl",
+    "smartctl",
+];
+
+#[derive(Deserialize)]
+struct SmartctlReport {
+    smart_status: Option<SmartStatus>,
+    temperature: Option<SmartTemperature>,
+    nvme_smart_health_information_log: Option<NvmeHealthLog>,
+}
+
+#[derive(Deserialize)]
+struct SmartStatus {
+    passed: bool,
+}
+
+#[derive(Deserialize)]
+struct SmartTemperature {
+    current: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct NvmeHealthLog {
+    percentage_used: Option<u32>,
+}
+
+pub struct SmartLookup {
+    health: Arc<Mutex<Option<DriveHealth>>>,
+}
+
+impl SmartLookup {
+    /// Read SMART data every few minutes on `scheduler`
+    pub fn register(scheduler: &Scheduler) -> Self {
+        let health = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&health);
+        scheduler.register(
+            "smart",
+            TaskPriority::Low,
+            REFRESH_INTERVAL,
+            SMARTCTL_TIMEOUT + Duration::from_secs(1),
+            move || {
+                let result = fetch();
+                *slot.lock().unwrap() = result.as_ref().ok().cloned();
+                result.map(|_| ())
+            },
+        );
+        Self { health }
+    }
+
+    /// The last reading, if any
+    pub fn latest(&self) -> Option<DriveHealth> {
+        self.health.lock().unwrap().clone()
+    }
+}
+
+fn fetch() -> Result<DriveHealth, String> {
+    // smartctl's exit status is a bit mask that is non-zero for plenty of
+    // readable drives, so go by whether the JSON has a verdict instead
+    let mut last_error = String::new();
+    let stdout = SMARTCTL_PATHS.iter().find_map(|path| {
+        let mut smartctl = Command::new(path);
+        smartctl.args(["-a", "-j", INTERNAL_DEVICE]);
+        command::output_within(&mut smartctl, SMARTCTL_TIMEOUT)
+            .map_err(|e| last_error = e)
+            .ok()
+    });
+    let stdout = stdout.ok_or(last_error)?;
+    let report: SmartctlReport = serde_json::from_slice(&stdout)
+        .map_err(|e| format!("unexpected smartctl output: {}", e))?;
+    let status = report
+        .smart_status
+        .ok_or_else(|| format!("no SMART status for {}", INTERNAL_DEVICE))?;
+    Ok(DriveHealth {
+        passed: status.passed,
+        wear_percent: report
+            .nvme_smart_health_information_log
+            .and_then(|log| log.percentage_used),
+        temp_c: report.temperature.and_then(|temp| temp.current),
+    })
+}
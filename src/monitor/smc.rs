@@ -0,0 +1,185 @@
+//! Direct reads from the System Management Controller through the AppleSMC
+//! user client. Self-contained so `src/bin/temp_test.rs` can include it.
+
+use std::ffi::{c_char, c_void};
+use std::mem::size_of;
+
+/// Selector of the AppleSMC struct method that handles every command
+const KERNEL_INDEX_SMC: u32 = 2;
+const SMC_CMD_READ_BYTES: u8 = 5;
+const SMC_CMD_READ_INDEX: u8 = 8;
+const SMC_CMD_READ_KEYINFO: u8 = 9;
+const KERN_SUCCESS: i32 = 0;
+/// kIOMainPortDefault
+const MAIN_PORT_DEFAULT: u32 = 0;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOServiceMatching(name: *const c_char) -> *mut c_void;
+    /// Consumes the `matching` dictionary
+    fn IOServiceGetMatchingService(main_port: u32, matching: *mut c_void) -> u32;
+    fn IOServiceOpen(service: u32, owning_task: u32, kind: u32, connect: *mut u32) -> i32;
+    fn IOServiceClose(connect: u32) -> i32;
+    fn IOObjectRelease(object: u32) -> i32;
+    fn IOConnectCallStructMethod(
+        connection: u32,
+        selector: u32,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+        output_size: *mut usize,
+    ) -> i32;
+}
+
+extern "C" {
+    static mach_task_self_: u32;
+}
+
+/// `SMCKeyData_t` as the kernel expects it (80 bytes). The version and
+/// power limit structs are never read; the array types keep their alignment.
+#[repr(C)]
+#[derive(Default)]
+struct KeyData {
+    key: u32,
+    _version: [u16; 3],
+    _p_limit: [u32; 4],
+    key_info: KeyInfo,
+    result: u8,
+    _status: u8,
+    data8: u8,
+    data32: u32,
+    bytes: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct KeyInfo {
+    data_size: u32,
+    data_type: u32,
+    _data_attributes: u8,
+}
+
+/// A key with the size and type the SMC reported for it
+pub struct SmcKey {
+    pub name: String,
+    code: u32,
+    size: u32,
+    /// Four characters, e.g. "flt " or "sp78"
+    pub data_type: String,
+}
+
+pub struct Smc {
+    connection: u32,
+}
+
+impl Smc {
+    /// None when the AppleSMC service is missing or refuses the connection
+    pub fn open() -> Option<Self> {
+        unsafe {
+            let matching = IOServiceMatching(c"AppleSMC".as_ptr());
+            if matching.is_null() {
+                return None;
+            }
+            let service = IOServiceGetMatchingService(MAIN_PORT_DEFAULT, matching);
+            if service == 0 {
+                return None;
+            }
+            let mut connection = 0;
+            let status = IOServiceOpen(service, mach_task_self_, 0, &mut connection);
+            IOObjectRelease(service);
+            (status == KERN_SUCCESS).then_some(Smc { connection })
+        }
+    }
+
+    /// Every key the SMC exposes, in index order
+    pub fn key_names(&self) -> Vec<String> {
+        let count = self
+            .key("#KEY")
+            .and_then(|key| self.read_bytes(&key))
+            .and_then(|bytes| Some(u32::from_be_bytes(bytes.get(..4)?.try_into().ok()?)))
+            .unwrap_or(0);
+        (0..count)
+            .filter_map(|index| {
+                let output = self.call(KeyData {
+                    data8: SMC_CMD_READ_INDEX,
+                    data32: index,
+                    ..KeyData::default()
+                })?;
+                Some(code_name(output.key))
+            })
+            .collect()
+    }
+
+    /// Size and type of `name`; None when the key doesn't exist
+    pub fn key(&self, name: &str) -> Option<SmcKey> {
+        let code = name_code(name)?;
+        let output = self.call(KeyData {
+            key: code,
+            data8: SMC_CMD_READ_KEYINFO,
+            ..KeyData::default()
+        })?;
+        Some(SmcKey {
+            name: name.to_string(),
+            code,
+            size: output.key_info.data_size,
+            data_type: code_name(output.key_info.data_type),
+        })
+    }
+
+    pub fn read_bytes(&self, key: &SmcKey) -> Option<Vec<u8>> {
+        let output = self.call(KeyData {
+            key: key.code,
+            key_info: KeyInfo {
+                data_size: key.size,
+                ..KeyInfo::default()
+            },
+            data8: SMC_CMD_READ_BYTES,
+            ..KeyData::default()
+        })?;
+        let len = (key.size as usize).min(output.bytes.len());
+        Some(output.bytes[..len].to_vec())
+    }
+
+    /// Numeric value of a `flt ` or `sp78` key; None for other types
+    pub fn read_value(&self, key: &SmcKey) -> Option<f32> {
+        let bytes = self.read_bytes(key)?;
+        match key.data_type.as_str() {
+            "flt " => Some(f32::from_le_bytes(bytes.get(..4)?.try_into().ok()?)),
+            "sp78" => Some(i16::from_be_bytes(bytes.get(..2)?.try_into().ok()?) as f32 / 256.0),
+            _ => None,
+        }
+    }
+
+    fn call(&self, input: KeyData) -> Option<KeyData> {
+        let mut output = KeyData::default();
+        let mut output_size = size_of::<KeyData>();
+        let status = unsafe {
+            IOConnectCallStructMethod(
+                self.connection,
+                KERNEL_INDEX_SMC,
+                &input as *const KeyData as *const c_void,
+                size_of::<KeyData>(),
+                &mut output as *mut KeyData as *mut c_void,
+                &mut output_size,
+            )
+        };
+        (status == KERN_SUCCESS && output.result == 0).then_some(output)
+    }
+}
+
+impl Drop for Smc {
+    fn drop(&mut self) {
+        unsafe {
+            IOServiceClose(self.connection);
+        }
+    }
+}
+
+fn name_code(name: &str) -> Option<u32> {
+    let bytes: [u8; 4] = name.as_bytes().try_into().ok()?;
+    Some(u32::from_be_bytes(bytes))
+}
+
+fn code_name(code: u32) -> String {
+    String::from_utf8_lossy(&code.to_be_bytes()).into_owned()
+}
@@ -1,11 +1,88 @@
+use super::smc::{Smc, SmcKey};
 use crate::model::{TemperatureReading, TemperatureStats};
 use sysinfo::Components;
 
+/// Temperature keys read straight from the SMC. Preferred over sysinfo's
+/// Components, which come back empty on some Apple Silicon release builds.
+pub struct SmcSensors {
+    smc: Smc,
+    keys: Vec<SmcKey>,
+}
+
+impl SmcSensors {
+    /// None when the SMC can't be opened or exposes no known temperature keys.
+    /// Keeps every T* key for the raw list, grouped or not.
+    pub fn open() -> Option<Self> {
+        let smc = Smc::open()?;
+        let keys: Vec<SmcKey> = smc
+            .key_names()
+            .into_iter()
+            .filter(|name| name.starts_with('T'))
+            .filter_map(|name| smc.key(&name))
+            .collect();
+        if !keys.iter().any(|key| smc_group(&key.name).is_some()) {
+            return None;
+        }
+        Some(Self { smc, keys })
+    }
+
+    pub fn collect(&self) -> TemperatureStats {
+        let mut cpu_temps = Vec::new();
+        let mut gpu_temps = Vec::new();
+        let mut ssd_temp: Option<f32> = None;
+        let mut raw = Vec::new();
+
+        for key in &self.keys {
+            let Some(temp) = self.smc.read_value(key).filter(|&t| is_valid(t)) else {
+                continue;
+            };
+            raw.push(TemperatureReading {
+                label: key.name.clone(),
+                temp_c: temp,
+            });
+            match smc_group(&key.name) {
+                Some(SmcGroup::Cpu) => cpu_temps.push(temp),
+                Some(SmcGroup::Gpu) => gpu_temps.push(temp),
+                // Several NAND sensors; the hottest one speaks for the SSD
+                Some(SmcGroup::Ssd) => ssd_temp = Some(ssd_temp.map_or(temp, |t| t.max(temp))),
+                None => {}
+            }
+        }
+
+        summarize(cpu_temps, gpu_temps, ssd_temp, Vec::new(), raw)
+    }
+}
+
+enum SmcGroup {
+    Cpu,
+    Gpu,
+    Ssd,
+}
+
+/// Tp* = CPU cores, Tg* = GPU, TH* = NAND
+fn smc_group(key: &str) -> Option<SmcGroup> {
+    if key.starts_with("Tp") {
+        Some(SmcGroup::Cpu)
+    } else if key.starts_with("Tg") {
+        Some(SmcGroup::Gpu)
+    } else if key.starts_with("TH") {
+        Some(SmcGroup::Ssd)
+    } else {
+        None
+    }
+}
+
+/// Rules out unplugged or uninitialized sensors
+fn is_valid(temp: f32) -> bool {
+    temp > 0.0 && temp <= 150.0
+}
+
 pub fn collect_from(components: &Components) -> TemperatureStats {
     let mut cpu_temps = Vec::new();
     let mut gpu_temps = Vec::new();
     let mut ssd_temp: Option<f32> = None;
     let mut other: Vec<(String, f32)> = Vec::new();
+    let mut raw = Vec::new();
 
     for comp in components {
         let temp = match comp.temperature() {
@@ -13,11 +90,15 @@ pub fn collect_from(components: &Components) -> TemperatureStats {
             None => continue,
         };
         // Skip invalid temperature readings
-        if temp <= 0.0 || temp > 150.0 {
+        if !is_valid(temp) {
             continue;
         }
         let label = comp.label();
         let lower = label.to_lowercase();
+        raw.push(TemperatureReading {
+            label: label.to_string(),
+            temp_c: temp,
+        });
 
         if lower.contains("nand") || lower.contains("ssd") || lower.contains("disk") {
             ssd_temp = Some(temp);
@@ -40,6 +121,17 @@ pub fn collect_from(components: &Components) -> TemperatureStats {
         }
     }
 
+    summarize(cpu_temps, gpu_temps, ssd_temp, other, raw)
+}
+
+/// Average the CPU and GPU sensors into one reading each, then SSD and the rest
+fn summarize(
+    cpu_temps: Vec<f32>,
+    gpu_temps: Vec<f32>,
+    ssd_temp: Option<f32>,
+    other: Vec<(String, f32)>,
+    raw: Vec<TemperatureReading>,
+) -> TemperatureStats {
     let mut readings = Vec::new();
 
     if !cpu_temps.is_empty() {
@@ -72,12 +164,6 @@ pub fn collect_from(components: &Components) -> TemperatureStats {
         });
     }
 
-    // Only fallback to powermetrics if no readings and we're confident it will work
-    // Avoid calling powermetrics on every tick as it requires privileges
-    if readings.is_empty() {
-        // Return empty readings instead of calling powermetrics which may cause issues
-        return TemperatureStats::default();
-    }
-
-    TemperatureStats { readings }
+    // No powermetrics fallback when readings are empty: it requires privileges
+    TemperatureStats { readings, raw }
 }
@@ -0,0 +1,59 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// A mount that appeared or went away, by mount point
+pub enum VolumeChange {
+    Mounted(String),
+    Unmounted(String),
+}
+
+/// Follows the set of mount points across polls. A mount has to be there
+/// (or gone) for two polls in a row before it counts, so disk images that
+/// come and go within one poll during installs never show up.
+#[derive(Default)]
+pub struct VolumeWatcher {
+    /// Mounts confirmed by two polls; None until the first poll
+    known: Option<BTreeSet<String>>,
+    /// Mounts seen by the previous poll
+    last: BTreeSet<String>,
+}
+
+impl VolumeWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one poll's mount points; returns the confirmed changes. The
+    /// first poll only records what is already mounted.
+    pub fn update(&mut self, mounts: &[String]) -> Vec<VolumeChange> {
+        let current: BTreeSet<String> = mounts.iter().cloned().collect();
+        let mut changes = Vec::new();
+        match self.known.as_mut() {
+            None => self.known = Some(current.clone()),
+            Some(known) => {
+                for mount in current.intersection(&self.last) {
+                    if known.insert(mount.clone()) {
+                        changes.push(VolumeChange::Mounted(mount.clone()));
+                    }
+                }
+                known.retain(|mount| {
+                    let gone = !current.contains(mount) && !self.last.contains(mount);
+                    if gone {
+                        changes.push(VolumeChange::Unmounted(mount.clone()));
+                    }
+                    !gone
+                });
+            }
+        }
+        self.last = current;
+        changes
+    }
+}
+
+/// "Backup" for /Volumes/Backup; the whole path for `/`
+pub fn mount_name(mount_point: &str) -> &str {
+    Path::new(mount_point)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(mount_point)
+}
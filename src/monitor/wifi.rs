@@ -0,0 +1,77 @@
+use crate::model::WifiInfo;
+use serde_json::Value;
+use std::process::Command;
+
+/// Query the current Wi-Fi connection via `system_profiler`.
+///
+/// Returns `Ok(None)` when there is no associated Wi-Fi interface
+/// (Ethernet-only, Wi-Fi off), and `Err` only when the tool itself fails.
+pub fn sample() -> Result<Option<WifiInfo>, String> {
+    let output = Command::new("system_profiler")
+        .arg("SPAirPortDataType")
+        .arg("-json")
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("system_profiler exited with {}", output.status));
+    }
+    let root: Value = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    Ok(parse(&root))
+}
+
+fn parse(root: &Value) -> Option<WifiInfo> {
+    let interfaces = root
+        .get("SPAirPortDataType")?
+        .as_array()?
+        .iter()
+        .filter_map(|entry| entry.get("spairport_airport_interfaces")?.as_array())
+        .flatten();
+
+    for iface in interfaces {
+        let Some(network) = iface.get("spairport_current_network_information") else {
+            continue;
+        };
+        // Without location permission macOS reports the SSID as "<redacted>"
+        let ssid = network
+            .get("_name")
+            .and_then(Value::as_str)
+            .filter(|name| !name.is_empty() && !name.starts_with('<'))
+            .map(str::to_string);
+        let (rssi_dbm, noise_dbm) = network
+            .get("spairport_signal_noise")
+            .and_then(Value::as_str)
+            .and_then(parse_signal_noise)
+            .unwrap_or((0, 0));
+        let channel = match network.get("spairport_network_channel") {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => String::new(),
+        };
+        let tx_rate_mbps = network
+            .get("spairport_network_rate")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+        return Some(WifiInfo {
+            ssid,
+            rssi_dbm,
+            noise_dbm,
+            channel,
+            tx_rate_mbps,
+        });
+    }
+    None
+}
+
+/// "-54 dBm / -92 dBm" → (-54, -92)
+fn parse_signal_noise(text: &str) -> Option<(i32, i32)> {
+    let mut parts = text.split('/').map(|part| {
+        part.trim()
+            .trim_end_matches("dBm")
+            .trim()
+            .parse::<i32>()
+            .ok()
+    });
+    let rssi = parts.next()??;
+    let noise = parts.next().flatten().unwrap_or(0);
+    Some((rssi, noise))
+}
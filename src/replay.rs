@@ -0,0 +1,309 @@
+//! `--replay <file> [--speed 10x]`: play a `--watch` recording through the
+//! full app (tray, menus, charts and alerts, with notifications off) to
+//! reproduce what a user saw. Lines that can't be read, newer schemas,
+//! panics and new issues are reported on stderr with the line they came
+//! from. The app stays open on the last sample once the recording ends.
+
+use crate::issues::{self, Issue};
+use crate::model::{NetworkStats, SystemStats};
+use crate::monitor::StatsSource;
+use crate::report::RECORDING_SCHEMA;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Poll interval when the recording has fewer than two timestamps
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+/// Fastest `--speed`; quicker than this the event loop can't keep up
+const MAX_SPEED: f64 = 100.0;
+
+/// Line of the sample being played, for the panic hook
+static CURRENT_LINE: AtomicUsize = AtomicUsize::new(0);
+
+/// What a recorded line carries besides the stats
+#[derive(Deserialize)]
+struct LineHeader {
+    /// Missing in recordings from before the schema was versioned
+    schema_version: Option<u32>,
+    /// App version that recorded the line
+    version: Option<String>,
+    /// Unix time of the sample
+    timestamp: Option<u64>,
+}
+
+struct Sample {
+    /// 1-based line in the file
+    line: usize,
+    stats: SystemStats,
+}
+
+pub struct ReplaySource {
+    samples: VecDeque<Sample>,
+    /// Typical gap between the recorded samples
+    interval: Duration,
+    /// Line of the sample played last; 0 before the first
+    line: usize,
+    played: usize,
+    skipped: usize,
+    /// Issues reported while playing, counted as divergences
+    diverged: usize,
+    /// Issue list after the last check, to tell new reports apart
+    issues_seen: Vec<Issue>,
+    reported: bool,
+}
+
+impl ReplaySource {
+    /// Read every sample of a recording up front. Unreadable lines are
+    /// skipped and reported; a file without any sample is an error.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        let mut samples = VecDeque::new();
+        let mut timestamps = Vec::new();
+        let mut skipped = 0;
+        let mut newer_schema_reported = false;
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed = serde_json::from_str::<serde_json::Value>(line).and_then(|value| {
+                let header = LineHeader::deserialize(&value)?;
+                let stats = SystemStats::deserialize(&value)?;
+                Ok((header, stats))
+            });
+            let (header, stats) = match parsed {
+                Ok(sample) => sample,
+                Err(e) => {
+                    eprintln!("replay: line {}: skipped, {}", line_number, e);
+                    skipped += 1;
+                    continue;
+                }
+            };
+            // Older lines miss fields, which read as empty; newer ones may
+            // carry fields this build doesn't know, which are ignored
+            let schema = header.schema_version.unwrap_or(1);
+            if schema > RECORDING_SCHEMA && !newer_schema_reported {
+                eprintln!(
+                    "replay: line {}: recorded with schema {} by version {}; this build reads {}, unknown fields are ignored",
+                    line_number,
+                    schema,
+                    header.version.as_deref().unwrap_or("unknown"),
+                    RECORDING_SCHEMA
+                );
+                newer_schema_reported = true;
+            }
+            timestamps.extend(header.timestamp);
+            samples.push_back(Sample {
+                line: line_number,
+                stats,
+            });
+        }
+        if samples.is_empty() {
+            return Err(format!("no samples in {}", path.display()));
+        }
+        Ok(Self {
+            samples,
+            interval: typical_interval(&timestamps).unwrap_or(DEFAULT_INTERVAL),
+            line: 0,
+            played: 0,
+            skipped,
+            diverged: 0,
+            issues_seen: issues::recent(),
+            reported: false,
+        })
+    }
+
+    /// Time between polls: the recording's own interval, sped up
+    pub fn interval(&self, speed: f64) -> Duration {
+        self.interval.div_f64(speed)
+    }
+
+    /// Print issues reported since the last check, against the sample
+    /// that was playing
+    fn report_new_issues(&mut self) {
+        let issues = issues::recent();
+        for issue in &issues {
+            let before = self
+                .issues_seen
+                .iter()
+                .find(|seen| seen.source == issue.source && seen.message == issue.message)
+                .map_or(0, |seen| seen.count);
+            if issue.count > before {
+                eprintln!("replay: {}: {}", at_line(self.line), issue.line());
+                self.diverged += 1;
+            }
+        }
+        self.issues_seen = issues;
+    }
+}
+
+impl StatsSource for ReplaySource {
+    fn poll(&mut self) -> SystemStats {
+        self.report_new_issues();
+        let Some(sample) = self.samples.pop_front() else {
+            return SystemStats::default();
+        };
+        self.line = sample.line;
+        self.played += 1;
+        CURRENT_LINE.store(sample.line, Ordering::Relaxed);
+        SystemStats {
+            timestamp: Instant::now(),
+            ..sample.stats
+        }
+    }
+
+    /// Replays run without fast polls; nothing new happens between samples
+    fn poll_fast(&mut self) -> NetworkStats {
+        NetworkStats::default()
+    }
+
+    fn finished(&mut self) -> bool {
+        if !self.samples.is_empty() {
+            return false;
+        }
+        if !self.reported {
+            self.report_new_issues();
+            eprintln!(
+                "replay: played {} samples, skipped {} lines, {} issues",
+                self.played, self.skipped, self.diverged
+            );
+            self.reported = true;
+        }
+        true
+    }
+}
+
+/// `--replay <file> [--speed 10x]`: the recording and the playback speed.
+/// None without `--replay`.
+pub fn from_args(args: &[String]) -> Option<Result<(ReplaySource, f64), String>> {
+    let i = args.iter().position(|arg| arg == "--replay")?;
+    let Some(path) = args.get(i + 1) else {
+        return Some(Err("--replay needs a recording from --watch".to_string()));
+    };
+    let speed = match args.iter().position(|arg| arg == "--speed") {
+        Some(i) => match args.get(i + 1).and_then(|arg| parse_speed(arg)) {
+            Some(speed) => speed,
+            None => return Some(Err(format!("--speed takes 1x to {}x", MAX_SPEED))),
+        },
+        None => 1.0,
+    };
+    Some(ReplaySource::load(Path::new(path)).map(|source| (source, speed)))
+}
+
+/// "10x" or "10" → 10.0; None outside 1x to `MAX_SPEED`
+fn parse_speed(text: &str) -> Option<f64> {
+    let speed: f64 = text.trim().trim_end_matches(['x', 'X']).parse().ok()?;
+    (1.0..=MAX_SPEED).contains(&speed).then_some(speed)
+}
+
+/// Median gap between consecutive timestamps; None with fewer than two
+fn typical_interval(timestamps: &[u64]) -> Option<Duration> {
+    let mut gaps: Vec<u64> = timestamps
+        .windows(2)
+        .map(|pair| pair[1].saturating_sub(pair[0]))
+        .filter(|&gap| gap > 0)
+        .collect();
+    gaps.sort_unstable();
+    gaps.get(gaps.len() / 2)
+        .map(|&secs| Duration::from_secs(secs))
+}
+
+fn at_line(line: usize) -> String {
+    match line {
+        0 => "before the first sample".to_string(),
+        line => format!("line {}", line),
+    }
+}
+
+/// Name the sample that was playing when the app panics, before the
+/// usual panic message
+pub fn report_panics() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        eprintln!(
+            "replay: panicked at {}",
+            at_line(CURRENT_LINE.load(Ordering::Relaxed))
+        );
+        default_hook(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn recording(name: &str, text: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("msm-replay-{}-{}.jsonl", name, std::process::id()));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_older_and_newer_lines_and_skips_broken_ones() {
+        let path = recording(
+            "schemas",
+            concat!(
+                // Before the schema was versioned: no schema_version, no memory
+                r#"{"version":"0.1.0","timestamp":100,"cpu":{"global_usage":12.5}}"#,
+                "\n",
+                "{not json\n",
+                "\n",
+                r#"{"schema_version":99,"timestamp":102,"cpu":{"global_usage":50.0},"#,
+                r#""memory":{"usage_percent":40.0},"gpu":{"usage":3}}"#,
+                "\n",
+                r#"{"schema_version":1,"timestamp":104,"cpu":{"global_usage":"busy"}}"#,
+                "\n",
+            ),
+        );
+        let mut source = ReplaySource::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(source.skipped, 2);
+        assert_eq!(source.interval(1.0), Duration::from_secs(2));
+        assert_eq!(source.interval(10.0), Duration::from_millis(200));
+        let first = source.poll();
+        assert_eq!(first.cpu.global_usage, 12.5);
+        assert_eq!(first.memory.usage_percent, 0.0);
+        assert_eq!(source.line, 1);
+        let second = source.poll();
+        assert_eq!(second.memory.usage_percent, 40.0);
+        assert_eq!(source.line, 4);
+        assert!(source.finished());
+        assert_eq!(source.played, 2);
+    }
+
+    #[test]
+    fn a_recording_without_samples_is_an_error() {
+        let path = recording("empty", "\n{oops\n");
+        let result = ReplaySource::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn speed_accepts_a_trailing_x() {
+        assert_eq!(parse_speed("10x"), Some(10.0));
+        assert_eq!(parse_speed("2.5"), Some(2.5));
+        assert_eq!(parse_speed("0.5x"), None);
+        assert_eq!(parse_speed("1000x"), None);
+        assert_eq!(parse_speed("fast"), None);
+    }
+
+    #[test]
+    fn typical_interval_is_the_median_gap() {
+        assert_eq!(typical_interval(&[]), None);
+        assert_eq!(typical_interval(&[5]), None);
+        // A long sleep between 20 and 500 doesn't stretch the interval
+        assert_eq!(
+            typical_interval(&[10, 15, 20, 500, 505]),
+            Some(Duration::from_secs(5))
+        );
+    }
+}
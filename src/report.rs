@@ -0,0 +1,378 @@
+use crate::format::format_bytes;
+use crate::health::unix_now;
+use crate::model::{
+    HourRollup, MinuteSummary, ProcessUsage, SystemStats, TemperatureReading, Trend, Trends,
+};
+use crate::ui::tray::format_uptime;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::process::Command;
+
+/// The stats plus when they were sampled and by which version
+#[derive(Serialize)]
+struct StatsReport<'a> {
+    version: &'static str,
+    timestamp: u64,
+    #[serde(flatten)]
+    stats: &'a SystemStats,
+}
+
+/// Bumped whenever the stats file changes in a way readers would notice
+const STATS_FILE_SCHEMA: u32 = 1;
+
+/// Bumped whenever a `--watch` line changes in a way `--replay` would
+/// notice
+pub const RECORDING_SCHEMA: u32 = 1;
+
+/// One `--watch` line, and one sample of a `--replay` recording
+#[derive(Serialize)]
+struct RecordedLine<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    report: StatsReport<'a>,
+}
+
+/// The stats file: the report plus a few derived fields
+#[derive(Serialize)]
+struct StatsFile<'a> {
+    schema_version: u32,
+    /// Highest reading; None without sensors
+    hottest_sensor: Option<&'a TemperatureReading>,
+    /// Busiest process by CPU; None unless process collection is on
+    top_process: Option<&'a ProcessUsage>,
+    #[serde(flatten)]
+    report: StatsReport<'a>,
+}
+
+/// Series of a history export, each with an avg and a max column
+const HISTORY_SERIES: [&str; 6] = [
+    "cpu",
+    "mem",
+    "disk",
+    "net_down_kbps",
+    "net_up_kbps",
+    "pageouts",
+];
+
+/// Export History as JSON
+#[derive(Serialize)]
+struct HistoryExport<'a> {
+    version: &'static str,
+    /// Oldest first
+    minutes: &'a VecDeque<MinuteSummary>,
+}
+
+/// Unix time the stats were sampled
+fn sampled_at(stats: &SystemStats) -> u64 {
+    unix_now().saturating_sub(stats.timestamp.elapsed().as_secs())
+}
+
+/// " ↑" after a reading with a trend, as the menu bar shows it
+fn arrow(trend: Option<Trend>) -> String {
+    trend.map(|t| format!(" {}", t.arrow())).unwrap_or_default()
+}
+
+/// Readable summary for pasting into support tickets, with the menu bar's
+/// trend arrows.
+pub fn plain_text(stats: &SystemStats, trends: &Trends) -> String {
+    let mut lines = vec![
+        format!("Mac State Monitor v{}", env!("CARGO_PKG_VERSION")),
+        format!("Sampled: {}", format_local_time(sampled_at(stats))),
+        String::new(),
+    ];
+
+    let cpu = &stats.cpu;
+    lines.push(format!(
+        "CPU: {:.1}%{} ({} cores), load {:.2} / {:.2} / {:.2}",
+        cpu.global_usage,
+        arrow(trends.cpu),
+        cpu.core_count,
+        cpu.load_average[0],
+        cpu.load_average[1],
+        cpu.load_average[2]
+    ));
+    let cores: Vec<String> = cpu
+        .per_core_usage
+        .iter()
+        .map(|usage| format!("{:.0}%", usage))
+        .collect();
+    lines.push(format!("Per core: {}", cores.join(" ")));
+    if let (Some(p), Some(e)) = (cpu.p_core_usage, cpu.e_core_usage) {
+        let mhz = |value: Option<u32>| value.map(|v| format!(" @ {} MHz", v)).unwrap_or_default();
+        lines.push(format!(
+            "Clusters: P {:.0}%{}, E {:.0}%{}",
+            p,
+            mhz(cpu.p_core_mhz),
+            e,
+            mhz(cpu.e_core_mhz)
+        ));
+    }
+    lines.push(format!("Uptime: {}", format_uptime(cpu.uptime_secs)));
+    if let Some(load) = &stats.system_load {
+        let files = load
+            .open_files
+            .map(|n| format!(", {} open files", n))
+            .unwrap_or_default();
+        lines.push(format!(
+            "Processes: {}, {} threads{}",
+            load.processes, load.threads, files
+        ));
+    }
+
+    let mem = &stats.memory;
+    lines.push(String::new());
+    lines.push(format!(
+        "Memory: {} / {} used ({:.0}%{}), {} available",
+        format_bytes(mem.used_bytes),
+        format_bytes(mem.total_bytes),
+        mem.usage_percent,
+        arrow(trends.mem),
+        format_bytes(mem.available_bytes)
+    ));
+    lines.push(format!(
+        "Swap: {} / {}",
+        format_bytes(mem.swap_used_bytes),
+        format_bytes(mem.swap_total_bytes)
+    ));
+    if let Some(activity) = &mem.swap_activity {
+        lines.push(format!(
+            "Paging: in {:.0}/s, out {:.0}/s, compressions {:.0}/s",
+            activity.pageins_per_sec, activity.pageouts_per_sec, activity.compressions_per_sec
+        ));
+    }
+
+    lines.push(String::new());
+    for (i, disk) in stats.disks.iter().enumerate() {
+        // Only the first disk has a history
+        let trend = trends.disk.filter(|_| i == 0);
+        lines.push(format!(
+            "Disk {} ({}): {} / {} used ({:.0}%{})",
+            disk.name,
+            disk.mount_point,
+            format_bytes(disk.total_bytes - disk.available_bytes),
+            format_bytes(disk.total_bytes),
+            disk.usage_percent,
+            arrow(trend)
+        ));
+    }
+
+    let net = &stats.network;
+    lines.push(String::new());
+    lines.push(format!(
+        "Network: down {}/s, up {}/s{}",
+        format_bytes(net.received_per_sec),
+        format_bytes(net.transmitted_per_sec),
+        arrow(trends.net)
+    ));
+    lines.push(format!(
+        "Network totals: {} received, {} sent",
+        format_bytes(net.total_received_bytes),
+        format_bytes(net.total_transmitted_bytes)
+    ));
+    lines.push(format!(
+        "Network this session: {} received, {} sent",
+        format_bytes(net.session_received_bytes),
+        format_bytes(net.session_transmitted_bytes)
+    ));
+    if let Some(wifi) = &net.wifi {
+        lines.push(format!(
+            "Wi-Fi: {}, {} dBm (noise {} dBm), channel {}, {} Mbps",
+            wifi.ssid.as_deref().unwrap_or("(hidden)"),
+            wifi.rssi_dbm,
+            wifi.noise_dbm,
+            wifi.channel,
+            wifi.tx_rate_mbps
+        ));
+    }
+
+    lines.push(String::new());
+    if stats.temperature.readings.is_empty() {
+        lines.push("Temperatures: none".to_string());
+    }
+    for reading in &stats.temperature.readings {
+        lines.push(format!(
+            "{}: {:.1}C{}",
+            reading.label,
+            reading.temp_c,
+            arrow(trends.temp(&reading.label))
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// The same data as JSON, with `version` and unix `timestamp` added.
+pub fn json(stats: &SystemStats) -> String {
+    serde_json::to_string_pretty(&report(stats)).unwrap_or_default()
+}
+
+/// Contents of `stats_output_path`
+pub fn stats_file_json(stats: &SystemStats) -> serde_json::Result<String> {
+    let file = StatsFile {
+        schema_version: STATS_FILE_SCHEMA,
+        hottest_sensor: stats
+            .temperature
+            .readings
+            .iter()
+            .max_by(|a, b| a.temp_c.total_cmp(&b.temp_c)),
+        top_process: stats
+            .processes
+            .iter()
+            .max_by(|a, b| a.cpu_percent.total_cmp(&b.cpu_percent)),
+        report: report(stats),
+    };
+    serde_json::to_string_pretty(&file)
+}
+
+/// The last hour as CSV, a row per minute with the avg and max of each
+/// series. Cells are empty where a series had no reading, and minutes
+/// without a poll (the Mac asleep) get a row of empty cells with 0 samples.
+pub fn history_csv(rollup: &HourRollup) -> String {
+    let mut header = vec!["unix_time".to_string(), "samples".to_string()];
+    for series in HISTORY_SERIES {
+        header.push(format!("{}_avg", series));
+        header.push(format!("{}_max", series));
+    }
+    let mut lines = vec![header.join(",")];
+    let empty = ",".repeat(HISTORY_SERIES.len() * 2);
+    let mut next = None;
+    for minute in rollup.minutes() {
+        let mut skipped = next.unwrap_or(minute.start);
+        while skipped < minute.start {
+            lines.push(format!("{},0{}", skipped, empty));
+            skipped += 60;
+        }
+        next = Some(minute.start + 60);
+        let mut cells = vec![minute.start.to_string(), minute.samples.to_string()];
+        for span in [
+            minute.cpu,
+            minute.mem,
+            minute.disk,
+            minute.net_down,
+            minute.net_up,
+            minute.pageouts,
+        ] {
+            match span {
+                Some(span) => {
+                    cells.push(format!("{:.2}", span.avg));
+                    cells.push(format!("{:.2}", span.max));
+                }
+                None => cells.extend([String::new(), String::new()]),
+            }
+        }
+        lines.push(cells.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// The last hour as JSON, a row per minute; series without a reading in
+/// a minute are null
+pub fn history_json(rollup: &HourRollup) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&HistoryExport {
+        version: env!("CARGO_PKG_VERSION"),
+        minutes: rollup.minutes(),
+    })
+}
+
+/// `json` on a single line with the recording schema, for streaming JSON
+/// lines that `--replay` can play back
+pub fn json_line(stats: &SystemStats) -> String {
+    let line = RecordedLine {
+        schema_version: RECORDING_SCHEMA,
+        report: report(stats),
+    };
+    serde_json::to_string(&line).unwrap_or_default()
+}
+
+fn report(stats: &SystemStats) -> StatsReport<'_> {
+    StatsReport {
+        version: env!("CARGO_PKG_VERSION"),
+        timestamp: sampled_at(stats),
+        stats,
+    }
+}
+
+/// "2024-05-01 14:03:12 CEST" via `date`, or the raw unix time.
+fn format_local_time(unix: u64) -> String {
+    Command::new("date")
+        .args(["-r", &unix.to_string(), "+%Y-%m-%d %H:%M:%S %Z"])
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|text| !text.is_empty())
+        .unwrap_or_else(|| unix.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::mock::sample;
+
+    #[test]
+    fn history_csv_leaves_missing_readings_empty() {
+        let mut rollup = HourRollup::default();
+        rollup.push(&sample(10.0, 40.0, 0.0), 120);
+        rollup.push(&sample(30.0, 40.0, 0.0), 150);
+        let csv = history_csv(&rollup);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("unix_time,samples,cpu_avg,cpu_max,mem_avg"));
+        assert_eq!(lines[0].split(',').count(), 14);
+        // No disk and no swap activity: their cells stay empty
+        assert_eq!(
+            lines[1],
+            "120,2,20.00,30.00,40.00,40.00,,,0.00,0.00,0.00,0.00,,"
+        );
+    }
+
+    #[test]
+    fn history_csv_fills_minutes_without_polls() {
+        let mut rollup = HourRollup::default();
+        rollup.push(&sample(10.0, 40.0, 0.0), 60);
+        rollup.push(&sample(30.0, 40.0, 0.0), 240);
+        let csv = history_csv(&rollup);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("60,1,10.00"));
+        assert_eq!(lines[2], "120,0,,,,,,,,,,,,");
+        assert_eq!(lines[3], "180,0,,,,,,,,,,,,");
+        assert!(lines[4].starts_with("240,1,30.00"));
+    }
+
+    #[test]
+    fn history_json_has_a_row_per_minute() {
+        let mut rollup = HourRollup::default();
+        rollup.push(&sample(10.0, 40.0, 0.0), 60);
+        rollup.push(&sample(30.0, 40.0, 0.0), 120);
+        let value: serde_json::Value =
+            serde_json::from_str(&history_json(&rollup).unwrap()).unwrap();
+        let minutes = value["minutes"].as_array().unwrap();
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[1]["start"], 120);
+        assert_eq!(minutes[1]["cpu"]["max"], 30.0);
+        assert!(minutes[0]["disk"].is_null());
+    }
+
+    #[test]
+    fn an_empty_history_exports_just_the_header() {
+        let rollup = HourRollup::default();
+        assert_eq!(history_csv(&rollup).lines().count(), 1);
+        assert!(history_json(&rollup).unwrap().contains("\"minutes\": []"));
+    }
+
+    #[test]
+    fn plain_text_carries_the_trend_arrows() {
+        let stats = sample(40.0, 50.0, 70.0);
+        let trends = Trends {
+            cpu: Some(Trend::Rising),
+            mem: Some(Trend::Flat),
+            temps: [("CPU".to_string(), Trend::Falling)].into(),
+            ..Trends::default()
+        };
+        let text = plain_text(&stats, &trends);
+        assert!(text.contains("CPU: 40.0% ↑ ("));
+        assert!(text.contains("(50% →)"));
+        assert!(text.contains("CPU: 70.0C ↓"));
+        assert!(!plain_text(&stats, &Trends::default()).contains('↑'));
+    }
+}
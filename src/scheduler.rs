@@ -0,0 +1,355 @@
+use std::cmp::Reverse;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Upper bound for the delay after repeated failures
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+/// Upper bound for the random delay spreading out task start times
+const MAX_JITTER: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaskPriority {
+    Low,
+    Normal,
+    High,
+}
+
+pub type TaskFn = Box<dyn FnMut() -> Result<(), String> + Send>;
+
+#[derive(Clone)]
+pub struct TaskStatus {
+    pub name: String,
+    pub last_run: Option<Instant>,
+    pub last_duration: Option<Duration>,
+    pub failures: u32,
+}
+
+struct Task {
+    name: String,
+    priority: TaskPriority,
+    interval: Duration,
+    timeout: Duration,
+    next_due: Instant,
+    last_run: Option<Instant>,
+    last_duration: Option<Duration>,
+    failures: u32,
+    run: Arc<Mutex<TaskFn>>,
+    /// A run is in progress on its own thread
+    running: bool,
+    /// The current run overran its timeout and was counted as a failure;
+    /// its result no longer counts
+    abandoned: bool,
+}
+
+struct State {
+    tasks: Vec<Task>,
+    suspended: bool,
+    shutdown: bool,
+}
+
+/// Single background worker running periodic sampler tasks.
+///
+/// When several tasks are due, the highest priority one runs first. Each run
+/// gets its own thread; the worker waits for it up to the task's timeout and
+/// then moves on, so a hung task can't hold up the others. Tasks that fail
+/// or overrun their timeout are retried with exponential backoff.
+pub struct Scheduler {
+    shared: Arc<(Mutex<State>, Condvar)>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        let shared = Arc::new((
+            Mutex::new(State {
+                tasks: Vec::new(),
+                suspended: false,
+                shutdown: false,
+            }),
+            Condvar::new(),
+        ));
+        let worker_shared = Arc::clone(&shared);
+        let _ = thread::Builder::new()
+            .name("sampler-scheduler".to_string())
+            .spawn(move || worker_loop(worker_shared));
+        Self { shared }
+    }
+
+    pub fn register(
+        &self,
+        name: &str,
+        priority: TaskPriority,
+        interval: Duration,
+        timeout: Duration,
+        run: impl FnMut() -> Result<(), String> + Send + 'static,
+    ) {
+        let (lock, cvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        let now = Instant::now();
+        let next_due = now + jitter(interval, state.tasks.len());
+        state.tasks.push(Task {
+            name: name.to_string(),
+            priority,
+            interval,
+            timeout,
+            next_due,
+            last_run: None,
+            last_duration: None,
+            failures: 0,
+            run: Arc::new(Mutex::new(Box::new(run))),
+            running: false,
+            abandoned: false,
+        });
+        cvar.notify_one();
+    }
+
+    /// Pause all but the high priority tasks (sleep, screen lock, battery
+    /// saver). On resume, overdue tasks are spread out again instead of all
+    /// firing at once.
+    pub fn set_suspended(&self, suspended: bool) {
+        let (lock, cvar) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        if state.suspended == suspended {
+            return;
+        }
+        state.suspended = suspended;
+        if !suspended {
+            let now = Instant::now();
+            for (i, task) in state.tasks.iter_mut().enumerate() {
+                if task.next_due <= now {
+                    task.next_due = now + jitter(task.interval, i);
+                }
+            }
+        }
+        cvar.notify_one();
+    }
+
+    pub fn task_status(&self) -> Vec<TaskStatus> {
+        let (lock, _) = &*self.shared;
+        let state = lock.lock().unwrap();
+        state
+            .tasks
+            .iter()
+            .map(|t| TaskStatus {
+                name: t.name.clone(),
+                last_run: t.last_run,
+                last_duration: t.last_duration,
+                failures: t.failures,
+            })
+            .collect()
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        let (lock, cvar) = &*self.shared;
+        if let Ok(mut state) = lock.lock() {
+            state.shutdown = true;
+        }
+        cvar.notify_one();
+    }
+}
+
+fn worker_loop(shared: Arc<(Mutex<State>, Condvar)>) {
+    let (lock, cvar) = &*shared;
+    let mut state = lock.lock().unwrap();
+    loop {
+        if state.shutdown {
+            return;
+        }
+
+        let now = Instant::now();
+        let suspended = state.suspended;
+        let runnable = |t: &Task| !t.running && (!suspended || t.priority == TaskPriority::High);
+        let due = state
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| runnable(t) && t.next_due <= now)
+            .max_by_key(|(_, t)| (t.priority, Reverse(t.next_due)))
+            .map(|(i, _)| i);
+
+        let Some(idx) = due else {
+            let next = state
+                .tasks
+                .iter()
+                .filter(|t| runnable(t))
+                .map(|t| t.next_due)
+                .min();
+            state = match next {
+                Some(next) => {
+                    cvar.wait_timeout(state, next.saturating_duration_since(now))
+                        .unwrap()
+                        .0
+                }
+                None => cvar.wait(state).unwrap(),
+            };
+            continue;
+        };
+
+        let task = &mut state.tasks[idx];
+        task.running = true;
+        let name = task.name.clone();
+        let timeout = task.timeout;
+        let run = Arc::clone(&task.run);
+        drop(state);
+
+        let started = Instant::now();
+        let (done_tx, done_rx) = mpsc::channel();
+        let runner_shared = Arc::clone(&shared);
+        let spawned = thread::Builder::new()
+            .name(format!("task-{}", name))
+            .spawn(move || {
+                let result = {
+                    let mut run = run.lock().unwrap_or_else(|e| e.into_inner());
+                    panic::catch_unwind(AssertUnwindSafe(|| (*run)()))
+                        .unwrap_or_else(|_| Err("panicked".to_string()))
+                };
+                let (lock, cvar) = &*runner_shared;
+                let mut state = lock.lock().unwrap();
+                finish(&mut state.tasks[idx], started, result);
+                let _ = done_tx.send(());
+                cvar.notify_one();
+            });
+
+        let outcome = match spawned {
+            Ok(_) => done_rx.recv_timeout(timeout),
+            Err(_) => Err(RecvTimeoutError::Disconnected),
+        };
+        state = lock.lock().unwrap();
+        let task = &mut state.tasks[idx];
+        match outcome {
+            Ok(()) => {}
+            // Finished just after the deadline
+            Err(_) if !task.running => {}
+            Err(RecvTimeoutError::Timeout) => {
+                log::warn!(
+                    "{} still running after {:?}; moving on without it",
+                    task.name,
+                    task.timeout
+                );
+                task.abandoned = true;
+                fail(task, started);
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                log::warn!("could not start a thread for {}", task.name);
+                task.running = false;
+                fail(task, started);
+            }
+        }
+    }
+}
+
+/// Record a finished run. An abandoned run was already counted as a
+/// failure when it overran, so only its duration is kept.
+fn finish(task: &mut Task, started: Instant, result: Result<(), String>) {
+    let elapsed = started.elapsed();
+    task.running = false;
+    task.last_run = Some(started);
+    task.last_duration = Some(elapsed);
+    if std::mem::take(&mut task.abandoned) {
+        return;
+    }
+    if result.is_ok() && elapsed <= task.timeout {
+        task.failures = 0;
+        task.next_due = started + task.interval;
+    } else {
+        fail(task, started);
+    }
+}
+
+/// Count a failure and back off before the next run.
+fn fail(task: &mut Task, started: Instant) {
+    task.failures = task.failures.saturating_add(1);
+    let backoff = task
+        .interval
+        .saturating_mul(1 << task.failures.min(6))
+        .min(MAX_BACKOFF)
+        .max(task.interval);
+    task.next_due = started + backoff;
+}
+
+/// Pseudo-random delay in `[0, min(interval, MAX_JITTER))`, salted per task.
+fn jitter(interval: Duration, salt: usize) -> Duration {
+    let max_ms = interval.min(MAX_JITTER).as_millis() as u64;
+    if max_ms == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    let mixed = nanos
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add((salt as u64).wrapping_mul(1442695040888963407));
+    Duration::from_millis((mixed >> 33) % max_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_run(scheduler: &Scheduler, name: &str) -> Option<Instant> {
+        scheduler
+            .task_status()
+            .into_iter()
+            .find(|t| t.name == name)
+            .and_then(|t| t.last_run)
+    }
+
+    #[test]
+    fn a_hung_task_does_not_hold_up_the_others() {
+        let scheduler = Scheduler::new();
+        let (_release, hang) = mpsc::channel::<()>();
+        let hang = Mutex::new(hang);
+        scheduler.register(
+            "hung",
+            TaskPriority::High,
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+            move || {
+                let _ = hang.lock().unwrap().recv();
+                Ok(())
+            },
+        );
+        scheduler.register(
+            "quick",
+            TaskPriority::Low,
+            Duration::from_millis(10),
+            Duration::from_secs(1),
+            || Ok(()),
+        );
+        thread::sleep(Duration::from_millis(300));
+        let status = scheduler.task_status();
+        let hung = status.iter().find(|t| t.name == "hung").unwrap();
+        assert_eq!(hung.failures, 1);
+        assert!(last_run(&scheduler, "quick").is_some());
+    }
+
+    #[test]
+    fn suspended_only_runs_high_priority_tasks() {
+        let scheduler = Scheduler::new();
+        scheduler.set_suspended(true);
+        for (name, priority) in [
+            ("heartbeat", TaskPriority::High),
+            ("wifi", TaskPriority::Normal),
+        ] {
+            scheduler.register(
+                name,
+                priority,
+                Duration::from_millis(10),
+                Duration::from_secs(1),
+                || Ok(()),
+            );
+        }
+        thread::sleep(Duration::from_millis(100));
+        assert!(last_run(&scheduler, "heartbeat").is_some());
+        assert!(last_run(&scheduler, "wifi").is_none());
+
+        scheduler.set_suspended(false);
+        thread::sleep(Duration::from_millis(100));
+        assert!(last_run(&scheduler, "wifi").is_some());
+    }
+}
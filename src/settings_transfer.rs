@@ -0,0 +1,109 @@
+use crate::config::{Config, CustomRunnerSet};
+use crate::ui::tray::custom_frames_root_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Bump when the export layout changes; `read` migrates older exports.
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "config.json";
+/// Frames live under this folder, one subfolder per custom runner set
+const RUNNERS_DIR: &str = "custom-runners";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format_version: u32,
+    app_version: String,
+}
+
+/// Write `config` and the frames of its custom runners into the folder `dir`.
+/// Frame paths in the exported config are relative to `dir`.
+pub fn export(config: &Config, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir.join(RUNNERS_DIR))?;
+
+    let mut sets = config.custom_runner_sets.clone();
+    for set in &mut sets {
+        let set_dir = Path::new(RUNNERS_DIR).join(&set.id);
+        fs::create_dir_all(dir.join(&set_dir))?;
+        for path in &mut set.frame_paths {
+            let src = Path::new(path.as_str());
+            let Some(name) = src.file_name() else {
+                continue;
+            };
+            let relative = set_dir.join(name);
+            fs::copy(src, dir.join(&relative))?;
+            *path = relative.to_string_lossy().to_string();
+        }
+    }
+
+    let mut value = config.stored_value().map_err(io::Error::other)?;
+    value["custom_runner_sets"] = serde_json::to_value(&sets).map_err(io::Error::other)?;
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    write_json(&dir.join(MANIFEST_FILE), &manifest)?;
+    write_json(&dir.join(CONFIG_FILE), &value)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+/// Config from an exported folder. Custom runner frame paths are still
+/// relative to `dir`; `install_runners` copies them in.
+pub fn read(dir: &Path) -> Result<Config, String> {
+    let manifest = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|_| "This folder is not a settings export".to_string())?;
+    let manifest: Manifest =
+        serde_json::from_str(&manifest).map_err(|e| format!("Unreadable manifest: {}", e))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "Exported by a newer version ({})",
+            manifest.app_version
+        ));
+    }
+    let data = fs::read_to_string(dir.join(CONFIG_FILE))
+        .map_err(|e| format!("Missing {}: {}", CONFIG_FILE, e))?;
+    let value: Value =
+        serde_json::from_str(&data).map_err(|e| format!("Unreadable {}: {}", CONFIG_FILE, e))?;
+    // Older format versions are migrated here once there are any
+    serde_json::from_value(value).map_err(|e| format!("Invalid {}: {}", CONFIG_FILE, e))
+}
+
+/// Copy the custom runners of an imported `config` into this Mac's frames
+/// folder under fresh set IDs, and point the config at the copies.
+pub fn install_runners(config: &mut Config, dir: &Path) -> io::Result<()> {
+    let root = custom_frames_root_dir();
+    let mut renamed = HashMap::new();
+    for (idx, set) in config.custom_runner_sets.iter_mut().enumerate() {
+        let new_id = format!("{}-{}", CustomRunnerSet::generate_id(), idx);
+        let target_dir = root.join(&new_id);
+        fs::create_dir_all(&target_dir)?;
+        for path in &mut set.frame_paths {
+            let src = dir.join(path.as_str());
+            let Some(name) = src.file_name() else {
+                continue;
+            };
+            let target = target_dir.join(name);
+            fs::copy(&src, &target)?;
+            *path = target.to_string_lossy().to_string();
+        }
+        renamed.insert(format!("custom:{}", set.id), format!("custom:{}", new_id));
+        set.id = new_id;
+    }
+
+    let rename = |id: &mut String| {
+        if let Some(new_id) = renamed.get(id.as_str()) {
+            *id = new_id.clone();
+        }
+    };
+    rename(&mut config.runner_id);
+    config.runner_rotation_ids.iter_mut().for_each(rename);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! SIGTERM, which launchd sends at logout, becomes a regular Quit so the
+//! app shuts down the same way as from the menu.
+
+use crate::ui::action::TrayAction;
+use crate::ui::tray::post_pending_event;
+use block2::{Block, RcBlock};
+use std::ffi::c_void;
+
+const SIGTERM: i32 = 15;
+const SIG_IGN: usize = 1;
+
+/// dispatch_queue_s, dispatch_source_type_s and friends
+#[repr(C)]
+struct DispatchObject {
+    _private: [u8; 0],
+}
+
+extern "C" {
+    static _dispatch_main_q: DispatchObject;
+    static _dispatch_source_type_signal: DispatchObject;
+    fn dispatch_source_create(
+        source_type: *const DispatchObject,
+        handle: usize,
+        mask: usize,
+        queue: *const DispatchObject,
+    ) -> *mut c_void;
+    fn dispatch_source_set_event_handler(source: *mut c_void, handler: &Block<dyn Fn()>);
+    fn dispatch_resume(object: *mut c_void);
+    fn signal(signum: i32, handler: usize) -> usize;
+}
+
+/// Watch for SIGTERM on the main queue, where posting an action is safe,
+/// instead of in a signal handler, where almost nothing is.
+pub fn quit_on_sigterm() {
+    unsafe {
+        let source = dispatch_source_create(
+            std::ptr::addr_of!(_dispatch_source_type_signal),
+            SIGTERM as usize,
+            0,
+            std::ptr::addr_of!(_dispatch_main_q),
+        );
+        if source.is_null() {
+            log::warn!("could not watch for SIGTERM");
+            return;
+        }
+        let handler = RcBlock::new(|| {
+            log::info!("SIGTERM received, quitting");
+            post_pending_event(TrayAction::Quit);
+        });
+        dispatch_source_set_event_handler(source, &handler);
+        // The default action would kill us before the source sees it
+        signal(SIGTERM, SIG_IGN);
+        // The source lives as long as the app, so it is never released
+        dispatch_resume(source);
+    }
+}
@@ -0,0 +1,720 @@
+use crate::config::Language;
+use objc2::msg_send;
+use objc2::runtime::{AnyClass, AnyObject};
+use std::ffi::{c_char, CStr};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Resolved UI language; `Language::Auto` never ends up here
+static CURRENT: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Locale {
+    En,
+    ZhHans,
+    Ja,
+}
+
+impl Locale {
+    fn load() -> Self {
+        match CURRENT.load(Ordering::Relaxed) {
+            1 => Locale::ZhHans,
+            2 => Locale::Ja,
+            _ => Locale::En,
+        }
+    }
+
+    /// "zh-Hans-CN", "zh-CN", "ja-JP", "en-US", ... as found in
+    /// `NSLocale.preferredLanguages`
+    fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.to_ascii_lowercase();
+        if tag.starts_with("zh-hans") || tag == "zh-cn" || tag == "zh-sg" {
+            Some(Locale::ZhHans)
+        } else if tag.starts_with("ja") {
+            Some(Locale::Ja)
+        } else if tag.starts_with("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+}
+
+/// Switch the UI language. Menus pick it up when they are rebuilt.
+pub fn set_language(language: Language) {
+    let locale = match language {
+        Language::Auto => preferred_languages()
+            .iter()
+            .find_map(|tag| Locale::from_tag(tag))
+            .unwrap_or(Locale::En),
+        Language::English => Locale::En,
+        Language::ChineseSimplified => Locale::ZhHans,
+        Language::Japanese => Locale::Ja,
+    };
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+fn preferred_languages() -> Vec<String> {
+    let mut tags = Vec::new();
+    unsafe {
+        let Some(class) = AnyClass::get(c"NSLocale") else {
+            return tags;
+        };
+        let languages: *mut AnyObject = msg_send![class, preferredLanguages];
+        if languages.is_null() {
+            return tags;
+        }
+        let count: usize = msg_send![languages, count];
+        for i in 0..count {
+            let tag: *mut AnyObject = msg_send![languages, objectAtIndex: i];
+            let utf8: *const c_char = msg_send![tag, UTF8String];
+            if !utf8.is_null() {
+                tags.push(CStr::from_ptr(utf8).to_string_lossy().into_owned());
+            }
+        }
+    }
+    tags
+}
+
+/// Translation of the English `key`, or `key` itself when there is none.
+pub fn t(key: &str) -> &str {
+    let table = match Locale::load() {
+        Locale::En => return key,
+        Locale::ZhHans => ZH_HANS,
+        Locale::Ja => JA,
+    };
+    table
+        .iter()
+        .find(|(english, _)| *english == key)
+        .map(|(_, translated)| *translated)
+        .unwrap_or(key)
+}
+
+/// `t(key)` with each `{}` replaced by the next of `args`.
+pub fn tf(key: &'static str, args: &[&str]) -> String {
+    let mut out = String::new();
+    let mut rest = t(key);
+    for arg in args {
+        let Some((head, tail)) = rest.split_once("{}") else {
+            break;
+        };
+        out.push_str(head);
+        out.push_str(arg);
+        rest = tail;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Font family for chart text; the default sans-serif has no CJK glyphs.
+pub fn chart_font() -> &'static str {
+    match Locale::load() {
+        Locale::En => "sans-serif",
+        Locale::ZhHans => "PingFang SC",
+        Locale::Ja => "Hiragino Sans",
+    }
+}
+
+const ZH_HANS: &[(&str, &str)] = &[
+    // Menus
+    ("Show Charts", "显示图表"),
+    ("Show Core Charts", "显示各核心图表"),
+    ("Mini Chart Window", "迷你图表窗口"),
+    ("Save Chart as Image…", "将图表存储为图像…"),
+    ("Chart Settings", "图表设置"),
+    ("Show Min/Avg/Max", "显示最小/平均/最大值"),
+    ("Fixed Temperature Axis (0-100)", "固定温度坐标轴 (0-100)"),
+    ("Chart Smoothing", "图表平滑"),
+    ("5-Sample Average", "5 点平均"),
+    ("15-Sample Average", "15 点平均"),
+    ("Network Chart", "网络图表"),
+    ("Pop Out Chart", "单独窗口显示图表"),
+    ("CPU Chart", "CPU 图表"),
+    ("Temperature Chart", "温度图表"),
+    ("Temperature", "温度"),
+    ("Cores", "核心"),
+    ("Network", "网络"),
+    ("Separate Panes", "分开显示"),
+    ("Upload Mirrored Below", "上行镜像在下方"),
+    ("Overlaid", "叠加显示"),
+    ("Chart Theme", "图表主题"),
+    ("Match System", "跟随系统"),
+    ("Dark", "深色"),
+    ("Light", "浅色"),
+    ("High Contrast", "高对比度"),
+    ("Copy Stats", "拷贝统计信息"),
+    ("Copy Stats as JSON", "以 JSON 格式拷贝统计信息"),
+    ("Display", "显示"),
+    ("Max of All", "最高值"),
+    ("Second Line", "第二行"),
+    ("Sensor Name", "传感器名称"),
+    ("Hottest Sensor", "最热的传感器"),
+    ("All Sensors", "所有传感器"),
+    ("No sensors found", "未找到传感器"),
+    ("Eject {}", "推出 {}"),
+    ("Notify on Mount/Unmount", "装载/卸载时通知"),
+    ("Volume Mounted", "宗卷已装载"),
+    ("Volume Unmounted", "宗卷已卸载"),
+    ("Today's Summary", "今日概览"),
+    ("CPU avg {}%, peak {}%", "CPU 平均 {}%，峰值 {}%"),
+    ("Memory peak {}%", "内存峰值 {}%"),
+    ("Network ↓ {} ↑ {}", "网络 ↓ {} ↑ {}"),
+    ("Max temperature {}°C", "最高温度 {}°C"),
+    ("(since the app started)", "（自应用启动起）"),
+    ("{} — {} free", "{} — 可用 {}"),
+    ("Health", "健康状态"),
+    ("OK", "正常"),
+    ("Failing", "异常"),
+    ("Wear {}%", "磨损 {}%"),
+    ("Volumes", "宗卷"),
+    ("external", "外置"),
+    ("read-only", "只读"),
+    ("Watched Processes", "监视的进程"),
+    (
+        "None (add process_watches to config.json)",
+        "无（在 config.json 中添加 process_watches）",
+    ),
+    ("Shortcut", "快捷键"),
+    ("Off", "关闭"),
+    ("Alert Sound", "警报声音"),
+    ("None", "无"),
+    ("Module Width", "模块宽度"),
+    ("Narrow", "窄"),
+    ("Normal", "标准"),
+    ("Wide", "宽"),
+    ("Auto", "自动"),
+    ("While Menu Is Open", "菜单打开时"),
+    ("Keep Updating", "持续更新"),
+    ("Pause Updates", "暂停更新"),
+    ("URL Commands", "URL 命令"),
+    ("Show or hide the charts", "显示或隐藏图表"),
+    ("Show or hide the temperature charts", "显示或隐藏温度图表"),
+    ("Show or hide the core charts", "显示或隐藏核心图表"),
+    (
+        "Pop out the CPU chart (also net, temp)",
+        "单独窗口显示 CPU 图表（也可用 net、temp）",
+    ),
+    ("Pause or resume the charts", "暂停或继续图表"),
+    ("Poll every 5 seconds", "每 5 秒刷新"),
+    ("Next runner", "下一个动画"),
+    ("Previous runner", "上一个动画"),
+    ("Turn shuffle on or off", "开启或关闭随机播放"),
+    ("Switch to the profile named Work", "切换到名为 Work 的配置"),
+    ("Update on Close", "关闭时更新"),
+    ("Low Power Behavior", "使用电池时"),
+    ("Reduce Animation", "减少动画"),
+    ("Reduce Animation + Polling", "减少动画和刷新"),
+    ("Refresh Interval", "刷新间隔"),
+    ("Custom…", "自定…"),
+    ("Custom ({})…", "自定（{}）…"),
+    ("Modules", "模块"),
+    ("Show Metric Closest to Alert", "显示最接近警报的指标"),
+    ("Closest to Alert", "最接近警报"),
+    (
+        "{} {} ({}% of alert at {})",
+        "{} {}（警报阈值的 {}%，阈值 {}）",
+    ),
+    ("Auto-hide NET below {} KB/s", "低于 {} KB/s 时自动隐藏 NET"),
+    ("Auto-hide TEMP below {}C", "低于 {}C 时自动隐藏 TEMP"),
+    ("CPU Display", "CPU 显示"),
+    ("Text", "文本"),
+    ("P/E Cores as Label", "以 P/E 核心作为标签"),
+    ("Sparkline", "迷你图"),
+    ("Both", "两者"),
+    ("Color Scheme", "配色方案"),
+    ("Default", "默认"),
+    ("Module Order", "模块顺序"),
+    ("Network by the Clock", "网络靠近时钟"),
+    ("Reversed", "倒序"),
+    ("Move {} Left", "{} 左移"),
+    ("Move {} Right", "{} 右移"),
+    ("Conservative", "保守"),
+    ("Test Alert", "测试警报"),
+    ("Notify When Alerts Clear", "警报解除时通知"),
+    ("Pause Alerts", "暂停警报"),
+    ("Resume Alerts", "恢复警报"),
+    ("Alert Thresholds", "警报阈值"),
+    ("Relaxed", "宽松"),
+    ("Standard", "标准"),
+    ("Strict", "严格"),
+    ("Diagnostics", "诊断"),
+    ("Effective Settings", "当前生效的设置"),
+    ("Open Log Folder", "打开日志文件夹"),
+    ("Issues ({})", "问题（{}）"),
+    ("Clear", "清除"),
+    ("Open Config Folder", "打开配置文件夹"),
+    ("Reveal Custom Runner Frames", "显示自定跑者帧"),
+    ("Profile App (30 s)…", "分析应用（30 秒）…"),
+    ("Profile", "配置方案"),
+    ("Save Current as Profile…", "将当前设置存为方案…"),
+    ("Export Settings…", "导出设置…"),
+    ("Import Settings…", "导入设置…"),
+    ("Launch at Login", "登录时启动"),
+    ("Language", "语言"),
+    ("System Default", "跟随系统"),
+    ("Uninstall…", "卸载…"),
+    ("Quit", "退出"),
+    // Runner menu
+    ("All", "全部"),
+    ("All {}", "全部{}"),
+    ("Only {}", "仅{}"),
+    ("Next Runner", "下一个跑者"),
+    ("Previous Runner", "上一个跑者"),
+    ("Shuffle", "随机播放"),
+    ("Import Custom Runner Frames…", "导入自定跑者帧…"),
+    ("Import Custom Runner from Folder…", "从文件夹导入自定跑者…"),
+    ("Display Time", "显示时长"),
+    ("Next runner in {}", "{} 后切换跑者"),
+    ("Single runner, no rotation", "仅一个跑者，不轮换"),
+    ("Frame Speed", "帧速度"),
+    ("Animation Speed", "动画速度"),
+    ("Very Fast", "很快"),
+    ("Very Slow", "很慢"),
+    ("Fast", "快"),
+    ("Slow", "慢"),
+    ("Idle Behavior", "空闲时"),
+    ("Animate Always", "始终播放动画"),
+    ("Sleep When Idle", "空闲时休眠"),
+    ("Cats", "猫"),
+    ("Custom", "自定义"),
+    ("Other", "其他"),
+    ("Dogs", "狗"),
+    ("Animals", "动物"),
+    ("Food", "食物"),
+    ("People", "人物"),
+    ("Machines", "机器"),
+    ("Nature", "自然"),
+    ("Fantasy", "奇幻"),
+    ("Abstract", "抽象"),
+    // Readings
+    ("Uptime", "运行时间"),
+    ("Last hour", "过去一小时"),
+    ("Last {}", "过去 {}"),
+    ("Load", "负载"),
+    ("Memory", "内存"),
+    ("Disk", "磁盘"),
+    ("Net", "网络"),
+    ("Used", "已用"),
+    ("Available", "可用"),
+    ("Swap", "交换"),
+    ("Swap activity", "交换活动"),
+    ("Compressions", "压缩"),
+    ("Download", "下载"),
+    ("Upload", "上传"),
+    ("Session", "本次运行"),
+    ("Since boot", "开机以来"),
+    ("Today", "今日"),
+    ("Reset Today", "重置今日统计"),
+    ("Menu Bar Shows", "菜单栏显示"),
+    ("Speed", "速率"),
+    ("Daily Total", "今日总量"),
+    ("Reset Session Counters", "重置本次统计"),
+    ("Public IP", "公网 IP"),
+    ("Latency", "延迟"),
+    ("timeout", "超时"),
+    ("Connections: {} ({} listening)", "连接：{}（监听 {}）"),
+    ("No listening ports", "无监听端口"),
+    ("unavailable", "不可用"),
+    ("none", "无"),
+    ("{} active", "{} 已连接"),
+    ("(hidden)", "（隐藏）"),
+    ("n/a", "不可用"),
+    ("Signal", "信号"),
+    ("Noise", "噪声"),
+    ("Channel", "信道"),
+    ("Tx Rate", "发送速率"),
+    ("Processes", "进程"),
+    ("Threads", "线程"),
+    ("Files", "文件"),
+    ("App", "应用"),
+    ("poll", "采样"),
+    ("chart", "图表"),
+    ("not running", "未运行"),
+    ("{} processes", "{} 个进程"),
+    // Notification titles
+    ("CPU Warning", "CPU 警告"),
+    ("CPU Critical", "CPU 严重"),
+    ("Memory Warning", "内存警告"),
+    ("Memory Critical", "内存严重"),
+    ("Temperature Warning", "温度警告"),
+    ("Temperature Critical", "温度严重"),
+    ("Process CPU High", "进程 CPU 使用率过高"),
+    ("Process Memory High", "进程内存占用过高"),
+    ("CPU Usage Recovered", "CPU 使用率已恢复"),
+    ("Memory Usage Recovered", "内存使用率已恢复"),
+    ("Temperature Recovered", "温度已恢复"),
+    ("Disk Health Warning", "磁盘健康警告"),
+    ("Disk Filling Up", "磁盘即将写满"),
+    ("Disk Health Recovered", "磁盘健康已恢复"),
+    ("Process CPU Recovered", "进程 CPU 使用率已恢复"),
+    ("Process Memory Recovered", "进程内存占用已恢复"),
+    ("Settings Exported", "设置已导出"),
+    ("Export History…", "导出历史记录…"),
+    ("History Exported", "历史记录已导出"),
+    ("Settings Imported", "设置已导入"),
+    ("Custom Runner Imported", "已导入自定跑者"),
+    ("{} frames", "{} 帧"),
+    ("Export Failed", "导出失败"),
+    ("Import Failed", "导入失败"),
+    ("Profile Complete", "分析完成"),
+    ("Profile Failed", "分析失败"),
+    ("Shortcut Unavailable", "快捷键不可用"),
+    ("Eject Failed", "推出失败"),
+    ("Could not open chart window", "无法打开图表窗口"),
+    ("Menu Bar Full", "菜单栏已满"),
+    (
+        "No room for {}. Their readings are in the CPU menu.",
+        "{} 没有空间显示，其读数可在 CPU 菜单中查看。",
+    ),
+    ("Menu bar full: {} hidden", "菜单栏已满：{} 未显示"),
+    // Dialogs and prompts
+    (
+        "Runner display time in seconds (1 to 3600):",
+        "跑者显示时间（秒，1 到 3600）：",
+    ),
+    ("Profile name:", "方案名称："),
+    ("Uninstall Mac State Monitor", "卸载 Mac State Monitor"),
+    (
+        "Remove the login item and quit? Afterwards, move the app to the Trash.",
+        "移除登录项并退出？之后请将应用移到废纸篓。",
+    ),
+    (
+        "Also delete settings, logs and custom runner frames in {}?",
+        "同时删除 {} 中的设置、日志和自定跑者帧？",
+    ),
+    ("Uninstall Incomplete", "卸载未完成"),
+    ("Export History", "导出历史记录"),
+    // Chart captions
+    ("MEM", "内存"),
+    ("DISK", "磁盘"),
+    ("TEMP", "温度"),
+    ("NET", "网络"),
+    ("NET Down", "网络下行"),
+    ("NET Up", "网络上行"),
+    ("Pageouts", "页面换出"),
+    ("Core {}", "核心 {}"),
+];
+
+const JA: &[(&str, &str)] = &[
+    // Menus
+    ("Show Charts", "グラフを表示"),
+    ("Show Core Charts", "コア別グラフを表示"),
+    ("Mini Chart Window", "ミニグラフウインドウ"),
+    ("Save Chart as Image…", "グラフを画像として保存…"),
+    ("Chart Settings", "グラフ設定"),
+    ("Show Min/Avg/Max", "最小/平均/最大を表示"),
+    ("Fixed Temperature Axis (0-100)", "温度軸を固定 (0-100)"),
+    ("Chart Smoothing", "グラフの平滑化"),
+    ("5-Sample Average", "5サンプル平均"),
+    ("15-Sample Average", "15サンプル平均"),
+    ("Network Chart", "ネットワークグラフ"),
+    ("Pop Out Chart", "グラフを別ウインドウで表示"),
+    ("CPU Chart", "CPUグラフ"),
+    ("Temperature Chart", "温度グラフ"),
+    ("Temperature", "温度"),
+    ("Cores", "コア"),
+    ("Network", "ネットワーク"),
+    ("Separate Panes", "別々のペイン"),
+    ("Upload Mirrored Below", "上りを下側に反転"),
+    ("Overlaid", "重ねて表示"),
+    ("Chart Theme", "グラフのテーマ"),
+    ("Match System", "システムに合わせる"),
+    ("Dark", "ダーク"),
+    ("Light", "ライト"),
+    ("High Contrast", "ハイコントラスト"),
+    ("Copy Stats", "統計をコピー"),
+    ("Copy Stats as JSON", "統計を JSON でコピー"),
+    ("Display", "表示"),
+    ("Max of All", "最大値"),
+    ("Second Line", "2 行目"),
+    ("Sensor Name", "センサー名"),
+    ("Hottest Sensor", "最も熱いセンサー"),
+    ("All Sensors", "すべてのセンサー"),
+    ("No sensors found", "センサーが見つかりません"),
+    ("Eject {}", "{} を取り出す"),
+    ("Notify on Mount/Unmount", "マウント/アンマウント時に通知"),
+    ("Volume Mounted", "ボリュームがマウントされました"),
+    ("Volume Unmounted", "ボリュームがアンマウントされました"),
+    ("Today's Summary", "今日のまとめ"),
+    ("CPU avg {}%, peak {}%", "CPU 平均 {}%、ピーク {}%"),
+    ("Memory peak {}%", "メモリのピーク {}%"),
+    ("Network ↓ {} ↑ {}", "ネットワーク ↓ {} ↑ {}"),
+    ("Max temperature {}°C", "最高温度 {}°C"),
+    ("(since the app started)", "（アプリの起動以降）"),
+    ("{} — {} free", "{} — 空き {}"),
+    ("Health", "健康状態"),
+    ("OK", "正常"),
+    ("Failing", "異常"),
+    ("Wear {}%", "摩耗 {}%"),
+    ("Volumes", "ボリューム"),
+    ("external", "外部"),
+    ("read-only", "読み出し専用"),
+    ("Watched Processes", "監視中のプロセス"),
+    (
+        "None (add process_watches to config.json)",
+        "なし（config.json に process_watches を追加）",
+    ),
+    ("Shortcut", "ショートカット"),
+    ("Off", "オフ"),
+    ("Alert Sound", "アラート音"),
+    ("None", "なし"),
+    ("Module Width", "モジュールの幅"),
+    ("Narrow", "狭い"),
+    ("Normal", "標準"),
+    ("Wide", "広い"),
+    ("Auto", "自動"),
+    ("While Menu Is Open", "メニューを開いている間"),
+    ("Keep Updating", "更新を続ける"),
+    ("Pause Updates", "更新を一時停止"),
+    ("URL Commands", "URLコマンド"),
+    ("Show or hide the charts", "グラフを表示/非表示"),
+    (
+        "Show or hide the temperature charts",
+        "温度グラフを表示/非表示",
+    ),
+    ("Show or hide the core charts", "コアグラフを表示/非表示"),
+    (
+        "Pop out the CPU chart (also net, temp)",
+        "CPUグラフを別ウインドウで表示（net、tempも可）",
+    ),
+    ("Pause or resume the charts", "グラフを一時停止/再開"),
+    ("Poll every 5 seconds", "5秒ごとに更新"),
+    ("Next runner", "次のランナー"),
+    ("Previous runner", "前のランナー"),
+    ("Turn shuffle on or off", "シャッフルのオン/オフ"),
+    (
+        "Switch to the profile named Work",
+        "Work という名前のプロファイルに切り替え",
+    ),
+    ("Update on Close", "閉じたときに更新"),
+    ("Low Power Behavior", "バッテリー駆動時"),
+    ("Reduce Animation", "アニメーションを減らす"),
+    ("Reduce Animation + Polling", "アニメーションと更新を減らす"),
+    ("Refresh Interval", "更新間隔"),
+    ("Custom…", "カスタム…"),
+    ("Custom ({})…", "カスタム（{}）…"),
+    ("Modules", "モジュール"),
+    (
+        "Show Metric Closest to Alert",
+        "アラートに最も近い指標を表示",
+    ),
+    ("Closest to Alert", "アラートに近い順"),
+    (
+        "{} {} ({}% of alert at {})",
+        "{} {}（アラートの {}%、しきい値 {}）",
+    ),
+    (
+        "Auto-hide NET below {} KB/s",
+        "{} KB/s 未満で NET を自動的に隠す",
+    ),
+    ("Auto-hide TEMP below {}C", "{}C 未満で TEMP を自動的に隠す"),
+    ("CPU Display", "CPU の表示"),
+    ("Text", "テキスト"),
+    ("P/E Cores as Label", "P/Eコアをラベルに表示"),
+    ("Sparkline", "スパークライン"),
+    ("Both", "両方"),
+    ("Color Scheme", "カラースキーム"),
+    ("Default", "デフォルト"),
+    ("Module Order", "モジュールの並び順"),
+    ("Network by the Clock", "ネットワークを時計の隣に"),
+    ("Reversed", "逆順"),
+    ("Move {} Left", "{} を左へ移動"),
+    ("Move {} Right", "{} を右へ移動"),
+    ("Conservative", "控えめ"),
+    ("Test Alert", "テストアラート"),
+    ("Notify When Alerts Clear", "アラート解除時に通知"),
+    ("Pause Alerts", "アラートを一時停止"),
+    ("Resume Alerts", "アラートを再開"),
+    ("Alert Thresholds", "アラートしきい値"),
+    ("Relaxed", "緩め"),
+    ("Standard", "標準"),
+    ("Strict", "厳しめ"),
+    ("Diagnostics", "診断"),
+    ("Effective Settings", "有効な設定"),
+    ("Open Log Folder", "ログフォルダを開く"),
+    ("Issues ({})", "問題（{}）"),
+    ("Clear", "クリア"),
+    ("Open Config Folder", "設定フォルダを開く"),
+    (
+        "Reveal Custom Runner Frames",
+        "カスタムランナーのフレームを表示",
+    ),
+    ("Profile App (30 s)…", "アプリをプロファイル（30 秒）…"),
+    ("Profile", "プロファイル"),
+    (
+        "Save Current as Profile…",
+        "現在の設定をプロファイルとして保存…",
+    ),
+    ("Export Settings…", "設定を書き出す…"),
+    ("Import Settings…", "設定を読み込む…"),
+    ("Launch at Login", "ログイン時に起動"),
+    ("Language", "言語"),
+    ("System Default", "システムのデフォルト"),
+    ("Uninstall…", "アンインストール…"),
+    ("Quit", "終了"),
+    // Runner menu
+    ("All", "すべて"),
+    ("All {}", "すべての{}"),
+    ("Only {}", "{}のみ"),
+    ("Next Runner", "次のランナー"),
+    ("Previous Runner", "前のランナー"),
+    ("Shuffle", "シャッフル"),
+    (
+        "Import Custom Runner Frames…",
+        "カスタムランナーのフレームを読み込む…",
+    ),
+    (
+        "Import Custom Runner from Folder…",
+        "フォルダからカスタムランナーを読み込む…",
+    ),
+    ("Display Time", "表示時間"),
+    ("Next runner in {}", "次のランナーまで {}"),
+    (
+        "Single runner, no rotation",
+        "ランナーは 1 つのみ、切り替えなし",
+    ),
+    ("Frame Speed", "フレーム速度"),
+    ("Animation Speed", "アニメーション速度"),
+    ("Very Fast", "とても速い"),
+    ("Very Slow", "とても遅い"),
+    ("Fast", "速い"),
+    ("Slow", "遅い"),
+    ("Idle Behavior", "アイドル時"),
+    ("Animate Always", "常にアニメーション"),
+    ("Sleep When Idle", "アイドル時はスリープ"),
+    ("Cats", "ネコ"),
+    ("Custom", "カスタム"),
+    ("Other", "その他"),
+    ("Dogs", "イヌ"),
+    ("Animals", "動物"),
+    ("Food", "食べ物"),
+    ("People", "人"),
+    ("Machines", "機械"),
+    ("Nature", "自然"),
+    ("Fantasy", "ファンタジー"),
+    ("Abstract", "抽象"),
+    // Readings
+    ("Uptime", "稼働時間"),
+    ("Last hour", "直近 1 時間"),
+    ("Last {}", "直近 {}"),
+    ("Load", "負荷"),
+    ("Memory", "メモリ"),
+    ("Disk", "ディスク"),
+    ("Net", "ネット"),
+    ("Used", "使用済み"),
+    ("Available", "利用可能"),
+    ("Swap", "スワップ"),
+    ("Swap activity", "スワップ活動"),
+    ("Compressions", "圧縮"),
+    ("Download", "ダウンロード"),
+    ("Upload", "アップロード"),
+    ("Session", "今回のセッション"),
+    ("Since boot", "起動以来"),
+    ("Today", "今日"),
+    ("Reset Today", "今日の通信量をリセット"),
+    ("Menu Bar Shows", "メニューバーの表示"),
+    ("Speed", "速度"),
+    ("Daily Total", "今日の合計"),
+    ("Reset Session Counters", "セッションカウンタをリセット"),
+    ("Public IP", "パブリック IP"),
+    ("Latency", "レイテンシ"),
+    ("timeout", "タイムアウト"),
+    ("Connections: {} ({} listening)", "接続: {}（待ち受け {}）"),
+    ("No listening ports", "待ち受けポートなし"),
+    ("unavailable", "利用不可"),
+    ("none", "なし"),
+    ("{} active", "{} 接続中"),
+    ("(hidden)", "（非表示）"),
+    ("n/a", "なし"),
+    ("Signal", "信号"),
+    ("Noise", "ノイズ"),
+    ("Channel", "チャンネル"),
+    ("Tx Rate", "送信レート"),
+    ("Processes", "プロセス"),
+    ("Threads", "スレッド"),
+    ("Files", "ファイル"),
+    ("App", "アプリ"),
+    ("poll", "取得"),
+    ("chart", "グラフ"),
+    ("not running", "実行されていません"),
+    ("{} processes", "{} プロセス"),
+    // Notification titles
+    ("CPU Warning", "CPU 警告"),
+    ("CPU Critical", "CPU 危険"),
+    ("Memory Warning", "メモリ警告"),
+    ("Memory Critical", "メモリ危険"),
+    ("Temperature Warning", "温度警告"),
+    ("Temperature Critical", "温度危険"),
+    ("Process CPU High", "プロセスの CPU 使用率が高い"),
+    ("Process Memory High", "プロセスのメモリ使用量が多い"),
+    ("CPU Usage Recovered", "CPU 使用率が回復しました"),
+    ("Memory Usage Recovered", "メモリ使用率が回復しました"),
+    ("Temperature Recovered", "温度が回復しました"),
+    ("Disk Health Warning", "ディスクの健康状態の警告"),
+    ("Disk Filling Up", "ディスクがまもなく満杯"),
+    ("Disk Health Recovered", "ディスクの健康状態が回復しました"),
+    (
+        "Process CPU Recovered",
+        "プロセスの CPU 使用率が回復しました",
+    ),
+    (
+        "Process Memory Recovered",
+        "プロセスのメモリ使用量が回復しました",
+    ),
+    ("Settings Exported", "設定を書き出しました"),
+    ("Export History…", "履歴を書き出す…"),
+    ("History Exported", "履歴を書き出しました"),
+    ("Settings Imported", "設定を読み込みました"),
+    ("Custom Runner Imported", "カスタムランナーを読み込みました"),
+    ("{} frames", "{} フレーム"),
+    ("Export Failed", "書き出しに失敗しました"),
+    ("Import Failed", "読み込みに失敗しました"),
+    ("Profile Complete", "プロファイル完了"),
+    ("Profile Failed", "プロファイルに失敗しました"),
+    ("Shortcut Unavailable", "ショートカットを使用できません"),
+    ("Eject Failed", "取り出しに失敗しました"),
+    (
+        "Could not open chart window",
+        "グラフウインドウを開けませんでした",
+    ),
+    ("Menu Bar Full", "メニューバーがいっぱいです"),
+    (
+        "No room for {}. Their readings are in the CPU menu.",
+        "{} を表示する場所がありません。値は CPU メニューで確認できます。",
+    ),
+    (
+        "Menu bar full: {} hidden",
+        "メニューバーがいっぱい: {} は非表示",
+    ),
+    // Dialogs and prompts
+    (
+        "Runner display time in seconds (1 to 3600):",
+        "ランナーの表示時間（秒、1〜3600）：",
+    ),
+    ("Profile name:", "プロファイル名："),
+    (
+        "Uninstall Mac State Monitor",
+        "Mac State Monitor をアンインストール",
+    ),
+    (
+        "Remove the login item and quit? Afterwards, move the app to the Trash.",
+        "ログイン項目を削除して終了しますか？その後、アプリをゴミ箱に移動してください。",
+    ),
+    (
+        "Also delete settings, logs and custom runner frames in {}?",
+        "{} にある設定、ログ、カスタムランナーのフレームも削除しますか？",
+    ),
+    (
+        "Uninstall Incomplete",
+        "アンインストールが完了しませんでした",
+    ),
+    ("Export History", "履歴を書き出す"),
+    // Chart captions
+    ("MEM", "メモリ"),
+    ("DISK", "ディスク"),
+    ("TEMP", "温度"),
+    ("NET", "ネット"),
+    ("NET Down", "ネット下り"),
+    ("NET Up", "ネット上り"),
+    ("Pageouts", "ページアウト"),
+    ("Core {}", "コア {}"),
+];
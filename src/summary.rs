@@ -0,0 +1,77 @@
+//! The end-of-day summary notification: CPU, memory and temperature peaks
+//! counted since local midnight, plus today's network traffic.
+
+use crate::format::format_bytes;
+use crate::model::SystemStats;
+use crate::strings::{t, tf};
+
+/// Running totals for the current local day
+#[derive(Default)]
+pub struct DailyLoad {
+    /// See `daily_traffic::local_day`
+    day: i32,
+    cpu_sum: f64,
+    samples: u64,
+    cpu_peak: f32,
+    mem_peak: f32,
+    temp_peak: Option<f32>,
+    /// Counting began after midnight: the app started today
+    partial: bool,
+}
+
+impl DailyLoad {
+    pub fn add(&mut self, stats: &SystemStats, day: i32) {
+        if day != self.day {
+            *self = Self {
+                day,
+                // The first day is only counted from launch
+                partial: self.day == 0,
+                ..Self::default()
+            };
+        }
+        self.cpu_sum += stats.cpu.global_usage as f64;
+        self.samples += 1;
+        self.cpu_peak = self.cpu_peak.max(stats.cpu.global_usage);
+        self.mem_peak = self.mem_peak.max(stats.memory.usage_percent);
+        let hottest = stats
+            .temperature
+            .readings
+            .iter()
+            .map(|r| r.temp_c)
+            .reduce(f32::max);
+        if let Some(hottest) = hottest {
+            self.temp_peak = Some(self.temp_peak.map_or(hottest, |peak| peak.max(hottest)));
+        }
+    }
+
+    /// Notification body, one reading per line
+    pub fn body(&self, stats: &SystemStats) -> String {
+        let average = self.cpu_sum / self.samples.max(1) as f64;
+        let mut lines = vec![
+            tf(
+                "CPU avg {}%, peak {}%",
+                &[&format!("{:.0}", average), &format!("{:.0}", self.cpu_peak)],
+            ),
+            tf("Memory peak {}%", &[&format!("{:.0}", self.mem_peak)]),
+            tf(
+                "Network ↓ {} ↑ {}",
+                &[
+                    &format_bytes(stats.network.today_received_bytes),
+                    &format_bytes(stats.network.today_transmitted_bytes),
+                ],
+            ),
+        ];
+        if let Some(peak) = self.temp_peak {
+            lines.push(tf("Max temperature {}°C", &[&format!("{:.0}", peak)]));
+        }
+        if self.partial {
+            lines.push(t("(since the app started)").to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// The summary hour was passed between two polls of the same day
+pub fn crossed(previous: Option<(i32, u8)>, now: (i32, u8), hour: u8) -> bool {
+    previous.is_some_and(|(day, last_hour)| day == now.0 && last_hour < hour && now.1 >= hour)
+}
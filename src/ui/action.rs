@@ -0,0 +1,712 @@
+use crate::config::{
+    AlertPreset, ChartSmoothing, ChartThemeChoice, ColorScheme, CpuStatusStyle, Hotkey, Language,
+    LowPowerBehavior, Module, NetChartStyle, NetDisplay, OpenMenuUpdates, RunnerIdleBehavior,
+    TempLabelStyle,
+};
+use crate::ui::chart_window::ChartMode;
+
+const QUIT_ID: &str = "quit";
+const UNINSTALL_ID: &str = "uninstall";
+const SHOW_CHARTS_ID: &str = "show_charts";
+const SHOW_TEMP_CHARTS_ID: &str = "show_temp_charts";
+const SHOW_CORE_CHARTS_ID: &str = "show_core_charts";
+const SHOW_CPU_CHARTS_ID: &str = "show_cpu_charts";
+const SHOW_NET_CHARTS_ID: &str = "show_net_charts";
+const POP_OUT_CHART_PREFIX: &str = "pop_out_";
+const SAVE_CHART_IMAGE_ID: &str = "save_chart_image";
+const CHART_MINI_MODE_ID: &str = "chart_mini_mode";
+const CHART_STATS_ID: &str = "chart_stats";
+const CHART_TEMP_FIXED_ID: &str = "chart_temp_fixed";
+const CHART_PAUSE_ID: &str = "chart_pause";
+const CHART_SMOOTHING_PREFIX: &str = "chart_smoothing_";
+const NET_CHART_STYLE_PREFIX: &str = "net_chart_";
+const CHART_THEME_PREFIX: &str = "chart_theme_";
+const COPY_STATS_ID: &str = "copy_stats";
+const COPY_STATS_JSON_ID: &str = "copy_stats_json";
+const EXPORT_HISTORY_ID: &str = "export_history";
+const TEST_ALERT_ID: &str = "test_alert";
+const ALERT_RECOVERY_ID: &str = "alert_recovery";
+const ALERT_PAUSE_ID: &str = "alert_pause";
+const VOLUME_NOTIFICATIONS_ID: &str = "volume_notifications";
+const ALERT_SOUND_PREFIX: &str = "alert_sound_";
+const ALERT_PRESET_PREFIX: &str = "alert_preset_";
+const ALERT_SOUND_NONE_ID: &str = "alert_sound_none";
+const PROFILE_APP_ID: &str = "profile_app";
+const OPEN_LOG_FOLDER_ID: &str = "open_log_folder";
+const CLEAR_ISSUES_ID: &str = "clear_issues";
+const OPEN_CONFIG_FOLDER_ID: &str = "open_config_folder";
+const REVEAL_CUSTOM_FRAMES_ID: &str = "reveal_custom_frames";
+const LAUNCH_AT_LOGIN_ID: &str = "launch_at_login";
+const EXPORT_SETTINGS_ID: &str = "export_settings";
+const IMPORT_SETTINGS_ID: &str = "import_settings";
+const RESET_SESSION_COUNTERS_ID: &str = "reset_session_counters";
+const RESET_DAILY_TRAFFIC_ID: &str = "reset_daily_traffic";
+const NET_DISPLAY_PREFIX: &str = "net_display_";
+const EJECT_DISK_PREFIX: &str = "eject_";
+const DISK_MOUNT_PREFIX: &str = "disk_mount_";
+const USE_PROFILE_PREFIX: &str = "use_profile_";
+const SAVE_PROFILE_ID: &str = "save_profile";
+const INTERVAL_PREFIX: &str = "interval_";
+const INTERVAL_CUSTOM_ID: &str = "interval_custom";
+const RUNNER_DISPLAY_CUSTOM_ID: &str = "runner_display_custom";
+const AUTO_HIDE_PREFIX: &str = "auto_hide_";
+const DIGEST_ID: &str = "show_digest";
+const COLOR_SCHEME_PREFIX: &str = "color_scheme_";
+const CPU_STYLE_PREFIX: &str = "cpu_style_";
+const CPU_CLUSTER_LABEL_ID: &str = "cpu_cluster_label";
+const HOTKEY_PREFIX: &str = "hotkey_";
+const HOTKEY_OFF_ID: &str = "hotkey_off";
+const LOW_POWER_PREFIX: &str = "low_power_";
+const LANGUAGE_PREFIX: &str = "language_";
+const OPEN_MENU_UPDATES_PREFIX: &str = "open_menu_updates_";
+const MODULE_WIDTH_PREFIX: &str = "module_width_";
+const MODULE_ORDER_PREFIX: &str = "module_order_";
+const MODULE_LEFT_PREFIX: &str = "module_left_";
+const MODULE_RIGHT_PREFIX: &str = "module_right_";
+const MODULE_WIDTH_AUTO_ID: &str = "module_width_auto";
+const TEMP_PREFIX: &str = "temp_";
+const TEMP_LABEL_PREFIX: &str = "temp_label_";
+const PIN_SENSOR_PREFIX: &str = "pin_sensor_";
+const RUNNER_DISPLAY_PREFIX: &str = "runner_display_";
+const RUNNER_FRAME_PREFIX: &str = "runner_frame_";
+const RUNNER_SPEED_PREFIX: &str = "runner_speed_";
+const RUNNER_IDLE_PREFIX: &str = "runner_idle_";
+const RUNNER_IMPORT_ID: &str = "runner_import_custom";
+const RUNNER_IMPORT_FOLDER_ID: &str = "runner_import_folder";
+const RUNNER_TOGGLE_PREFIX: &str = "runner_toggle_";
+const RUNNER_CATEGORY_PREFIX: &str = "runner_category_";
+const RUNNER_ONLY_CATEGORY_PREFIX: &str = "runner_only_";
+const RUNNER_ALL_ID: &str = "runner_all";
+const RUNNER_NEXT_ID: &str = "runner_next";
+const RUNNER_PREVIOUS_ID: &str = "runner_previous";
+const RUNNER_SHUFFLE_ID: &str = "runner_shuffle";
+
+/// Action triggered by a tray menu item.
+#[derive(Clone, PartialEq)]
+pub enum TrayAction {
+    Quit,
+    /// Remove the login item (and optionally the config folder), then quit
+    Uninstall,
+    ShowCharts(ChartMode),
+    /// Open a separate window with just this chart
+    PopOutChart(ChartMode),
+    /// Freeze the full chart window, or resume it
+    ToggleChartPause,
+    SaveChartImage,
+    ToggleChartMiniMode,
+    ToggleChartStats,
+    ToggleChartTempRange,
+    SetChartSmoothing(ChartSmoothing),
+    SetNetChartStyle(NetChartStyle),
+    SetChartTheme(ChartThemeChoice),
+    CopyStats,
+    CopyStatsJson,
+    /// The last hour, a row per minute, as CSV or JSON
+    ExportHistory,
+    TestAlert,
+    ToggleAlertRecovery,
+    /// Pause Alerts on the Touch Bar; not saved
+    ToggleAlertPause,
+    ToggleVolumeNotifications,
+    /// Sound name for firing alerts; None is silent
+    SetAlertSound(Option<String>),
+    SetAlertPreset(AlertPreset),
+    ProfileApp,
+    OpenLogFolder,
+    /// Empty the Issues submenu and drop the runner's badge
+    ClearIssues,
+    OpenConfigFolder,
+    /// Show the custom runner frames folder selected in Finder
+    RevealCustomFrames,
+    ToggleLaunchAtLogin,
+    ExportSettings,
+    ImportSettings,
+    /// Zero the network traffic counted since launch
+    ResetSessionCounters,
+    /// Zero the network traffic counted today
+    ResetDailyTraffic,
+    SetNetDisplay(NetDisplay),
+    /// Eject the removable volume mounted here
+    EjectDisk(String),
+    /// Show or hide the volume mounted here
+    ToggleDiskMount(String),
+    SwitchProfile(String),
+    /// Prompt for a name and store the live settings under it
+    SaveProfile,
+    SetPollInterval(f64),
+    /// Prompt for a poll interval
+    CustomPollInterval,
+    ToggleAutoHide(Module),
+    ToggleDigest,
+    SetColorScheme(ColorScheme),
+    SetCpuStatusStyle(CpuStatusStyle),
+    /// Show P/E cluster usage as the CPU item's second line
+    ToggleCpuClusterLabel,
+    SetChartHotkey(Option<Hotkey>),
+    SetModuleWidth(Option<f64>),
+    /// Modules from left to right
+    SetModuleOrder(Vec<Module>),
+    MoveModuleLeft(Module),
+    MoveModuleRight(Module),
+    SetOpenMenuUpdates(OpenMenuUpdates),
+    SetLowPowerBehavior(LowPowerBehavior),
+    SetLanguage(Language),
+    SetTempComponent(String),
+    SetTempLabelStyle(TempLabelStyle),
+    /// Pin a raw sensor as a reading of its own, or unpin it
+    TogglePinnedSensor(String),
+    SetRunnerDisplaySecs(u64),
+    CustomRunnerDisplaySecs,
+    SetRunnerFrameMs(u64),
+    /// Frame duration of runners without their own Frame Speed
+    SetRunnerAnimationSpeed(u64),
+    SetRunnerIdleBehavior(RunnerIdleBehavior),
+    ToggleRunner(String),
+    SelectRunnerCategory(String),
+    OnlyRunnerCategory(String),
+    SelectAllRunners,
+    NextRunner,
+    PreviousRunner,
+    ToggleRunnerShuffle,
+    ImportCustomRunner,
+    ImportCustomRunnerFolder,
+}
+
+impl TrayAction {
+    /// Stable string ID, kept for anything that needs to persist an action.
+    pub fn id(&self) -> String {
+        match self {
+            Self::Quit => QUIT_ID.to_string(),
+            Self::Uninstall => UNINSTALL_ID.to_string(),
+            Self::ShowCharts(ChartMode::All) => SHOW_CHARTS_ID.to_string(),
+            Self::ShowCharts(ChartMode::TempOnly) => SHOW_TEMP_CHARTS_ID.to_string(),
+            Self::ShowCharts(ChartMode::Cores) => SHOW_CORE_CHARTS_ID.to_string(),
+            Self::ShowCharts(ChartMode::CpuOnly) => SHOW_CPU_CHARTS_ID.to_string(),
+            Self::ShowCharts(ChartMode::NetOnly) => SHOW_NET_CHARTS_ID.to_string(),
+            Self::PopOutChart(mode) => format!("{}{}", POP_OUT_CHART_PREFIX, mode.id()),
+            Self::ToggleChartPause => CHART_PAUSE_ID.to_string(),
+            Self::SaveChartImage => SAVE_CHART_IMAGE_ID.to_string(),
+            Self::ToggleChartMiniMode => CHART_MINI_MODE_ID.to_string(),
+            Self::ToggleChartStats => CHART_STATS_ID.to_string(),
+            Self::ToggleChartTempRange => CHART_TEMP_FIXED_ID.to_string(),
+            Self::SetChartSmoothing(smoothing) => {
+                format!("{}{}", CHART_SMOOTHING_PREFIX, smoothing.id())
+            }
+            Self::SetNetChartStyle(style) => format!("{}{}", NET_CHART_STYLE_PREFIX, style.id()),
+            Self::SetChartTheme(theme) => format!("{}{}", CHART_THEME_PREFIX, theme.id()),
+            Self::CopyStats => COPY_STATS_ID.to_string(),
+            Self::CopyStatsJson => COPY_STATS_JSON_ID.to_string(),
+            Self::ExportHistory => EXPORT_HISTORY_ID.to_string(),
+            Self::TestAlert => TEST_ALERT_ID.to_string(),
+            Self::ToggleAlertRecovery => ALERT_RECOVERY_ID.to_string(),
+            Self::ToggleAlertPause => ALERT_PAUSE_ID.to_string(),
+            Self::ToggleVolumeNotifications => VOLUME_NOTIFICATIONS_ID.to_string(),
+            Self::SetAlertSound(None) => ALERT_SOUND_NONE_ID.to_string(),
+            Self::SetAlertSound(Some(name)) => format!("{}{}", ALERT_SOUND_PREFIX, name),
+            Self::SetAlertPreset(preset) => format!("{}{}", ALERT_PRESET_PREFIX, preset.id()),
+            Self::ProfileApp => PROFILE_APP_ID.to_string(),
+            Self::OpenLogFolder => OPEN_LOG_FOLDER_ID.to_string(),
+            Self::ClearIssues => CLEAR_ISSUES_ID.to_string(),
+            Self::OpenConfigFolder => OPEN_CONFIG_FOLDER_ID.to_string(),
+            Self::RevealCustomFrames => REVEAL_CUSTOM_FRAMES_ID.to_string(),
+            Self::ToggleLaunchAtLogin => LAUNCH_AT_LOGIN_ID.to_string(),
+            Self::ExportSettings => EXPORT_SETTINGS_ID.to_string(),
+            Self::ImportSettings => IMPORT_SETTINGS_ID.to_string(),
+            Self::ResetSessionCounters => RESET_SESSION_COUNTERS_ID.to_string(),
+            Self::ResetDailyTraffic => RESET_DAILY_TRAFFIC_ID.to_string(),
+            Self::SetNetDisplay(display) => format!("{}{}", NET_DISPLAY_PREFIX, display.id()),
+            Self::EjectDisk(mount_point) => format!("{}{}", EJECT_DISK_PREFIX, mount_point),
+            Self::ToggleDiskMount(mount_point) => format!("{}{}", DISK_MOUNT_PREFIX, mount_point),
+            Self::SwitchProfile(name) => format!("{}{}", USE_PROFILE_PREFIX, name),
+            Self::SaveProfile => SAVE_PROFILE_ID.to_string(),
+            Self::SetPollInterval(secs) => format!("{}{}", INTERVAL_PREFIX, secs),
+            Self::CustomPollInterval => INTERVAL_CUSTOM_ID.to_string(),
+            Self::ToggleAutoHide(module) => format!("{}{}", AUTO_HIDE_PREFIX, module.id()),
+            Self::ToggleDigest => DIGEST_ID.to_string(),
+            Self::SetColorScheme(scheme) => format!("{}{}", COLOR_SCHEME_PREFIX, scheme.id()),
+            Self::SetCpuStatusStyle(style) => format!("{}{}", CPU_STYLE_PREFIX, style.id()),
+            Self::ToggleCpuClusterLabel => CPU_CLUSTER_LABEL_ID.to_string(),
+            Self::SetChartHotkey(None) => HOTKEY_OFF_ID.to_string(),
+            Self::SetChartHotkey(Some(hotkey)) => {
+                format!("{}{}_{}", HOTKEY_PREFIX, hotkey.modifiers, hotkey.key_code)
+            }
+            Self::SetModuleWidth(None) => MODULE_WIDTH_AUTO_ID.to_string(),
+            Self::SetModuleWidth(Some(width)) => format!("{}{}", MODULE_WIDTH_PREFIX, width),
+            Self::SetModuleOrder(order) => {
+                let ids: Vec<&str> = order.iter().map(|m| m.id()).collect();
+                format!("{}{}", MODULE_ORDER_PREFIX, ids.join(","))
+            }
+            Self::MoveModuleLeft(module) => format!("{}{}", MODULE_LEFT_PREFIX, module.id()),
+            Self::MoveModuleRight(module) => format!("{}{}", MODULE_RIGHT_PREFIX, module.id()),
+            Self::SetOpenMenuUpdates(mode) => format!("{}{}", OPEN_MENU_UPDATES_PREFIX, mode.id()),
+            Self::SetLanguage(language) => format!("{}{}", LANGUAGE_PREFIX, language.id()),
+            Self::SetLowPowerBehavior(behavior) => {
+                format!("{}{}", LOW_POWER_PREFIX, behavior.id())
+            }
+            Self::SetTempComponent(label) => format!("{}{}", TEMP_PREFIX, label),
+            Self::SetTempLabelStyle(style) => format!("{}{}", TEMP_LABEL_PREFIX, style.id()),
+            Self::TogglePinnedSensor(label) => format!("{}{}", PIN_SENSOR_PREFIX, label),
+            Self::SetRunnerDisplaySecs(secs) => format!("{}{}", RUNNER_DISPLAY_PREFIX, secs),
+            Self::CustomRunnerDisplaySecs => RUNNER_DISPLAY_CUSTOM_ID.to_string(),
+            Self::SetRunnerFrameMs(ms) => format!("{}{}", RUNNER_FRAME_PREFIX, ms),
+            Self::SetRunnerAnimationSpeed(ms) => format!("{}{}", RUNNER_SPEED_PREFIX, ms),
+            Self::SetRunnerIdleBehavior(behavior) => {
+                format!("{}{}", RUNNER_IDLE_PREFIX, behavior.id())
+            }
+            Self::ToggleRunner(id) => format!("{}{}", RUNNER_TOGGLE_PREFIX, id),
+            Self::SelectRunnerCategory(name) => format!("{}{}", RUNNER_CATEGORY_PREFIX, name),
+            Self::OnlyRunnerCategory(name) => format!("{}{}", RUNNER_ONLY_CATEGORY_PREFIX, name),
+            Self::SelectAllRunners => RUNNER_ALL_ID.to_string(),
+            Self::NextRunner => RUNNER_NEXT_ID.to_string(),
+            Self::PreviousRunner => RUNNER_PREVIOUS_ID.to_string(),
+            Self::ToggleRunnerShuffle => RUNNER_SHUFFLE_ID.to_string(),
+            Self::ImportCustomRunner => RUNNER_IMPORT_ID.to_string(),
+            Self::ImportCustomRunnerFolder => RUNNER_IMPORT_FOLDER_ID.to_string(),
+        }
+    }
+
+    /// Inverse of [`TrayAction::id`].
+    pub fn parse(id: &str) -> Option<Self> {
+        match id {
+            QUIT_ID => return Some(Self::Quit),
+            UNINSTALL_ID => return Some(Self::Uninstall),
+            SHOW_CHARTS_ID => return Some(Self::ShowCharts(ChartMode::All)),
+            SHOW_TEMP_CHARTS_ID => return Some(Self::ShowCharts(ChartMode::TempOnly)),
+            SHOW_CORE_CHARTS_ID => return Some(Self::ShowCharts(ChartMode::Cores)),
+            SHOW_CPU_CHARTS_ID => return Some(Self::ShowCharts(ChartMode::CpuOnly)),
+            SHOW_NET_CHARTS_ID => return Some(Self::ShowCharts(ChartMode::NetOnly)),
+            SAVE_CHART_IMAGE_ID => return Some(Self::SaveChartImage),
+            CHART_MINI_MODE_ID => return Some(Self::ToggleChartMiniMode),
+            CHART_STATS_ID => return Some(Self::ToggleChartStats),
+            CHART_TEMP_FIXED_ID => return Some(Self::ToggleChartTempRange),
+            CHART_PAUSE_ID => return Some(Self::ToggleChartPause),
+            COPY_STATS_ID => return Some(Self::CopyStats),
+            COPY_STATS_JSON_ID => return Some(Self::CopyStatsJson),
+            EXPORT_HISTORY_ID => return Some(Self::ExportHistory),
+            TEST_ALERT_ID => return Some(Self::TestAlert),
+            INTERVAL_CUSTOM_ID => return Some(Self::CustomPollInterval),
+            DIGEST_ID => return Some(Self::ToggleDigest),
+            RUNNER_DISPLAY_CUSTOM_ID => return Some(Self::CustomRunnerDisplaySecs),
+            ALERT_RECOVERY_ID => return Some(Self::ToggleAlertRecovery),
+            ALERT_PAUSE_ID => return Some(Self::ToggleAlertPause),
+            VOLUME_NOTIFICATIONS_ID => return Some(Self::ToggleVolumeNotifications),
+            ALERT_SOUND_NONE_ID => return Some(Self::SetAlertSound(None)),
+            PROFILE_APP_ID => return Some(Self::ProfileApp),
+            OPEN_LOG_FOLDER_ID => return Some(Self::OpenLogFolder),
+            CLEAR_ISSUES_ID => return Some(Self::ClearIssues),
+            OPEN_CONFIG_FOLDER_ID => return Some(Self::OpenConfigFolder),
+            REVEAL_CUSTOM_FRAMES_ID => return Some(Self::RevealCustomFrames),
+            LAUNCH_AT_LOGIN_ID => return Some(Self::ToggleLaunchAtLogin),
+            EXPORT_SETTINGS_ID => return Some(Self::ExportSettings),
+            IMPORT_SETTINGS_ID => return Some(Self::ImportSettings),
+            RESET_SESSION_COUNTERS_ID => return Some(Self::ResetSessionCounters),
+            RESET_DAILY_TRAFFIC_ID => return Some(Self::ResetDailyTraffic),
+            SAVE_PROFILE_ID => return Some(Self::SaveProfile),
+            CPU_CLUSTER_LABEL_ID => return Some(Self::ToggleCpuClusterLabel),
+            HOTKEY_OFF_ID => return Some(Self::SetChartHotkey(None)),
+            MODULE_WIDTH_AUTO_ID => return Some(Self::SetModuleWidth(None)),
+            RUNNER_ALL_ID => return Some(Self::SelectAllRunners),
+            RUNNER_NEXT_ID => return Some(Self::NextRunner),
+            RUNNER_PREVIOUS_ID => return Some(Self::PreviousRunner),
+            RUNNER_SHUFFLE_ID => return Some(Self::ToggleRunnerShuffle),
+            RUNNER_IMPORT_ID => return Some(Self::ImportCustomRunner),
+            RUNNER_IMPORT_FOLDER_ID => return Some(Self::ImportCustomRunnerFolder),
+            _ => {}
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_DISPLAY_PREFIX) {
+            return rest.parse().ok().map(Self::SetRunnerDisplaySecs);
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_FRAME_PREFIX) {
+            return rest.parse().ok().map(Self::SetRunnerFrameMs);
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_SPEED_PREFIX) {
+            return rest.parse().ok().map(Self::SetRunnerAnimationSpeed);
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_IDLE_PREFIX) {
+            return RunnerIdleBehavior::from_id(rest).map(Self::SetRunnerIdleBehavior);
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_CATEGORY_PREFIX) {
+            return Some(Self::SelectRunnerCategory(rest.to_string()));
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_ONLY_CATEGORY_PREFIX) {
+            return Some(Self::OnlyRunnerCategory(rest.to_string()));
+        }
+        if let Some(rest) = id.strip_prefix(RUNNER_TOGGLE_PREFIX) {
+            return Some(Self::ToggleRunner(rest.to_string()));
+        }
+        if let Some(rest) = id.strip_prefix(AUTO_HIDE_PREFIX) {
+            return Module::from_id(rest).map(Self::ToggleAutoHide);
+        }
+        if let Some(rest) = id.strip_prefix(CHART_SMOOTHING_PREFIX) {
+            return ChartSmoothing::from_id(rest).map(Self::SetChartSmoothing);
+        }
+        if let Some(rest) = id.strip_prefix(NET_CHART_STYLE_PREFIX) {
+            return NetChartStyle::from_id(rest).map(Self::SetNetChartStyle);
+        }
+        if let Some(rest) = id.strip_prefix(CHART_THEME_PREFIX) {
+            return ChartThemeChoice::from_id(rest).map(Self::SetChartTheme);
+        }
+        if let Some(rest) = id.strip_prefix(POP_OUT_CHART_PREFIX) {
+            return ChartMode::from_id(rest).map(Self::PopOutChart);
+        }
+        if let Some(rest) = id.strip_prefix(NET_DISPLAY_PREFIX) {
+            return NetDisplay::from_id(rest).map(Self::SetNetDisplay);
+        }
+        if let Some(rest) = id.strip_prefix(COLOR_SCHEME_PREFIX) {
+            return ColorScheme::from_id(rest).map(Self::SetColorScheme);
+        }
+        if let Some(rest) = id.strip_prefix(CPU_STYLE_PREFIX) {
+            return CpuStatusStyle::from_id(rest).map(Self::SetCpuStatusStyle);
+        }
+        if let Some(rest) = id.strip_prefix(HOTKEY_PREFIX) {
+            let (modifiers, key_code) = rest.split_once('_')?;
+            let hotkey = Hotkey::new(key_code.parse().ok()?, modifiers.parse().ok()?);
+            return Some(Self::SetChartHotkey(Some(hotkey)));
+        }
+        if let Some(rest) = id.strip_prefix(LANGUAGE_PREFIX) {
+            return Language::from_id(rest).map(Self::SetLanguage);
+        }
+        if let Some(rest) = id.strip_prefix(LOW_POWER_PREFIX) {
+            return LowPowerBehavior::from_id(rest).map(Self::SetLowPowerBehavior);
+        }
+        if let Some(rest) = id.strip_prefix(OPEN_MENU_UPDATES_PREFIX) {
+            return OpenMenuUpdates::from_id(rest).map(Self::SetOpenMenuUpdates);
+        }
+        if let Some(rest) = id.strip_prefix(MODULE_ORDER_PREFIX) {
+            let order: Option<Vec<Module>> = rest.split(',').map(Module::from_id).collect();
+            return order.map(Self::SetModuleOrder);
+        }
+        if let Some(rest) = id.strip_prefix(MODULE_LEFT_PREFIX) {
+            return Module::from_id(rest).map(Self::MoveModuleLeft);
+        }
+        if let Some(rest) = id.strip_prefix(MODULE_RIGHT_PREFIX) {
+            return Module::from_id(rest).map(Self::MoveModuleRight);
+        }
+        if let Some(rest) = id.strip_prefix(MODULE_WIDTH_PREFIX) {
+            return rest
+                .parse()
+                .ok()
+                .map(|width| Self::SetModuleWidth(Some(width)));
+        }
+        if let Some(rest) = id.strip_prefix(INTERVAL_PREFIX) {
+            return rest.parse().ok().map(Self::SetPollInterval);
+        }
+        if let Some(rest) = id.strip_prefix(ALERT_PRESET_PREFIX) {
+            return AlertPreset::from_id(rest).map(Self::SetAlertPreset);
+        }
+        if let Some(rest) = id.strip_prefix(ALERT_SOUND_PREFIX) {
+            return Some(Self::SetAlertSound(Some(rest.to_string())));
+        }
+        if let Some(rest) = id.strip_prefix(EJECT_DISK_PREFIX) {
+            return Some(Self::EjectDisk(rest.to_string()));
+        }
+        if let Some(rest) = id.strip_prefix(DISK_MOUNT_PREFIX) {
+            return Some(Self::ToggleDiskMount(rest.to_string()));
+        }
+        if let Some(rest) = id.strip_prefix(USE_PROFILE_PREFIX) {
+            return Some(Self::SwitchProfile(rest.to_string()));
+        }
+        if let Some(rest) = id.strip_prefix(PIN_SENSOR_PREFIX) {
+            return Some(Self::TogglePinnedSensor(rest.to_string()));
+        }
+        // Before TEMP_PREFIX, which takes any sensor label
+        if let Some(style) = id
+            .strip_prefix(TEMP_LABEL_PREFIX)
+            .and_then(TempLabelStyle::from_id)
+        {
+            return Some(Self::SetTempLabelStyle(style));
+        }
+        if let Some(rest) = id.strip_prefix(TEMP_PREFIX) {
+            return Some(Self::SetTempComponent(rest.to_string()));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of `TrayAction` variants; `variant` fails to compile when one
+    /// is added, and the test fails until it has a sample here too
+    const VARIANTS: usize = 68;
+
+    fn variant(action: &TrayAction) -> usize {
+        use TrayAction::*;
+        match action {
+            Quit => 0,
+            Uninstall => 1,
+            ShowCharts(_) => 2,
+            PopOutChart(_) => 3,
+            ToggleChartPause => 4,
+            SaveChartImage => 5,
+            ToggleChartMiniMode => 6,
+            ToggleChartStats => 7,
+            ToggleChartTempRange => 8,
+            SetChartSmoothing(_) => 9,
+            SetNetChartStyle(_) => 10,
+            SetChartTheme(_) => 11,
+            CopyStats => 12,
+            CopyStatsJson => 13,
+            TestAlert => 14,
+            ToggleAlertRecovery => 15,
+            ToggleVolumeNotifications => 16,
+            SetAlertSound(_) => 17,
+            SetAlertPreset(_) => 18,
+            ProfileApp => 19,
+            OpenLogFolder => 20,
+            ClearIssues => 21,
+            OpenConfigFolder => 22,
+            RevealCustomFrames => 23,
+            ToggleLaunchAtLogin => 24,
+            ExportSettings => 25,
+            ImportSettings => 26,
+            ResetSessionCounters => 27,
+            ResetDailyTraffic => 28,
+            SetNetDisplay(_) => 29,
+            EjectDisk(_) => 30,
+            ToggleDiskMount(_) => 31,
+            SwitchProfile(_) => 32,
+            SaveProfile => 33,
+            SetPollInterval(_) => 34,
+            CustomPollInterval => 35,
+            ToggleAutoHide(_) => 36,
+            ToggleDigest => 37,
+            SetColorScheme(_) => 38,
+            SetCpuStatusStyle(_) => 39,
+            ToggleCpuClusterLabel => 40,
+            SetChartHotkey(_) => 41,
+            SetModuleWidth(_) => 42,
+            SetModuleOrder(_) => 43,
+            MoveModuleLeft(_) => 44,
+            MoveModuleRight(_) => 45,
+            SetOpenMenuUpdates(_) => 46,
+            SetLowPowerBehavior(_) => 47,
+            SetLanguage(_) => 48,
+            SetTempComponent(_) => 49,
+            SetTempLabelStyle(_) => 50,
+            TogglePinnedSensor(_) => 51,
+            SetRunnerDisplaySecs(_) => 52,
+            CustomRunnerDisplaySecs => 53,
+            SetRunnerFrameMs(_) => 54,
+            SetRunnerAnimationSpeed(_) => 55,
+            SetRunnerIdleBehavior(_) => 56,
+            ToggleRunner(_) => 57,
+            SelectRunnerCategory(_) => 58,
+            OnlyRunnerCategory(_) => 59,
+            SelectAllRunners => 60,
+            NextRunner => 61,
+            PreviousRunner => 62,
+            ToggleRunnerShuffle => 63,
+            ImportCustomRunner => 64,
+            ImportCustomRunnerFolder => 65,
+            ToggleAlertPause => 66,
+            ExportHistory => 67,
+        }
+    }
+
+    fn samples() -> Vec<TrayAction> {
+        use TrayAction::*;
+        let modes = [
+            ChartMode::All,
+            ChartMode::TempOnly,
+            ChartMode::Cores,
+            ChartMode::CpuOnly,
+            ChartMode::NetOnly,
+        ];
+        let mut samples = vec![
+            Quit,
+            Uninstall,
+            ToggleChartPause,
+            SaveChartImage,
+            ToggleChartMiniMode,
+            ToggleChartStats,
+            ToggleChartTempRange,
+            CopyStats,
+            CopyStatsJson,
+            ExportHistory,
+            TestAlert,
+            ToggleAlertRecovery,
+            ToggleAlertPause,
+            ToggleVolumeNotifications,
+            SetAlertSound(None),
+            SetAlertSound(Some("Glass".to_string())),
+            ProfileApp,
+            OpenLogFolder,
+            ClearIssues,
+            OpenConfigFolder,
+            RevealCustomFrames,
+            ToggleLaunchAtLogin,
+            ExportSettings,
+            ImportSettings,
+            ResetSessionCounters,
+            ResetDailyTraffic,
+            EjectDisk("/Volumes/Backup Disk".to_string()),
+            ToggleDiskMount("/Volumes/USB_1".to_string()),
+            SwitchProfile("On the go".to_string()),
+            SaveProfile,
+            SetPollInterval(0.5),
+            SetPollInterval(60.0),
+            CustomPollInterval,
+            ToggleDigest,
+            ToggleCpuClusterLabel,
+            SetChartHotkey(None),
+            SetChartHotkey(Some(Hotkey::new(46, (1 << 12) | (1 << 11)))),
+            SetModuleWidth(None),
+            SetModuleWidth(Some(54.0)),
+            SetModuleOrder(Module::ALL.to_vec()),
+            SetModuleOrder(vec![Module::Temp, Module::Cpu]),
+            SetTempComponent("CPU Die".to_string()),
+            TogglePinnedSensor("GPU Cluster 2".to_string()),
+            SetRunnerDisplaySecs(600),
+            CustomRunnerDisplaySecs,
+            SetRunnerFrameMs(60),
+            SetRunnerAnimationSpeed(150),
+            ToggleRunner("runcat:cat".to_string()),
+            ToggleRunner("custom:1700000000".to_string()),
+            SelectRunnerCategory("Animals".to_string()),
+            OnlyRunnerCategory("Vehicles".to_string()),
+            SelectAllRunners,
+            NextRunner,
+            PreviousRunner,
+            ToggleRunnerShuffle,
+            ImportCustomRunner,
+            ImportCustomRunnerFolder,
+        ];
+        samples.extend(modes.map(ShowCharts));
+        samples.extend(modes.map(PopOutChart));
+        samples.extend(
+            [
+                ChartSmoothing::Off,
+                ChartSmoothing::Avg5,
+                ChartSmoothing::Avg15,
+            ]
+            .map(SetChartSmoothing),
+        );
+        samples.extend(
+            [
+                NetChartStyle::Separate,
+                NetChartStyle::Mirrored,
+                NetChartStyle::Overlaid,
+            ]
+            .map(SetNetChartStyle),
+        );
+        samples.extend(
+            [
+                ChartThemeChoice::Auto,
+                ChartThemeChoice::Dark,
+                ChartThemeChoice::Light,
+                ChartThemeChoice::HighContrast,
+            ]
+            .map(SetChartTheme),
+        );
+        samples.extend(
+            [
+                AlertPreset::Relaxed,
+                AlertPreset::Standard,
+                AlertPreset::Strict,
+            ]
+            .map(SetAlertPreset),
+        );
+        samples.extend([NetDisplay::Speed, NetDisplay::DailyTotal].map(SetNetDisplay));
+        samples.extend(Module::ALL.map(ToggleAutoHide));
+        samples.extend(Module::ALL.map(MoveModuleLeft));
+        samples.extend(Module::ALL.map(MoveModuleRight));
+        samples.extend(
+            [
+                ColorScheme::Default,
+                ColorScheme::Conservative,
+                ColorScheme::Off,
+            ]
+            .map(SetColorScheme),
+        );
+        samples.extend(
+            [
+                CpuStatusStyle::Text,
+                CpuStatusStyle::Sparkline,
+                CpuStatusStyle::Both,
+            ]
+            .map(SetCpuStatusStyle),
+        );
+        samples.extend(
+            [
+                OpenMenuUpdates::Live,
+                OpenMenuUpdates::Paused,
+                OpenMenuUpdates::Deferred,
+            ]
+            .map(SetOpenMenuUpdates),
+        );
+        samples.extend(
+            [
+                LowPowerBehavior::Off,
+                LowPowerBehavior::Animation,
+                LowPowerBehavior::Polling,
+            ]
+            .map(SetLowPowerBehavior),
+        );
+        samples.extend(
+            [
+                Language::Auto,
+                Language::English,
+                Language::ChineseSimplified,
+                Language::Japanese,
+            ]
+            .map(SetLanguage),
+        );
+        samples.extend(
+            [
+                TempLabelStyle::Static,
+                TempLabelStyle::SensorName,
+                TempLabelStyle::Hottest,
+            ]
+            .map(SetTempLabelStyle),
+        );
+        samples.extend(
+            [RunnerIdleBehavior::Animate, RunnerIdleBehavior::Sleep].map(SetRunnerIdleBehavior),
+        );
+        samples
+    }
+
+    #[test]
+    fn every_action_round_trips_through_its_id() {
+        let samples = samples();
+        let mut seen = [false; VARIANTS];
+        for action in &samples {
+            seen[variant(action)] = true;
+            let id = action.id();
+            assert!(
+                TrayAction::parse(&id) == Some(action.clone()),
+                "{} does not parse back to its action",
+                id
+            );
+        }
+        let missing: Vec<usize> = (0..VARIANTS).filter(|&i| !seen[i]).collect();
+        assert!(
+            missing.is_empty(),
+            "variants without a sample: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let mut ids: Vec<String> = samples().iter().map(TrayAction::id).collect();
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn unknown_ids_are_rejected() {
+        for id in [
+            "",
+            "nope",
+            "interval_fast",
+            "module_order_cpu,gpu",
+            "hotkey_1",
+        ] {
+            assert!(TrayAction::parse(id).is_none(), "{} parsed", id);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Light and dark appearance. Status item titles are colored for the menu
+//! bar they sit on, which can differ from the app's own appearance, and are
+//! redrawn as soon as the system switches between the two.
+
+use block2::RcBlock;
+use objc2::msg_send;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::{ns_string, NSString};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// True once after the system switched between light and dark
+pub fn take_changed() -> bool {
+    CHANGED.swap(false, Ordering::Relaxed)
+}
+
+/// Follow light/dark switches on the main queue. Call once.
+pub fn watch_appearance() {
+    unsafe {
+        let Some(center_class) = AnyClass::get(c"NSDistributedNotificationCenter") else {
+            return;
+        };
+        let center: *mut AnyObject = msg_send![center_class, defaultCenter];
+        let Some(queue_class) = AnyClass::get(c"NSOperationQueue") else {
+            return;
+        };
+        let queue: *mut AnyObject = msg_send![queue_class, mainQueue];
+        if center.is_null() || queue.is_null() {
+            log::warn!("could not watch the system appearance");
+            return;
+        }
+        let handler = RcBlock::new(|_notification: *mut AnyObject| {
+            CHANGED.store(true, Ordering::Relaxed);
+        });
+        // The center keeps the observer, and with it the block, for the
+        // lifetime of the app
+        let _: *mut AnyObject = msg_send![
+            center,
+            addObserverForName: ns_string!("AppleInterfaceThemeChangedNotification"),
+            object: std::ptr::null::<AnyObject>(),
+            queue: queue,
+            usingBlock: &*handler
+        ];
+    }
+}
+
+/// The app as a whole is drawn dark
+pub fn app_is_dark() -> bool {
+    let Some(app_class) = AnyClass::get(c"NSApplication") else {
+        return true;
+    };
+    unsafe {
+        let app: *mut AnyObject = msg_send![app_class, sharedApplication];
+        if app.is_null() {
+            return true;
+        }
+        is_dark(app)
+    }
+}
+
+/// `object` (a view, window or the app) is drawn with one of the dark
+/// appearances
+///
+/// # Safety
+/// `object` must respond to `effectiveAppearance`.
+pub unsafe fn is_dark(object: *const AnyObject) -> bool {
+    let appearance: *mut AnyObject = msg_send![object, effectiveAppearance];
+    if appearance.is_null() {
+        return true;
+    }
+    let name: *mut NSString = msg_send![appearance, name];
+    name.as_ref()
+        .map_or(true, |name| name.to_string().contains("Dark"))
+}
@@ -1,34 +1,452 @@
-use crate::model::HistoryBuffer;
+use crate::alert::notify;
+use crate::config::{ChartThemeChoice, NetChartStyle, WindowFrame};
+use crate::format::format_bytes;
+use crate::model::{Aggregates, HistoryBuffer, Series};
+use crate::strings::{chart_font, t, tf};
+use crate::ui::{appearance, motion};
+use objc2::msg_send;
+use objc2::runtime::AnyObject;
 use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
 use plotters_bitmap::BitMapBackend;
 use std::collections::VecDeque;
 use std::num::NonZeroU32;
-use tao::dpi::LogicalSize;
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tao::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
 use tao::event_loop::EventLoopWindowTarget;
+use tao::keyboard::KeyCode;
+use tao::platform::macos::WindowExtMacOS;
 use tao::window::{Window, WindowBuilder};
 
 const WIN_WIDTH: u32 = 800;
 const WIN_HEIGHT: u32 = 520;
+/// Mini mode: a borderless, always-on-top strip of CPU and NET
+const MINI_WIDTH: u32 = 320;
+const MINI_HEIGHT: u32 = 180;
+/// Default size of a popped-out single chart
+const POP_OUT_WIDTH: u32 = 480;
+const POP_OUT_HEIGHT: u32 = 300;
+/// Live resizing sends an event per pixel; re-render at most this often
+const RESIZE_RENDER_INTERVAL: Duration = Duration::from_millis(50);
+/// NSWindowOcclusionStateVisible
+const WINDOW_OCCLUSION_VISIBLE: usize = 1 << 1;
+const EXPORT_WIDTH: u32 = 1600;
+const EXPORT_HEIGHT: u32 = 1040;
+/// Exports are drawn like a Retina window at twice the default size
+const EXPORT_SCALE: Scale = Scale(2.0);
 const FIXED_TEMPS: [&str; 3] = ["CPU", "GPU", "SSD"];
+/// Size of the sparkline attached to alert notifications
+const ALERT_SPARKLINE_WIDTH: u32 = 360;
+const ALERT_SPARKLINE_HEIGHT: u32 = 120;
 
-// Modern dark theme colors
-const BG_COLOR: RGBColor = RGBColor(28, 28, 32);
-const GRID_COLOR: RGBColor = RGBColor(45, 45, 52);
-const TEXT_COLOR: RGBColor = RGBColor(220, 220, 225);
-const TEMP_COLORS: [RGBColor; 3] = [
-    RGBColor(255, 95, 87),  // CPU - Coral
-    RGBColor(80, 200, 200), // GPU - Teal
-    RGBColor(255, 203, 0),  // SSD - Gold
-];
-const CPU_COLOR: RGBColor = RGBColor(90, 200, 250);
-const MEM_COLOR: RGBColor = RGBColor(175, 130, 255);
-const NET_DOWN_COLOR: RGBColor = RGBColor(50, 215, 75);
-const NET_UP_COLOR: RGBColor = RGBColor(255, 159, 10);
+/// Every color the chart window draws with
+struct ChartTheme {
+    background: RGBColor,
+    /// Drawn at partial opacity over the background
+    grid: RGBColor,
+    text: RGBColor,
+    /// CPU, GPU and SSD
+    temps: [RGBColor; 3],
+    /// Pinned raw sensors, by pin slot so a sensor keeps its color
+    pinned_temps: [RGBColor; 3],
+    cpu: RGBColor,
+    mem: RGBColor,
+    mem_wired: RGBColor,
+    mem_compressed: RGBColor,
+    disk: RGBColor,
+    net_down: RGBColor,
+    net_up: RGBColor,
+    pageouts: RGBColor,
+    latency: RGBColor,
+    latency_warn: RGBColor,
+}
 
-#[derive(Clone, Copy, PartialEq)]
+const DARK_THEME: ChartTheme = ChartTheme {
+    background: RGBColor(28, 28, 32),
+    grid: RGBColor(45, 45, 52),
+    text: RGBColor(220, 220, 225),
+    temps: [
+        RGBColor(255, 95, 87),  // CPU - Coral
+        RGBColor(80, 200, 200), // GPU - Teal
+        RGBColor(255, 203, 0),  // SSD - Gold
+    ],
+    pinned_temps: [
+        RGBColor(255, 55, 95),   // Pink
+        RGBColor(100, 210, 255), // Sky
+        RGBColor(191, 90, 242),  // Purple
+    ],
+    cpu: RGBColor(90, 200, 250),
+    mem: RGBColor(175, 130, 255),
+    mem_wired: RGBColor(255, 120, 90),
+    mem_compressed: RGBColor(255, 214, 10),
+    disk: RGBColor(10, 132, 255),
+    net_down: RGBColor(50, 215, 75),
+    net_up: RGBColor(255, 159, 10),
+    pageouts: RGBColor(255, 105, 180),
+    latency: RGBColor(100, 210, 255),
+    latency_warn: RGBColor(255, 69, 58),
+};
+
+/// Darker series and a grid strong enough to read at partial opacity on white
+const LIGHT_THEME: ChartTheme = ChartTheme {
+    background: RGBColor(250, 250, 252),
+    grid: RGBColor(140, 140, 150),
+    text: RGBColor(28, 28, 32),
+    temps: [
+        RGBColor(215, 58, 50),
+        RGBColor(0, 135, 135),
+        RGBColor(180, 130, 0),
+    ],
+    pinned_temps: [
+        RGBColor(205, 30, 90),
+        RGBColor(0, 115, 195),
+        RGBColor(135, 55, 200),
+    ],
+    cpu: RGBColor(0, 115, 230),
+    mem: RGBColor(120, 80, 220),
+    mem_wired: RGBColor(215, 85, 45),
+    mem_compressed: RGBColor(190, 140, 0),
+    disk: RGBColor(0, 85, 195),
+    net_down: RGBColor(30, 150, 55),
+    net_up: RGBColor(225, 115, 0),
+    pageouts: RGBColor(205, 45, 135),
+    latency: RGBColor(0, 125, 195),
+    latency_warn: RGBColor(210, 35, 35),
+};
+
+/// Black, white text and saturated series
+const HIGH_CONTRAST_THEME: ChartTheme = ChartTheme {
+    background: RGBColor(0, 0, 0),
+    grid: RGBColor(150, 150, 150),
+    text: RGBColor(255, 255, 255),
+    temps: [
+        RGBColor(255, 64, 64),
+        RGBColor(0, 255, 255),
+        RGBColor(255, 255, 0),
+    ],
+    pinned_temps: [
+        RGBColor(255, 0, 160),
+        RGBColor(90, 170, 255),
+        RGBColor(200, 120, 255),
+    ],
+    cpu: RGBColor(0, 200, 255),
+    mem: RGBColor(200, 150, 255),
+    mem_wired: RGBColor(255, 130, 60),
+    mem_compressed: RGBColor(255, 255, 0),
+    disk: RGBColor(80, 160, 255),
+    net_down: RGBColor(0, 255, 80),
+    net_up: RGBColor(255, 170, 0),
+    pageouts: RGBColor(255, 90, 200),
+    latency: RGBColor(0, 220, 255),
+    latency_warn: RGBColor(255, 50, 50),
+};
+
+impl ChartTheme {
+    /// Auto picks Dark or Light from the system appearance, so it follows
+    /// a switch on the next render
+    fn for_choice(choice: ChartThemeChoice) -> &'static Self {
+        match choice {
+            ChartThemeChoice::Auto if appearance::app_is_dark() => &DARK_THEME,
+            ChartThemeChoice::Auto => &LIGHT_THEME,
+            ChartThemeChoice::Dark => &DARK_THEME,
+            ChartThemeChoice::Light => &LIGHT_THEME,
+            ChartThemeChoice::HighContrast => &HIGH_CONTRAST_THEME,
+        }
+    }
+}
+
+const PAUSE_ICON_SIZE: f64 = 6.0;
+/// Opacity of the raw series drawn under a smoothed line
+const RAW_LINE_ALPHA: f64 = 0.35;
+const KBPS_UNITS: RateUnits = RateUnits {
+    value: format_kbps,
+    axis: format_kbps_axis,
+};
+const PAGES_UNITS: RateUnits = RateUnits {
+    value: format_pages,
+    axis: format_pages_axis,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ChartMode {
     All,
     TempOnly,
+    /// One small chart per core
+    Cores,
+    CpuOnly,
+    /// Download and upload, laid out per the network chart style
+    NetOnly,
+}
+
+impl ChartMode {
+    /// Key of a pop-out window's remembered frame in the config
+    pub fn id(self) -> &'static str {
+        match self {
+            ChartMode::All => "all",
+            ChartMode::TempOnly => "temp",
+            ChartMode::Cores => "cores",
+            ChartMode::CpuOnly => "cpu",
+            ChartMode::NetOnly => "net",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "all" => Some(ChartMode::All),
+            "temp" => Some(ChartMode::TempOnly),
+            "cores" => Some(ChartMode::Cores),
+            "cpu" => Some(ChartMode::CpuOnly),
+            "net" => Some(ChartMode::NetOnly),
+            _ => None,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            ChartMode::All => "System Monitor",
+            ChartMode::TempOnly => t("Temperature"),
+            ChartMode::Cores => t("Cores"),
+            ChartMode::CpuOnly => "CPU",
+            ChartMode::NetOnly => t("Network"),
+        }
+    }
+}
+
+/// Config-driven choices of what the chart window draws
+#[derive(Clone, Copy)]
+struct ChartOptions {
+    /// Add a pageouts/sec panel next to the network charts
+    show_pageouts: bool,
+    /// Samples per moving-average point; 1 draws the raw series only
+    smoothing: usize,
+    net_style: NetChartStyle,
+    /// Mini mode draws CPU and a single NET pane whatever the mode
+    mini: bool,
+    /// Append min/avg/max over the visible window to each caption
+    show_stats: bool,
+    /// Add a latency pane next to the network charts
+    show_latency: bool,
+    /// Latency above this is drawn in red, in ms
+    latency_warn_ms: f64,
+    /// Resolved from `ChartWindow::theme` before each render
+    theme: &'static ChartTheme,
+}
+
+/// Caption suffix for `data`; empty with the overlay off or no samples
+fn stats_caption(
+    options: ChartOptions,
+    data: impl IntoIterator<Item = f64>,
+    format: impl Fn(f64) -> String,
+) -> String {
+    if !options.show_stats {
+        return String::new();
+    }
+    // " · min 5 · avg 31 · max 88"
+    Aggregates::of(data)
+        .map(|stats| {
+            format!(
+                " · min {} · avg {} · max {}",
+                format(stats.min),
+                format(stats.avg),
+                format(stats.max)
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Caption and axis label formatting of a rate chart
+#[derive(Clone, Copy)]
+struct RateUnits {
+    value: fn(f64) -> String,
+    axis: fn(f64) -> String,
+}
+
+/// Device pixels per point. Sizes below are given in points and scaled, so
+/// text and lines keep their size on 1x displays and stay sharp on Retina.
+#[derive(Clone, Copy)]
+struct Scale(f64);
+
+impl Scale {
+    fn px(self, points: f64) -> i32 {
+        (points * self.0).round() as i32
+    }
+
+    /// Line width; never thinner than one device pixel
+    fn stroke(self, points: f64) -> u32 {
+        self.px(points).max(1) as u32
+    }
+}
+
+fn caption_style(theme: &ChartTheme, scale: Scale) -> TextStyle<'static> {
+    (chart_font(), scale.px(18.0))
+        .into_font()
+        .color(&theme.text)
+}
+
+fn axis_label_style(theme: &ChartTheme, scale: Scale) -> TextStyle<'static> {
+    (chart_font(), scale.px(12.0))
+        .into_font()
+        .color(&theme.text.mix(0.7))
+}
+
+/// Pixel bounds of a chart's plotting area and how samples map onto it
+struct PlotArea {
+    x: Range<i32>,
+    y: Range<i32>,
+    /// Upper end of the x axis range (`0..range_len`)
+    range_len: usize,
+    samples: usize,
+}
+
+impl PlotArea {
+    fn new<Y: Ranged>(
+        chart: &ChartContext<BitMapBackend, Cartesian2d<RangedCoordusize, Y>>,
+        range_len: usize,
+        samples: usize,
+    ) -> Self {
+        let (x, y) = chart.plotting_area().get_pixel_range();
+        Self {
+            x,
+            y,
+            range_len,
+            samples,
+        }
+    }
+
+    /// Index of the sample under pixel `(px, py)`, if it is inside this area
+    fn sample_at(&self, (px, py): (i32, i32)) -> Option<usize> {
+        if self.samples == 0 || !self.x.contains(&px) || !self.y.contains(&py) {
+            return None;
+        }
+        let width = (self.x.end - self.x.start).max(1) as f64;
+        let index = ((px - self.x.start) as f64 / width * self.range_len as f64).round();
+        Some((index as usize).min(self.samples - 1))
+    }
+
+    fn x_of(&self, index: usize) -> i32 {
+        let width = (self.x.end - self.x.start) as f64;
+        self.x.start + (index as f64 / self.range_len.max(1) as f64 * width).round() as i32
+    }
+}
+
+/// Y-axis bounds that follow the data with hysteresis: they only move when
+/// data leaves them or when they have grown far wider than needed.
+#[derive(Clone, Copy, PartialEq)]
+pub struct AutoRange {
+    pub lo: f32,
+    pub hi: f32,
+}
+
+impl AutoRange {
+    /// `step` rounds the bounds; `limits` clamps them (e.g. 0..100 for percent).
+    pub fn follow(
+        current: Option<AutoRange>,
+        data: impl Iterator<Item = f32>,
+        step: f32,
+        limits: Range<f32>,
+    ) -> Option<AutoRange> {
+        let (min, max) = data.fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if min > max {
+            return current;
+        }
+        let pad = ((max - min) * 0.15).max(step / 2.0);
+        let target = AutoRange {
+            lo: ((min - pad) / step).floor() * step,
+            hi: ((max + pad) / step).ceil() * step,
+        }
+        .clamp(&limits);
+
+        if let Some(cur) = current {
+            let contains = cur.lo <= min && max <= cur.hi;
+            let too_wide = (cur.hi - cur.lo) > 2.0 * (target.hi - target.lo);
+            if contains && !too_wide {
+                return Some(cur);
+            }
+        }
+        Some(target)
+    }
+
+    fn clamp(self, limits: &Range<f32>) -> AutoRange {
+        AutoRange {
+            lo: self.lo.max(limits.start),
+            hi: self.hi.min(limits.end),
+        }
+    }
+
+    fn range(self) -> Range<f32> {
+        self.lo..self.hi
+    }
+}
+
+/// Temperature axis steps; the same in °C and °F since readings are plotted as-is
+const TEMP_STEP: f32 = 10.0;
+/// Wide enough for sensor readings in either unit
+const TEMP_LIMITS: Range<f32> = -100.0..300.0;
+
+/// Y-axis state of the CPU, MEM, DISK and TEMP panels
+#[derive(Default)]
+struct AxisZoom {
+    /// Auto-zoom the percent panels
+    enabled: bool,
+    cpu: Option<AutoRange>,
+    mem: Option<AutoRange>,
+    disk: Option<AutoRange>,
+    /// Lock the TEMP axis to 0..100 instead of following the readings
+    temp_fixed: bool,
+    temp: Option<AutoRange>,
+}
+
+/// Y ranges of the CPU, MEM, DISK and TEMP panels
+struct AxisRanges {
+    cpu: Range<f32>,
+    mem: Range<f32>,
+    disk: Range<f32>,
+    temp: Range<f32>,
+}
+
+impl AxisZoom {
+    /// Y ranges for the panels: full scale unless auto-zoomed. The TEMP axis
+    /// follows the plotted sensors unless locked, and starts at 0..100.
+    fn ranges(&mut self, history: &HistoryBuffer, pinned: &[String]) -> AxisRanges {
+        let full = AutoRange { lo: 0.0, hi: 100.0 };
+        let temp = if self.temp_fixed {
+            full.range()
+        } else {
+            let readings = FIXED_TEMPS
+                .iter()
+                .copied()
+                .chain(pinned.iter().map(String::as_str))
+                .filter_map(|name| history.temps.get(name))
+                .flat_map(|data| data.readings().copied())
+                .filter(|v| v.is_finite());
+            self.temp = AutoRange::follow(self.temp, readings, TEMP_STEP, TEMP_LIMITS);
+            self.temp.unwrap_or(full).range()
+        };
+        if !self.enabled {
+            return AxisRanges {
+                cpu: 0.0..100.0,
+                mem: 0.0..100.0,
+                disk: 0.0..100.0,
+                temp,
+            };
+        }
+        self.cpu = AutoRange::follow(self.cpu, history.cpu_usage.iter().copied(), 5.0, 0.0..100.0);
+        self.mem = AutoRange::follow(self.mem, history.mem_usage.iter().copied(), 5.0, 0.0..100.0);
+        self.disk = AutoRange::follow(
+            self.disk,
+            history.disk_usage.readings().copied(),
+            5.0,
+            0.0..100.0,
+        );
+        AxisRanges {
+            cpu: self.cpu.unwrap_or(full).range(),
+            mem: self.mem.unwrap_or(full).range(),
+            disk: self.disk.unwrap_or(full).range(),
+            temp,
+        }
+    }
 }
 
 pub struct ChartWindow {
@@ -37,6 +455,27 @@ pub struct ChartWindow {
     surface: Option<softbuffer::Surface<&'static Window, &'static Window>>,
     visible: bool,
     mode: ChartMode,
+    zoom: AxisZoom,
+    options: ChartOptions,
+    /// Snapshot rendered instead of the live history while paused
+    frozen: Option<HistoryBuffer>,
+    /// Cursor position in physical pixels while it is over the window
+    cursor: Option<(i32, i32)>,
+    /// How long the most recent render took
+    last_render: Option<Duration>,
+    /// Top-left corner of the mini window in points
+    mini_position: Option<(f64, f64)>,
+    /// Raw sensors drawn in the TEMP chart after CPU/GPU/SSD
+    pinned: Vec<String>,
+    /// A popped-out window: its mode is fixed and it is never mini
+    pop_out: bool,
+    /// Where a pop-out opens, in points; the last place it was left
+    frame: Option<WindowFrame>,
+    /// When a resize last rendered right away
+    last_resize_render: Option<Instant>,
+    /// Keep redrawing continuously under Reduce Motion
+    ignore_reduce_motion: bool,
+    theme: ChartThemeChoice,
 }
 
 impl ChartWindow {
@@ -47,27 +486,216 @@ impl ChartWindow {
             surface: None,
             visible: false,
             mode: ChartMode::All,
+            zoom: AxisZoom::default(),
+            options: ChartOptions {
+                show_pageouts: false,
+                smoothing: 1,
+                net_style: NetChartStyle::Separate,
+                mini: false,
+                show_stats: false,
+                show_latency: false,
+                latency_warn_ms: 0.0,
+                theme: &DARK_THEME,
+            },
+            frozen: None,
+            cursor: None,
+            last_render: None,
+            mini_position: None,
+            pinned: Vec::new(),
+            pop_out: false,
+            frame: None,
+            last_resize_render: None,
+            ignore_reduce_motion: false,
+            theme: ChartThemeChoice::default(),
+        }
+    }
+
+    /// A window for a single chart, next to the full one
+    pub fn pop_out(mode: ChartMode, frame: Option<WindowFrame>) -> Self {
+        Self {
+            mode,
+            pop_out: true,
+            frame,
+            ..Self::new()
+        }
+    }
+
+    /// Freeze the view on the current history, or resume live updates.
+    pub fn toggle_pause(&mut self, history: &HistoryBuffer) {
+        self.frozen = match self.frozen {
+            Some(_) => None,
+            None => Some(history.clone()),
+        };
+    }
+
+    pub fn set_auto_zoom(&mut self, enabled: bool) {
+        self.zoom = AxisZoom {
+            enabled,
+            temp_fixed: self.zoom.temp_fixed,
+            ..AxisZoom::default()
+        };
+    }
+
+    pub fn set_temp_fixed_range(&mut self, fixed: bool) {
+        if self.zoom.temp_fixed != fixed {
+            self.zoom.temp_fixed = fixed;
+            self.zoom.temp = None;
+        }
+    }
+
+    pub fn set_show_pageouts(&mut self, enabled: bool) {
+        self.options.show_pageouts = enabled;
+    }
+
+    pub fn set_smoothing(&mut self, samples: usize) {
+        self.options.smoothing = samples.max(1);
+    }
+
+    pub fn set_net_style(&mut self, style: NetChartStyle) {
+        self.options.net_style = style;
+    }
+
+    pub fn set_show_stats(&mut self, enabled: bool) {
+        self.options.show_stats = enabled;
+    }
+
+    pub fn set_theme(&mut self, theme: ChartThemeChoice) {
+        self.theme = theme;
+    }
+
+    pub fn set_ignore_reduce_motion(&mut self, ignore: bool) {
+        self.ignore_reduce_motion = ignore;
+    }
+
+    pub fn set_latency(&mut self, enabled: bool, warn_ms: u32) {
+        self.options.show_latency = enabled;
+        self.options.latency_warn_ms = warn_ms as f64;
+    }
+
+    pub fn set_pinned_sensors(&mut self, pinned: &[String]) {
+        if self.pinned != pinned {
+            self.pinned = pinned.to_vec();
         }
     }
 
+    /// Switch mini mode. A live window can't drop its title bar, so an open
+    /// one is closed; true when it was showing and should be shown again.
+    pub fn set_mini(&mut self, enabled: bool, position: Option<(f64, f64)>) -> bool {
+        self.mini_position = position;
+        if self.options.mini == enabled {
+            return false;
+        }
+        self.options.mini = enabled;
+        let was_visible = self.visible;
+        if self.window.is_some() {
+            self.handle_close();
+        }
+        was_visible
+    }
+
     pub fn toggle(&mut self, event_loop: &EventLoopWindowTarget<()>, mode: ChartMode) {
         if self.visible && self.mode == mode {
-            if let Some(w) = &self.window {
-                w.set_visible(false);
-            }
-            self.visible = false;
+            self.hide();
         } else {
             self.mode = mode;
-            if self.window.is_none() {
-                self.create_window(event_loop);
+            self.show(event_loop);
+        }
+    }
+
+    /// Bring the window up, creating it first if needed.
+    pub fn show(&mut self, event_loop: &EventLoopWindowTarget<()>) {
+        if self.window.is_none() {
+            // The tray keeps working; the next click tries again
+            if let Err(e) = self.create_window(event_loop) {
+                log::error!("could not open chart window: {}", e);
+                notify(t("Could not open chart window"), &e);
+                return;
             }
-            if let Some(w) = &self.window {
-                w.set_visible(true);
-                w.set_focus();
-                w.request_redraw();
+        }
+        if let Some(w) = &self.window {
+            w.set_visible(true);
+            w.set_focus();
+            w.request_redraw();
+        }
+        self.visible = true;
+    }
+
+    /// A mouse press in the borderless mini window starts moving it; true
+    /// when it did.
+    pub fn drag(&self) -> bool {
+        if let (true, Some(w)) = (self.options.mini, &self.window) {
+            if let Err(e) = w.drag_window() {
+                log::warn!("could not drag chart window: {}", e);
+                return false;
             }
-            self.visible = true;
+            return true;
         }
+        false
+    }
+
+    /// The window moved to `position`; in mini mode returns it in points
+    /// so it can be remembered.
+    pub fn moved(&mut self, position: PhysicalPosition<i32>) -> Option<(f64, f64)> {
+        let window = self.window.as_ref().filter(|_| self.options.mini)?;
+        let position: LogicalPosition<f64> = position.to_logical(window.scale_factor());
+        self.mini_position = Some((position.x, position.y));
+        self.mini_position
+    }
+
+    /// Where a pop-out sits now, in points, so it can be remembered
+    pub fn frame(&mut self) -> Option<WindowFrame> {
+        let window = self.window.as_ref().filter(|_| self.pop_out)?;
+        let scale = window.scale_factor();
+        let position: LogicalPosition<f64> = window.outer_position().ok()?.to_logical(scale);
+        let size: LogicalSize<f64> = window.inner_size().to_logical(scale);
+        self.frame = Some(WindowFrame {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        });
+        self.frame
+    }
+
+    /// Window-local keys: Escape hides, T/A switch mode. A pop-out keeps its
+    /// mode and pauses on Space by itself.
+    pub fn handle_key(&mut self, key: KeyCode, history: &HistoryBuffer) {
+        match key {
+            KeyCode::Escape => self.hide(),
+            KeyCode::Space if self.pop_out => {
+                self.toggle_pause(history);
+                self.render(history);
+            }
+            KeyCode::KeyT if !self.pop_out => self.set_mode(ChartMode::TempOnly, history),
+            KeyCode::KeyA if !self.pop_out => self.set_mode(ChartMode::All, history),
+            _ => {}
+        }
+    }
+
+    fn set_mode(&mut self, mode: ChartMode, history: &HistoryBuffer) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.render(history);
+        }
+    }
+
+    /// Cursor moved to `position` (physical pixels), or left the window.
+    pub fn set_cursor(&mut self, position: Option<(f64, f64)>, history: &HistoryBuffer) {
+        let cursor = position.map(|(x, y)| (x as i32, y as i32));
+        if self.cursor != cursor {
+            self.cursor = cursor;
+            self.render(history);
+        }
+    }
+
+    /// Hide but keep the window and surface around for a fast reopen.
+    fn hide(&mut self) {
+        if let Some(w) = &self.window {
+            w.set_visible(false);
+        }
+        self.visible = false;
+        self.frozen = None;
+        self.cursor = None;
     }
 
     pub fn is_visible(&self) -> bool {
@@ -78,220 +706,1149 @@ impl ChartWindow {
         self.window.as_ref().map(|w| w.id())
     }
 
+    /// The NSWindow behind the chart, once it has been created
+    pub fn ns_window(&self) -> Option<*const AnyObject> {
+        let ns_window = self.window.as_ref()?.ns_window() as *const AnyObject;
+        (!ns_window.is_null()).then_some(ns_window)
+    }
+
+    /// Fit the surface to the new size and redraw now instead of showing
+    /// stretched content until the next poll. During a live resize this
+    /// renders at most every `RESIZE_RENDER_INTERVAL`; the redraw request
+    /// picks up the final size.
+    pub fn handle_resize(&mut self, size: PhysicalSize<u32>, history: &HistoryBuffer) {
+        if let (Some(surface), Some(width), Some(height)) = (
+            &mut self.surface,
+            NonZeroU32::new(size.width),
+            NonZeroU32::new(size.height),
+        ) {
+            if let Err(e) = surface.resize(width, height) {
+                log::warn!("chart surface resize failed: {}", e);
+            }
+        }
+        let now = Instant::now();
+        if self
+            .last_resize_render
+            .is_some_and(|at| now.duration_since(at) < RESIZE_RENDER_INTERVAL)
+        {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+            return;
+        }
+        self.last_resize_render = Some(now);
+        self.render(history);
+    }
+
+    /// Minimized, on another Space or fully covered
+    fn is_occluded(window: &Window) -> bool {
+        let ns_window = window.ns_window() as *const AnyObject;
+        if ns_window.is_null() {
+            return false;
+        }
+        let occlusion: usize = unsafe { msg_send![ns_window, occlusionState] };
+        occlusion & WINDOW_OCCLUSION_VISIBLE == 0
+    }
+
     pub fn handle_close(&mut self) {
         self.surface = None;
         self.context = None;
         self.window = None;
         self.visible = false;
+        self.frozen = None;
     }
 
-    fn create_window(&mut self, event_loop: &EventLoopWindowTarget<()>) {
-        let window = Box::new(
-            WindowBuilder::new()
-                .with_title("System Monitor")
+    fn create_window(&mut self, event_loop: &EventLoopWindowTarget<()>) -> Result<(), String> {
+        let title = if self.pop_out {
+            format!("{} — System Monitor", self.mode.title())
+        } else {
+            "System Monitor".to_string()
+        };
+        let builder = WindowBuilder::new().with_title(title).with_visible(false);
+        let builder = if self.pop_out {
+            let builder = builder
+                .with_min_inner_size(LogicalSize::new(240u32, 160u32))
+                .with_resizable(true);
+            match self.frame {
+                Some(frame) => builder
+                    .with_inner_size(LogicalSize::new(frame.width, frame.height))
+                    .with_position(LogicalPosition::new(frame.x, frame.y)),
+                None => builder.with_inner_size(LogicalSize::new(POP_OUT_WIDTH, POP_OUT_HEIGHT)),
+            }
+        } else if self.options.mini {
+            let builder = builder
+                .with_inner_size(LogicalSize::new(MINI_WIDTH, MINI_HEIGHT))
+                .with_resizable(false)
+                .with_decorations(false)
+                .with_always_on_top(true);
+            match self.mini_position {
+                Some((x, y)) => builder.with_position(LogicalPosition::new(x, y)),
+                None => builder,
+            }
+        } else {
+            builder
                 .with_inner_size(LogicalSize::new(WIN_WIDTH, WIN_HEIGHT))
                 .with_min_inner_size(LogicalSize::new(400u32, 240u32))
                 .with_resizable(true)
-                .with_visible(false)
+        };
+        let window = Box::new(
+            builder
                 .build(event_loop)
-                .expect("failed to create chart window"),
+                .map_err(|e| format!("window: {}", e))?,
         );
 
         let window_ref: &'static Window = unsafe { &*(window.as_ref() as *const Window) };
 
-        let context =
-            softbuffer::Context::new(window_ref).expect("failed to create softbuffer context");
-        let surface =
-            softbuffer::Surface::new(&context, window_ref).expect("failed to create surface");
+        let context = softbuffer::Context::new(window_ref)
+            .map_err(|e| format!("softbuffer context: {}", e))?;
+        let surface = softbuffer::Surface::new(&context, window_ref)
+            .map_err(|e| format!("softbuffer surface: {}", e))?;
 
         self.window = Some(window);
         self.context = Some(unsafe { std::mem::transmute(context) });
         self.surface = Some(unsafe { std::mem::transmute(surface) });
+        Ok(())
     }
 
     pub fn render(&mut self, history: &HistoryBuffer) {
         if !self.visible {
             return;
         }
+        let started = Instant::now();
 
         let window = match &self.window {
             Some(w) => w,
             None => return,
         };
+        // Nobody can see it; the window is drawn again once it gains focus
+        if Self::is_occluded(window) {
+            return;
+        }
 
+        // Draw at device resolution; the scale factor sizes text and lines
         let phys = window.inner_size();
         let width = phys.width;
         let height = phys.height;
-        if width == 0 || height == 0 {
+        let scale = Scale(window.scale_factor());
+        let Some((surface_width, surface_height)) = surface_size(phys) else {
             return;
-        }
+        };
 
         let surface = match &mut self.surface {
             Some(s) => s,
             None => return,
         };
 
-        let _ = surface.resize(
-            NonZeroU32::new(width).unwrap(),
-            NonZeroU32::new(height).unwrap(),
-        );
+        if let Err(e) = surface.resize(surface_width, surface_height) {
+            log::warn!("chart surface resize failed: {}", e);
+            return;
+        }
 
         let (w, h) = (width as usize, height as usize);
         let mut pixel_buf = vec![0u8; w * h * 3];
+        self.options.theme = ChartTheme::for_choice(self.theme);
+        let shown = self.frozen.as_ref().unwrap_or(history);
+        let ranges = self.zoom.ranges(shown, &self.pinned);
 
         {
             let backend = BitMapBackend::with_buffer(&mut pixel_buf, (width, height));
             let root = backend.into_drawing_area();
-            let _ = root.fill(&BG_COLOR);
-
-            match self.mode {
-                ChartMode::All => {
-                    // 3 rows x 2 cols layout
-                    let rows = root.split_evenly((3, 1));
-                    let top = rows[0].split_evenly((1, 2));
-                    let mid = rows[1].split_evenly((1, 2));
-
-                    draw_percent_chart(&top[0], "CPU", &history.cpu_usage, &CPU_COLOR);
-                    draw_percent_chart(&top[1], "MEM", &history.mem_usage, &MEM_COLOR);
-                    draw_net_chart(&mid[0], "NET Down", &history.net_down, &NET_DOWN_COLOR);
-                    draw_net_chart(&mid[1], "NET Up", &history.net_up, &NET_UP_COLOR);
-                    draw_temp_combined(&rows[2], history);
-                }
-                ChartMode::TempOnly => {
-                    draw_temp_combined(&root, history);
-                }
+            let areas = draw_charts(
+                &root,
+                self.mode,
+                shown,
+                &ranges,
+                self.options,
+                &self.pinned,
+                scale,
+            );
+            if let Some(cursor) = self.cursor {
+                draw_hover(&root, &areas, cursor, self.mode, shown, self.options, scale);
+            }
+            draw_pause_indicator(&root, self.frozen.is_some(), self.options.theme, scale);
+            if let Err(e) = root.present() {
+                log::warn!("chart rendering failed: {}", e);
             }
-
-            let _ = root.present();
         }
 
         // Copy RGB to softbuffer (ARGB format)
-        let mut buf = surface.buffer_mut().unwrap();
-        for i in 0..w * h {
-            let r = pixel_buf[i * 3] as u32;
-            let g = pixel_buf[i * 3 + 1] as u32;
-            let b = pixel_buf[i * 3 + 2] as u32;
-            buf[i] = (255 << 24) | (r << 16) | (g << 8) | b;
+        let mut buf = match surface.buffer_mut() {
+            Ok(buf) => buf,
+            Err(e) => {
+                log::warn!("chart surface buffer unavailable: {}", e);
+                return;
+            }
+        };
+        for (pixel, rgb) in buf.iter_mut().zip(pixel_buf.chunks_exact(3)) {
+            let (r, g, b) = (rgb[0] as u32, rgb[1] as u32, rgb[2] as u32);
+            *pixel = (255 << 24) | (r << 16) | (g << 8) | b;
+        }
+        if let Err(e) = buf.present() {
+            log::warn!("chart surface present failed: {}", e);
+        }
+
+        // Under Reduce Motion only new data, the cursor and resizes redraw
+        if self.ignore_reduce_motion || !motion::reduce_motion() {
+            window.request_redraw();
+        }
+        self.last_render = Some(started.elapsed());
+    }
+
+    pub fn last_render(&self) -> Option<Duration> {
+        self.last_render
+    }
+
+    /// Render the current chart mode at a fixed high resolution into a PNG file.
+    pub fn export_png(&mut self, history: &HistoryBuffer, path: &Path) -> Result<(), String> {
+        self.options.theme = ChartTheme::for_choice(self.theme);
+        let shown = self.frozen.as_ref().unwrap_or(history);
+        let ranges = self.zoom.ranges(shown, &self.pinned);
+        let root = BitMapBackend::new(path, (EXPORT_WIDTH, EXPORT_HEIGHT)).into_drawing_area();
+        draw_charts(
+            &root,
+            self.mode,
+            shown,
+            &ranges,
+            self.options,
+            &self.pinned,
+            EXPORT_SCALE,
+        );
+        root.present().map_err(|e| e.to_string())
+    }
+}
+
+/// Recent `values` of an alert's metric ("cpu", "memory" or "temperature")
+/// as a PNG at `path`, for its notification. Drawn in the light theme: this
+/// runs off the main thread, where the appearance can't be asked.
+pub fn render_alert_sparkline(kind: &str, values: &[f32], path: &Path) -> Result<(), String> {
+    if values.len() < 2 {
+        return Err("fewer than two samples".to_string());
+    }
+    let theme = &LIGHT_THEME;
+    let color = match kind {
+        "memory" => theme.mem,
+        "temperature" => theme.temps[0],
+        _ => theme.cpu,
+    };
+    let (min, max) = values
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let pad = ((max - min) * 0.1).max(1.0);
+    let (low, high) = (min - pad, max + pad);
+    let root = BitMapBackend::new(path, (ALERT_SPARKLINE_WIDTH, ALERT_SPARKLINE_HEIGHT))
+        .into_drawing_area();
+    root.fill(&theme.background).map_err(|e| e.to_string())?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(6)
+        .build_cartesian_2d(0..values.len() - 1, low..high)
+        .map_err(|e| e.to_string())?;
+    let points = || values.iter().copied().enumerate();
+    chart
+        .draw_series(AreaSeries::new(points(), low, color.mix(0.2).filled()))
+        .map_err(|e| e.to_string())?;
+    chart
+        .draw_series(LineSeries::new(points(), color.stroke_width(2)))
+        .map_err(|e| e.to_string())?;
+    root.present().map_err(|e| e.to_string())
+}
+
+fn draw_charts(
+    root: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    mode: ChartMode,
+    history: &HistoryBuffer,
+    ranges: &AxisRanges,
+    options: ChartOptions,
+    pinned: &[String],
+    scale: Scale,
+) -> Vec<PlotArea> {
+    let theme = options.theme;
+    let _ = root.fill(&theme.background);
+    let ChartOptions {
+        show_pageouts,
+        net_style,
+        mini,
+        show_latency,
+        ..
+    } = options;
+
+    if mini {
+        // Split with the NET lines overlaid unless mirroring was chosen
+        let panes = root.split_evenly((1, 2));
+        return vec![
+            draw_percent_chart(
+                &panes[0],
+                "CPU",
+                &history.cpu_usage,
+                &theme.cpu,
+                ranges.cpu.clone(),
+                options,
+                scale,
+            ),
+            draw_net_combined(
+                &panes[1],
+                history,
+                net_style == NetChartStyle::Mirrored,
+                options,
+                scale,
+            ),
+        ];
+    }
+    match mode {
+        ChartMode::All => {
+            // CPU/MEM/DISK, network, then temperatures. A single network
+            // pane leaves room for DISK in the middle row.
+            let combined_net = net_style != NetChartStyle::Separate;
+            let rows = root.split_evenly((3, 1));
+            let top = rows[0].split_evenly((1, if combined_net { 2 } else { 3 }));
+            let mid_panes = 2 + show_pageouts as usize + show_latency as usize;
+            let mid = rows[1].split_evenly((1, mid_panes));
+            let disk_area = if combined_net { &mid[1] } else { &top[2] };
+
+            let mut areas = vec![
+                draw_percent_chart(
+                    &top[0],
+                    "CPU",
+                    &history.cpu_usage,
+                    &theme.cpu,
+                    ranges.cpu.clone(),
+                    options,
+                    scale,
+                ),
+                if history.mem_app.is_empty() {
+                    draw_percent_chart(
+                        &top[1],
+                        t("MEM"),
+                        &history.mem_usage,
+                        &theme.mem,
+                        ranges.mem.clone(),
+                        options,
+                        scale,
+                    )
+                } else {
+                    draw_memory_stacked(&top[1], history, options, scale)
+                },
+                draw_percent_chart(
+                    disk_area,
+                    t("DISK"),
+                    &history.disk_usage,
+                    &theme.disk,
+                    ranges.disk.clone(),
+                    options,
+                    scale,
+                ),
+            ];
+            if combined_net {
+                areas.push(draw_net_combined(
+                    &mid[0],
+                    history,
+                    net_style == NetChartStyle::Mirrored,
+                    options,
+                    scale,
+                ));
+            } else {
+                areas.push(draw_rate_chart(
+                    &mid[0],
+                    t("NET Down"),
+                    &history.net_down,
+                    &theme.net_down,
+                    KBPS_UNITS,
+                    options,
+                    scale,
+                ));
+                areas.push(draw_rate_chart(
+                    &mid[1],
+                    t("NET Up"),
+                    &history.net_up,
+                    &theme.net_up,
+                    KBPS_UNITS,
+                    options,
+                    scale,
+                ));
+            }
+            if show_pageouts {
+                areas.push(draw_rate_chart(
+                    &mid[2],
+                    t("Pageouts"),
+                    &history.pageouts,
+                    &theme.pageouts,
+                    PAGES_UNITS,
+                    options,
+                    scale,
+                ));
+            }
+            if show_latency {
+                areas.push(draw_latency_chart(
+                    &mid[mid_panes - 1],
+                    &history.latency,
+                    options,
+                    scale,
+                ));
+            }
+            areas.push(draw_temp_combined(
+                &rows[2],
+                history,
+                ranges.temp.clone(),
+                options,
+                pinned,
+                scale,
+            ));
+            areas
         }
-        let _ = buf.present();
+        ChartMode::TempOnly => vec![draw_temp_combined(
+            root,
+            history,
+            ranges.temp.clone(),
+            options,
+            pinned,
+            scale,
+        )],
+        ChartMode::Cores => draw_core_grid(root, &history.cores, options, scale),
+        ChartMode::CpuOnly => vec![draw_percent_chart(
+            root,
+            "CPU",
+            &history.cpu_usage,
+            &theme.cpu,
+            ranges.cpu.clone(),
+            options,
+            scale,
+        )],
+        ChartMode::NetOnly => {
+            let separate = net_style == NetChartStyle::Separate;
+            let rows = root.split_evenly((1 + separate as usize + show_latency as usize, 1));
+            let mut areas = if separate {
+                vec![
+                    draw_rate_chart(
+                        &rows[0],
+                        t("NET Down"),
+                        &history.net_down,
+                        &theme.net_down,
+                        KBPS_UNITS,
+                        options,
+                        scale,
+                    ),
+                    draw_rate_chart(
+                        &rows[1],
+                        t("NET Up"),
+                        &history.net_up,
+                        &theme.net_up,
+                        KBPS_UNITS,
+                        options,
+                        scale,
+                    ),
+                ]
+            } else {
+                vec![draw_net_combined(
+                    &rows[0],
+                    history,
+                    net_style == NetChartStyle::Mirrored,
+                    options,
+                    scale,
+                )]
+            };
+            if show_latency {
+                areas.push(draw_latency_chart(
+                    &rows[rows.len() - 1],
+                    &history.latency,
+                    options,
+                    scale,
+                ));
+            }
+            areas
+        }
+    }
+}
+
+/// Reading `back` polls before the newest one; None where that poll had none
+fn value_back<T: Copy>(data: &Series<T>, back: usize) -> Option<T> {
+    let index = data.len().checked_sub(back + 1)?;
+    data.reading(index).copied()
+}
 
-        window.request_redraw();
+/// Readings at `back` samples before the newest, for the hover box
+fn hover_lines(
+    mode: ChartMode,
+    history: &HistoryBuffer,
+    back: usize,
+    options: ChartOptions,
+) -> Vec<String> {
+    let mut lines = vec![match back {
+        0 => "latest sample".to_string(),
+        1 => "1 sample ago".to_string(),
+        n => format!("{} samples ago", n),
+    }];
+    let percent = |name: &str, data: &Series<f32>| {
+        value_back(data, back).map(|v| format!("{} {:.1}%", name, v))
+    };
+    let temps = FIXED_TEMPS.iter().filter_map(|name| {
+        let v = value_back(history.temps.get(*name)?, back)?;
+        Some(format!("{} temp {:.0}C", name, v))
+    });
+    let net = |lines: &mut Vec<String>| {
+        lines.extend(
+            value_back(&history.net_down, back).map(|v| format!("Down {}", format_kbps(v))),
+        );
+        lines.extend(value_back(&history.net_up, back).map(|v| format!("Up {}", format_kbps(v))));
+        if options.show_latency {
+            let latency = &history.latency;
+            lines.extend(latency.len().checked_sub(back + 1).map(|index| {
+                format!(
+                    "Latency {}",
+                    format_latency(latency.reading(index).copied())
+                )
+            }));
+        }
+    };
+    if options.mini {
+        lines.extend(percent("CPU", &history.cpu_usage));
+        net(&mut lines);
+        return lines;
+    }
+    match mode {
+        ChartMode::All => {
+            lines.extend(percent("CPU", &history.cpu_usage));
+            lines.extend(percent("MEM", &history.mem_usage));
+            for (name, data) in [
+                ("App", &history.mem_app),
+                ("Wired", &history.mem_wired),
+                ("Compressed", &history.mem_compressed),
+            ] {
+                lines.extend(
+                    value_back(data, back)
+                        .map(|v| format!("  {} {}", name, format_bytes(v as u64))),
+                );
+            }
+            lines.extend(percent("DISK", &history.disk_usage));
+            net(&mut lines);
+            if options.show_pageouts {
+                lines.extend(
+                    value_back(&history.pageouts, back)
+                        .map(|v| format!("Pageouts {}", format_pages(v))),
+                );
+            }
+            lines.extend(temps);
+        }
+        ChartMode::TempOnly => lines.extend(temps),
+        ChartMode::CpuOnly => lines.extend(percent("CPU", &history.cpu_usage)),
+        ChartMode::NetOnly => net(&mut lines),
+        ChartMode::Cores => {
+            for (i, data) in history.cores.iter().enumerate() {
+                lines.extend(percent(&format!("Core {}", i + 1), data));
+            }
+        }
+    }
+    lines
+}
+
+/// Crosshair through the sample under the cursor in every panel, plus a box
+/// with the values at that sample. Nothing when the cursor is off the charts.
+fn draw_hover(
+    root: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    areas: &[PlotArea],
+    cursor: (i32, i32),
+    mode: ChartMode,
+    history: &HistoryBuffer,
+    options: ChartOptions,
+    scale: Scale,
+) {
+    let theme = options.theme;
+    let Some(back) = areas.iter().find_map(|area| {
+        let index = area.sample_at(cursor)?;
+        Some(area.samples - 1 - index)
+    }) else {
+        return;
+    };
+
+    let line_style = theme.text.mix(0.5).stroke_width(scale.stroke(0.5));
+    for area in areas.iter().filter(|area| back < area.samples) {
+        let x = area.x_of(area.samples - 1 - back);
+        let _ = root.draw(&PathElement::new(
+            vec![(x, area.y.start), (x, area.y.end)],
+            line_style,
+        ));
+    }
+
+    let lines = hover_lines(mode, history, back, options);
+    let font = (chart_font(), scale.px(11.0))
+        .into_font()
+        .color(&theme.text);
+    let line_height = scale.px(13.0);
+    let padding = scale.px(4.0);
+    let offset = scale.px(8.0);
+    let text_width = lines
+        .iter()
+        .filter_map(|line| root.estimate_text_size(line, &font).ok())
+        .map(|(w, _)| w as i32)
+        .max()
+        .unwrap_or(0);
+    let box_w = text_width + padding * 2;
+    let box_h = line_height * lines.len() as i32 + padding * 2;
+
+    // Beside the cursor, flipped to stay inside the window
+    let (width, height) = root.dim_in_pixel();
+    let (cx, cy) = cursor;
+    let x = if cx + offset + box_w > width as i32 {
+        cx - offset - box_w
+    } else {
+        cx + offset
+    };
+    let y = (cy + offset).min(height as i32 - box_h).max(0);
+    let _ = root.draw(&Rectangle::new(
+        [(x, y), (x + box_w, y + box_h)],
+        theme.background.mix(0.9).filled(),
+    ));
+    let _ = root.draw(&Rectangle::new(
+        [(x, y), (x + box_w, y + box_h)],
+        theme.grid.stroke_width(scale.stroke(0.5)),
+    ));
+    for (i, line) in lines.iter().enumerate() {
+        let _ = root.draw_text(
+            line,
+            &font,
+            (x + padding, y + padding + i as i32 * line_height),
+        );
+    }
+}
+
+/// Near-square grid of per-core percent charts
+fn draw_core_grid(
+    root: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    cores: &[Series<f32>],
+    options: ChartOptions,
+    scale: Scale,
+) -> Vec<PlotArea> {
+    let theme = options.theme;
+    if cores.is_empty() {
+        return Vec::new();
+    }
+    let cols = (cores.len() as f64).sqrt().ceil() as usize;
+    let rows = cores.len().div_ceil(cols);
+    let cells = root.split_evenly((rows, cols));
+    cells
+        .iter()
+        .zip(cores)
+        .enumerate()
+        .map(|(i, (cell, data))| {
+            draw_percent_chart(
+                cell,
+                &tf("Core {}", &[&(i + 1).to_string()]),
+                data,
+                &theme.cpu,
+                0.0..100.0,
+                options,
+                scale,
+            )
+        })
+        .collect()
+}
+
+/// Pause bars while frozen, a play triangle while live, in the top-right corner.
+fn draw_pause_indicator(
+    root: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    paused: bool,
+    theme: &ChartTheme,
+    scale: Scale,
+) {
+    let (width, _) = root.dim_in_pixel();
+    let size = scale.px(PAUSE_ICON_SIZE);
+    let inset = scale.px(4.0);
+    let x = width as i32 - size - inset;
+    let y = inset;
+    let style = theme.text.filled();
+    if paused {
+        let bar = size / 3;
+        let _ = root.draw(&Rectangle::new([(x, y), (x + bar, y + size)], style));
+        let _ = root.draw(&Rectangle::new(
+            [(x + size - bar, y), (x + size, y + size)],
+            style,
+        ));
+    } else {
+        let _ = root.draw(&Polygon::new(
+            vec![(x, y), (x + size, y + size / 2), (x, y + size)],
+            style,
+        ));
     }
 }
 
 fn draw_percent_chart(
     area: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
     name: &str,
-    data: &VecDeque<f32>,
+    data: &Series<f32>,
     color: &RGBColor,
-) {
+    y_range: Range<f32>,
+    options: ChartOptions,
+    scale: Scale,
+) -> PlotArea {
+    let theme = options.theme;
+    let smoothing = options.smoothing;
     let current = data
-        .back()
+        .len()
+        .checked_sub(1)
+        .and_then(|newest| data.reading(newest))
         .map(|v| format!("{:.1}%", v))
         .unwrap_or("--".into());
-    let caption = format!("{}  {}", name, current);
+    let stats = stats_caption(options, data.readings().map(|&v| v as f64), |v| {
+        format!("{:.0}", v)
+    });
+    let caption = format!("{}  {}{}", name, current, stats);
+    let baseline = y_range.start;
+    let zoomed = y_range != (0.0..100.0);
+
+    // Mark zoomed axes so screenshots aren't read as full scale
+    if zoomed {
+        let (w, h) = area.dim_in_pixel();
+        let style = (chart_font(), scale.px(10.0))
+            .into_font()
+            .color(&theme.text.mix(0.4))
+            .pos(Pos::new(HPos::Right, VPos::Bottom));
+        let corner = (w as i32 - scale.px(4.0), h as i32 - scale.px(2.0));
+        let _ = area.draw_text("zoomed · scale 0–100", &style, corner);
+    }
 
     let mut chart = ChartBuilder::on(area)
-        .caption(&caption, ("sans-serif", 36).into_font().color(&TEXT_COLOR))
-        .margin(6)
+        .caption(&caption, caption_style(theme, scale))
+        .margin(scale.px(3.0))
         .x_label_area_size(0)
-        .y_label_area_size(34)
-        .build_cartesian_2d(0..data.len().max(1), 0.0f32..100.0)
+        .y_label_area_size(scale.px(17.0))
+        .build_cartesian_2d(0..data.len().max(1), y_range)
         .unwrap();
 
     let _ = chart
         .configure_mesh()
-        .light_line_style(GRID_COLOR.mix(0.3))
-        .bold_line_style(GRID_COLOR.mix(0.6))
+        .light_line_style(theme.grid.mix(0.3))
+        .bold_line_style(theme.grid.mix(0.6))
         .y_labels(3)
         .y_label_formatter(&|v| format!("{:.0}%", v))
-        .label_style(("sans-serif", 24).into_font().color(&TEXT_COLOR.mix(0.7)))
+        .label_style(axis_label_style(theme, scale))
         .draw();
 
-    let series: Vec<(usize, f32)> = data.iter().enumerate().map(|(i, &v)| (i, v)).collect();
-
-    if !series.is_empty() {
+    for series in runs(data) {
+        let smoothed: Vec<(usize, f32)> = smooth_run(&series, smoothing)
+            .into_iter()
+            .map(|(i, v)| (i, v as f32))
+            .collect();
         let _ = chart.draw_series(AreaSeries::new(
-            series.iter().cloned(),
-            0.0,
+            smoothed.iter().cloned(),
+            baseline,
             color.mix(0.2).filled(),
         ));
+        // Keep spikes visible under the averaged line
+        if smoothing > 1 {
+            let _ = chart.draw_series(LineSeries::new(
+                series.iter().cloned(),
+                color.mix(RAW_LINE_ALPHA).stroke_width(scale.stroke(1.0)),
+            ));
+        }
         let _ = chart.draw_series(LineSeries::new(
-            series.iter().cloned(),
-            color.stroke_width(2),
+            smoothed.iter().cloned(),
+            color.stroke_width(scale.stroke(1.0)),
         ));
     }
+    PlotArea::new(&chart, data.len().max(1), data.len())
+}
+
+/// Surface size for a window of `size`; None while it has no area, as
+/// minimized or mid-resize windows can report
+fn surface_size(size: PhysicalSize<u32>) -> Option<(NonZeroU32, NonZeroU32)> {
+    Some((NonZeroU32::new(size.width)?, NonZeroU32::new(size.height)?))
+}
+
+/// "12 KB/s" / "1.5 MB/s" for a KB/s value
+fn format_kbps(v: f64) -> String {
+    if v >= 1024.0 {
+        format!("{:.1} MB/s", v / 1024.0)
+    } else {
+        format!("{:.0} KB/s", v)
+    }
+}
+
+/// "23 ms", or "timeout" for a probe that timed out (None)
+fn format_latency(v: Option<f64>) -> String {
+    match v {
+        Some(v) => format!("{:.0} ms", v),
+        None => "timeout".to_string(),
+    }
+}
+
+fn format_pages(v: f64) -> String {
+    format!("{:.0}/s", v)
+}
+
+fn format_pages_axis(v: f64) -> String {
+    format!("{:.0}", v)
+}
+
+/// Trailing mean over up to `window` samples; the first points average
+/// whatever history exists so far
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            sum += v;
+            if i >= window {
+                sum -= values[i - window];
+            }
+            sum / (i + 1).min(window) as f64
+        })
+        .collect()
+}
+
+/// Points of each stretch between sampling gaps, x being the sample index,
+/// so a line stops at a gap instead of bridging it
+fn runs<T: Copy>(data: &Series<T>) -> Vec<Vec<(usize, T)>> {
+    data.segments()
+        .into_iter()
+        .map(|segment| segment.clone().zip(data.range(segment).copied()).collect())
+        .collect()
+}
+
+/// `moving_average` within one run, so a gap doesn't carry the average
+/// across it
+fn smooth_run<T: Copy + Into<f64>>(run: &[(usize, T)], window: usize) -> Vec<(usize, f64)> {
+    let values: Vec<f64> = run.iter().map(|&(_, v)| v.into()).collect();
+    run.iter()
+        .map(|&(i, _)| i)
+        .zip(moving_average(&values, window))
+        .collect()
+}
+
+fn format_kbps_axis(v: f64) -> String {
+    if v >= 1024.0 {
+        format!("{:.0}M", v / 1024.0)
+    } else {
+        format!("{:.0}K", v)
+    }
+}
+
+/// Area chart of a non-negative rate, scaled to its own maximum.
+/// App, wired and compressed memory stacked in that order, with the
+/// remaining (free) memory up to the total shaded above them.
+fn draw_memory_stacked(
+    area: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    history: &HistoryBuffer,
+    options: ChartOptions,
+    scale: Scale,
+) -> PlotArea {
+    let theme = options.theme;
+    let len = history.mem_app.len();
+    let total = history.mem_total.max(1.0);
+    // Running totals, bottom layer first
+    let mut tops: Vec<Vec<f64>> = Vec::new();
+    let mut sum = vec![0.0; len];
+    for data in [
+        &history.mem_app,
+        &history.mem_wired,
+        &history.mem_compressed,
+    ] {
+        for (acc, v) in sum.iter_mut().zip(data) {
+            *acc += v;
+        }
+        tops.push(sum.clone());
+    }
+
+    let used = sum.last().map(|v| format_bytes(*v as u64));
+    let stats = stats_caption(options, sum.iter().copied(), |v| format_bytes(v as u64));
+    let caption = format!(
+        "{}  {} / {}{}",
+        t("MEM"),
+        used.unwrap_or("--".into()),
+        format_bytes(total as u64),
+        stats
+    );
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(&caption, caption_style(theme, scale))
+        .margin(scale.px(3.0))
+        .x_label_area_size(0)
+        .y_label_area_size(scale.px(42.0))
+        .build_cartesian_2d(0..len.max(1), 0.0..total)
+        .unwrap();
+
+    let _ = chart
+        .configure_mesh()
+        .light_line_style(theme.grid.mix(0.3))
+        .bold_line_style(theme.grid.mix(0.6))
+        .y_labels(3)
+        .y_label_formatter(&|v| format_bytes(*v as u64))
+        .label_style(axis_label_style(theme, scale))
+        .draw();
+
+    if len > 0 {
+        let zero = vec![0.0; len];
+        let full = vec![total; len];
+        let bands = [
+            (&zero, &tops[0], theme.mem.mix(0.5)),
+            (&tops[0], &tops[1], theme.mem_wired.mix(0.5)),
+            (&tops[1], &tops[2], theme.mem_compressed.mix(0.5)),
+            (&tops[2], &full, theme.text.mix(0.06)),
+        ];
+        // The three series share their gaps; one set of bands per run
+        for segment in history.mem_app.segments() {
+            for (lower, upper, fill) in bands {
+                let mut points: Vec<(usize, f64)> =
+                    segment.clone().map(|i| (i, upper[i])).collect();
+                points.extend(segment.clone().rev().map(|i| (i, lower[i])));
+                let _ = chart.draw_series(std::iter::once(Polygon::new(points, fill.filled())));
+            }
+            let used_line = segment.map(|i| (i, tops[2][i]));
+            let _ = chart.draw_series(LineSeries::new(
+                used_line,
+                theme.mem.stroke_width(scale.stroke(1.0)),
+            ));
+        }
+    }
+    PlotArea::new(&chart, len.max(1), len)
 }
 
-fn draw_net_chart(
+fn draw_rate_chart(
     area: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
     name: &str,
-    data: &VecDeque<f64>,
+    data: &Series<f64>,
     color: &RGBColor,
-) {
+    units: RateUnits,
+    options: ChartOptions,
+    scale: Scale,
+) -> PlotArea {
+    let theme = options.theme;
+    let smoothing = options.smoothing;
     let max_val = data.iter().cloned().fold(10.0_f64, |a, b| a.max(b)) * 1.2;
 
     let current = data
         .back()
-        .map(|v| {
-            if *v >= 1024.0 {
-                format!("{:.1} MB/s", v / 1024.0)
-            } else {
-                format!("{:.0} KB/s", v)
-            }
-        })
+        .map(|v| (units.value)(*v))
         .unwrap_or("--".into());
-    let caption = format!("{}  {}", name, current);
+    let stats = stats_caption(options, data.iter().copied(), units.axis);
+    let caption = format!("{}  {}{}", name, current, stats);
 
     let mut chart = ChartBuilder::on(area)
-        .caption(&caption, ("sans-serif", 36).into_font().color(&TEXT_COLOR))
-        .margin(6)
+        .caption(&caption, caption_style(theme, scale))
+        .margin(scale.px(3.0))
         .x_label_area_size(0)
-        .y_label_area_size(42)
+        .y_label_area_size(scale.px(21.0))
         .build_cartesian_2d(0..data.len().max(1), 0.0..max_val)
         .unwrap();
 
     let _ = chart
         .configure_mesh()
-        .light_line_style(GRID_COLOR.mix(0.3))
-        .bold_line_style(GRID_COLOR.mix(0.6))
+        .light_line_style(theme.grid.mix(0.3))
+        .bold_line_style(theme.grid.mix(0.6))
         .y_labels(3)
-        .y_label_formatter(&|v| {
-            if *v >= 1024.0 {
-                format!("{:.0}M", v / 1024.0)
-            } else {
-                format!("{:.0}K", v)
-            }
-        })
-        .label_style(("sans-serif", 24).into_font().color(&TEXT_COLOR.mix(0.7)))
+        .y_label_formatter(&|v| (units.axis)(*v))
+        .label_style(axis_label_style(theme, scale))
         .draw();
 
-    let series: Vec<(usize, f64)> = data.iter().enumerate().map(|(i, &v)| (i, v)).collect();
-
-    if !series.is_empty() {
+    for series in runs(data) {
+        let smoothed = smooth_run(&series, smoothing);
         let _ = chart.draw_series(AreaSeries::new(
-            series.iter().cloned(),
+            smoothed.iter().cloned(),
             0.0,
             color.mix(0.2).filled(),
         ));
+        if smoothing > 1 {
+            let _ = chart.draw_series(LineSeries::new(
+                series.iter().cloned(),
+                color.mix(RAW_LINE_ALPHA).stroke_width(scale.stroke(1.0)),
+            ));
+        }
         let _ = chart.draw_series(LineSeries::new(
-            series.iter().cloned(),
-            color.stroke_width(2),
+            smoothed.iter().cloned(),
+            color.stroke_width(scale.stroke(1.0)),
         ));
     }
+    PlotArea::new(&chart, data.len().max(1), data.len())
 }
 
-fn draw_temp_combined(
+/// Latency as a line broken wherever a probe timed out, with the warning
+/// level marked and the stretches above it drawn in red
+fn draw_latency_chart(
+    area: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    data: &Series<f64>,
+    options: ChartOptions,
+    scale: Scale,
+) -> PlotArea {
+    let theme = options.theme;
+    let warn = options.latency_warn_ms;
+    let max_val = data.readings().copied().fold(warn.max(10.0), f64::max) * 1.2;
+
+    let current = match data.len() {
+        0 => "--".to_string(),
+        len => format_latency(data.reading(len - 1).copied()),
+    };
+    let stats = stats_caption(options, data.readings().copied(), |v| format!("{:.0}", v));
+    let caption = format!("{}  {}{}", t("Latency"), current, stats);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(&caption, caption_style(theme, scale))
+        .margin(scale.px(3.0))
+        .x_label_area_size(0)
+        .y_label_area_size(scale.px(21.0))
+        .build_cartesian_2d(0..data.len().max(1), 0.0..max_val)
+        .unwrap();
+
+    let _ = chart
+        .configure_mesh()
+        .light_line_style(theme.grid.mix(0.3))
+        .bold_line_style(theme.grid.mix(0.6))
+        .y_labels(3)
+        .y_label_formatter(&|v| format!("{:.0}", v))
+        .label_style(axis_label_style(theme, scale))
+        .draw();
+
+    if warn > 0.0 {
+        let _ = chart.draw_series(LineSeries::new(
+            [(0, warn), (data.len().max(1), warn)],
+            theme.latency_warn.mix(0.4).stroke_width(scale.stroke(1.0)),
+        ));
+    }
+    // One line per run of answered probes; a lone sample gets a dot. A
+    // timeout ends a run the way a sampling gap does.
+    for run in runs(data) {
+        for pair in run.windows(2) {
+            let color = if pair[0].1.max(pair[1].1) > warn && warn > 0.0 {
+                theme.latency_warn
+            } else {
+                theme.latency
+            };
+            let _ = chart.draw_series(LineSeries::new(
+                pair.iter().copied(),
+                color.stroke_width(scale.stroke(1.0)),
+            ));
+        }
+        if let [(x, y)] = run[..] {
+            let _ = chart.draw_series(std::iter::once(Circle::new(
+                (x, y),
+                scale.px(1.5),
+                theme.latency.filled(),
+            )));
+        }
+    }
+    PlotArea::new(&chart, data.len().max(1), data.len())
+}
+
+/// Download and upload in one pane: upload mirrored below zero, or both
+/// overlaid from zero. The y-range comes from the larger of the two.
+fn draw_net_combined(
     area: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
     history: &HistoryBuffer,
-) {
-    let empty = VecDeque::new();
-    let all_data: Vec<(&str, &VecDeque<f32>, &RGBColor)> = FIXED_TEMPS
+    mirrored: bool,
+    options: ChartOptions,
+    scale: Scale,
+) -> PlotArea {
+    let theme = options.theme;
+    let smoothing = options.smoothing;
+    let len = history.net_down.len().max(history.net_up.len());
+    let peak = history
+        .net_down
         .iter()
-        .enumerate()
-        .map(|(i, &name)| {
+        .chain(&history.net_up)
+        .cloned()
+        .fold(10.0_f64, |a, b| a.max(b))
+        * 1.2;
+    let y_range = if mirrored { -peak..peak } else { 0.0..peak };
+
+    let current =
+        |data: &VecDeque<f64>| data.back().map(|v| format_kbps(*v)).unwrap_or("--".into());
+    let caption = format!(
+        "{}  D {}  U {}",
+        t("NET"),
+        current(&history.net_down),
+        current(&history.net_up)
+    );
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(&caption, caption_style(theme, scale))
+        .margin(scale.px(3.0))
+        .x_label_area_size(0)
+        .y_label_area_size(scale.px(21.0))
+        .build_cartesian_2d(0..len.max(1), y_range)
+        .unwrap();
+
+    let _ = chart
+        .configure_mesh()
+        .light_line_style(theme.grid.mix(0.3))
+        .bold_line_style(theme.grid.mix(0.6))
+        .y_labels(3)
+        .y_label_formatter(&|v| format_kbps_axis(v.abs()))
+        .label_style(axis_label_style(theme, scale))
+        .draw();
+
+    let upload_sign = if mirrored { -1.0 } else { 1.0 };
+    for (name, data, color, sign) in [
+        (t("NET Down"), &history.net_down, &theme.net_down, 1.0),
+        (t("NET Up"), &history.net_up, &theme.net_up, upload_sign),
+    ] {
+        if data.is_empty() {
+            continue;
+        }
+        for run in runs(data) {
+            let series: Vec<(usize, f64)> = run.iter().map(|&(i, v)| (i, v * sign)).collect();
+            let smoothed = smooth_run(&series, smoothing);
+            let _ = chart.draw_series(AreaSeries::new(
+                smoothed.iter().cloned(),
+                0.0,
+                color.mix(0.2).filled(),
+            ));
+            if smoothing > 1 {
+                let _ = chart.draw_series(LineSeries::new(
+                    series.iter().cloned(),
+                    color.mix(RAW_LINE_ALPHA).stroke_width(scale.stroke(1.0)),
+                ));
+            }
+            let _ = chart.draw_series(LineSeries::new(
+                smoothed.iter().cloned(),
+                color.stroke_width(scale.stroke(1.0)),
+            ));
+        }
+        // One legend entry per series, however many runs it was drawn in
+        let stats = stats_caption(options, data.iter().copied(), format_kbps_axis);
+        let _ = chart
+            .draw_series(LineSeries::new(
+                std::iter::empty::<(usize, f64)>(),
+                color.stroke_width(scale.stroke(1.0)),
+            ))
+            .unwrap()
+            .label(format!("{}{}", name, stats))
+            .legend(move |(x, y)| {
+                PathElement::new(
+                    vec![(x, y), (x + scale.px(15.0), y)],
+                    color.stroke_width(scale.stroke(1.5)),
+                )
+            });
+    }
+
+    let _ = chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(theme.background.mix(0.8))
+        .border_style(theme.grid)
+        .label_font(
+            (chart_font(), scale.px(12.0))
+                .into_font()
+                .color(&theme.text),
+        )
+        .draw();
+    PlotArea::new(&chart, len.max(1), len)
+}
+
+fn draw_temp_combined(
+    area: &DrawingArea<BitMapBackend, plotters::coord::Shift>,
+    history: &HistoryBuffer,
+    y_range: Range<f32>,
+    options: ChartOptions,
+    pinned: &[String],
+    scale: Scale,
+) -> PlotArea {
+    let theme = options.theme;
+    let empty = Series::default();
+    let fixed = FIXED_TEMPS.iter().copied().zip(&theme.temps);
+    let pinned = pinned.iter().map(String::as_str).zip(&theme.pinned_temps);
+    let all_data: Vec<(&str, &Series<f32>, &RGBColor)> = fixed
+        .chain(pinned)
+        .map(|(name, color)| {
             let data = history.temps.get(name).unwrap_or(&empty);
-            (name, data, &TEMP_COLORS[i])
+            (name, data, color)
         })
         .collect();
 
@@ -302,44 +1859,58 @@ fn draw_temp_combined(
     }
 
     let mut chart = ChartBuilder::on(area)
-        .caption("TEMP", ("sans-serif", 36).into_font().color(&TEXT_COLOR))
-        .margin(6)
+        .caption(t("TEMP"), caption_style(theme, scale))
+        .margin(scale.px(3.0))
         .x_label_area_size(0)
-        .y_label_area_size(34)
-        .build_cartesian_2d(0..max_len, 0.0f32..100.0)
+        .y_label_area_size(scale.px(17.0))
+        .build_cartesian_2d(0..max_len, y_range.clone())
         .unwrap();
 
     let _ = chart
         .configure_mesh()
-        .light_line_style(GRID_COLOR.mix(0.3))
-        .bold_line_style(GRID_COLOR.mix(0.6))
+        .light_line_style(theme.grid.mix(0.3))
+        .bold_line_style(theme.grid.mix(0.6))
         .y_labels(3)
         .y_label_formatter(&|v| format!("{:.0}", v))
-        .label_style(("sans-serif", 24).into_font().color(&TEXT_COLOR.mix(0.7)))
+        .label_style(axis_label_style(theme, scale))
         .draw();
 
     for (name, data, color) in &all_data {
-        let series: Vec<(usize, f32)> = data.iter().enumerate().map(|(i, &v)| (i, v)).collect();
-        if !series.is_empty() {
+        if !data.is_empty() {
             let val = data
-                .back()
+                .len()
+                .checked_sub(1)
+                .and_then(|newest| data.reading(newest))
                 .map(|v| format!("{:.0}", v))
                 .unwrap_or("--".into());
-            let label = format!("{} {}", name, val);
-            let _ = chart.draw_series(AreaSeries::new(
-                series.iter().cloned(),
-                0.0,
-                color.mix(0.2).filled(),
-            ));
+            let stats = stats_caption(options, data.readings().map(|&v| v as f64), |v| {
+                format!("{:.0}", v)
+            });
+            let label = format!("{} {}{}", name, val, stats);
+            for series in runs(data) {
+                let _ = chart.draw_series(AreaSeries::new(
+                    series.iter().cloned(),
+                    y_range.start,
+                    color.mix(0.2).filled(),
+                ));
+                let _ = chart.draw_series(LineSeries::new(
+                    series.iter().cloned(),
+                    (*color).stroke_width(scale.stroke(1.0)),
+                ));
+            }
+            // One legend entry per sensor, however many runs it was drawn in
             let _ = chart
                 .draw_series(LineSeries::new(
-                    series.iter().cloned(),
-                    (*color).stroke_width(2),
+                    std::iter::empty::<(usize, f32)>(),
+                    (*color).stroke_width(scale.stroke(1.0)),
                 ))
                 .unwrap()
                 .label(label)
                 .legend(move |(x, y)| {
-                    PathElement::new(vec![(x, y), (x + 30, y)], (*color).stroke_width(3))
+                    PathElement::new(
+                        vec![(x, y), (x + scale.px(15.0), y)],
+                        (*color).stroke_width(scale.stroke(1.5)),
+                    )
                 });
         }
     }
@@ -347,8 +1918,87 @@ fn draw_temp_combined(
     let _ = chart
         .configure_series_labels()
         .position(SeriesLabelPosition::UpperLeft)
-        .background_style(BG_COLOR.mix(0.8))
-        .border_style(GRID_COLOR)
-        .label_font(("sans-serif", 24).into_font().color(&TEXT_COLOR))
+        .background_style(theme.background.mix(0.8))
+        .border_style(theme.grid)
+        .label_font(
+            (chart_font(), scale.px(12.0))
+                .into_font()
+                .color(&theme.text),
+        )
         .draw();
+    let samples = all_data
+        .iter()
+        .map(|(_, data, _)| data.len())
+        .max()
+        .unwrap_or(0);
+    PlotArea::new(&chart, max_len, samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::mock::sample;
+
+    fn follow(current: Option<AutoRange>, data: &[f32]) -> Option<(f32, f32)> {
+        AutoRange::follow(current, data.iter().copied(), TEMP_STEP, TEMP_LIMITS)
+            .map(|range| (range.lo, range.hi))
+    }
+
+    #[test]
+    fn temp_axis_fits_the_readings() {
+        // Padded by at least half a step, then rounded out to whole steps
+        assert_eq!(follow(None, &[35.0, 65.0]), Some((30.0, 70.0)));
+        // Sensors past 100C are not clipped
+        assert_eq!(follow(None, &[95.0, 110.0]), Some((90.0, 120.0)));
+        // Fahrenheit readings fit the same way
+        assert_eq!(follow(None, &[140.0, 190.0]), Some((130.0, 200.0)));
+        assert_eq!(follow(None, &[]), None);
+    }
+
+    #[test]
+    fn temp_axis_holds_until_the_readings_leave_it() {
+        let current = Some(AutoRange { lo: 20.0, hi: 70.0 });
+        // Inside and not much wider than needed: kept
+        assert_eq!(follow(current, &[40.0, 50.0]), Some((20.0, 70.0)));
+        // A reading above it widens it
+        assert_eq!(follow(current, &[40.0, 72.0]), Some((30.0, 80.0)));
+        // Far wider than needed: narrowed
+        let wide = Some(AutoRange { lo: 0.0, hi: 100.0 });
+        assert_eq!(follow(wide, &[40.0, 50.0]), Some((30.0, 60.0)));
+        // No readings: unchanged
+        assert_eq!(follow(current, &[]), Some((20.0, 70.0)));
+    }
+
+    #[test]
+    fn temp_axis_starts_full_scale_and_can_be_locked() {
+        let mut history = HistoryBuffer::new(10);
+        let mut zoom = AxisZoom::default();
+        assert_eq!(zoom.ranges(&history, &[]).temp, 0.0..100.0);
+        history.push(&sample(10.0, 50.0, 45.0));
+        assert_eq!(zoom.ranges(&history, &[]).temp, 40.0..50.0);
+        zoom.temp_fixed = true;
+        assert_eq!(zoom.ranges(&history, &[]).temp, 0.0..100.0);
+    }
+
+    #[test]
+    fn a_window_without_area_is_not_drawn() {
+        assert!(surface_size(PhysicalSize::new(0, 0)).is_none());
+        assert!(surface_size(PhysicalSize::new(800, 0)).is_none());
+        assert!(surface_size(PhysicalSize::new(0, 600)).is_none());
+        let (width, height) = surface_size(PhysicalSize::new(1, 600)).unwrap();
+        assert_eq!((width.get(), height.get()), (1, 600));
+    }
+
+    #[test]
+    fn alert_sparklines_are_pngs() {
+        let path = std::env::temp_dir().join(format!("msm-sparkline-{}.png", std::process::id()));
+        render_alert_sparkline("cpu", &[20.0, 35.0, 80.0, 97.0], &path).unwrap();
+        let png = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        // A flat line still gets a range to draw in
+        render_alert_sparkline("temperature", &[70.0, 70.0], &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(render_alert_sparkline("memory", &[50.0], &path).is_err());
+    }
 }
@@ -0,0 +1,268 @@
+use objc2::msg_send;
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::{ns_string, NSPoint, NSRect, NSSize, NSString};
+use std::cmp::Ordering;
+use std::fs;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+
+/// Largest side an imported frame keeps, in pixels; the status item is 22 pt
+pub const MAX_FRAME_PX: usize = 128;
+/// Extensions the import dialogs accept
+pub const IMAGE_EXTENSIONS: [&str; 8] =
+    ["png", "jpg", "jpeg", "gif", "bmp", "tiff", "webp", "heic"];
+/// NSBitmapImageFileTypePNG
+const FILE_TYPE_PNG: usize = 4;
+/// NSImageInterpolationHigh
+const INTERPOLATION_HIGH: usize = 3;
+
+/// Decode every file and redraw it as a PNG on a transparent square canvas,
+/// the same size for the whole set: the largest frame's longer side, capped
+/// at `MAX_FRAME_PX`. Err lists the files that don't decode as images.
+/// Call on the main thread.
+pub fn normalize(files: &[PathBuf]) -> Result<Vec<Vec<u8>>, Vec<PathBuf>> {
+    let mut reps = Vec::new();
+    let mut unreadable = Vec::new();
+    for file in files {
+        match decode(file) {
+            Some(rep) => reps.push((file, rep)),
+            None => unreadable.push(file.clone()),
+        }
+    }
+    if !unreadable.is_empty() {
+        return Err(unreadable);
+    }
+    let side = reps
+        .iter()
+        .map(|(_, rep)| pixel_size(rep))
+        .map(|(width, height)| width.max(height))
+        .max()
+        .unwrap_or(0)
+        .min(MAX_FRAME_PX);
+
+    let mut frames = Vec::new();
+    for (file, rep) in &reps {
+        match redraw(rep, side) {
+            Some(png) => frames.push(png),
+            None => unreadable.push(file.to_path_buf()),
+        }
+    }
+    if unreadable.is_empty() {
+        Ok(frames)
+    } else {
+        Err(unreadable)
+    }
+}
+
+/// Image files directly inside `dir`, skipping hidden files, in frame order
+pub fn images_in(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("could not read {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_image(path))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    sort_frames(&mut files);
+    files
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Order frames by file name with numbers compared by value, so frame2
+/// comes before frame10
+pub fn sort_frames(files: &mut [PathBuf]) {
+    files.sort_by(|a, b| {
+        natural_cmp(
+            &a.file_name().unwrap_or_default().to_string_lossy(),
+            &b.file_name().unwrap_or_default().to_string_lossy(),
+        )
+    });
+}
+
+/// Compare digit runs by value ("2" < "10", "02" == "2" but sorted before
+/// it) and everything else by character, ignoring case
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let (Some(&x), Some(&y)) = (a_chars.peek(), b_chars.peek()) else {
+            return a_chars.peek().is_some().cmp(&b_chars.peek().is_some());
+        };
+        let order = if x.is_ascii_digit() && y.is_ascii_digit() {
+            let (x_run, y_run) = (digit_run(&mut a_chars), digit_run(&mut b_chars));
+            let (x_value, y_value) = (x_run.trim_start_matches('0'), y_run.trim_start_matches('0'));
+            x_value
+                .len()
+                .cmp(&y_value.len())
+                .then_with(|| x_value.cmp(y_value))
+                .then_with(|| y_run.len().cmp(&x_run.len()))
+        } else {
+            a_chars.next();
+            b_chars.next();
+            x.to_lowercase().cmp(y.to_lowercase())
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+fn digit_run(chars: &mut Peekable<Chars>) -> String {
+    let mut run = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        run.push(c);
+    }
+    run
+}
+
+/// The first image in `path` (HEIC, GIF, ... via ImageIO); None for
+/// anything that isn't a bitmap with a size
+fn decode(path: &Path) -> Option<Retained<AnyObject>> {
+    let path = NSString::from_str(&path.to_string_lossy());
+    let rep: Option<Retained<AnyObject>> = unsafe {
+        let class = AnyClass::get(c"NSImageRep")?;
+        msg_send![class, imageRepWithContentsOfFile: &*path]
+    };
+    rep.filter(|rep| {
+        let (width, height) = pixel_size(rep);
+        width > 0 && height > 0
+    })
+}
+
+fn pixel_size(rep: &AnyObject) -> (usize, usize) {
+    let width: isize = unsafe { msg_send![rep, pixelsWide] };
+    let height: isize = unsafe { msg_send![rep, pixelsHigh] };
+    (width.max(0) as usize, height.max(0) as usize)
+}
+
+/// `rep` scaled to fit a `side`×`side` canvas and centered on it, as PNG
+fn redraw(rep: &AnyObject, side: usize) -> Option<Vec<u8>> {
+    let (width, height) = pixel_size(rep);
+    let scale = side as f64 / width.max(height) as f64;
+    let (draw_width, draw_height) = (width as f64 * scale, height as f64 * scale);
+    let rect = NSRect::new(
+        NSPoint::new(
+            (side as f64 - draw_width) / 2.0,
+            (side as f64 - draw_height) / 2.0,
+        ),
+        NSSize::new(draw_width, draw_height),
+    );
+
+    unsafe {
+        let rep_class = AnyClass::get(c"NSBitmapImageRep")?;
+        let alloc: Allocated<AnyObject> = msg_send![rep_class, alloc];
+        let canvas: Option<Retained<AnyObject>> = msg_send![
+            alloc,
+            initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>(),
+            pixelsWide: side as isize,
+            pixelsHigh: side as isize,
+            bitsPerSample: 8_isize,
+            samplesPerPixel: 4_isize,
+            hasAlpha: true,
+            isPlanar: false,
+            colorSpaceName: ns_string!("NSDeviceRGBColorSpace"),
+            bytesPerRow: (side * 4) as isize,
+            bitsPerPixel: 32_isize
+        ];
+        let canvas = canvas?;
+        let data: *mut u8 = msg_send![&canvas, bitmapData];
+        if data.is_null() {
+            return None;
+        }
+        std::ptr::write_bytes(data, 0, side * side * 4);
+
+        let context_class = AnyClass::get(c"NSGraphicsContext")?;
+        let context: Option<Retained<AnyObject>> =
+            msg_send![context_class, graphicsContextWithBitmapImageRep: &*canvas];
+        let context = context?;
+        let _: () = msg_send![context_class, saveGraphicsState];
+        let _: () = msg_send![context_class, setCurrentContext: &*context];
+        let _: () = msg_send![&context, setImageInterpolation: INTERPOLATION_HIGH];
+        let drawn: bool = msg_send![rep, drawInRect: rect];
+        let _: () = msg_send![&context, flushGraphics];
+        let _: () = msg_send![context_class, restoreGraphicsState];
+        if !drawn {
+            return None;
+        }
+
+        let properties: Retained<AnyObject> =
+            msg_send![AnyClass::get(c"NSDictionary")?, dictionary];
+        let png: Option<Retained<AnyObject>> = msg_send![
+            &canvas,
+            representationUsingType: FILE_TYPE_PNG,
+            properties: &*properties
+        ];
+        let png = png?;
+        let len: usize = msg_send![&png, length];
+        let bytes: *const u8 = msg_send![&png, bytes];
+        if bytes.is_null() {
+            return None;
+        }
+        Some(std::slice::from_raw_parts(bytes, len).to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(names: &[&str]) -> Vec<String> {
+        let mut files: Vec<PathBuf> = names.iter().map(PathBuf::from).collect();
+        sort_frames(&mut files);
+        files
+            .iter()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(natural_cmp("disk2", "disk10"), Ordering::Less);
+        assert_eq!(natural_cmp("frame10", "frame9"), Ordering::Greater);
+        assert_eq!(
+            sorted(&["frame10.png", "frame2.png", "frame1.png"]),
+            ["frame1.png", "frame2.png", "frame10.png"]
+        );
+    }
+
+    #[test]
+    fn case_is_ignored() {
+        assert_eq!(natural_cmp("Frame1", "frame1"), Ordering::Equal);
+        assert_eq!(natural_cmp("B1", "a2"), Ordering::Greater);
+        assert_eq!(
+            sorted(&["b.png", "A.png", "c.png"]),
+            ["A.png", "b.png", "c.png"]
+        );
+    }
+
+    #[test]
+    fn leading_zeros_sort_before_the_same_value() {
+        assert_eq!(natural_cmp("frame02", "frame2"), Ordering::Less);
+        assert_eq!(natural_cmp("frame002", "frame10"), Ordering::Less);
+        assert_eq!(natural_cmp("frame", "frame0"), Ordering::Less);
+    }
+
+    #[test]
+    fn only_the_file_name_counts() {
+        assert_eq!(
+            sorted(&["z/frame2.png", "a/frame10.png"]),
+            ["z/frame2.png", "a/frame10.png"]
+        );
+    }
+}
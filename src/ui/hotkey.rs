@@ -0,0 +1,245 @@
+use crate::alert::notify;
+use crate::config::Hotkey;
+use crate::issues;
+use crate::strings::t;
+use crate::ui::action::TrayAction;
+use crate::ui::chart_window::ChartMode;
+use crate::ui::tray::post_pending_event;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ffi::c_void;
+use std::sync::Once;
+
+type OSStatus = i32;
+type EventTargetRef = *mut c_void;
+type EventHandlerCallRef = *mut c_void;
+type EventRef = *mut c_void;
+type EventHandlerRef = *mut c_void;
+type EventHotKeyRef = *mut c_void;
+type EventHandlerUPP = unsafe extern "C" fn(EventHandlerCallRef, EventRef, *mut c_void) -> OSStatus;
+
+#[repr(C)]
+struct EventTypeSpec {
+    event_class: u32,
+    event_kind: u32,
+}
+
+#[repr(C)]
+struct EventHotKeyID {
+    signature: u32,
+    id: u32,
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn GetApplicationEventTarget() -> EventTargetRef;
+    fn InstallEventHandler(
+        target: EventTargetRef,
+        handler: EventHandlerUPP,
+        num_types: u32,
+        list: *const EventTypeSpec,
+        user_data: *mut c_void,
+        out_ref: *mut EventHandlerRef,
+    ) -> OSStatus;
+    fn RegisterEventHotKey(
+        key_code: u32,
+        modifiers: u32,
+        id: EventHotKeyID,
+        target: EventTargetRef,
+        options: u32,
+        out_ref: *mut EventHotKeyRef,
+    ) -> OSStatus;
+    fn UnregisterEventHotKey(hotkey: EventHotKeyRef) -> OSStatus;
+}
+
+/// 'keyb'
+const K_EVENT_CLASS_KEYBOARD: u32 = 0x6B65_7962;
+const K_EVENT_HOT_KEY_PRESSED: u32 = 5;
+/// 'msmk', identifies our hotkey in the Carbon event
+const HOTKEY_SIGNATURE: u32 = 0x6D73_6D6B;
+const EVENT_HOT_KEY_EXISTS_ERR: OSStatus = -9878;
+
+/// Carbon modifier masks
+const CMD_KEY: u32 = 1 << 8;
+const SHIFT_KEY: u32 = 1 << 9;
+const OPTION_KEY: u32 = 1 << 11;
+const CONTROL_KEY: u32 = 1 << 12;
+
+/// Virtual key codes of the ANSI letter keys
+const LETTER_KEY_CODES: [(u32, char); 26] = [
+    (0, 'A'),
+    (11, 'B'),
+    (8, 'C'),
+    (2, 'D'),
+    (14, 'E'),
+    (3, 'F'),
+    (5, 'G'),
+    (4, 'H'),
+    (34, 'I'),
+    (38, 'J'),
+    (40, 'K'),
+    (37, 'L'),
+    (46, 'M'),
+    (45, 'N'),
+    (31, 'O'),
+    (35, 'P'),
+    (12, 'Q'),
+    (15, 'R'),
+    (1, 'S'),
+    (17, 'T'),
+    (32, 'U'),
+    (9, 'V'),
+    (13, 'W'),
+    (7, 'X'),
+    (16, 'Y'),
+    (6, 'Z'),
+];
+
+static INSTALL_HANDLER: Once = Once::new();
+
+thread_local! {
+    /// What a press of the shortcut queues, from `chart_hotkey_action`
+    static HOTKEY_ACTION: RefCell<TrayAction> =
+        RefCell::new(TrayAction::ShowCharts(ChartMode::All));
+}
+
+/// Carbon delivers hotkey events on the main thread, like menu actions.
+unsafe extern "C" fn hotkey_pressed(
+    _next: EventHandlerCallRef,
+    _event: EventRef,
+    _user_data: *mut c_void,
+) -> OSStatus {
+    post_pending_event(HOTKEY_ACTION.with(|action| action.borrow().clone()));
+    0
+}
+
+/// The chart window's global shortcut, re-registered when the config changes.
+pub struct HotkeyRegistration {
+    current: Option<(Hotkey, EventHotKeyRef)>,
+    /// Combos we already told the user about, so a failure notifies only once
+    failures_notified: HashSet<Hotkey>,
+}
+
+impl HotkeyRegistration {
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            failures_notified: HashSet::new(),
+        }
+    }
+
+    /// Point the shortcut at the menu action with this `TrayAction::id`. An
+    /// unknown id leaves it on the chart window.
+    pub fn set_action(&self, id: &str) {
+        let action = TrayAction::parse(id).unwrap_or_else(|| {
+            issues::report("hotkey", format!("unknown shortcut action \"{}\"", id));
+            TrayAction::ShowCharts(ChartMode::All)
+        });
+        HOTKEY_ACTION.with(|current| *current.borrow_mut() = action);
+    }
+
+    /// Register `hotkey` in place of the current one; None just unregisters.
+    pub fn sync(&mut self, hotkey: Option<Hotkey>) {
+        if self.current.map(|(key, _)| key) == hotkey {
+            return;
+        }
+        if let Some((_, hotkey_ref)) = self.current.take() {
+            unsafe {
+                UnregisterEventHotKey(hotkey_ref);
+            }
+        }
+        let Some(hotkey) = hotkey else {
+            return;
+        };
+        match register(hotkey) {
+            Ok(hotkey_ref) => self.current = Some((hotkey, hotkey_ref)),
+            Err(status) => {
+                log::warn!(
+                    "failed to register hotkey {}: OSStatus {}",
+                    label(hotkey),
+                    status
+                );
+                if self.failures_notified.insert(hotkey) {
+                    let reason = if status == EVENT_HOT_KEY_EXISTS_ERR {
+                        "is already used by another app"
+                    } else {
+                        "could not be registered"
+                    };
+                    notify(
+                        t("Shortcut Unavailable"),
+                        &format!("{} {}", label(hotkey), reason),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn register(hotkey: Hotkey) -> Result<EventHotKeyRef, OSStatus> {
+    unsafe {
+        INSTALL_HANDLER.call_once(|| {
+            let spec = EventTypeSpec {
+                event_class: K_EVENT_CLASS_KEYBOARD,
+                event_kind: K_EVENT_HOT_KEY_PRESSED,
+            };
+            InstallEventHandler(
+                GetApplicationEventTarget(),
+                hotkey_pressed,
+                1,
+                &spec,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+        });
+        let mut hotkey_ref: EventHotKeyRef = std::ptr::null_mut();
+        let id = EventHotKeyID {
+            signature: HOTKEY_SIGNATURE,
+            id: 1,
+        };
+        let status = RegisterEventHotKey(
+            hotkey.key_code,
+            hotkey.modifiers,
+            id,
+            GetApplicationEventTarget(),
+            0,
+            &mut hotkey_ref,
+        );
+        if status == 0 {
+            Ok(hotkey_ref)
+        } else {
+            Err(status)
+        }
+    }
+}
+
+/// "⌃⌥M"
+pub fn label(hotkey: Hotkey) -> String {
+    let mut text = String::new();
+    for (mask, symbol) in [
+        (CONTROL_KEY, '⌃'),
+        (OPTION_KEY, '⌥'),
+        (SHIFT_KEY, '⇧'),
+        (CMD_KEY, '⌘'),
+    ] {
+        if hotkey.modifiers & mask != 0 {
+            text.push(symbol);
+        }
+    }
+    match LETTER_KEY_CODES
+        .iter()
+        .find(|(code, _)| *code == hotkey.key_code)
+    {
+        Some((_, letter)) => text.push(*letter),
+        None => text.push_str(&format!("key {}", hotkey.key_code)),
+    }
+    text
+}
+
+/// Shortcuts offered in the Shortcut submenu
+pub fn presets() -> [Hotkey; 3] {
+    [
+        Hotkey::new(46, CONTROL_KEY | OPTION_KEY),
+        Hotkey::new(8, CONTROL_KEY | OPTION_KEY),
+        Hotkey::new(46, CONTROL_KEY | SHIFT_KEY),
+    ]
+}
@@ -1,2 +1,16 @@
+pub mod action;
+pub mod appearance;
 pub mod chart_window;
+pub mod frames;
+pub mod hotkey;
+pub mod motion;
+pub mod pasteboard;
+pub mod prompt;
+pub mod session;
+pub mod sound;
+pub mod sparkline;
+#[cfg(feature = "touch-bar")]
+pub mod touch_bar;
 pub mod tray;
+pub mod url_scheme;
+pub mod user_notifications;
@@ -0,0 +1,61 @@
+//! The Reduce Motion accessibility setting, kept current by watching for
+//! display option changes so toggling it in System Settings applies at once.
+
+use block2::RcBlock;
+use objc2::msg_send;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::ns_string;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REDUCE_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Reduce Motion as of the last change notification
+pub fn reduce_motion() -> bool {
+    REDUCE_MOTION.load(Ordering::Relaxed)
+}
+
+/// Read the setting and follow its changes on the main queue. Call once.
+pub fn watch_reduce_motion() {
+    unsafe {
+        let Some(workspace) = shared_workspace() else {
+            return;
+        };
+        refresh(workspace);
+        let center: *mut AnyObject = msg_send![workspace, notificationCenter];
+        let Some(queue_class) = AnyClass::get(c"NSOperationQueue") else {
+            return;
+        };
+        let queue: *mut AnyObject = msg_send![queue_class, mainQueue];
+        if center.is_null() || queue.is_null() {
+            log::warn!("could not watch the Reduce Motion setting");
+            return;
+        }
+        let handler = RcBlock::new(|_notification: *mut AnyObject| {
+            if let Some(workspace) = shared_workspace() {
+                refresh(workspace);
+            }
+        });
+        // The center keeps the observer, and with it the block, for the
+        // lifetime of the app
+        let _: *mut AnyObject = msg_send![
+            center,
+            addObserverForName: ns_string!("NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification"),
+            object: std::ptr::null::<AnyObject>(),
+            queue: queue,
+            usingBlock: &*handler
+        ];
+    }
+}
+
+fn shared_workspace() -> Option<*mut AnyObject> {
+    let class = AnyClass::get(c"NSWorkspace")?;
+    let workspace: *mut AnyObject = unsafe { msg_send![class, sharedWorkspace] };
+    (!workspace.is_null()).then_some(workspace)
+}
+
+unsafe fn refresh(workspace: *mut AnyObject) {
+    let enabled: bool = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+    if REDUCE_MOTION.swap(enabled, Ordering::Relaxed) != enabled {
+        log::info!("Reduce Motion {}", if enabled { "on" } else { "off" });
+    }
+}
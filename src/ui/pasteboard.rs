@@ -0,0 +1,23 @@
+use objc2::msg_send;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::{ns_string, NSString};
+
+/// Replace the general pasteboard's contents with `text`.
+pub fn copy_text(text: &str) {
+    unsafe {
+        let Some(class) = AnyClass::get(c"NSPasteboard") else {
+            return;
+        };
+        let pasteboard: *mut AnyObject = msg_send![class, generalPasteboard];
+        if pasteboard.is_null() {
+            return;
+        }
+        let _: isize = msg_send![pasteboard, clearContents];
+        let text = NSString::from_str(text);
+        let _: bool = msg_send![
+            pasteboard,
+            setString: &*text,
+            forType: ns_string!("public.utf8-plain-text")
+        ];
+    }
+}
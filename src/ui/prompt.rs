@@ -0,0 +1,24 @@
+use std::process::Command;
+
+/// Ask for a line of text in a modal dialog. None when cancelled.
+pub fn ask_text(message: &str, default: &str) -> Option<String> {
+    let script = format!(
+        "text returned of (display dialog \"{}\" default answer \"{}\")",
+        escape(message),
+        escape(default)
+    );
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Quote for an AppleScript string literal
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,105 @@
+//! Sleep, wake and screen lock. The background samplers pause while nobody
+//! can see their readings and pick up again, spread out, afterwards.
+
+use block2::RcBlock;
+use objc2::msg_send;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::{ns_string, NSString};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ASLEEP: AtomicBool = AtomicBool::new(false);
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// The Mac is going to sleep or the screen is locked
+pub fn inactive() -> bool {
+    ASLEEP.load(Ordering::Relaxed) || LOCKED.load(Ordering::Relaxed)
+}
+
+/// True once after the Mac slept, woke, or the screen was locked or unlocked
+pub fn take_changed() -> bool {
+    CHANGED.swap(false, Ordering::Relaxed)
+}
+
+/// Follow sleep and screen lock on the main queue. Call once.
+pub fn watch_session() {
+    unsafe {
+        let Some(queue_class) = AnyClass::get(c"NSOperationQueue") else {
+            return;
+        };
+        let queue: *mut AnyObject = msg_send![queue_class, mainQueue];
+        let workspace_center: *mut AnyObject = match AnyClass::get(c"NSWorkspace") {
+            Some(class) => {
+                let workspace: *mut AnyObject = msg_send![class, sharedWorkspace];
+                if workspace.is_null() {
+                    std::ptr::null_mut()
+                } else {
+                    msg_send![workspace, notificationCenter]
+                }
+            }
+            None => std::ptr::null_mut(),
+        };
+        let distributed_center: *mut AnyObject =
+            match AnyClass::get(c"NSDistributedNotificationCenter") {
+                Some(class) => msg_send![class, defaultCenter],
+                None => std::ptr::null_mut(),
+            };
+        if queue.is_null() || workspace_center.is_null() || distributed_center.is_null() {
+            log::warn!("could not watch for sleep and screen lock");
+            return;
+        }
+        observe(
+            workspace_center,
+            queue,
+            ns_string!("NSWorkspaceWillSleepNotification"),
+            &ASLEEP,
+            true,
+        );
+        observe(
+            workspace_center,
+            queue,
+            ns_string!("NSWorkspaceDidWakeNotification"),
+            &ASLEEP,
+            false,
+        );
+        // Screen lock is only posted to the distributed center
+        observe(
+            distributed_center,
+            queue,
+            ns_string!("com.apple.screenIsLocked"),
+            &LOCKED,
+            true,
+        );
+        observe(
+            distributed_center,
+            queue,
+            ns_string!("com.apple.screenIsUnlocked"),
+            &LOCKED,
+            false,
+        );
+    }
+}
+
+/// Set `flag` to `value` whenever `center` posts `name`
+unsafe fn observe(
+    center: *mut AnyObject,
+    queue: *mut AnyObject,
+    name: &NSString,
+    flag: &'static AtomicBool,
+    value: bool,
+) {
+    let handler = RcBlock::new(move |_notification: *mut AnyObject| {
+        if flag.swap(value, Ordering::Relaxed) != value {
+            CHANGED.store(true, Ordering::Relaxed);
+        }
+    });
+    // The center keeps the observer, and with it the block, for the
+    // lifetime of the app
+    let _: *mut AnyObject = msg_send![
+        center,
+        addObserverForName: name,
+        object: std::ptr::null::<AnyObject>(),
+        queue: queue,
+        usingBlock: &*handler
+    ];
+}
@@ -0,0 +1,53 @@
+use objc2::msg_send;
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::NSString;
+use std::cell::RefCell;
+
+/// The sounds in /System/Library/Sounds, offered in the Alert Sound menu
+pub const SYSTEM_SOUNDS: [&str; 14] = [
+    "Basso",
+    "Blow",
+    "Bottle",
+    "Frog",
+    "Funk",
+    "Glass",
+    "Hero",
+    "Morse",
+    "Ping",
+    "Pop",
+    "Purr",
+    "Sosumi",
+    "Submarine",
+    "Tink",
+];
+
+thread_local! {
+    /// The sound playing now, kept alive until the next one starts
+    static PLAYING: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+}
+
+/// Start `sound`, a system sound name or a file path, and return at once.
+/// Call on the main thread; NSSound plays asynchronously.
+pub fn play(sound: &str) {
+    unsafe {
+        let Some(class) = AnyClass::get(c"NSSound") else {
+            return;
+        };
+        let name = NSString::from_str(sound);
+        let player: Option<Retained<AnyObject>> = if sound.contains('/') {
+            let alloc: Allocated<AnyObject> = msg_send![class, alloc];
+            msg_send![alloc, initWithContentsOfFile: &*name, byReference: true]
+        } else {
+            msg_send![class, soundNamed: &*name]
+        };
+        let Some(player) = player else {
+            log::warn!("no sound named {:?}", sound);
+            return;
+        };
+        // A sound that is still playing from the last alert starts over
+        let _: bool = msg_send![&player, stop];
+        let _: bool = msg_send![&player, play];
+        PLAYING.with(|playing| *playing.borrow_mut() = Some(player));
+    }
+}
@@ -0,0 +1,93 @@
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2::{msg_send, AnyThread};
+use objc2_app_kit::NSImage;
+use objc2_foundation::{ns_string, NSSize};
+use std::collections::VecDeque;
+
+/// Sparkline size in points
+pub const SPARKLINE_WIDTH: f64 = 40.0;
+pub const SPARKLINE_HEIGHT: f64 = 18.0;
+/// Samples shown (one minute at the default 1 s interval)
+const SPARKLINE_POINTS: usize = 60;
+/// Alpha of the area under the line
+const FILL_ALPHA: u8 = 70;
+
+/// Render the most recent percent values (0–100) as a template image, so the
+/// menu bar tints it for light and dark appearance. `scale` is the backing
+/// scale factor of the screen the item is on.
+pub fn render(values: &VecDeque<f32>, scale: f64) -> Option<Retained<NSImage>> {
+    let scale = scale.max(1.0);
+    let width = (SPARKLINE_WIDTH * scale).round() as usize;
+    let height = (SPARKLINE_HEIGHT * scale).round() as usize;
+    let skip = values.len().saturating_sub(SPARKLINE_POINTS);
+    let recent: Vec<f32> = values.iter().skip(skip).copied().collect();
+    let pixels = rasterize(&recent, width, height, scale);
+
+    unsafe {
+        let rep_class = AnyClass::get(c"NSBitmapImageRep")?;
+        let alloc: Allocated<AnyObject> = msg_send![rep_class, alloc];
+        let rep: Option<Retained<AnyObject>> = msg_send![
+            alloc,
+            initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>(),
+            pixelsWide: width as isize,
+            pixelsHigh: height as isize,
+            bitsPerSample: 8_isize,
+            samplesPerPixel: 4_isize,
+            hasAlpha: true,
+            isPlanar: false,
+            colorSpaceName: ns_string!("NSDeviceRGBColorSpace"),
+            bytesPerRow: (width * 4) as isize,
+            bitsPerPixel: 32_isize
+        ];
+        let rep = rep?;
+        let data: *mut u8 = msg_send![&rep, bitmapData];
+        if data.is_null() {
+            return None;
+        }
+        std::ptr::copy_nonoverlapping(pixels.as_ptr(), data, pixels.len());
+
+        let image = NSImage::initWithSize(
+            NSImage::alloc(),
+            NSSize::new(SPARKLINE_WIDTH, SPARKLINE_HEIGHT),
+        );
+        let _: () = msg_send![&image, addRepresentation: &*rep];
+        image.setTemplate(true);
+        Some(image)
+    }
+}
+
+/// Black RGBA pixels, top row first: a filled area under a ~1 pt line.
+fn rasterize(values: &[f32], width: usize, height: usize, scale: f64) -> Vec<u8> {
+    let mut pixels = vec![0u8; width * height * 4];
+    if values.is_empty() || width < 2 || height < 2 {
+        return pixels;
+    }
+    let line = scale.round().max(1.0) as usize;
+    let y_at = |x: usize| {
+        let pos = x as f32 / (width - 1) as f32 * (values.len() - 1) as f32;
+        let i = pos.floor() as usize;
+        let next = values[(i + 1).min(values.len() - 1)];
+        let v = values[i] + (next - values[i]) * (pos - i as f32);
+        ((1.0 - v.clamp(0.0, 100.0) / 100.0) * (height - 1) as f32).round() as usize
+    };
+
+    let mut set_alpha = |x: usize, y: usize, alpha: u8| {
+        let px = &mut pixels[(y * width + x) * 4..][..4];
+        px[3] = px[3].max(alpha);
+    };
+    let mut prev_y = y_at(0);
+    for x in 0..width {
+        let y = y_at(x);
+        for fy in y..height {
+            set_alpha(x, fy, FILL_ALPHA);
+        }
+        // Connect to the previous column so steep jumps stay continuous
+        let (top, bottom) = (y.min(prev_y), y.max(prev_y));
+        for ly in top..=(bottom + line - 1).min(height - 1) {
+            set_alpha(x, ly, 255);
+        }
+        prev_y = y;
+    }
+    pixels
+}
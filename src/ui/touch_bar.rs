@@ -0,0 +1,212 @@
+//! CPU/MEM/TEMP and two buttons in the Touch Bar. The bar is set on the
+//! application, so it shows whenever the app is active, and on the chart
+//! windows. Macs without a Touch Bar never ask for it.
+
+use crate::config::Config;
+use crate::model::SystemStats;
+use crate::strings::t;
+use crate::ui::action::TrayAction;
+use crate::ui::chart_window::ChartMode;
+use crate::ui::tray::{format_percent, format_temp, menu_bar_temp, post_pending_event};
+use objc2::rc::{Allocated, Retained};
+use objc2::runtime::{AnyClass, AnyObject, ClassBuilder, Sel};
+use objc2::{msg_send, sel};
+use objc2_foundation::NSString;
+use std::ptr;
+use std::sync::Once;
+
+const CPU_ITEM_ID: &str = "com.mac-state-monitor.touchbar.cpu";
+const MEM_ITEM_ID: &str = "com.mac-state-monitor.touchbar.mem";
+const TEMP_ITEM_ID: &str = "com.mac-state-monitor.touchbar.temp";
+const PAUSE_ALERTS_ITEM_ID: &str = "com.mac-state-monitor.touchbar.pause-alerts";
+const SHOW_CHARTS_ITEM_ID: &str = "com.mac-state-monitor.touchbar.show-charts";
+/// Button tags, mapped back to actions by the handler
+const PAUSE_ALERTS_TAG: isize = 1;
+const SHOW_CHARTS_TAG: isize = 2;
+
+static REGISTER_HANDLER: Once = Once::new();
+static mut HANDLER_INSTANCE: *const AnyObject = ptr::null();
+
+/// Button presses arrive on the main thread, like menu actions.
+unsafe extern "C" fn button_pressed(_this: *const AnyObject, _sel: Sel, sender: *const AnyObject) {
+    if sender.is_null() {
+        return;
+    }
+    let tag: isize = msg_send![sender, tag];
+    match tag {
+        PAUSE_ALERTS_TAG => post_pending_event(TrayAction::ToggleAlertPause),
+        SHOW_CHARTS_TAG => post_pending_event(TrayAction::ShowCharts(ChartMode::All)),
+        _ => {}
+    }
+}
+
+fn ensure_handler() -> *const AnyObject {
+    REGISTER_HANDLER.call_once(|| unsafe {
+        let Some(superclass) = AnyClass::get(c"NSObject") else {
+            return;
+        };
+        let Some(mut builder) = ClassBuilder::new(c"TouchBarHandler", superclass) else {
+            return;
+        };
+        builder.add_method(
+            sel!(touchBarButtonPressed:),
+            button_pressed as unsafe extern "C" fn(*const AnyObject, Sel, *const AnyObject),
+        );
+        let cls = builder.register();
+        // Lives as long as the app, like the menu handler
+        HANDLER_INSTANCE = msg_send![cls, new];
+    });
+    unsafe { HANDLER_INSTANCE }
+}
+
+pub struct TouchBar {
+    bar: Retained<AnyObject>,
+    cpu: Retained<AnyObject>,
+    mem: Retained<AnyObject>,
+    temp: Retained<AnyObject>,
+    pause_alerts: Retained<AnyObject>,
+    /// Texts last set on the CPU, MEM and TEMP labels, so polls that
+    /// change nothing skip the AppKit calls
+    shown: [String; 3],
+    /// Pause button currently reads "Resume Alerts"
+    shown_paused: bool,
+}
+
+impl TouchBar {
+    /// None if AppKit can't make one
+    pub fn new() -> Option<Self> {
+        unsafe {
+            let bar_class = AnyClass::get(c"NSTouchBar")?;
+            let handler = ensure_handler();
+            if handler.is_null() {
+                return None;
+            }
+            let cpu = label("CPU")?;
+            let mem = label("MEM")?;
+            let temp = label("TEMP")?;
+            let pause_alerts = button(t("Pause Alerts"), PAUSE_ALERTS_TAG, handler)?;
+            let show_charts = button(t("Show Charts"), SHOW_CHARTS_TAG, handler)?;
+
+            let set_class = AnyClass::get(c"NSMutableSet")?;
+            let array_class = AnyClass::get(c"NSMutableArray")?;
+            let items: Retained<AnyObject> = msg_send![set_class, set];
+            let identifiers: Retained<AnyObject> = msg_send![array_class, array];
+            for (id, view) in [
+                (CPU_ITEM_ID, &cpu),
+                (MEM_ITEM_ID, &mem),
+                (TEMP_ITEM_ID, &temp),
+                (PAUSE_ALERTS_ITEM_ID, &pause_alerts),
+                (SHOW_CHARTS_ITEM_ID, &show_charts),
+            ] {
+                let id = NSString::from_str(id);
+                let item = custom_item(&id, view)?;
+                let _: () = msg_send![&items, addObject: &*item];
+                let _: () = msg_send![&identifiers, addObject: &*id];
+            }
+
+            let alloc: Allocated<AnyObject> = msg_send![bar_class, alloc];
+            let bar: Option<Retained<AnyObject>> = msg_send![alloc, init];
+            let bar = bar?;
+            let _: () = msg_send![&bar, setTemplateItems: &*items];
+            let _: () = msg_send![&bar, setDefaultItemIdentifiers: &*identifiers];
+            Some(Self {
+                bar,
+                cpu,
+                mem,
+                temp,
+                pause_alerts,
+                shown: Default::default(),
+                shown_paused: false,
+            })
+        }
+    }
+
+    /// Show the bar while the app is active, and while one of `windows`
+    /// is key.
+    pub fn attach(&self, windows: &[*const AnyObject]) {
+        set_touch_bar(Some(&self.bar), windows);
+    }
+
+    /// New readings, using the menu bar's formatting and TEMP sensor. A bar
+    /// that isn't on screen (or a Mac without a Touch Bar) is left alone;
+    /// it catches up at the next poll after it shows.
+    pub fn update(&mut self, stats: &SystemStats, config: &Config, alerts_paused: bool) {
+        let visible: bool = unsafe { msg_send![&self.bar, isVisible] };
+        if !visible {
+            return;
+        }
+        let texts = [
+            format!("CPU {}", format_percent(stats.cpu.global_usage)),
+            format!("MEM {}", format_percent(stats.memory.usage_percent)),
+            format!(
+                "TEMP {}",
+                format_temp(menu_bar_temp(stats, config).map(|r| r.temp_c))
+            ),
+        ];
+        let labels = [&self.cpu, &self.mem, &self.temp];
+        for ((text, shown), label) in texts.into_iter().zip(&mut self.shown).zip(labels) {
+            if *shown != text {
+                let _: () =
+                    unsafe { msg_send![label, setStringValue: &*NSString::from_str(&text)] };
+                *shown = text;
+            }
+        }
+        if self.shown_paused != alerts_paused {
+            let title = if alerts_paused {
+                t("Resume Alerts")
+            } else {
+                t("Pause Alerts")
+            };
+            let _: () =
+                unsafe { msg_send![&self.pause_alerts, setTitle: &*NSString::from_str(title)] };
+            self.shown_paused = alerts_paused;
+        }
+    }
+}
+
+/// Take the Touch Bar off the app and `windows`.
+pub fn detach(windows: &[*const AnyObject]) {
+    set_touch_bar(None, windows);
+}
+
+fn set_touch_bar(bar: Option<&AnyObject>, windows: &[*const AnyObject]) {
+    let bar = bar.map_or(ptr::null(), |bar| bar as *const AnyObject);
+    unsafe {
+        if let Some(app_class) = AnyClass::get(c"NSApplication") {
+            let app: *const AnyObject = msg_send![app_class, sharedApplication];
+            if !app.is_null() {
+                let _: () = msg_send![app, setTouchBar: bar];
+            }
+        }
+        for &window in windows {
+            let _: () = msg_send![window, setTouchBar: bar];
+        }
+    }
+}
+
+unsafe fn label(text: &str) -> Option<Retained<AnyObject>> {
+    let class = AnyClass::get(c"NSTextField")?;
+    msg_send![class, labelWithString: &*NSString::from_str(text)]
+}
+
+unsafe fn button(title: &str, tag: isize, target: *const AnyObject) -> Option<Retained<AnyObject>> {
+    let class = AnyClass::get(c"NSButton")?;
+    let button: Option<Retained<AnyObject>> = msg_send![
+        class,
+        buttonWithTitle: &*NSString::from_str(title),
+        target: target,
+        action: sel!(touchBarButtonPressed:)
+    ];
+    let button = button?;
+    let _: () = msg_send![&button, setTag: tag];
+    Some(button)
+}
+
+unsafe fn custom_item(identifier: &NSString, view: &AnyObject) -> Option<Retained<AnyObject>> {
+    let class = AnyClass::get(c"NSCustomTouchBarItem")?;
+    let alloc: Allocated<AnyObject> = msg_send![class, alloc];
+    let item: Option<Retained<AnyObject>> = msg_send![alloc, initWithIdentifier: identifier];
+    let item = item?;
+    let _: () = msg_send![&item, setView: view];
+    Some(item)
+}
@@ -0,0 +1,182 @@
+use super::{motion_allowed, set_module_title, top_threshold, ModuleTitle};
+use crate::config::{AlertThresholds, ColorThresholds, Module};
+use crate::model::SystemStats;
+use objc2::MainThreadMarker;
+use objc2_app_kit::NSStatusItem;
+use std::time::{Duration, Instant};
+
+/// Least time the digest item stays on one metric
+const DIGEST_HOLD: Duration = Duration::from_secs(10);
+/// The SSD has no usage alert; the digest ranks it against this instead
+const DIGEST_DISK_THRESHOLD: f32 = 90.0;
+
+/// Threshold flash: on/off phase length and number of pulses
+const FLASH_PHASE_MS: u128 = 200;
+const FLASH_PULSES: u128 = 3;
+
+/// A metric of the digest item, measured against its alert threshold
+pub struct DigestMetric {
+    pub module: Module,
+    pub value: f32,
+    threshold: f32,
+}
+
+impl DigestMetric {
+    /// 1.0 at the threshold
+    pub fn ratio(&self) -> f32 {
+        self.value / self.threshold.max(1.0)
+    }
+
+    pub fn value_text(&self) -> String {
+        match self.module {
+            Module::Temp => format!("{:.0}C", self.value),
+            _ => format!("{:.0}%", self.value),
+        }
+    }
+
+    pub fn threshold_text(&self) -> String {
+        match self.module {
+            Module::Temp => format!("{:.0}C", self.threshold),
+            _ => format!("{:.0}%", self.threshold),
+        }
+    }
+
+    /// Kind reported by `AlertManager::active_kinds`
+    pub fn alert_kind(&self) -> &'static str {
+        match self.module {
+            Module::Cpu => "cpu",
+            Module::Mem => "memory",
+            Module::Temp => "temperature",
+            Module::Disk => "disk_health",
+            Module::Net => "",
+        }
+    }
+}
+
+/// CPU, MEM, SSD and the hottest sensor, highest ratio to their warning
+/// levels first
+pub fn rank_digest_metrics(stats: &SystemStats, levels: &AlertThresholds) -> Vec<DigestMetric> {
+    let (cpu, mem, temp) = (
+        levels.cpu.warning,
+        levels.memory.warning,
+        levels.temperature.warning,
+    );
+    let mut metrics = vec![
+        DigestMetric {
+            module: Module::Cpu,
+            value: stats.cpu.global_usage,
+            threshold: cpu,
+        },
+        DigestMetric {
+            module: Module::Mem,
+            value: stats.memory.usage_percent,
+            threshold: mem,
+        },
+    ];
+    if let Some(disk) = stats.disks.first() {
+        metrics.push(DigestMetric {
+            module: Module::Disk,
+            value: disk.usage_percent,
+            threshold: DIGEST_DISK_THRESHOLD,
+        });
+    }
+    if let Some(reading) = stats.temperature.hottest() {
+        metrics.push(DigestMetric {
+            module: Module::Temp,
+            value: reading.temp_c,
+            threshold: temp,
+        });
+    }
+    metrics.sort_by(|a, b| b.ratio().total_cmp(&a.ratio()));
+    metrics
+}
+
+/// Which metric the digest item shows. A higher one takes over only after
+/// `DIGEST_HOLD`, so two close metrics don't trade places every poll;
+/// one that crosses its threshold takes over at once.
+#[derive(Default)]
+pub struct Digest {
+    shown: Option<Module>,
+    since: Option<Instant>,
+    /// Visibility the item was last set to
+    pub visible: Option<bool>,
+}
+
+impl Digest {
+    pub fn pick<'a>(&mut self, ranked: &'a [DigestMetric], now: Instant) -> Option<&'a DigestMetric> {
+        let top = ranked.first()?;
+        let current = self
+            .shown
+            .and_then(|module| ranked.iter().find(|m| m.module == module));
+        if let Some(current) = current {
+            let held = self
+                .since
+                .is_some_and(|since| now.duration_since(since) < DIGEST_HOLD);
+            let urgent = top.ratio() >= 1.0 && current.ratio() < 1.0;
+            if top.module == current.module || (held && !urgent) {
+                return Some(current);
+            }
+        }
+        self.shown = Some(top.module);
+        self.since = Some(now);
+        Some(top)
+    }
+}
+
+/// Pulses a module's value when it first crosses into the red zone.
+#[derive(Default)]
+pub struct ThresholdFlash {
+    above: bool,
+    started: Option<Instant>,
+    pub title: Option<ModuleTitle>,
+}
+
+impl ThresholdFlash {
+    /// Record the latest reading; starts pulsing on the upward crossing of
+    /// the module's top color threshold only.
+    pub fn observe(&mut self, title: ModuleTitle, colors: Option<&ColorThresholds>, now: Instant) {
+        let above = top_threshold(colors, title.module).is_some_and(|top| title.value >= top);
+        if !above {
+            self.started = None;
+        } else if !self.above && motion_allowed() {
+            self.started = Some(now);
+        }
+        self.above = above;
+        self.title = Some(title);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Draw the title, in label color during the off half of a pulse.
+    pub fn draw(
+        &mut self,
+        item: &NSStatusItem,
+        colors: Option<&ColorThresholds>,
+        now: Instant,
+        mtm: MainThreadMarker,
+    ) {
+        let Some(title) = &self.title else {
+            return;
+        };
+        let mut metric = Some((title.module, title.value));
+        if let Some(started) = self.started {
+            let phase = now.duration_since(started).as_millis() / FLASH_PHASE_MS;
+            if phase >= FLASH_PULSES * 2 {
+                self.started = None;
+            } else if phase % 2 == 0 {
+                metric = None;
+            }
+        }
+        set_module_title(
+            item,
+            &title.value_line(),
+            &title.line2,
+            metric,
+            colors,
+            title.trend,
+            mtm,
+        );
+    }
+}
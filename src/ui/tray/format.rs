@@ -0,0 +1,306 @@
+use crate::alert;
+use crate::config::{Config, ProcessWatch, TempLabelStyle, HOTTEST_TEMP_COMPONENT};
+use crate::format::format_bytes;
+use crate::model::{
+    AppUsage, CpuStats, DiskStats, DriveHealth, HistoryBuffer, ProcessUsage, SystemStats,
+    TemperatureReading, Trends,
+};
+use crate::strings::{t, tf};
+
+/// "Processes: 612  Threads: 3,420  Files: 9,876"
+pub fn format_system_load(stats: &SystemStats) -> String {
+    let Some(load) = &stats.system_load else {
+        return format!("{}: --  {}: --", t("Processes"), t("Threads"));
+    };
+    let mut text = format!(
+        "{}: {}  {}: {}",
+        t("Processes"),
+        format_count(load.processes as u64),
+        t("Threads"),
+        format_count(load.threads as u64)
+    );
+    if let Some(files) = load.open_files {
+        text.push_str(&format!("  {}: {}", t("Files"), format_count(files as u64)));
+    }
+    text
+}
+
+/// "App: 0.4% CPU, 31.2 MB · poll 3 ms · chart 6 ms"
+pub fn format_app_usage(usage: &AppUsage) -> String {
+    let mut text = format!(
+        "{}: {:.1}% CPU, {} · {} {:.0} ms",
+        t("App"),
+        usage.cpu_percent,
+        format_bytes(usage.memory_bytes),
+        t("poll"),
+        usage.poll_ms
+    );
+    if let Some(render_ms) = usage.render_ms {
+        text.push_str(&format!(" · {} {:.0} ms", t("chart"), render_ms));
+    }
+    text
+}
+
+/// 0.5 → "0.5s", 30 → "30s", 120 → "2 min"
+pub fn format_interval(secs: f64) -> String {
+    if secs >= 60.0 && secs % 60.0 == 0.0 {
+        format!("{} min", secs / 60.0)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// "Last 1 min: CPU avg 23% max 91% · Net avg ↓1.2 MB/s ↑40 KB/s" over the
+/// last hour of history, or as much of it as was recorded
+pub fn format_history_summary(history: &HistoryBuffer, poll_secs: f64) -> Option<String> {
+    let hour = history.rollup.overall()?;
+    let cpu = hour.cpu?;
+    let covered_secs = (hour.samples as f64 * poll_secs) as u64;
+    let span = if covered_secs >= 3600 {
+        t("Last hour").to_string()
+    } else {
+        tf("Last {}", &[&format_interval(covered_secs as f64)])
+    };
+    let mut line = format!("{}: CPU avg {:.0}% max {:.0}%", span, cpu.avg, cpu.max);
+    if let (Some(down), Some(up)) = (hour.net_down, hour.net_up) {
+        // The NET series are in KB/s
+        let speed = |kbps: f64| format_speed((kbps * 1024.0) as u64);
+        line.push_str(&format!(
+            " · Net avg ↓{} ↑{}",
+            speed(down.avg),
+            speed(up.avg)
+        ));
+    }
+    Some(line)
+}
+
+/// 3420 → "3,420"
+fn format_count(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// "Backup (exfat, external): 1.2 TB / 2.0 TB (60%)"
+pub fn format_disk_line(disk: &DiskStats) -> String {
+    let mut traits = vec![disk.file_system.as_str()];
+    if disk.is_removable {
+        traits.push(t("external"));
+    }
+    if disk.is_read_only {
+        traits.push(t("read-only"));
+    }
+    traits.retain(|part| !part.is_empty());
+    let traits = if traits.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", traits.join(", "))
+    };
+    format!(
+        "{}{}: {} / {} ({:.0}%)",
+        disk_display_name(disk),
+        traits,
+        format_bytes(disk.total_bytes - disk.available_bytes),
+        format_bytes(disk.total_bytes),
+        disk.usage_percent
+    )
+}
+
+pub fn disk_display_name(disk: &DiskStats) -> &str {
+    if disk.name.is_empty() {
+        &disk.mount_point
+    } else {
+        &disk.name
+    }
+}
+
+/// "  Health: OK · Wear 7% · 38C", indented under its volume
+pub fn format_health_line(health: &DriveHealth) -> String {
+    let status = if health.passed { t("OK") } else { t("Failing") };
+    let mut parts = vec![format!("  {}: {}", t("Health"), status)];
+    parts.extend(
+        health
+            .wear_percent
+            .map(|wear| tf("Wear {}%", &[&wear.to_string()])),
+    );
+    parts.extend(health.temp_c.map(|temp| format!("{:.0}C", temp)));
+    parts.join(" · ")
+}
+
+/// What the DISK menu's layout depends on
+pub fn disk_signature(disk: &DiskStats) -> String {
+    format!(
+        "{}\0{}\0{}\0{}\0{}\0{}",
+        disk.name,
+        disk.mount_point,
+        disk.file_system,
+        disk.is_removable,
+        disk.is_read_only,
+        disk.health.is_some()
+    )
+}
+
+/// "VPN: utun3 active", or "VPN: none"
+pub fn format_vpn_line(interfaces: &[String]) -> String {
+    if interfaces.is_empty() {
+        format!("VPN: {}", t("none"))
+    } else {
+        format!("VPN: {}", tf("{} active", &[&interfaces.join(", ")]))
+    }
+}
+
+pub fn format_speed(bytes_per_sec: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    const GB: u64 = 1024 * MB;
+    if bytes_per_sec >= GB {
+        format!("{:.1}G", bytes_per_sec as f64 / GB as f64)
+    } else if bytes_per_sec >= MB {
+        format!("{:.1}M", bytes_per_sec as f64 / MB as f64)
+    } else if bytes_per_sec >= KB {
+        format!("{:.0}K", bytes_per_sec as f64 / KB as f64)
+    } else {
+        format!("{}B", bytes_per_sec)
+    }
+}
+
+/// "Dropbox: 12% CPU, 4.1 GB (3 processes)" or "{pattern}: not running"
+pub fn format_watch_line(watch: &ProcessWatch, processes: &[ProcessUsage]) -> String {
+    let Some(usage) = alert::watch_usage(watch, processes) else {
+        return format!("{}: {}", watch.name_pattern, t("not running"));
+    };
+    let count = if usage.count > 1 {
+        format!(" ({})", tf("{} processes", &[&usage.count.to_string()]))
+    } else {
+        String::new()
+    };
+    format!(
+        "{}: {:.0}% CPU, {}{}",
+        usage.label,
+        usage.cpu_percent,
+        format_bytes(usage.memory_bytes),
+        count
+    )
+}
+
+/// "72%": line one of the CPU, MEM and DISK items
+pub fn format_percent(value: f32) -> String {
+    format!("{:.0}%", value)
+}
+
+/// "64C", or "--C" without a reading: line one of the TEMP item
+pub fn format_temp(temp_c: Option<f32>) -> String {
+    temp_c
+        .map(|c| format!("{:.0}C", c))
+        .unwrap_or_else(|| "--C".to_string())
+}
+
+/// The reading the TEMP item shows when it isn't cycling
+pub fn menu_bar_temp<'a>(
+    stats: &'a SystemStats,
+    config: &Config,
+) -> Option<&'a TemperatureReading> {
+    let hottest = config.menubar_temp_component == HOTTEST_TEMP_COMPONENT
+        || config.temp_label_style == TempLabelStyle::Hottest;
+    if hottest {
+        stats.temperature.hottest()
+    } else {
+        stats.temperature.find(&config.menubar_temp_component)
+    }
+}
+
+/// Trend arrows for the modules; none with `show_trend_arrows` off
+pub fn module_trends(history: &HistoryBuffer, config: &Config) -> Trends {
+    if !config.show_trend_arrows {
+        return Trends::default();
+    }
+    Trends::of(history, config.poll_interval().as_secs_f64())
+}
+
+/// Label of the sensor the TEMP item shows, for the Display checkmark
+pub fn selected_temp_label(stats: &SystemStats, config: &Config) -> Option<String> {
+    if config.menubar_temp_component == HOTTEST_TEMP_COMPONENT {
+        return None;
+    }
+    stats
+        .temperature
+        .find(&config.menubar_temp_component)
+        .map(|r| r.label.clone())
+}
+
+/// "Battery Proximity" → "BATT": fits line two of the TEMP item
+pub fn short_temp_label(label: &str) -> String {
+    let word = label.split_whitespace().next().unwrap_or(label);
+    word.chars().take(4).collect::<String>().to_uppercase()
+}
+
+/// "P: 72% @ 3.2 GHz  E: 18% @ 1.0 GHz"; None on Intel
+pub fn format_clusters(cpu: &CpuStats) -> Option<String> {
+    let cluster = |name: &str, usage: f32, mhz: Option<u32>| match mhz {
+        Some(mhz) => format!("{}: {:.0}% @ {:.1} GHz", name, usage, mhz as f64 / 1000.0),
+        None => format!("{}: {:.0}%", name, usage),
+    };
+    Some(format!(
+        "{}  {}",
+        cluster("P", cpu.p_core_usage?, cpu.p_core_mhz),
+        cluster("E", cpu.e_core_usage?, cpu.e_core_mhz)
+    ))
+}
+
+/// Second line of the CPU item: "P72 E18" when enabled and known
+pub fn cpu_label(stats: &SystemStats, config: &Config) -> String {
+    match (stats.cpu.p_core_usage, stats.cpu.e_core_usage) {
+        (Some(p), Some(e)) if config.cpu_cluster_label => format!("P{:.0} E{:.0}", p, e),
+        _ => "CPU".to_string(),
+    }
+}
+
+pub fn format_load_average(load: &[f64; 3]) -> String {
+    format!(
+        "{}: {:.1} / {:.1} / {:.1}",
+        t("Load"),
+        load[0],
+        load[1],
+        load[2]
+    )
+}
+
+pub fn format_uptime(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = (secs % 86400) / 3600;
+    let minutes = (secs % 3600) / 60;
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speeds_fit_the_menu_bar() {
+        assert_eq!(format_speed(0), "0B");
+        assert_eq!(format_speed(1023), "1023B");
+        assert_eq!(format_speed(1024), "1K");
+        assert_eq!(format_speed(1536 * 1024), "1.5M");
+        assert_eq!(format_speed(3 * 1024 * 1024 * 1024), "3.0G");
+    }
+
+    #[test]
+    fn uptime_shows_the_two_largest_units() {
+        assert_eq!(format_uptime(59), "0m");
+        assert_eq!(format_uptime(3 * 3600 + 5 * 60), "3h 5m");
+        assert_eq!(format_uptime(2 * 86400 + 7 * 3600 + 59), "2d 7h");
+    }
+}
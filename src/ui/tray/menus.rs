@@ -0,0 +1,1068 @@
+use super::format::{
+    format_app_usage, format_clusters, format_interval, format_load_average, format_speed,
+    format_system_load, format_uptime, format_watch_line,
+};
+use super::runner::{runner_categories, RunnerMenuOption};
+use super::{
+    ensure_menu_handler, forget_title, get_color_for_value, module_label, title_changed,
+    to_total_cpu_percent, CpuMenuItems, CHART_THEME_TAG, MENU_ACTIONS, MENU_FONT, MODULE_MOVE_TAG,
+    MODULE_ORDER_PRESETS, MODULE_ORDER_TAG, MODULE_WIDTH_PRESETS, POLL_INTERVAL_PRESETS,
+    SPEED_MENU,
+};
+use crate::config::{
+    config_path, AlertPreset, ChartSmoothing, ChartThemeChoice, ColorScheme, Config,
+    CpuStatusStyle, Language, LowPowerBehavior, Module, NetChartStyle, OpenMenuUpdates,
+    RunnerIdleBehavior, DIGEST_MODULE,
+};
+use crate::format::format_bytes;
+use crate::model::SystemStats;
+use crate::strings::{t, tf};
+use crate::ui::action::TrayAction;
+use crate::ui::chart_window::ChartMode;
+use crate::ui::{hotkey, sound, url_scheme};
+use objc2::rc::Retained;
+use objc2::{msg_send, sel, AnyThread, MainThreadMarker};
+use objc2_app_kit::{
+    NSColor, NSControlStateValueOff, NSControlStateValueOn, NSImage, NSMenu, NSMenuItem,
+    NSStatusItem,
+};
+use objc2_foundation::{ns_string, NSMutableAttributedString, NSRange, NSString};
+use std::collections::HashMap;
+
+/// CPU/system menu (tags 100-199)
+pub fn build_native_menu(
+    stats: &SystemStats,
+    config: &Config,
+    mtm: MainThreadMarker,
+    runner_options: &[RunnerMenuOption],
+    runner_preview_images: &HashMap<String, Retained<NSImage>>,
+    effective_settings: &[String],
+    out: &mut CpuMenuItems,
+) -> Retained<NSMenu> {
+    unsafe {
+        let menu = NSMenu::new(mtm);
+        menu.setAutoenablesItems(false);
+        let mut tag: isize = 100;
+        let cpu_percent = to_total_cpu_percent(stats);
+        *out = CpuMenuItems::default();
+
+        MENU_ACTIONS.with(|actions| {
+            let mut actions = actions.borrow_mut();
+            actions.retain(|k, _| *k < 100 || *k >= 400);
+
+            // About
+            let version = env!("CARGO_PKG_VERSION");
+            let about_item = make_info_item(&format!("Mac State Monitor v{}", version), mtm);
+            let config_file = config_path();
+            about_item.setToolTip(Some(&NSString::from_str(&config_file.to_string_lossy())));
+            // The msm:// URLs Shortcuts and scripts can open
+            let about_sub = NSMenu::new(mtm);
+            about_sub.setAutoenablesItems(false);
+            about_sub.addItem(&make_info_item(t("URL Commands"), mtm));
+            for (url, description) in url_scheme::URL_COMMANDS {
+                let line = format!("{}  {}", url, t(description));
+                about_sub.addItem(&make_info_item(&line, mtm));
+            }
+            about_item.setSubmenu(Some(&about_sub));
+            menu.addItem(&about_item);
+            let app_usage_item = make_info_item(&format_app_usage(&stats.app_usage), mtm);
+            menu.addItem(&app_usage_item);
+            out.app_usage = Some(app_usage_item);
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // CPU
+            let cpu_item = make_info_item(&format!("CPU: {:.1}%", cpu_percent), mtm);
+            menu.addItem(&cpu_item);
+            out.readings.push(cpu_item);
+
+            // P/E clusters (Apple Silicon)
+            if let Some(text) = format_clusters(&stats.cpu) {
+                let clusters_item = make_info_item(&text, mtm);
+                menu.addItem(&clusters_item);
+                out.clusters = Some(clusters_item);
+            }
+
+            // Load average
+            let load_item = make_info_item("", mtm);
+            set_menu_item_colored(
+                &load_item,
+                &format_load_average(&stats.cpu.load_average),
+                &get_color_for_value(stats.cpu.load_percent()),
+                mtm,
+            );
+            menu.addItem(&load_item);
+            out.readings.push(load_item);
+
+            // Uptime
+            let uptime_item = make_info_item(
+                &format!("{}: {}", t("Uptime"), format_uptime(stats.cpu.uptime_secs)),
+                mtm,
+            );
+            menu.addItem(&uptime_item);
+            out.readings.push(uptime_item);
+
+            // Processes / threads
+            let load_counts_item = make_info_item(&format_system_load(stats), mtm);
+            menu.addItem(&load_counts_item);
+            out.readings.push(load_counts_item);
+
+            // Memory
+            let mem = &stats.memory;
+            let mem_item = make_info_item(
+                &format!(
+                    "{}: {} / {} ({:.0}%)",
+                    t("Memory"),
+                    format_bytes(mem.used_bytes),
+                    format_bytes(mem.total_bytes),
+                    mem.usage_percent
+                ),
+                mtm,
+            );
+            menu.addItem(&mem_item);
+            out.readings.push(mem_item);
+
+            // Disk (first only)
+            if let Some(disk) = stats.disks.first() {
+                let name = if disk.name.is_empty() {
+                    &disk.mount_point
+                } else {
+                    &disk.name
+                };
+                let disk_item = make_info_item(
+                    &format!(
+                        "{} {}: {} / {} ({:.0}%)",
+                        t("Disk"),
+                        name,
+                        format_bytes(disk.total_bytes - disk.available_bytes),
+                        format_bytes(disk.total_bytes),
+                        disk.usage_percent
+                    ),
+                    mtm,
+                );
+                menu.addItem(&disk_item);
+                out.readings.push(disk_item);
+            }
+
+            // Network
+            let net_item = make_info_item(
+                &format!(
+                    "{}: D {} /s  U {} /s",
+                    t("Net"),
+                    format_speed(stats.network.received_per_sec),
+                    format_speed(stats.network.transmitted_per_sec)
+                ),
+                mtm,
+            );
+            menu.addItem(&net_item);
+            out.readings.push(net_item);
+
+            // Averages over the history, filled in by update_cpu_menu
+            let summary_item = make_info_item("", mtm);
+            summary_item.setHidden(true);
+            menu.addItem(&summary_item);
+            out.summary = Some(summary_item);
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Temperature
+            for reading in &stats.temperature.readings {
+                let temp_item =
+                    make_info_item(&format!("{}: {:.0}C", reading.label, reading.temp_c), mtm);
+                menu.addItem(&temp_item);
+                out.readings.push(temp_item);
+            }
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Watched Processes (configured in config.json)
+            let watches_sub_item = NSMenuItem::new(mtm);
+            watches_sub_item.setTitle(&NSString::from_str(t("Watched Processes")));
+            let watches_sub = NSMenu::new(mtm);
+            watches_sub.setAutoenablesItems(false);
+            if config.process_watches.is_empty() {
+                watches_sub.addItem(&make_info_item(
+                    t("None (add process_watches to config.json)"),
+                    mtm,
+                ));
+            }
+            for watch in &config.process_watches {
+                let watch_item = make_info_item(&format_watch_line(watch, &stats.processes), mtm);
+                watches_sub.addItem(&watch_item);
+                out.watches.push(watch_item);
+            }
+            watches_sub_item.setSubmenu(Some(&watches_sub));
+            menu.addItem(&watches_sub_item);
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Show Charts
+            let charts_item = make_action_item(t("Show Charts"), tag, mtm);
+            actions.insert(tag, TrayAction::ShowCharts(ChartMode::All));
+            tag += 1;
+            menu.addItem(&charts_item);
+
+            let core_charts_item = make_action_item(t("Show Core Charts"), tag, mtm);
+            actions.insert(tag, TrayAction::ShowCharts(ChartMode::Cores));
+            tag += 1;
+            menu.addItem(&core_charts_item);
+
+            // Pop Out Chart: one chart in its own window, e.g. on another display
+            let pop_out_item = NSMenuItem::new(mtm);
+            pop_out_item.setTitle(&NSString::from_str(t("Pop Out Chart")));
+            let pop_out_sub = NSMenu::new(mtm);
+            for (mode, label) in [
+                (ChartMode::CpuOnly, "CPU Chart"),
+                (ChartMode::NetOnly, "Network Chart"),
+                (ChartMode::TempOnly, "Temperature Chart"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                actions.insert(tag, TrayAction::PopOutChart(mode));
+                tag += 1;
+                pop_out_sub.addItem(&item);
+            }
+            pop_out_item.setSubmenu(Some(&pop_out_sub));
+            menu.addItem(&pop_out_item);
+
+            let mini_item = make_action_item(t("Mini Chart Window"), tag, mtm);
+            if config.chart_mini_mode {
+                mini_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleChartMiniMode);
+            tag += 1;
+            menu.addItem(&mini_item);
+
+            // Save Chart as Image
+            let save_chart_item = make_action_item(t("Save Chart as Image…"), tag, mtm);
+            actions.insert(tag, TrayAction::SaveChartImage);
+            tag += 1;
+            menu.addItem(&save_chart_item);
+
+            // Chart Settings: what the chart window draws
+            let chart_settings_item = NSMenuItem::new(mtm);
+            chart_settings_item.setTitle(&NSString::from_str(t("Chart Settings")));
+            let chart_settings_sub = NSMenu::new(mtm);
+            let stats_item = make_action_item(t("Show Min/Avg/Max"), tag, mtm);
+            if config.chart_show_stats {
+                stats_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleChartStats);
+            tag += 1;
+            chart_settings_sub.addItem(&stats_item);
+
+            let temp_range_item = make_action_item(t("Fixed Temperature Axis (0-100)"), tag, mtm);
+            if config.chart_temp_fixed_range {
+                temp_range_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleChartTempRange);
+            tag += 1;
+            chart_settings_sub.addItem(&temp_range_item);
+
+            // Chart Smoothing: moving average over the CPU, percent and rate charts
+            let smoothing_sub_item = NSMenuItem::new(mtm);
+            smoothing_sub_item.setTitle(&NSString::from_str(t("Chart Smoothing")));
+            let smoothing_sub = NSMenu::new(mtm);
+            for (smoothing, label) in [
+                (ChartSmoothing::Off, "Off"),
+                (ChartSmoothing::Avg5, "5-Sample Average"),
+                (ChartSmoothing::Avg15, "15-Sample Average"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if smoothing == config.chart_smoothing {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetChartSmoothing(smoothing));
+                tag += 1;
+                smoothing_sub.addItem(&item);
+            }
+            smoothing_sub_item.setSubmenu(Some(&smoothing_sub));
+            chart_settings_sub.addItem(&smoothing_sub_item);
+
+            // Network Chart: one pane per direction, or both in one
+            let net_chart_sub_item = NSMenuItem::new(mtm);
+            net_chart_sub_item.setTitle(&NSString::from_str(t("Network Chart")));
+            let net_chart_sub = NSMenu::new(mtm);
+            for (style, label) in [
+                (NetChartStyle::Separate, "Separate Panes"),
+                (NetChartStyle::Mirrored, "Upload Mirrored Below"),
+                (NetChartStyle::Overlaid, "Overlaid"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if style == config.net_chart_style {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetNetChartStyle(style));
+                tag += 1;
+                net_chart_sub.addItem(&item);
+            }
+            net_chart_sub_item.setSubmenu(Some(&net_chart_sub));
+            chart_settings_sub.addItem(&net_chart_sub_item);
+
+            // Chart Theme: fixed tags, the main range has no room left
+            let theme_sub_item = NSMenuItem::new(mtm);
+            theme_sub_item.setTitle(&NSString::from_str(t("Chart Theme")));
+            let theme_sub = NSMenu::new(mtm);
+            for (offset, (theme, label)) in [
+                (ChartThemeChoice::Auto, "Match System"),
+                (ChartThemeChoice::Dark, "Dark"),
+                (ChartThemeChoice::Light, "Light"),
+                (ChartThemeChoice::HighContrast, "High Contrast"),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let theme_tag = CHART_THEME_TAG + offset as isize;
+                let item = make_action_item(t(label), theme_tag, mtm);
+                if theme == config.chart_theme {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(theme_tag, TrayAction::SetChartTheme(theme));
+                theme_sub.addItem(&item);
+            }
+            theme_sub_item.setSubmenu(Some(&theme_sub));
+            chart_settings_sub.addItem(&theme_sub_item);
+            chart_settings_item.setSubmenu(Some(&chart_settings_sub));
+            menu.addItem(&chart_settings_item);
+
+            // Copy Stats (plain text / JSON) for support tickets
+            let copy_item = make_action_item(t("Copy Stats"), tag, mtm);
+            actions.insert(tag, TrayAction::CopyStats);
+            tag += 1;
+            menu.addItem(&copy_item);
+            let copy_json_item = make_action_item(t("Copy Stats as JSON"), tag, mtm);
+            actions.insert(tag, TrayAction::CopyStatsJson);
+            tag += 1;
+            menu.addItem(&copy_json_item);
+            let export_history_item = make_action_item(t("Export History…"), tag, mtm);
+            actions.insert(tag, TrayAction::ExportHistory);
+            tag += 1;
+            menu.addItem(&export_history_item);
+
+            // Shortcut: global hotkey that toggles the chart window
+            let hotkey_sub_item = NSMenuItem::new(mtm);
+            hotkey_sub_item.setTitle(&NSString::from_str(t("Shortcut")));
+            let hotkey_sub = NSMenu::new(mtm);
+            let presets = hotkey::presets().map(Some);
+            for preset in presets.into_iter().chain([None]) {
+                let label = preset
+                    .map(hotkey::label)
+                    .unwrap_or_else(|| t("Off").to_string());
+                let item = make_action_item(&label, tag, mtm);
+                if preset == config.chart_hotkey {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetChartHotkey(preset));
+                tag += 1;
+                hotkey_sub.addItem(&item);
+            }
+            hotkey_sub_item.setSubmenu(Some(&hotkey_sub));
+            menu.addItem(&hotkey_sub_item);
+
+            // Module Width: fixed presets or sized to fit the titles
+            let width_sub_item = NSMenuItem::new(mtm);
+            width_sub_item.setTitle(&NSString::from_str(t("Module Width")));
+            let width_sub = NSMenu::new(mtm);
+            let width_choices = MODULE_WIDTH_PRESETS
+                .map(|(width, label)| (Some(width), label))
+                .into_iter()
+                .chain([(None, "Auto")]);
+            for (width, label) in width_choices {
+                let item = make_action_item(t(label), tag, mtm);
+                if width == config.module_width {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetModuleWidth(width));
+                tag += 1;
+                width_sub.addItem(&item);
+            }
+            width_sub_item.setSubmenu(Some(&width_sub));
+            menu.addItem(&width_sub_item);
+
+            // While Menu Is Open: live, paused or caught up on close
+            let open_sub_item = NSMenuItem::new(mtm);
+            open_sub_item.setTitle(&NSString::from_str(t("While Menu Is Open")));
+            let open_sub = NSMenu::new(mtm);
+            for (mode, label) in [
+                (OpenMenuUpdates::Live, "Keep Updating"),
+                (OpenMenuUpdates::Paused, "Pause Updates"),
+                (OpenMenuUpdates::Deferred, "Update on Close"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if mode == config.open_menu_updates {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetOpenMenuUpdates(mode));
+                tag += 1;
+                open_sub.addItem(&item);
+            }
+            open_sub_item.setSubmenu(Some(&open_sub));
+            menu.addItem(&open_sub_item);
+
+            // Low Power Behavior: what to slow down on battery
+            let power_sub_item = NSMenuItem::new(mtm);
+            power_sub_item.setTitle(&NSString::from_str(t("Low Power Behavior")));
+            let power_sub = NSMenu::new(mtm);
+            for (behavior, label) in [
+                (LowPowerBehavior::Off, "Off"),
+                (LowPowerBehavior::Animation, "Reduce Animation"),
+                (LowPowerBehavior::Polling, "Reduce Animation + Polling"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if behavior == config.low_power {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetLowPowerBehavior(behavior));
+                tag += 1;
+                power_sub.addItem(&item);
+            }
+            power_sub_item.setSubmenu(Some(&power_sub));
+            menu.addItem(&power_sub_item);
+
+            // Refresh interval
+            let interval_sub_item = NSMenuItem::new(mtm);
+            interval_sub_item.setTitle(&NSString::from_str(t("Refresh Interval")));
+            let interval_sub = NSMenu::new(mtm);
+            let current_interval = config.poll_interval().as_secs_f64();
+            for secs in POLL_INTERVAL_PRESETS {
+                let item = make_action_item(&format_interval(secs), tag, mtm);
+                if secs == current_interval {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetPollInterval(secs));
+                tag += 1;
+                interval_sub.addItem(&item);
+            }
+            let is_custom = !POLL_INTERVAL_PRESETS.contains(&current_interval);
+            let custom_label = if is_custom {
+                tf("Custom ({})…", &[&format_interval(current_interval)])
+            } else {
+                t("Custom…").to_string()
+            };
+            let custom_item = make_action_item(&custom_label, tag, mtm);
+            if is_custom {
+                custom_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::CustomPollInterval);
+            tag += 1;
+            interval_sub.addItem(&custom_item);
+            interval_sub_item.setSubmenu(Some(&interval_sub));
+            menu.addItem(&interval_sub_item);
+
+            // Modules: auto-hide rules (unchecking force-shows the module)
+            let modules_sub_item = NSMenuItem::new(mtm);
+            modules_sub_item.setTitle(&NSString::from_str(t("Modules")));
+            let modules_sub = NSMenu::new(mtm);
+            let rules = &config.auto_hide;
+            for (module, enabled, label) in [
+                (
+                    Module::Net,
+                    rules.net,
+                    tf(
+                        "Auto-hide NET below {} KB/s",
+                        &[&rules.net_idle_kbps.to_string()],
+                    ),
+                ),
+                (
+                    Module::Temp,
+                    rules.temp,
+                    tf(
+                        "Auto-hide TEMP below {}C",
+                        &[&format!("{:.0}", rules.temp_cool_c)],
+                    ),
+                ),
+            ] {
+                let item = make_action_item(&label, tag, mtm);
+                if enabled {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::ToggleAutoHide(module));
+                tag += 1;
+                modules_sub.addItem(&item);
+            }
+            modules_sub.addItem(&NSMenuItem::separatorItem(mtm));
+            let digest_item = make_action_item(t("Show Metric Closest to Alert"), tag, mtm);
+            if config.module_visible(DIGEST_MODULE) {
+                digest_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleDigest);
+            tag += 1;
+            modules_sub.addItem(&digest_item);
+
+            // Module Order: presets, plus moving CPU; the other modules
+            // move from their own menus
+            modules_sub.addItem(&NSMenuItem::separatorItem(mtm));
+            let order_sub_item = NSMenuItem::new(mtm);
+            order_sub_item.setTitle(&NSString::from_str(t("Module Order")));
+            let order_sub = NSMenu::new(mtm);
+            let order = config.module_order();
+            for (i, (name, preset)) in MODULE_ORDER_PRESETS.iter().enumerate() {
+                let labels: Vec<&str> = preset.iter().map(|&m| module_label(m)).collect();
+                let label = format!("{} ({})", t(name), labels.join(" · "));
+                let order_tag = MODULE_ORDER_TAG + i as isize;
+                let item = make_action_item(&label, order_tag, mtm);
+                if order == *preset {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(order_tag, TrayAction::SetModuleOrder(preset.to_vec()));
+                order_sub.addItem(&item);
+            }
+            order_sub_item.setSubmenu(Some(&order_sub));
+            modules_sub.addItem(&order_sub_item);
+            for (item, move_tag, action) in
+                module_move_items(Module::Cpu, &order, MODULE_MOVE_TAG, mtm)
+            {
+                actions.insert(move_tag, action);
+                modules_sub.addItem(&item);
+            }
+            modules_sub_item.setSubmenu(Some(&modules_sub));
+            menu.addItem(&modules_sub_item);
+
+            // CPU Display
+            let cpu_style_sub_item = NSMenuItem::new(mtm);
+            cpu_style_sub_item.setTitle(&NSString::from_str(t("CPU Display")));
+            let cpu_style_sub = NSMenu::new(mtm);
+            for (style, label) in [
+                (CpuStatusStyle::Text, "Text"),
+                (CpuStatusStyle::Sparkline, "Sparkline"),
+                (CpuStatusStyle::Both, "Both"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if style == config.cpu_status_style {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetCpuStatusStyle(style));
+                tag += 1;
+                cpu_style_sub.addItem(&item);
+            }
+            // Apple Silicon only: P/E usage in place of the "CPU" label
+            if stats.cpu.p_core_usage.is_some() {
+                cpu_style_sub.addItem(&NSMenuItem::separatorItem(mtm));
+                let item = make_action_item(t("P/E Cores as Label"), tag, mtm);
+                if config.cpu_cluster_label {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::ToggleCpuClusterLabel);
+                tag += 1;
+                cpu_style_sub.addItem(&item);
+            }
+            cpu_style_sub_item.setSubmenu(Some(&cpu_style_sub));
+            menu.addItem(&cpu_style_sub_item);
+
+            // Color Scheme (tables are edited in config.json)
+            let scheme_sub_item = NSMenuItem::new(mtm);
+            scheme_sub_item.setTitle(&NSString::from_str(t("Color Scheme")));
+            let scheme_sub = NSMenu::new(mtm);
+            for (scheme, label) in [
+                (ColorScheme::Default, "Default"),
+                (ColorScheme::Conservative, "Conservative"),
+                (ColorScheme::Off, "Off"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if scheme == config.color_scheme {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetColorScheme(scheme));
+                tag += 1;
+                scheme_sub.addItem(&item);
+            }
+            scheme_sub_item.setSubmenu(Some(&scheme_sub));
+            menu.addItem(&scheme_sub_item);
+
+            // Language: languages are listed in their own script
+            let language_sub_item = NSMenuItem::new(mtm);
+            language_sub_item.setTitle(&NSString::from_str(t("Language")));
+            let language_sub = NSMenu::new(mtm);
+            for (language, label) in [
+                (Language::Auto, t("System Default")),
+                (Language::English, "English"),
+                (Language::ChineseSimplified, "简体中文"),
+                (Language::Japanese, "日本語"),
+            ] {
+                let item = make_action_item(label, tag, mtm);
+                if language == config.language {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetLanguage(language));
+                tag += 1;
+                language_sub.addItem(&item);
+            }
+            language_sub_item.setSubmenu(Some(&language_sub));
+            menu.addItem(&language_sub_item);
+
+            // Test Alert: exercises the webhook/command hooks
+            let test_alert_item = make_action_item(t("Test Alert"), tag, mtm);
+            actions.insert(tag, TrayAction::TestAlert);
+            tag += 1;
+            menu.addItem(&test_alert_item);
+            let recovery_item = make_action_item(t("Notify When Alerts Clear"), tag, mtm);
+            if config.alert_recovery_notifications {
+                recovery_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleAlertRecovery);
+            tag += 1;
+            menu.addItem(&recovery_item);
+
+            // Alert Thresholds: warning/critical presets; exact levels live
+            // in config.json
+            let thresholds_sub_item = NSMenuItem::new(mtm);
+            thresholds_sub_item.setTitle(&NSString::from_str(t("Alert Thresholds")));
+            let thresholds_sub = NSMenu::new(mtm);
+            for (preset, name) in [
+                (AlertPreset::Relaxed, t("Relaxed")),
+                (AlertPreset::Standard, t("Standard")),
+                (AlertPreset::Strict, t("Strict")),
+            ] {
+                let levels = preset.thresholds();
+                let label = format!(
+                    "{} (CPU {:.0}/{:.0} · MEM {:.0}/{:.0} · TEMP {:.0}/{:.0})",
+                    name,
+                    levels.cpu.warning,
+                    levels.cpu.critical,
+                    levels.memory.warning,
+                    levels.memory.critical,
+                    levels.temperature.warning,
+                    levels.temperature.critical
+                );
+                let item = make_action_item(&label, tag, mtm);
+                if levels == config.alert_thresholds {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetAlertPreset(preset));
+                tag += 1;
+                thresholds_sub.addItem(&item);
+            }
+            thresholds_sub_item.setSubmenu(Some(&thresholds_sub));
+            menu.addItem(&thresholds_sub_item);
+
+            // Alert Sound: system sounds; per-kind overrides live in config.json
+            let sound_sub_item = NSMenuItem::new(mtm);
+            sound_sub_item.setTitle(&NSString::from_str(t("Alert Sound")));
+            let sound_sub = NSMenu::new(mtm);
+            let none_item = make_action_item(t("None"), tag, mtm);
+            if config.alert_sound.is_none() {
+                none_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::SetAlertSound(None));
+            tag += 1;
+            sound_sub.addItem(&none_item);
+            sound_sub.addItem(&NSMenuItem::separatorItem(mtm));
+            for name in sound::SYSTEM_SOUNDS {
+                let item = make_action_item(name, tag, mtm);
+                if config.alert_sound.as_deref() == Some(name) {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetAlertSound(Some(name.to_string())));
+                tag += 1;
+                sound_sub.addItem(&item);
+            }
+            sound_sub_item.setSubmenu(Some(&sound_sub));
+            menu.addItem(&sound_sub_item);
+
+            // Diagnostics
+            let diagnostics_sub_item = NSMenuItem::new(mtm);
+            diagnostics_sub_item.setTitle(&NSString::from_str(t("Diagnostics")));
+            let diagnostics_sub = NSMenu::new(mtm);
+            diagnostics_sub.setAutoenablesItems(false);
+            let settings_header = NSMenuItem::new(mtm);
+            settings_header.setTitle(&NSString::from_str(t("Effective Settings")));
+            settings_header.setEnabled(false);
+            diagnostics_sub.addItem(&settings_header);
+            for line in effective_settings {
+                diagnostics_sub.addItem(&make_info_item(line, mtm));
+            }
+            diagnostics_sub.addItem(&NSMenuItem::separatorItem(mtm));
+            let log_item = make_action_item(t("Open Log Folder"), tag, mtm);
+            actions.insert(tag, TrayAction::OpenLogFolder);
+            tag += 1;
+            diagnostics_sub.addItem(&log_item);
+            if config.diagnostics_enabled {
+                diagnostics_sub.addItem(&NSMenuItem::separatorItem(mtm));
+                let profile_item = make_action_item(t("Profile App (30 s)…"), tag, mtm);
+                actions.insert(tag, TrayAction::ProfileApp);
+                tag += 1;
+                diagnostics_sub.addItem(&profile_item);
+            }
+            diagnostics_sub_item.setSubmenu(Some(&diagnostics_sub));
+            menu.addItem(&diagnostics_sub_item);
+
+            // Profile: named setups, e.g. office and on the go
+            let profile_sub_item = NSMenuItem::new(mtm);
+            profile_sub_item.setTitle(&NSString::from_str(t("Profile")));
+            let profile_sub = NSMenu::new(mtm);
+            for name in config.profiles.keys() {
+                let item = make_action_item(name, tag, mtm);
+                if config.active_profile.as_ref() == Some(name) {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SwitchProfile(name.clone()));
+                tag += 1;
+                profile_sub.addItem(&item);
+            }
+            if !config.profiles.is_empty() {
+                profile_sub.addItem(&NSMenuItem::separatorItem(mtm));
+            }
+            let save_profile_item = make_action_item(t("Save Current as Profile…"), tag, mtm);
+            actions.insert(tag, TrayAction::SaveProfile);
+            tag += 1;
+            profile_sub.addItem(&save_profile_item);
+            profile_sub_item.setSubmenu(Some(&profile_sub));
+            menu.addItem(&profile_sub_item);
+
+            // Settings transfer between Macs
+            let export_item = make_action_item(t("Export Settings…"), tag, mtm);
+            actions.insert(tag, TrayAction::ExportSettings);
+            tag += 1;
+            menu.addItem(&export_item);
+            let import_item = make_action_item(t("Import Settings…"), tag, mtm);
+            actions.insert(tag, TrayAction::ImportSettings);
+            tag += 1;
+            menu.addItem(&import_item);
+
+            // Where config.json and custom runner frames live, for support
+            let config_folder_item = make_action_item(t("Open Config Folder"), tag, mtm);
+            actions.insert(tag, TrayAction::OpenConfigFolder);
+            tag += 1;
+            menu.addItem(&config_folder_item);
+            let frames_item = make_action_item(t("Reveal Custom Runner Frames"), tag, mtm);
+            actions.insert(tag, TrayAction::RevealCustomFrames);
+            tag += 1;
+            menu.addItem(&frames_item);
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Launch at Login
+            let login_item = make_action_item(t("Launch at Login"), tag, mtm);
+            let state = if config.launch_at_login {
+                NSControlStateValueOn
+            } else {
+                NSControlStateValueOff
+            };
+            login_item.setState(state);
+            actions.insert(tag, TrayAction::ToggleLaunchAtLogin);
+            tag += 1;
+            menu.addItem(&login_item);
+            out.login = Some(login_item);
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            let uninstall_item = make_action_item(t("Uninstall…"), tag, mtm);
+            actions.insert(tag, TrayAction::Uninstall);
+            tag += 1;
+            menu.addItem(&uninstall_item);
+
+            // Quit
+            let quit_item = make_action_item(t("Quit"), tag, mtm);
+            actions.insert(tag, TrayAction::Quit);
+            menu.addItem(&quit_item);
+        });
+
+        menu
+    }
+}
+
+pub fn build_runner_menu(
+    config: &Config,
+    mtm: MainThreadMarker,
+    runner_options: &[RunnerMenuOption],
+    runner_preview_images: &HashMap<String, Retained<NSImage>>,
+    current_frame_ms: u64,
+    preview_items: &mut HashMap<String, Retained<NSMenuItem>>,
+    countdown_item: &mut Option<Retained<NSMenuItem>>,
+) -> Retained<NSMenu> {
+    unsafe {
+        let menu = NSMenu::new(mtm);
+        menu.setAutoenablesItems(false);
+        let mut tag: isize = 200;
+
+        MENU_ACTIONS.with(|actions| {
+            let mut actions = actions.borrow_mut();
+            actions.retain(|k, _| *k < 200 || *k >= 400);
+
+            let effective_rotation_ids = if config.runner_rotation_ids.is_empty() {
+                vec![config.runner_id.clone()]
+            } else {
+                config.runner_rotation_ids.clone()
+            };
+
+            // "All" option
+            let all_selected = runner_options.iter().all(|opt| effective_rotation_ids.contains(&opt.id));
+            let all_item = make_action_item(t("All"), tag, mtm);
+            if all_selected {
+                all_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::SelectAllRunners);
+            tag += 1;
+            menu.addItem(&all_item);
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Categorize runners
+            for (cat_name, cat_opts) in runner_categories(config, runner_options) {
+                let cat_name = cat_name.as_str();
+                let cat_menu_item = NSMenuItem::new(mtm);
+                cat_menu_item.setTitle(&NSString::from_str(t(cat_name)));
+                let cat_sub = NSMenu::new(mtm);
+
+                // Category-level toggle
+                let cat_all_item = make_action_item(&tf("All {}", &[t(cat_name)]), tag, mtm);
+                let cat_all_selected = cat_opts.iter().all(|opt| effective_rotation_ids.contains(&opt.id));
+                if cat_all_selected {
+                    cat_all_item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SelectRunnerCategory(cat_name.to_string()));
+                tag += 1;
+                cat_sub.addItem(&cat_all_item);
+
+                // Category-only rotation
+                let cat_only_item = make_action_item(&tf("Only {}", &[t(cat_name)]), tag, mtm);
+                let cat_only_selected = cat_all_selected
+                    && effective_rotation_ids
+                        .iter()
+                        .all(|id| cat_opts.iter().any(|opt| &opt.id == id));
+                if cat_only_selected {
+                    cat_only_item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::OnlyRunnerCategory(cat_name.to_string()));
+                tag += 1;
+                cat_sub.addItem(&cat_only_item);
+                cat_sub.addItem(&NSMenuItem::separatorItem(mtm));
+
+                for opt in &cat_opts {
+                    let item = make_action_item(&opt.title, tag, mtm);
+                    if effective_rotation_ids.contains(&opt.id) {
+                        item.setState(NSControlStateValueOn);
+                    }
+                    if let Some(preview) = runner_preview_images.get(&opt.id) {
+                        item.setImage(Some(preview));
+                    }
+                    preview_items.insert(opt.id.clone(), item.clone());
+                    actions.insert(tag, TrayAction::ToggleRunner(opt.id.clone()));
+                    tag += 1;
+                    cat_sub.addItem(&item);
+                }
+
+                cat_menu_item.setSubmenu(Some(&cat_sub));
+                menu.addItem(&cat_menu_item);
+            }
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Countdown to the next switch, filled in each poll
+            let countdown = make_info_item("", mtm);
+            menu.addItem(&countdown);
+            *countdown_item = Some(countdown);
+
+            // Manual navigation and shuffle
+            let next_item = make_action_item(t("Next Runner"), tag, mtm);
+            actions.insert(tag, TrayAction::NextRunner);
+            tag += 1;
+            menu.addItem(&next_item);
+            let previous_item = make_action_item(t("Previous Runner"), tag, mtm);
+            actions.insert(tag, TrayAction::PreviousRunner);
+            tag += 1;
+            menu.addItem(&previous_item);
+            let shuffle_item = make_action_item(t("Shuffle"), tag, mtm);
+            if config.runner_rotation_shuffle {
+                shuffle_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleRunnerShuffle);
+            tag += 1;
+            menu.addItem(&shuffle_item);
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+            // Import custom runner
+            let import_item = make_action_item(t("Import Custom Runner Frames…"), tag, mtm);
+            actions.insert(tag, TrayAction::ImportCustomRunner);
+            tag += 1;
+            menu.addItem(&import_item);
+            let import_folder_item =
+                make_action_item(t("Import Custom Runner from Folder…"), tag, mtm);
+            actions.insert(tag, TrayAction::ImportCustomRunnerFolder);
+            tag += 1;
+            menu.addItem(&import_folder_item);
+
+            // Display time
+            let display_sub_item = NSMenuItem::new(mtm);
+            display_sub_item.setTitle(&NSString::from_str(t("Display Time")));
+            let display_sub = NSMenu::new(mtm);
+            let current_display_secs = config.runner_display_secs;
+            let display_presets = [
+                (60_u64, "1 min"),
+                (600_u64, "10 min"),
+                (1800_u64, "30 min"),
+                (3600_u64, "1 h"),
+            ];
+            for (secs, label) in display_presets {
+                let item = make_action_item(&label, tag, mtm);
+                if secs == current_display_secs {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetRunnerDisplaySecs(secs));
+                tag += 1;
+                display_sub.addItem(&item);
+            }
+            let is_custom = !display_presets
+                .iter()
+                .any(|&(secs, _)| secs == current_display_secs);
+            let custom_label = if is_custom {
+                tf("Custom ({})…", &[&format!("{}s", current_display_secs)])
+            } else {
+                t("Custom…").to_string()
+            };
+            let custom_item = make_action_item(&custom_label, tag, mtm);
+            if is_custom {
+                custom_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::CustomRunnerDisplaySecs);
+            tag += 1;
+            display_sub.addItem(&custom_item);
+            display_sub_item.setSubmenu(Some(&display_sub));
+            menu.addItem(&display_sub_item);
+
+            // Frame speed (applies to the currently playing runner)
+            let speed_sub_item = NSMenuItem::new(mtm);
+            speed_sub_item.setTitle(&NSString::from_str(t("Frame Speed")));
+            let speed_sub = NSMenu::new(mtm);
+            for (ms, label) in [(60_u64, "Fast"), (100_u64, "Normal"), (160_u64, "Slow")] {
+                let item = make_action_item(t(label), tag, mtm);
+                if ms == current_frame_ms {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetRunnerFrameMs(ms));
+                tag += 1;
+                speed_sub.addItem(&item);
+            }
+            speed_sub_item.setSubmenu(Some(&speed_sub));
+            menu.addItem(&speed_sub_item);
+
+            // Animation Speed: the default for runners without a Frame Speed
+            // of their own, previewed live while an option is highlighted
+            let animation_sub_item = NSMenuItem::new(mtm);
+            animation_sub_item.setTitle(&NSString::from_str(t("Animation Speed")));
+            let animation_sub = NSMenu::new(mtm);
+            for (ms, label) in [
+                (50_u64, "Very Fast"),
+                (75_u64, "Fast"),
+                (100_u64, "Normal"),
+                (150_u64, "Slow"),
+                (200_u64, "Very Slow"),
+            ] {
+                let item = make_action_item(&format!("{} ({} ms)", t(label), ms), tag, mtm);
+                if ms == config.runner_frame_ms {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetRunnerAnimationSpeed(ms));
+                tag += 1;
+                animation_sub.addItem(&item);
+            }
+            let handler = ensure_menu_handler();
+            let _: () = msg_send![&animation_sub, setDelegate: handler];
+            SPEED_MENU.with(|speed_menu| speed_menu.set(Retained::as_ptr(&animation_sub) as usize));
+            animation_sub_item.setSubmenu(Some(&animation_sub));
+            menu.addItem(&animation_sub_item);
+
+            // Idle behavior
+            let idle_sub_item = NSMenuItem::new(mtm);
+            idle_sub_item.setTitle(&NSString::from_str(t("Idle Behavior")));
+            let idle_sub = NSMenu::new(mtm);
+            for (behavior, label) in [
+                (RunnerIdleBehavior::Animate, "Animate Always"),
+                (RunnerIdleBehavior::Sleep, "Sleep When Idle"),
+            ] {
+                let item = make_action_item(t(label), tag, mtm);
+                if behavior == config.runner_idle_behavior {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetRunnerIdleBehavior(behavior));
+                tag += 1;
+                idle_sub.addItem(&item);
+            }
+            idle_sub_item.setSubmenu(Some(&idle_sub));
+            menu.addItem(&idle_sub_item);
+        });
+
+        menu
+    }
+}
+
+// ── Menu helpers ──
+
+pub unsafe fn make_action_item(title: &str, tag: isize, mtm: MainThreadMarker) -> Retained<NSMenuItem> {
+    let item = NSMenuItem::new(mtm);
+    item.setTitle(&NSString::from_str(title));
+    item.setEnabled(true);
+    item.setTag(tag);
+    item.setAction(Some(sel!(menuActionTriggered:)));
+    let handler = ensure_menu_handler();
+    let _: () = msg_send![&item, setTarget: handler];
+    item
+}
+
+/// Set `menu` on a status item, with the handler as delegate so open and
+/// close are tracked.
+pub fn attach_menu(item: &NSStatusItem, menu: &NSMenu) {
+    unsafe {
+        let handler = ensure_menu_handler();
+        let _: () = msg_send![menu, setDelegate: handler];
+    }
+    item.setMenu(Some(menu));
+}
+
+/// Info menu item with label color text (non-interactive but not grayed out)
+pub fn make_info_item(title: &str, mtm: MainThreadMarker) -> Retained<NSMenuItem> {
+    unsafe {
+        let item = NSMenuItem::new(mtm);
+        item.setEnabled(true);
+        item.setTag(-1);
+        item.setAction(Some(sel!(menuActionTriggered:)));
+        let handler = ensure_menu_handler();
+        let _: () = msg_send![&item, setTarget: handler];
+        forget_title(&*item);
+        set_menu_item_white(&item, title, mtm);
+        item
+    }
+}
+
+/// Set menu item title with system label color attributed string
+pub fn set_menu_item_white(item: &NSMenuItem, title: &str, mtm: MainThreadMarker) {
+    set_menu_item_colored(item, title, &NSColor::labelColor(), mtm);
+}
+
+/// Set menu item title with an explicit text color
+pub fn set_menu_item_colored(item: &NSMenuItem, title: &str, color: &NSColor, _mtm: MainThreadMarker) {
+    let color_id = color as *const NSColor as usize;
+    if !title_changed(item, format!("{}\0{:x}", title, color_id)) {
+        return;
+    }
+    unsafe {
+        let ns_text = NSString::from_str(title);
+        let attr_str =
+            NSMutableAttributedString::initWithString(NSMutableAttributedString::alloc(), &ns_text);
+        let range = NSRange::new(0, title.encode_utf16().count());
+        let color_key = ns_string!("NSColor");
+        attr_str.addAttribute_value_range(color_key, color, range);
+        let font_key = ns_string!("NSFont");
+        MENU_FONT.with(|font| attr_str.addAttribute_value_range(font_key, font, range));
+        let _: () = msg_send![item, setAttributedTitle: &*attr_str];
+    }
+}
+
+/// "Move TEMP Left" and "Move TEMP Right" with tags `tag` and `tag + 1`;
+/// disabled at either end of `order`
+pub fn module_move_items(
+    module: Module,
+    order: &[Module],
+    tag: isize,
+    mtm: MainThreadMarker,
+) -> [(Retained<NSMenuItem>, isize, TrayAction); 2] {
+    let index = order.iter().position(|&m| m == module).unwrap_or(0);
+    let label = module_label(module);
+    let left = unsafe { make_action_item(&tf("Move {} Left", &[label]), tag, mtm) };
+    left.setEnabled(index > 0);
+    let right = unsafe { make_action_item(&tf("Move {} Right", &[label]), tag + 1, mtm) };
+    right.setEnabled(index + 1 < order.len());
+    [
+        (left, tag, TrayAction::MoveModuleLeft(module)),
+        (right, tag + 1, TrayAction::MoveModuleRight(module)),
+    ]
+}
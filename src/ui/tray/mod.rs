@@ -0,0 +1,2709 @@
+mod digest;
+mod format;
+mod menus;
+mod runner;
+
+use crate::alert;
+use crate::config::{
+    ColorThresholds, Config, CpuStatusStyle, CustomRunnerSet, Module, NetDisplay, OpenMenuUpdates,
+    RunnerIconMode, TempLabelStyle, DIGEST_MODULE, HOTTEST_TEMP_COMPONENT, MAX_PINNED_SENSORS,
+};
+use crate::format::format_bytes;
+use crate::issues::{self, Issue};
+use crate::model::{HistoryBuffer, SystemStats, Trend};
+use crate::strings::{t, tf};
+use crate::ui::action::TrayAction;
+use crate::ui::chart_window::ChartMode;
+use crate::ui::{appearance, frames, motion, sparkline};
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, ClassBuilder, Sel};
+use objc2::{msg_send, sel, AnyThread, ClassType, MainThreadMarker, Message};
+use objc2_app_kit::{
+    NSCellImagePosition, NSColor, NSControlStateValueOff, NSControlStateValueOn, NSFont, NSImage,
+    NSImageScaling, NSMenu, NSMenuItem, NSMutableParagraphStyle, NSSquareStatusItemLength,
+    NSStatusBar, NSStatusItem, NSTextAlignment, NSVariableStatusItemLength,
+};
+use objc2_foundation::{ns_string, NSMutableAttributedString, NSRange, NSSize, NSString};
+use rfd::{FileDialog, MessageDialog, MessageLevel};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use digest::{rank_digest_metrics, Digest, ThresholdFlash};
+use format::{
+    cpu_label, disk_display_name, disk_signature, format_app_usage, format_clusters,
+    format_disk_line, format_health_line, format_history_summary, format_load_average,
+    format_speed, format_system_load, format_vpn_line, format_watch_line, selected_temp_label,
+    short_temp_label,
+};
+pub use format::{format_percent, format_temp, format_uptime, menu_bar_temp, module_trends};
+use menus::{
+    attach_menu, build_native_menu, build_runner_menu, make_action_item, make_info_item,
+    module_move_items, set_menu_item_colored, set_menu_item_white,
+};
+pub use runner::custom_frames_root_dir;
+use runner::{runner_categories, write_custom_frames, RunnerAnimator};
+
+/// Width of a two-line module status item, in points
+const MODULE_WIDTH: f64 = 42.0;
+/// Refresh Interval choices, in seconds
+const POLL_INTERVAL_PRESETS: [f64; 7] = [0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0];
+/// Fixed widths offered in the Module Width submenu
+const MODULE_WIDTH_PRESETS: [(f64, &str); 3] = [(36.0, "Narrow"), (42.0, "Normal"), (54.0, "Wide")];
+/// Auto width: padding around the measured title, bounds, and the change
+/// needed before an item is resized
+const MODULE_WIDTH_PADDING: f64 = 8.0;
+const MODULE_WIDTH_MIN: f64 = 28.0;
+const MODULE_WIDTH_MAX: f64 = 80.0;
+const MODULE_WIDTH_DAMPING: f64 = 4.0;
+/// Below this RSSI the NET status item is colored as a warning
+const WEAK_WIFI_RSSI_DBM: i32 = -75;
+/// How often to look again for status items the menu bar had no room for
+const MENU_BAR_SPACE_RECHECK: Duration = Duration::from_secs(30);
+/// Move Left/Right items of the module menus, two per module (tags 700-709)
+const MODULE_MOVE_TAG: isize = 700;
+/// Module Order presets in the Modules submenu (tags 710-719)
+const MODULE_ORDER_TAG: isize = 710;
+/// Clear in the Issues submenu of the main menu
+const CLEAR_ISSUES_TAG: isize = 720;
+/// Chart Theme choices in Chart Settings (tags 721-724)
+const CHART_THEME_TAG: isize = 721;
+/// Module Order presets, left to right
+const MODULE_ORDER_PRESETS: [(&str, [Module; 5]); 3] = [
+    ("Default", Module::ALL),
+    (
+        "Network by the Clock",
+        [
+            Module::Temp,
+            Module::Cpu,
+            Module::Mem,
+            Module::Disk,
+            Module::Net,
+        ],
+    ),
+    (
+        "Reversed",
+        [
+            Module::Temp,
+            Module::Net,
+            Module::Disk,
+            Module::Mem,
+            Module::Cpu,
+        ],
+    ),
+];
+/// `NSWindowOcclusionStateVisible`
+const WINDOW_OCCLUSION_VISIBLE: usize = 1 << 1;
+
+thread_local! {
+    static MENU_ACTIONS: RefCell<HashMap<isize, TrayAction>> = RefCell::new(HashMap::new());
+    static PENDING_EVENT: RefCell<Option<TrayAction>> = RefCell::new(None);
+    /// Last title (text + color) set on each menu item / status item, by address
+    static RENDERED_TITLES: RefCell<HashMap<usize, String>> = RefCell::new(HashMap::new());
+    static MENU_FONT: Retained<NSFont> =
+        unsafe { msg_send![NSFont::class(), menuFontOfSize: 13.0_f64] };
+    static MODULE_TITLE_ATTRS: ModuleTitleAttrs = ModuleTitleAttrs::new();
+    /// `Config::module_width` in effect; None sizes module items to their titles
+    static FIXED_MODULE_WIDTH: Cell<Option<f64>> = const { Cell::new(None) };
+    /// Address of the status item menu currently showing, or 0
+    static OPEN_MENU: Cell<usize> = const { Cell::new(0) };
+    static MENU_CLOSED: Cell<bool> = const { Cell::new(false) };
+    /// Address of the runner's Animation Speed submenu, or 0
+    static SPEED_MENU: Cell<usize> = const { Cell::new(0) };
+    /// Frame duration of the Animation Speed option under the pointer
+    static SPEED_PREVIEW: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Title updates sent to AppKit and skipped because nothing changed
+static TITLE_UPDATES: AtomicU64 = AtomicU64::new(0);
+static TITLE_UPDATES_SKIPPED: AtomicU64 = AtomicU64::new(0);
+
+/// (applied, skipped) title updates since launch, for diagnostics
+pub fn title_update_counts() -> (u64, u64) {
+    (
+        TITLE_UPDATES.load(Ordering::Relaxed),
+        TITLE_UPDATES_SKIPPED.load(Ordering::Relaxed),
+    )
+}
+
+/// Remember `key` as the title of `object`. False when it already was, so the
+/// caller can skip the AppKit update.
+fn title_changed<T>(object: &T, key: String) -> bool {
+    let addr = object as *const T as usize;
+    let changed = RENDERED_TITLES.with(|titles| {
+        let mut titles = titles.borrow_mut();
+        if titles.get(&addr) == Some(&key) {
+            return false;
+        }
+        titles.insert(addr, key);
+        true
+    });
+    let counter = if changed {
+        &TITLE_UPDATES
+    } else {
+        &TITLE_UPDATES_SKIPPED
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    changed
+}
+
+/// Drop the cached title of a new or externally changed object, so the next
+/// update is applied even if an old object at the same address had that title.
+fn forget_title<T>(object: &T) {
+    let addr = object as *const T as usize;
+    RENDERED_TITLES.with(|titles| titles.borrow_mut().remove(&addr));
+}
+
+/// Font, paragraph style and baseline shared by every module title
+struct ModuleTitleAttrs {
+    font: Retained<NSFont>,
+    paragraph: Retained<NSMutableParagraphStyle>,
+    baseline_offset: Retained<objc2_foundation::NSNumber>,
+}
+
+impl ModuleTitleAttrs {
+    fn new() -> Self {
+        unsafe {
+            let font: Retained<NSFont> = msg_send![
+                NSFont::class(),
+                monospacedDigitSystemFontOfSize: 9.0_f64,
+                weight: 0.4_f64
+            ];
+            // Tight line spacing, centered
+            let paragraph = NSMutableParagraphStyle::new();
+            paragraph.setAlignment(NSTextAlignment::Center);
+            let _: () = msg_send![&paragraph, setLineSpacing: 0.0_f64];
+            let _: () = msg_send![&paragraph, setMaximumLineHeight: 10.0_f64];
+            let _: () = msg_send![&paragraph, setMinimumLineHeight: 10.0_f64];
+            // Baseline offset for vertical centering
+            let baseline_offset: Retained<objc2_foundation::NSNumber> = msg_send![
+                objc2_foundation::NSNumber::class(),
+                numberWithDouble: -4.0_f64
+            ];
+            Self {
+                font,
+                paragraph,
+                baseline_offset,
+            }
+        }
+    }
+}
+
+pub fn take_pending_event() -> Option<TrayAction> {
+    PENDING_EVENT.with(|p| p.borrow_mut().take())
+}
+
+/// Queue an action from outside the menus (e.g. the global hotkey).
+pub fn post_pending_event(action: TrayAction) {
+    PENDING_EVENT.with(|p| {
+        *p.borrow_mut() = Some(action);
+    });
+}
+
+/// True once after a status item menu closed.
+pub fn take_menu_closed() -> bool {
+    MENU_CLOSED.with(|closed| closed.replace(false))
+}
+
+/// A status item menu is showing
+pub fn any_menu_open() -> bool {
+    OPEN_MENU.with(Cell::get) != 0
+}
+
+static REGISTER_HANDLER: Once = Once::new();
+static mut HANDLER_INSTANCE: *const AnyObject = std::ptr::null();
+
+unsafe extern "C" fn menu_action_triggered(
+    _this: *const AnyObject,
+    _sel: Sel,
+    sender: *const AnyObject,
+) {
+    if sender.is_null() {
+        return;
+    }
+    let tag: isize = msg_send![sender, tag];
+    MENU_ACTIONS.with(|actions| {
+        let actions = actions.borrow();
+        if let Some(action) = actions.get(&tag) {
+            PENDING_EVENT.with(|p| {
+                *p.borrow_mut() = Some(action.clone());
+            });
+        }
+    });
+}
+
+/// NSMenuDelegate: remember which status item menu is showing.
+unsafe extern "C" fn menu_will_open(_this: *const AnyObject, _sel: Sel, menu: *const AnyObject) {
+    if SPEED_MENU.with(Cell::get) == menu as usize {
+        return;
+    }
+    OPEN_MENU.with(|open| open.set(menu as usize));
+}
+
+unsafe extern "C" fn menu_did_close(_this: *const AnyObject, _sel: Sel, menu: *const AnyObject) {
+    if SPEED_MENU.with(Cell::get) == menu as usize {
+        SPEED_PREVIEW.with(|preview| preview.set(None));
+        return;
+    }
+    OPEN_MENU.with(|open| {
+        if open.get() == menu as usize {
+            open.set(0);
+        }
+    });
+    MENU_CLOSED.with(|closed| closed.set(true));
+}
+
+/// NSMenuDelegate: the runner animates at the highlighted Animation Speed
+/// while that submenu is open.
+unsafe extern "C" fn menu_will_highlight(
+    _this: *const AnyObject,
+    _sel: Sel,
+    menu: *const AnyObject,
+    item: *const AnyObject,
+) {
+    if SPEED_MENU.with(Cell::get) != menu as usize {
+        return;
+    }
+    let ms = if item.is_null() {
+        None
+    } else {
+        let tag: isize = msg_send![item, tag];
+        MENU_ACTIONS.with(|actions| match actions.borrow().get(&tag) {
+            Some(TrayAction::SetRunnerAnimationSpeed(ms)) => Some(*ms),
+            _ => None,
+        })
+    };
+    SPEED_PREVIEW.with(|preview| preview.set(ms));
+}
+
+fn ensure_menu_handler() -> *const AnyObject {
+    REGISTER_HANDLER.call_once(|| unsafe {
+        let superclass = AnyClass::get(c"NSObject").unwrap();
+        let mut builder = ClassBuilder::new(c"MenuHandler", superclass).unwrap();
+        builder.add_method(
+            sel!(menuActionTriggered:),
+            menu_action_triggered as unsafe extern "C" fn(*const AnyObject, Sel, *const AnyObject),
+        );
+        builder.add_method(
+            sel!(menuWillOpen:),
+            menu_will_open as unsafe extern "C" fn(*const AnyObject, Sel, *const AnyObject),
+        );
+        builder.add_method(
+            sel!(menuDidClose:),
+            menu_did_close as unsafe extern "C" fn(*const AnyObject, Sel, *const AnyObject),
+        );
+        builder.add_method(
+            sel!(menu:willHighlightItem:),
+            menu_will_highlight
+                as unsafe extern "C" fn(*const AnyObject, Sel, *const AnyObject, *const AnyObject),
+        );
+        let cls = builder.register();
+        let instance: *const AnyObject = msg_send![cls, new];
+        HANDLER_INSTANCE = instance;
+    });
+    unsafe { HANDLER_INSTANCE }
+}
+
+pub struct TrayManager {
+    items: Option<ModuleItems>,
+    mtm: MainThreadMarker,
+    last_cpu_usage: f32,
+    runner: RunnerAnimator,
+    temp_menu: Option<Retained<NSMenu>>,
+    temp_reading_items: Vec<Retained<NSMenuItem>>,
+    /// Sensor labels the TEMP menu was built for
+    temp_menu_labels: Vec<String>,
+    cpu_menu: Option<Retained<NSMenu>>,
+    cpu_items: CpuMenuItems,
+    /// Runner menu items by runner id, for previews that load after the build
+    runner_preview_items: HashMap<String, Retained<NSMenuItem>>,
+    /// "Next runner in 4:32" line of the runner menu
+    runner_countdown_item: Option<Retained<NSMenuItem>>,
+    /// Frame and template tint last set on the runner button, so polls and
+    /// animation ticks that land on the same frame skip the AppKit calls
+    runner_applied: Option<(Retained<NSImage>, bool)>,
+    mem_menu: Option<Retained<NSMenu>>,
+    mem_reading_items: Vec<Retained<NSMenuItem>>,
+    disk_menu: Option<Retained<NSMenu>>,
+    disk_reading_items: Vec<Retained<NSMenuItem>>,
+    /// SMART lines, one under each volume that has health data
+    disk_health_items: Vec<Retained<NSMenuItem>>,
+    /// `disk_signature` of each disk the DISK menu was built for
+    disk_menu_disks: Vec<String>,
+    /// Every detected mount when the DISK menu was built, for "Volumes"
+    disk_menu_mounts: Vec<String>,
+    net_menu: Option<Retained<NSMenu>>,
+    net_reading_items: Vec<Retained<NSMenuItem>>,
+    net_wifi_items: Vec<Retained<NSMenuItem>>,
+    net_ip_items: Vec<Retained<NSMenuItem>>,
+    /// "Connections: …" with the listening ports as its submenu
+    net_connections_item: Option<Retained<NSMenuItem>>,
+    /// "Latency: 23 ms"; None while the latency probe is off
+    net_latency_item: Option<Retained<NSMenuItem>>,
+    /// Submenu lines the connections item currently holds
+    net_listening_lines: Vec<String>,
+    net_auto_hide: AutoHideState,
+    temp_auto_hide: AutoHideState,
+    /// Modules whose items were last set hidden, left out of
+    /// `visible_modules` or auto-hidden
+    hidden_modules: Option<Vec<Module>>,
+    cpu_flash: ThresholdFlash,
+    mem_flash: ThresholdFlash,
+    disk_flash: ThresholdFlash,
+    temp_flash: ThresholdFlash,
+    temp_cycle: TempCycle,
+    digest: Digest,
+    digest_menu: Option<Retained<NSMenu>>,
+    digest_items: Vec<Retained<NSMenuItem>>,
+    /// CPU status style the item's width and image position are set up for
+    cpu_style: Option<CpuStatusStyle>,
+    /// Module width setting the items are sized for
+    module_width: Option<Option<f64>>,
+    /// Left-to-right order the module items were created in
+    module_order: Vec<Module>,
+    open_menu_updates: OpenMenuUpdates,
+    net_display: NetDisplay,
+    /// 1-minute trend of the NET rates, kept for the fast polls in between
+    net_trend: Option<Trend>,
+    /// Latency shown in red above this many ms; None while the probe is off
+    latency_warn_ms: Option<u32>,
+    /// Color tables in effect (None when the color scheme is Off)
+    color_thresholds: Option<ColorThresholds>,
+    effective_settings: Vec<String>,
+    /// Problems the app ran into, newest first, for the Issues submenu
+    issues: Vec<Issue>,
+    /// The runner shows its "!" badge
+    issue_badge: bool,
+    /// "Last hour: CPU avg …" line of the main menu; None without history
+    history_summary: Option<String>,
+    /// Modules whose status items got no room in the menu bar
+    crowded: Vec<Module>,
+    /// When menu bar space was last checked
+    space_checked: Option<Instant>,
+    /// The menu bar full notification is shown once per launch
+    crowded_notified: bool,
+}
+
+/// CPU menu items updated in place between rebuilds
+#[derive(Default)]
+struct CpuMenuItems {
+    readings: Vec<Retained<NSMenuItem>>,
+    watches: Vec<Retained<NSMenuItem>>,
+    login: Option<Retained<NSMenuItem>>,
+    app_usage: Option<Retained<NSMenuItem>>,
+    clusters: Option<Retained<NSMenuItem>>,
+    summary: Option<Retained<NSMenuItem>>,
+}
+
+/// Runner state after filtering and clamping, for the diagnostics readout
+pub struct RunnerSettings {
+    pub selected_id: String,
+    pub rotation_ids: Vec<String>,
+    pub display_secs: u64,
+    pub frame_ms: u64,
+    pub idle_threshold: f32,
+}
+
+/// Tracks how long an auto-hide condition has held, so items don't flap.
+#[derive(Default)]
+struct AutoHideState {
+    consecutive: u32,
+    hidden: bool,
+}
+
+impl AutoHideState {
+    /// Feed one poll; the item hides once `holds` has been true for more
+    /// than `after_polls` polls in a row.
+    fn observe(&mut self, holds: bool, after_polls: u32) {
+        self.consecutive = if holds {
+            self.consecutive.saturating_add(1)
+        } else {
+            0
+        };
+        self.hidden = self.consecutive > after_polls;
+    }
+}
+
+/// Text of a module's status item, kept so the animation tick can redraw it
+struct ModuleTitle {
+    line1: String,
+    line2: String,
+    module: Module,
+    value: f32,
+    trend: Option<Trend>,
+    /// The module's alert fired and hasn't recovered
+    alert: bool,
+}
+
+impl ModuleTitle {
+    fn value_line(&self) -> String {
+        if self.alert {
+            format!("{}{}", ALERT_MARK, self.line1)
+        } else {
+            self.line1.clone()
+        }
+    }
+}
+
+/// TEMP item rotating through the sensors that have a reading
+#[derive(Default)]
+struct TempCycle {
+    titles: Vec<ModuleTitle>,
+    index: usize,
+    switched: Option<Instant>,
+    /// None while cycling is off
+    interval: Option<Duration>,
+}
+
+impl TempCycle {
+    /// Replace the readings, staying on the same sensor if it is still there.
+    fn set_titles(&mut self, titles: Vec<ModuleTitle>) {
+        let current = self.titles.get(self.index).map(|t| t.line2.clone());
+        self.index = current
+            .and_then(|label| titles.iter().position(|t| t.line2 == label))
+            .unwrap_or(0);
+        self.titles = titles;
+    }
+
+    /// Move to the next sensor once the interval has passed; true if it moved.
+    fn advance(&mut self, now: Instant) -> bool {
+        let Some(interval) = self.interval else {
+            return false;
+        };
+        let switched = *self.switched.get_or_insert(now);
+        if self.titles.len() < 2 || now.duration_since(switched) < interval {
+            return false;
+        }
+        self.index = (self.index + 1) % self.titles.len();
+        self.switched = Some(now);
+        true
+    }
+
+    fn draw(&self, item: &NSStatusItem, colors: Option<&ColorThresholds>, mtm: MainThreadMarker) {
+        match self.titles.get(self.index) {
+            Some(title) => set_module_title(
+                item,
+                &title.value_line(),
+                &title.line2,
+                Some((title.module, title.value)),
+                colors,
+                title.trend,
+                mtm,
+            ),
+            None => set_module_title(item, "--C", "TEMP", None, colors, None, mtm),
+        }
+    }
+}
+
+struct ModuleItems {
+    runner: Retained<NSStatusItem>,
+    cpu: Retained<NSStatusItem>,
+    mem: Retained<NSStatusItem>,
+    disk: Retained<NSStatusItem>,
+    net: Retained<NSStatusItem>,
+    temp: Retained<NSStatusItem>,
+    /// Only visible when `visible_modules` lists `DIGEST_MODULE`
+    digest: Retained<NSStatusItem>,
+}
+
+impl ModuleItems {
+    fn get(&self, module: Module) -> &NSStatusItem {
+        match module {
+            Module::Cpu => &self.cpu,
+            Module::Mem => &self.mem,
+            Module::Disk => &self.disk,
+            Module::Net => &self.net,
+            Module::Temp => &self.temp,
+        }
+    }
+
+    fn all(&self) -> [&NSStatusItem; 7] {
+        [
+            &self.runner,
+            &self.cpu,
+            &self.mem,
+            &self.disk,
+            &self.net,
+            &self.temp,
+            &self.digest,
+        ]
+    }
+}
+
+impl TrayManager {
+    pub fn new(mtm: MainThreadMarker) -> Self {
+        ensure_menu_handler();
+        Self {
+            items: None,
+            mtm,
+            last_cpu_usage: 0.0,
+            runner: RunnerAnimator::new(),
+            temp_menu: None,
+            temp_reading_items: Vec::new(),
+            temp_menu_labels: Vec::new(),
+            cpu_menu: None,
+            cpu_items: CpuMenuItems::default(),
+            runner_preview_items: HashMap::new(),
+            runner_countdown_item: None,
+            runner_applied: None,
+            mem_menu: None,
+            mem_reading_items: Vec::new(),
+            disk_menu: None,
+            disk_reading_items: Vec::new(),
+            disk_health_items: Vec::new(),
+            disk_menu_disks: Vec::new(),
+            disk_menu_mounts: Vec::new(),
+            net_menu: None,
+            net_reading_items: Vec::new(),
+            net_wifi_items: Vec::new(),
+            net_ip_items: Vec::new(),
+            net_connections_item: None,
+            net_latency_item: None,
+            net_listening_lines: Vec::new(),
+            net_auto_hide: AutoHideState::default(),
+            temp_auto_hide: AutoHideState::default(),
+            hidden_modules: None,
+            cpu_flash: ThresholdFlash::default(),
+            mem_flash: ThresholdFlash::default(),
+            disk_flash: ThresholdFlash::default(),
+            temp_flash: ThresholdFlash::default(),
+            temp_cycle: TempCycle::default(),
+            digest: Digest::default(),
+            digest_menu: None,
+            digest_items: Vec::new(),
+            cpu_style: None,
+            module_width: None,
+            module_order: Module::ALL.to_vec(),
+            open_menu_updates: OpenMenuUpdates::default(),
+            net_display: NetDisplay::default(),
+            net_trend: None,
+            latency_warn_ms: None,
+            color_thresholds: None,
+            effective_settings: Vec::new(),
+            issues: Vec::new(),
+            issue_badge: false,
+            history_summary: None,
+            crowded: Vec::new(),
+            space_checked: None,
+            crowded_notified: false,
+        }
+    }
+
+    /// `interacting`: a menu or chart window is in use, so the runner holds
+    /// its frame
+    pub fn animate(&mut self, now: Instant, interacting: bool) {
+        if self.items.is_none() {
+            return;
+        }
+        self.runner.set_paused(interacting, now);
+        let previous_id = self.runner.selected_id.clone();
+        if let Some(frame) = self.runner.advance(now, self.last_cpu_usage) {
+            self.apply_runner_frame(Some(frame.as_ref()));
+        }
+        // Rotation switched runner: refresh menu checkmarks (e.g. Frame Speed)
+        if self.runner.selected_id != previous_id {
+            self.invalidate_cpu_menu();
+        }
+        for (id, image) in self.runner.take_ready_previews() {
+            if let Some(item) = self.runner_preview_items.get(&id) {
+                item.setImage(Some(&image));
+            }
+        }
+
+        // Threshold flashes
+        let mtm = self.mtm;
+        let items = self.items.as_ref().unwrap();
+        let colors = self.color_thresholds.as_ref();
+        if !menu_is_open(&items.temp, mtm) && self.temp_cycle.advance(now) {
+            self.temp_cycle.draw(&items.temp, colors, mtm);
+        }
+        for (flash, item) in [
+            (&mut self.cpu_flash, &items.cpu),
+            (&mut self.mem_flash, &items.mem),
+            (&mut self.disk_flash, &items.disk),
+            (&mut self.temp_flash, &items.temp),
+        ] {
+            if flash.is_active() {
+                flash.draw(item, colors, now, mtm);
+            }
+        }
+    }
+
+    pub fn sync_runner_config(&mut self, config: &Config) {
+        if self.runner.sync_config(config) {
+            self.invalidate_cpu_menu();
+        }
+        if let Some(frame) = self.runner.current_frame() {
+            self.apply_runner_frame(Some(frame.as_ref()));
+        }
+    }
+
+    /// Number of frames imported; None when cancelled or a file didn't decode
+    pub fn import_custom_runner_frames(&mut self, config: &mut Config) -> Option<usize> {
+        let mut files = FileDialog::new()
+            .set_title("Select animation frames in order")
+            .add_filter("Images", &frames::IMAGE_EXTENSIONS)
+            .pick_files()?;
+        frames::sort_frames(&mut files);
+        let set_name = files
+            .first()
+            .and_then(|p| p.file_stem())
+            .map(|s| s.to_string_lossy().to_string());
+        self.import_custom_frames(config, &files, set_name)
+    }
+
+    /// Import every image in a folder, named after the folder
+    pub fn import_custom_runner_folder(&mut self, config: &mut Config) -> Option<usize> {
+        let dir = FileDialog::new()
+            .set_title("Select a folder of animation frames")
+            .pick_folder()?;
+        let files = frames::images_in(&dir);
+        if files.len() < 2 {
+            MessageDialog::new()
+                .set_level(MessageLevel::Error)
+                .set_title("Import Custom Runner from Folder")
+                .set_description(format!(
+                    "{} needs at least two image files ({}).",
+                    dir.display(),
+                    frames::IMAGE_EXTENSIONS.join(", ")
+                ))
+                .show();
+            return None;
+        }
+        let set_name = dir.file_name().map(|s| s.to_string_lossy().to_string());
+        self.import_custom_frames(config, &files, set_name)
+    }
+
+    /// Normalize and copy `files`, in order, as a new custom runner and
+    /// select it
+    fn import_custom_frames(
+        &mut self,
+        config: &mut Config,
+        files: &[PathBuf],
+        set_name: Option<String>,
+    ) -> Option<usize> {
+        if files.len() < 2 {
+            return None;
+        }
+        let set_name = set_name
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "Custom Runner".to_string());
+
+        let pngs = match frames::normalize(files) {
+            Ok(pngs) => pngs,
+            Err(unreadable) => {
+                let names: Vec<String> = unreadable
+                    .iter()
+                    .map(|p| {
+                        p.file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .collect();
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title("Import Custom Runner Frames")
+                    .set_description(format!(
+                        "These files could not be read as images:\n\n{}",
+                        names.join("\n")
+                    ))
+                    .show();
+                return None;
+            }
+        };
+
+        let (set_id, copied) = match write_custom_frames(&pngs) {
+            Ok(v) if !v.1.is_empty() => v,
+            Ok(_) => return None,
+            Err(e) => {
+                issues::report("runner", format!("could not save runner frames: {}", e));
+                return None;
+            }
+        };
+        let count = copied.len();
+
+        let new_set = CustomRunnerSet::new(set_id.clone(), set_name, copied);
+        let selected_id = format!("custom:{}", new_set.id);
+        config.custom_runner_sets.push(new_set);
+        config.runner_id = selected_id;
+        self.runner.sync_config(config);
+        self.invalidate_cpu_menu();
+        Some(count)
+    }
+
+    pub fn set_runner_frame_ms(&mut self, config: &mut Config, ms: u64) {
+        let runner_id = self.runner.selected_id.clone();
+        if let Some(custom_id) = runner_id.strip_prefix("custom:") {
+            if let Some(set) = config
+                .custom_runner_sets
+                .iter_mut()
+                .find(|set| set.id == custom_id)
+            {
+                set.frame_ms = Some(ms);
+            }
+        } else {
+            config.runner_frame_overrides.insert(runner_id, ms);
+        }
+        self.runner.sync_config(config);
+        self.invalidate_cpu_menu();
+    }
+
+    pub fn toggle_runner_in_rotation(&mut self, config: &mut Config, runner_id: &str) -> bool {
+        if !self.runner.runner_id_exists(runner_id) {
+            return false;
+        }
+
+        if let Some(idx) = config
+            .runner_rotation_ids
+            .iter()
+            .position(|id| id == runner_id)
+        {
+            if config.runner_rotation_ids.len() == 1 {
+                return false;
+            }
+            config.runner_rotation_ids.remove(idx);
+            if config.runner_id == runner_id {
+                config.runner_id = config
+                    .runner_rotation_ids
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "runcat:cat".to_string());
+            }
+        } else {
+            config.runner_rotation_ids.push(runner_id.to_string());
+        }
+
+        self.runner.sync_config(config);
+        self.invalidate_cpu_menu();
+        true
+    }
+
+    pub fn select_all_runners(&mut self, config: &mut Config) {
+        let all_ids: Vec<String> = self.runner.menu_options().iter().map(|o| o.id.clone()).collect();
+        let currently_all = all_ids.iter().all(|id| config.runner_rotation_ids.contains(id));
+        if currently_all {
+            // Deselect all → keep only the currently playing runner
+            let keep = self.runner.selected_id.clone();
+            let keep = if all_ids.contains(&keep) { keep } else {
+                all_ids.first().cloned().unwrap_or_else(|| "runcat:cat".to_string())
+            };
+            config.runner_rotation_ids = vec![keep.clone()];
+            config.runner_id = keep;
+        } else {
+            // Select all, keep current runner_id unchanged
+            config.runner_rotation_ids = all_ids;
+            if !config.runner_rotation_ids.contains(&config.runner_id) {
+                config.runner_id = config.runner_rotation_ids.first().cloned().unwrap_or_else(|| "runcat:cat".to_string());
+            }
+        }
+        self.runner.sync_config(config);
+        self.invalidate_cpu_menu();
+    }
+
+    /// Available runner ids of a category, or None for an unknown category.
+    fn runner_category_ids(&self, config: &Config, category: &str) -> Option<Vec<String>> {
+        let all_options = self.runner.menu_options();
+        let (_, options) = runner_categories(config, &all_options)
+            .into_iter()
+            .find(|(name, _)| name == category)?;
+        Some(options.iter().map(|o| o.id.clone()).collect())
+    }
+
+    pub fn step_runner(&mut self, forward: bool) {
+        if self.runner.step(forward, Instant::now()) {
+            if let Some(frame) = self.runner.current_frame() {
+                self.apply_runner_frame(Some(frame.as_ref()));
+            }
+            self.invalidate_cpu_menu();
+        }
+    }
+
+    pub fn select_runner_category(&mut self, config: &mut Config, category: &str) {
+        let Some(cat_ids) = self.runner_category_ids(config, category) else {
+            return;
+        };
+
+        let all_in_rotation = cat_ids.iter().all(|id| config.runner_rotation_ids.contains(id));
+        if all_in_rotation {
+            // Remove category runners (but keep at least one runner total)
+            config.runner_rotation_ids.retain(|id| !cat_ids.contains(id));
+            if config.runner_rotation_ids.is_empty() {
+                config.runner_rotation_ids = vec!["runcat:cat".to_string()];
+            }
+        } else {
+            // Add all category runners
+            for id in &cat_ids {
+                if !config.runner_rotation_ids.contains(id) {
+                    config.runner_rotation_ids.push(id.clone());
+                }
+            }
+        }
+        config.runner_id = config.runner_rotation_ids.first().cloned().unwrap_or_else(|| "runcat:cat".to_string());
+        self.runner.sync_config(config);
+        self.invalidate_cpu_menu();
+    }
+
+    /// Rotate through exactly one category's runners.
+    pub fn select_only_runner_category(&mut self, config: &mut Config, category: &str) {
+        let Some(cat_ids) = self.runner_category_ids(config, category) else {
+            return;
+        };
+        if cat_ids.is_empty() {
+            return;
+        }
+        if !cat_ids.contains(&config.runner_id) {
+            config.runner_id = cat_ids[0].clone();
+        }
+        config.runner_rotation_ids = cat_ids;
+        self.runner.sync_config(config);
+        self.invalidate_cpu_menu();
+    }
+
+    pub fn runner_settings(&self) -> RunnerSettings {
+        RunnerSettings {
+            selected_id: self.runner.selected_id.clone(),
+            rotation_ids: self.runner.rotation_ids.clone(),
+            display_secs: self.runner.display_secs,
+            frame_ms: self.runner.effective_frame_ms(),
+            idle_threshold: self.runner.idle_threshold,
+        }
+    }
+
+    /// Lines for Diagnostics > Effective Settings; rebuilds the menu on change.
+    pub fn set_effective_settings(&mut self, lines: Vec<String>) {
+        if self.effective_settings != lines {
+            self.effective_settings = lines;
+            self.invalidate_cpu_menu();
+        }
+    }
+
+    /// Recent problems, newest first; rebuilds the menu on change. The
+    /// runner carries a "!" while there are any.
+    pub fn set_issues(&mut self, issues: Vec<Issue>) {
+        if self.issues != issues {
+            self.issues = issues;
+            self.invalidate_cpu_menu();
+        }
+        self.apply_issue_badge();
+    }
+
+    fn apply_issue_badge(&mut self) {
+        let badge = !self.issues.is_empty();
+        if badge == self.issue_badge {
+            return;
+        }
+        let Some(items) = &self.items else {
+            return;
+        };
+        let Some(button) = items.runner.button(self.mtm) else {
+            return;
+        };
+        if badge {
+            items.runner.setLength(NSVariableStatusItemLength);
+            button.setImagePosition(NSCellImagePosition::ImageLeft);
+            unsafe {
+                let ns_text = NSString::from_str("!");
+                let attr_str = NSMutableAttributedString::initWithString(
+                    NSMutableAttributedString::alloc(),
+                    &ns_text,
+                );
+                let range = NSRange::new(0, 1);
+                let color = NSColor::systemOrangeColor();
+                attr_str.addAttribute_value_range(ns_string!("NSColor"), &color, range);
+                let font: Retained<NSFont> =
+                    msg_send![NSFont::class(), boldSystemFontOfSize: 10.0_f64];
+                attr_str.addAttribute_value_range(ns_string!("NSFont"), &font, range);
+                let _: () = msg_send![&button, setAttributedTitle: &*attr_str];
+            }
+        } else {
+            items.runner.setLength(NSSquareStatusItemLength);
+            button.setImagePosition(NSCellImagePosition::ImageOnly);
+            button.setTitle(&NSString::from_str(""));
+        }
+        self.issue_badge = badge;
+    }
+
+    pub fn invalidate_cpu_menu(&mut self) {
+        self.cpu_menu = None;
+        self.cpu_items = CpuMenuItems::default();
+        self.runner_preview_items.clear();
+        self.runner_countdown_item = None;
+    }
+
+    /// Drop every dropdown so they are rebuilt and attached again
+    fn invalidate_all_menus(&mut self) {
+        self.invalidate_cpu_menu();
+        self.invalidate_temp_menu();
+        self.mem_menu = None;
+        self.mem_reading_items.clear();
+        self.invalidate_disk_menu();
+        self.invalidate_net_menu();
+        self.digest_menu = None;
+        self.digest_items.clear();
+    }
+
+    pub fn invalidate_net_menu(&mut self) {
+        self.net_menu = None;
+        self.net_reading_items.clear();
+        self.net_wifi_items.clear();
+        self.net_ip_items.clear();
+        self.net_latency_item = None;
+    }
+
+    /// Item that carries the CPU menu: the CPU item, or when that got no room,
+    /// the first module item that did
+    fn cpu_menu_host(&self) -> Module {
+        if !self.crowded.contains(&Module::Cpu) {
+            return Module::Cpu;
+        }
+        [Module::Mem, Module::Disk, Module::Net, Module::Temp]
+            .into_iter()
+            .find(|module| !self.crowded.contains(module))
+            .unwrap_or(Module::Cpu)
+    }
+
+    /// Move Left / Move Right at the end of a module's own menu
+    fn add_move_items(&self, module: Module, menu: &NSMenu) {
+        let mtm = self.mtm;
+        menu.addItem(&NSMenuItem::separatorItem(mtm));
+        let base = MODULE_MOVE_TAG
+            + 2 * Module::ALL.iter().position(|&m| m == module).unwrap_or(0) as isize;
+        MENU_ACTIONS.with(|actions| {
+            let mut actions = actions.borrow_mut();
+            for (item, tag, action) in module_move_items(module, &self.module_order, base, mtm) {
+                actions.insert(tag, action);
+                menu.addItem(&item);
+            }
+        });
+    }
+
+    /// Attach a module's own menu, unless its item stands in for the CPU item
+    fn attach_module_menu(&self, module: Module, menu: &NSMenu) {
+        let Some(items) = &self.items else {
+            return;
+        };
+        if self.cpu_menu_host() != module {
+            attach_menu(items.get(module), menu);
+        }
+    }
+
+    /// Look for status items that got no room in the menu bar (crowded bar,
+    /// behind the notch) and point to the CPU menu instead, which repeats
+    /// their readings. Runs every `MENU_BAR_SPACE_RECHECK`, so items that
+    /// failed to be created are retried and freed space is noticed.
+    fn check_menu_bar_space(&mut self, now: Instant) {
+        if self
+            .space_checked
+            .is_some_and(|at| now.duration_since(at) < MENU_BAR_SPACE_RECHECK)
+        {
+            return;
+        }
+        self.space_checked = Some(now);
+        let mtm = self.mtm;
+
+        let failed = self
+            .items
+            .as_ref()
+            .is_some_and(|items| items.all().iter().any(|item| item.button(mtm).is_none()));
+        if failed {
+            log::warn!("status item has no button; creating the status items again");
+            self.recreate_items();
+        }
+        let Some(items) = &self.items else {
+            return;
+        };
+
+        let modules = [
+            Module::Cpu,
+            Module::Mem,
+            Module::Disk,
+            Module::Net,
+            Module::Temp,
+        ];
+        let crowded: Vec<Module> = modules
+            .into_iter()
+            .filter(|&module| !has_room(items.get(module), mtm))
+            .collect();
+        // Everything occluded means a locked screen or a full-screen app,
+        // not a full menu bar
+        if crowded.len() == modules.len() || crowded == self.crowded {
+            return;
+        }
+
+        if crowded.is_empty() {
+            log::info!("menu bar has room for all status items again");
+        } else {
+            let names: Vec<&str> = crowded.iter().map(|&m| module_label(m)).collect();
+            log::warn!("menu bar full; no room for {}", names.join(", "));
+            if !self.crowded_notified {
+                self.crowded_notified = true;
+                alert::notify(
+                    t("Menu Bar Full"),
+                    &tf(
+                        "No room for {}. Their readings are in the CPU menu.",
+                        &[&names.join(", ")],
+                    ),
+                );
+            }
+        }
+        self.crowded = crowded;
+        self.invalidate_all_menus();
+    }
+
+    /// Take the status items out of the menu bar; removing them explicitly
+    /// keeps ghosts from lingering after quit
+    pub fn remove_items(&mut self) {
+        if let Some(items) = self.items.take() {
+            let status_bar = NSStatusBar::systemStatusBar();
+            for item in items.all() {
+                status_bar.removeStatusItem(item);
+            }
+        }
+    }
+
+    /// Remove the status items and create them afresh
+    fn recreate_items(&mut self) {
+        self.remove_items();
+        self.runner_applied = None;
+        self.issue_badge = false;
+        self.cpu_style = None;
+        self.module_width = None;
+        self.net_auto_hide = AutoHideState::default();
+        self.temp_auto_hide = AutoHideState::default();
+        self.hidden_modules = None;
+        self.digest = Digest::default();
+        self.invalidate_all_menus();
+        self.ensure_items();
+    }
+
+    fn apply_runner_frame(&mut self, frame: Option<&NSImage>) {
+        let Some(items) = &self.items else {
+            return;
+        };
+        let white_mode = self.runner.icon_mode == RunnerIconMode::White;
+        let use_template_tint = white_mode && !self.runner.active_frames_precolored_white;
+        let unchanged = match (&self.runner_applied, frame) {
+            (Some((applied, tint)), Some(img)) => {
+                std::ptr::eq(&**applied, img) && *tint == use_template_tint
+            }
+            _ => false,
+        };
+        if unchanged {
+            return;
+        }
+        if let Some(button) = items.runner.button(self.mtm) {
+            if let Some(img) = frame {
+                img.setTemplate(use_template_tint);
+            }
+            button.setImage(frame);
+
+            let tint_changed =
+                self.runner_applied.as_ref().map(|(_, tint)| *tint) != Some(use_template_tint);
+            if tint_changed {
+                unsafe {
+                    if use_template_tint {
+                        let white = NSColor::whiteColor();
+                        let _: () = msg_send![&button, setContentTintColor: Some(&*white)];
+                    } else {
+                        let _: () =
+                            msg_send![&button, setContentTintColor: Option::<&NSColor>::None];
+                    }
+                }
+            }
+        }
+        self.runner_applied = frame.map(|img| (img.retain(), use_template_tint));
+    }
+
+    fn ensure_items(&mut self) {
+        if self.items.is_some() {
+            return;
+        }
+        let status_bar = NSStatusBar::systemStatusBar();
+        // Each new status item goes left of the ones before it: create
+        // from the clock leftwards
+        let digest = status_bar.statusItemWithLength(MODULE_WIDTH);
+        set_status_item_visible(&digest, false);
+        let mut modules: Vec<(Module, Retained<NSStatusItem>)> = self
+            .module_order
+            .iter()
+            .rev()
+            .map(|&module| (module, status_bar.statusItemWithLength(MODULE_WIDTH)))
+            .collect();
+        let mut take = |module: Module| match modules.iter().position(|(m, _)| *m == module) {
+            Some(index) => modules.swap_remove(index).1,
+            None => status_bar.statusItemWithLength(MODULE_WIDTH),
+        };
+        let (cpu, mem, disk, net, temp) = (
+            take(Module::Cpu),
+            take(Module::Mem),
+            take(Module::Disk),
+            take(Module::Net),
+            take(Module::Temp),
+        );
+        let runner = status_bar.statusItemWithLength(NSSquareStatusItemLength);
+
+        // 初始化 runner button 的静态属性（只设置一次）
+        if let Some(button) = runner.button(self.mtm) {
+            button.setImagePosition(NSCellImagePosition::ImageOnly);
+            button.setImageScaling(NSImageScaling::ScaleProportionallyDown);
+            button.setImageHugsTitle(false);
+            button.setTitle(&NSString::from_str(""));
+        }
+        for item in [&cpu, &mem, &disk, &net, &temp, &digest] {
+            forget_title(&**item);
+        }
+
+        self.items = Some(ModuleItems {
+            runner,
+            cpu,
+            mem,
+            disk,
+            net,
+            temp,
+            digest,
+        });
+    }
+
+    fn ensure_temp_menu(&mut self, stats: &SystemStats, config: &Config) {
+        if self.temp_menu.is_some() {
+            // Update existing reading items
+            if !self.holds_updates(&self.temp_menu) {
+                self.update_temp_readings(stats);
+            }
+            return;
+        }
+        let mtm = self.mtm;
+        let labels: Vec<String> = stats
+            .temperature
+            .readings
+            .iter()
+            .map(|r| r.label.clone())
+            .collect();
+        let selected = selected_temp_label(stats, config);
+        unsafe {
+            let menu = NSMenu::new(mtm);
+            menu.setAutoenablesItems(false);
+            let mut tag: isize = 400;
+
+            MENU_ACTIONS.with(|actions| {
+                let mut actions = actions.borrow_mut();
+                actions.retain(|k, _| *k < 400 || *k >= 500);
+
+                let charts_item = make_action_item(t("Show Charts"), tag, mtm);
+                actions.insert(tag, TrayAction::ShowCharts(ChartMode::TempOnly));
+                tag += 1;
+                menu.addItem(&charts_item);
+
+                menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+                // Display: the sensors actually present, plus the hottest of them
+                let temp_choice_item = NSMenuItem::new(mtm);
+                temp_choice_item.setTitle(&NSString::from_str(t("Display")));
+                let temp_sub = NSMenu::new(mtm);
+                let max_item = make_action_item(t("Max of All"), tag, mtm);
+                if config.menubar_temp_component == HOTTEST_TEMP_COMPONENT {
+                    max_item.setState(NSControlStateValueOn);
+                }
+                actions.insert(
+                    tag,
+                    TrayAction::SetTempComponent(HOTTEST_TEMP_COMPONENT.to_string()),
+                );
+                tag += 1;
+                temp_sub.addItem(&max_item);
+                temp_sub.addItem(&NSMenuItem::separatorItem(mtm));
+                for label in &labels {
+                    let item = make_action_item(label, tag, mtm);
+                    if selected.as_deref() == Some(label.as_str()) {
+                        item.setState(NSControlStateValueOn);
+                    }
+                    actions.insert(tag, TrayAction::SetTempComponent(label.clone()));
+                    tag += 1;
+                    temp_sub.addItem(&item);
+                }
+                temp_choice_item.setSubmenu(Some(&temp_sub));
+                menu.addItem(&temp_choice_item);
+
+                // Second Line: what line two of the TEMP item says
+                let label_style_item = NSMenuItem::new(mtm);
+                label_style_item.setTitle(&NSString::from_str(t("Second Line")));
+                let label_style_sub = NSMenu::new(mtm);
+                for (style, label) in [
+                    (TempLabelStyle::Static, "TEMP"),
+                    (TempLabelStyle::SensorName, t("Sensor Name")),
+                    (TempLabelStyle::Hottest, t("Hottest Sensor")),
+                ] {
+                    let item = make_action_item(label, tag, mtm);
+                    if style == config.temp_label_style {
+                        item.setState(NSControlStateValueOn);
+                    }
+                    actions.insert(tag, TrayAction::SetTempLabelStyle(style));
+                    tag += 1;
+                    label_style_sub.addItem(&item);
+                }
+                label_style_item.setSubmenu(Some(&label_style_sub));
+                menu.addItem(&label_style_item);
+
+                // All Sensors: every raw sensor; checked ones are pinned as readings
+                let raw_item = NSMenuItem::new(mtm);
+                raw_item.setTitle(&NSString::from_str(t("All Sensors")));
+                let raw_sub = NSMenu::new(mtm);
+                raw_sub.setAutoenablesItems(false);
+                let pins_full = config.pinned_sensors.len() >= MAX_PINNED_SENSORS;
+                for reading in &stats.temperature.raw {
+                    // The TEMP tag range ends at 500
+                    if tag >= 500 {
+                        break;
+                    }
+                    let item = make_action_item(
+                        &format!("{}: {:.0}C", reading.label, reading.temp_c),
+                        tag,
+                        mtm,
+                    );
+                    if config.pinned_sensors.contains(&reading.label) {
+                        item.setState(NSControlStateValueOn);
+                    } else if pins_full {
+                        item.setEnabled(false);
+                    }
+                    actions.insert(tag, TrayAction::TogglePinnedSensor(reading.label.clone()));
+                    tag += 1;
+                    raw_sub.addItem(&item);
+                }
+                if stats.temperature.raw.is_empty() {
+                    raw_sub.addItem(&make_info_item(t("No sensors found"), mtm));
+                }
+                raw_item.setSubmenu(Some(&raw_sub));
+                menu.addItem(&raw_item);
+
+                menu.addItem(&NSMenuItem::separatorItem(mtm));
+            });
+
+            // Create reading items for each sensor
+            self.temp_reading_items.clear();
+            for reading in &stats.temperature.readings {
+                let item =
+                    make_info_item(&format!("{}: {:.0}C", reading.label, reading.temp_c), mtm);
+                menu.addItem(&item);
+                self.temp_reading_items.push(item);
+            }
+            if stats.temperature.readings.is_empty() {
+                let item = make_info_item(t("No sensors found"), mtm);
+                menu.addItem(&item);
+                self.temp_reading_items.push(item);
+            }
+
+            self.add_move_items(Module::Temp, &menu);
+            self.attach_module_menu(Module::Temp, &menu);
+            self.temp_menu = Some(menu);
+            self.temp_menu_labels = labels;
+        }
+    }
+
+    fn update_temp_readings(&mut self, stats: &SystemStats) {
+        let readings = &stats.temperature.readings;
+        // A sensor appeared or went away: rebuild, including the Display list
+        if !readings
+            .iter()
+            .map(|r| &r.label)
+            .eq(self.temp_menu_labels.iter())
+        {
+            self.invalidate_temp_menu();
+            return;
+        }
+        for (item, reading) in self.temp_reading_items.iter().zip(readings) {
+            set_menu_item_white(
+                item,
+                &format!("{}: {:.0}C", reading.label, reading.temp_c),
+                self.mtm,
+            );
+        }
+    }
+
+    pub fn invalidate_disk_menu(&mut self) {
+        self.disk_menu = None;
+        self.disk_reading_items.clear();
+        self.disk_health_items.clear();
+    }
+
+    pub fn invalidate_temp_menu(&mut self) {
+        self.temp_menu = None;
+        self.temp_reading_items.clear();
+        self.temp_menu_labels.clear();
+    }
+
+    fn ensure_cpu_menu(&mut self, stats: &SystemStats, config: &Config) {
+        if self.cpu_menu.is_some() {
+            if !self.holds_updates(&self.cpu_menu) {
+                self.update_cpu_menu(stats, config);
+            }
+            self.update_runner_countdown();
+            return;
+        }
+        let mtm = self.mtm;
+        let runner_options = self.runner.menu_options();
+        let runner_preview_images = self.runner.preview_images(&runner_options);
+        let menu = build_native_menu(
+            stats,
+            config,
+            mtm,
+            &runner_options,
+            &runner_preview_images,
+            &self.effective_settings,
+            &mut self.cpu_items,
+        );
+        if !self.crowded.is_empty() {
+            let names: Vec<&str> = self.crowded.iter().map(|&m| module_label(m)).collect();
+            let note = make_info_item(&tf("Menu bar full: {} hidden", &[&names.join(", ")]), mtm);
+            menu.insertItem_atIndex(&NSMenuItem::separatorItem(mtm), 0);
+            menu.insertItem_atIndex(&note, 0);
+        }
+        if !self.issues.is_empty() {
+            let issues_item = NSMenuItem::new(mtm);
+            let count = self.issues.len().to_string();
+            issues_item.setTitle(&NSString::from_str(&tf("Issues ({})", &[&count])));
+            let issues_sub = NSMenu::new(mtm);
+            issues_sub.setAutoenablesItems(false);
+            for issue in &self.issues {
+                issues_sub.addItem(&make_info_item(&issue.line(), mtm));
+            }
+            issues_sub.addItem(&NSMenuItem::separatorItem(mtm));
+            let clear_item = unsafe { make_action_item(t("Clear"), CLEAR_ISSUES_TAG, mtm) };
+            issues_sub.addItem(&clear_item);
+            MENU_ACTIONS.with(|actions| {
+                let mut actions = actions.borrow_mut();
+                actions.insert(CLEAR_ISSUES_TAG, TrayAction::ClearIssues);
+            });
+            issues_item.setSubmenu(Some(&issues_sub));
+            menu.insertItem_atIndex(&NSMenuItem::separatorItem(mtm), 0);
+            menu.insertItem_atIndex(&issues_item, 0);
+        }
+        let items = self.items.as_ref().unwrap();
+        attach_menu(items.get(self.cpu_menu_host()), &menu);
+        self.cpu_menu = Some(menu);
+
+        // Build separate runner menu
+        let runner_menu = build_runner_menu(
+            config,
+            mtm,
+            &runner_options,
+            &runner_preview_images,
+            self.runner.effective_frame_ms(),
+            &mut self.runner_preview_items,
+            &mut self.runner_countdown_item,
+        );
+        items.runner.setMenu(Some(&runner_menu));
+        self.update_runner_countdown();
+    }
+
+    /// Time left on the current runner; the line stays put while the
+    /// rotation has a single runner and nothing switches.
+    fn update_runner_countdown(&self) {
+        let Some(item) = &self.runner_countdown_item else {
+            return;
+        };
+        let text = match self.runner.next_switch_in(Instant::now()) {
+            Some(left) => {
+                let secs = left.as_secs();
+                let countdown = format!("{}:{:02}", secs / 60, secs % 60);
+                tf("Next runner in {}", &[&countdown])
+            }
+            None => t("Single runner, no rotation").to_string(),
+        };
+        set_menu_item_white(item, &text, self.mtm);
+    }
+
+    fn update_cpu_menu(&self, stats: &SystemStats, config: &Config) {
+        let mtm = self.mtm;
+        let mut idx = 0;
+        let cpu_percent = to_total_cpu_percent(stats);
+
+        if let Some(item) = &self.cpu_items.app_usage {
+            set_menu_item_white(item, &format_app_usage(&stats.app_usage), mtm);
+        }
+        if let Some(item) = &self.cpu_items.summary {
+            match &self.history_summary {
+                Some(summary) => {
+                    set_menu_item_white(item, summary, mtm);
+                    item.setHidden(false);
+                }
+                None => item.setHidden(true),
+            }
+        }
+
+        // CPU
+        if let Some(item) = self.cpu_items.readings.get(idx) {
+            set_menu_item_white(item, &format!("CPU: {:.1}%", cpu_percent), mtm);
+        }
+        idx += 1;
+        if let (Some(item), Some(text)) = (&self.cpu_items.clusters, format_clusters(&stats.cpu)) {
+            set_menu_item_white(item, &text, mtm);
+        }
+
+        // Load average
+        if let Some(item) = self.cpu_items.readings.get(idx) {
+            set_menu_item_colored(
+                item,
+                &format_load_average(&stats.cpu.load_average),
+                &get_color_for_value(stats.cpu.load_percent()),
+                mtm,
+            );
+        }
+        idx += 1;
+
+        // Uptime
+        if let Some(item) = self.cpu_items.readings.get(idx) {
+            set_menu_item_white(
+                item,
+                &format!("{}: {}", t("Uptime"), format_uptime(stats.cpu.uptime_secs)),
+                mtm,
+            );
+        }
+        idx += 1;
+
+        // Processes / threads
+        if let Some(item) = self.cpu_items.readings.get(idx) {
+            set_menu_item_white(item, &format_system_load(stats), mtm);
+        }
+        idx += 1;
+
+        // Memory
+        if let Some(item) = self.cpu_items.readings.get(idx) {
+            let mem = &stats.memory;
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: {} / {} ({:.0}%)",
+                    t("Memory"),
+                    format_bytes(mem.used_bytes),
+                    format_bytes(mem.total_bytes),
+                    mem.usage_percent
+                ),
+                mtm,
+            );
+        }
+        idx += 1;
+
+        // Disk (just first one)
+        if let Some(disk) = stats.disks.first() {
+            if let Some(item) = self.cpu_items.readings.get(idx) {
+                let name = if disk.name.is_empty() {
+                    &disk.mount_point
+                } else {
+                    &disk.name
+                };
+                set_menu_item_white(
+                    item,
+                    &format!(
+                        "{} {}: {} / {} ({:.0}%)",
+                        t("Disk"),
+                        name,
+                        format_bytes(disk.total_bytes - disk.available_bytes),
+                        format_bytes(disk.total_bytes),
+                        disk.usage_percent
+                    ),
+                    mtm,
+                );
+            }
+            idx += 1;
+        }
+
+        // Network
+        if let Some(item) = self.cpu_items.readings.get(idx) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: D {} /s  U {} /s",
+                    t("Net"),
+                    format_speed(stats.network.received_per_sec),
+                    format_speed(stats.network.transmitted_per_sec)
+                ),
+                mtm,
+            );
+        }
+        idx += 1;
+
+        // Temperature readings
+        for reading in &stats.temperature.readings {
+            if let Some(item) = self.cpu_items.readings.get(idx) {
+                set_menu_item_white(
+                    item,
+                    &format!("{}: {:.0}C", reading.label, reading.temp_c),
+                    mtm,
+                );
+            }
+            idx += 1;
+        }
+
+        // Watched processes
+        for (watch, item) in config.process_watches.iter().zip(&self.cpu_items.watches) {
+            set_menu_item_white(item, &format_watch_line(watch, &stats.processes), mtm);
+        }
+
+        // Update Launch at Login checkmark
+        if let Some(login_item) = &self.cpu_items.login {
+            let state = if config.launch_at_login {
+                NSControlStateValueOn
+            } else {
+                NSControlStateValueOff
+            };
+            login_item.setState(state);
+        }
+    }
+
+    // ── MEM menu (tags 300-399) ──
+
+    fn ensure_mem_menu(&mut self, stats: &SystemStats) {
+        if self.mem_menu.is_some() {
+            if !self.holds_updates(&self.mem_menu) {
+                self.update_mem_menu(stats);
+            }
+            return;
+        }
+        let mtm = self.mtm;
+        let menu = NSMenu::new(mtm);
+        menu.setAutoenablesItems(false);
+        self.mem_reading_items.clear();
+
+        // Used
+        let used_item = make_info_item("", mtm);
+        menu.addItem(&used_item);
+        self.mem_reading_items.push(used_item);
+
+        // Available
+        let avail_item = make_info_item("", mtm);
+        menu.addItem(&avail_item);
+        self.mem_reading_items.push(avail_item);
+
+        // Swap
+        let swap_item = make_info_item("", mtm);
+        menu.addItem(&swap_item);
+        self.mem_reading_items.push(swap_item);
+
+        // Swap activity
+        let activity_item = make_info_item("", mtm);
+        menu.addItem(&activity_item);
+        self.mem_reading_items.push(activity_item);
+
+        // Compressions
+        let compress_item = make_info_item("", mtm);
+        menu.addItem(&compress_item);
+        self.mem_reading_items.push(compress_item);
+
+        self.update_mem_menu(stats);
+
+        self.add_move_items(Module::Mem, &menu);
+        self.attach_module_menu(Module::Mem, &menu);
+        self.mem_menu = Some(menu);
+    }
+
+    fn update_mem_menu(&self, stats: &SystemStats) {
+        let mtm = self.mtm;
+        let mem = &stats.memory;
+        if let Some(item) = self.mem_reading_items.get(0) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: {} / {} ({:.0}%)",
+                    t("Used"),
+                    format_bytes(mem.used_bytes),
+                    format_bytes(mem.total_bytes),
+                    mem.usage_percent
+                ),
+                mtm,
+            );
+        }
+        if let Some(item) = self.mem_reading_items.get(1) {
+            set_menu_item_white(
+                item,
+                &format!("{}: {}", t("Available"), format_bytes(mem.available_bytes)),
+                mtm,
+            );
+        }
+        if let Some(item) = self.mem_reading_items.get(2) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: {} / {}",
+                    t("Swap"),
+                    format_bytes(mem.swap_used_bytes),
+                    format_bytes(mem.swap_total_bytes)
+                ),
+                mtm,
+            );
+        }
+        let (activity, compressions) = match mem.swap_activity {
+            Some(a) => (
+                format!(
+                    "{}: in {:.0}/s out {:.0}/s",
+                    t("Swap activity"),
+                    a.pageins_per_sec,
+                    a.pageouts_per_sec
+                ),
+                format!("{}: {:.0}/s", t("Compressions"), a.compressions_per_sec),
+            ),
+            None => (
+                format!("{}: --", t("Swap activity")),
+                format!("{}: --", t("Compressions")),
+            ),
+        };
+        if let Some(item) = self.mem_reading_items.get(3) {
+            set_menu_item_white(item, &activity, mtm);
+        }
+        if let Some(item) = self.mem_reading_items.get(4) {
+            set_menu_item_white(item, &compressions, mtm);
+        }
+    }
+
+    // ── DISK menu (tags 600-699) ──
+
+    fn ensure_disk_menu(&mut self, stats: &SystemStats, config: &Config) {
+        if self.disk_menu.is_some() {
+            if !self.holds_updates(&self.disk_menu) {
+                self.update_disk_menu(stats);
+            }
+            return;
+        }
+        let mtm = self.mtm;
+        let menu = NSMenu::new(mtm);
+        menu.setAutoenablesItems(false);
+        self.disk_reading_items.clear();
+        self.disk_health_items.clear();
+        self.disk_menu_disks = stats.disks.iter().map(disk_signature).collect();
+        self.disk_menu_mounts = stats.disk_mounts.clone();
+
+        MENU_ACTIONS.with(|actions| {
+            let mut actions = actions.borrow_mut();
+            actions.retain(|k, _| *k < 600 || *k >= 700);
+            let mut tag: isize = 600;
+
+            for disk in &stats.disks {
+                let item = make_info_item("", mtm);
+                menu.addItem(&item);
+                self.disk_reading_items.push(item);
+                if disk.health.is_some() {
+                    let health_item = make_info_item("", mtm);
+                    menu.addItem(&health_item);
+                    self.disk_health_items.push(health_item);
+                }
+                // Eject sits right under the volume it belongs to
+                if disk.is_removable {
+                    let title = tf("Eject {}", &[disk_display_name(disk)]);
+                    let eject_item = unsafe { make_action_item(&title, tag, mtm) };
+                    actions.insert(tag, TrayAction::EjectDisk(disk.mount_point.clone()));
+                    tag += 1;
+                    menu.addItem(&eject_item);
+                }
+            }
+
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+            // Volumes: every detected mount, checked when it's shown above
+            if !stats.disk_mounts.is_empty() {
+                let volumes_sub_item = NSMenuItem::new(mtm);
+                volumes_sub_item.setTitle(&NSString::from_str(t("Volumes")));
+                let volumes_sub = NSMenu::new(mtm);
+                for mount_point in &stats.disk_mounts {
+                    let item = unsafe { make_action_item(mount_point, tag, mtm) };
+                    if stats.disks.iter().any(|d| &d.mount_point == mount_point) {
+                        item.setState(NSControlStateValueOn);
+                    }
+                    actions.insert(tag, TrayAction::ToggleDiskMount(mount_point.clone()));
+                    tag += 1;
+                    volumes_sub.addItem(&item);
+                }
+                volumes_sub_item.setSubmenu(Some(&volumes_sub));
+                menu.addItem(&volumes_sub_item);
+            }
+
+            let notify_item = unsafe { make_action_item(t("Notify on Mount/Unmount"), tag, mtm) };
+            if config.notify_volume_changes {
+                notify_item.setState(NSControlStateValueOn);
+            }
+            actions.insert(tag, TrayAction::ToggleVolumeNotifications);
+            menu.addItem(&notify_item);
+        });
+
+        self.update_disk_menu(stats);
+
+        self.add_move_items(Module::Disk, &menu);
+        self.attach_module_menu(Module::Disk, &menu);
+        self.disk_menu = Some(menu);
+    }
+
+    fn update_disk_menu(&mut self, stats: &SystemStats) {
+        // Rebuild when a volume mounts, unmounts, is renamed or filtered
+        if !stats
+            .disks
+            .iter()
+            .map(disk_signature)
+            .eq(self.disk_menu_disks.iter().map(String::as_str))
+            || stats.disk_mounts != self.disk_menu_mounts
+        {
+            self.invalidate_disk_menu();
+            return;
+        }
+        let mtm = self.mtm;
+        for (disk, item) in stats.disks.iter().zip(&self.disk_reading_items) {
+            set_menu_item_white(item, &format_disk_line(disk), mtm);
+        }
+        let healths = stats.disks.iter().filter_map(|d| d.health.as_ref());
+        for (health, item) in healths.zip(&self.disk_health_items) {
+            let line = format_health_line(health);
+            if health.passed {
+                set_menu_item_white(item, &line, mtm);
+            } else {
+                set_menu_item_colored(item, &line, &NSColor::systemRedColor(), mtm);
+            }
+        }
+    }
+
+    // ── NET menu (tags 500-599) ──
+
+    fn ensure_net_menu(&mut self, stats: &SystemStats) {
+        if self.net_menu.is_some() {
+            if !self.holds_updates(&self.net_menu) {
+                self.update_net_menu(stats);
+            }
+            return;
+        }
+        let mtm = self.mtm;
+        let menu = NSMenu::new(mtm);
+        menu.setAutoenablesItems(false);
+        self.net_reading_items.clear();
+
+        // Download speed
+        let dl_item = make_info_item("", mtm);
+        menu.addItem(&dl_item);
+        self.net_reading_items.push(dl_item);
+
+        // Upload speed
+        let ul_item = make_info_item("", mtm);
+        menu.addItem(&ul_item);
+        self.net_reading_items.push(ul_item);
+
+        // Latency to the ping target, when set
+        self.net_latency_item = None;
+        if self.latency_warn_ms.is_some() {
+            let item = make_info_item("", mtm);
+            menu.addItem(&item);
+            self.net_latency_item = Some(item);
+        }
+
+        menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+        // Traffic since launch (or the last reset)
+        let session = make_info_item("", mtm);
+        menu.addItem(&session);
+        self.net_reading_items.push(session);
+
+        // Traffic since local midnight
+        let today = make_info_item("", mtm);
+        menu.addItem(&today);
+        self.net_reading_items.push(today);
+
+        // Interface counters, which start at boot
+        let since_boot = make_info_item("", mtm);
+        menu.addItem(&since_boot);
+        self.net_reading_items.push(since_boot);
+
+        MENU_ACTIONS.with(|actions| {
+            let mut actions = actions.borrow_mut();
+            actions.retain(|k, _| *k < 500 || *k >= 600);
+            let reset_item = unsafe { make_action_item(t("Reset Session Counters"), 500, mtm) };
+            menu.addItem(&reset_item);
+            actions.insert(500, TrayAction::ResetSessionCounters);
+            let reset_today = unsafe { make_action_item(t("Reset Today"), 501, mtm) };
+            menu.addItem(&reset_today);
+            actions.insert(501, TrayAction::ResetDailyTraffic);
+
+            // Menu Bar Shows: rates, or today's total on metered connections
+            let display_sub_item = NSMenuItem::new(mtm);
+            display_sub_item.setTitle(&NSString::from_str(t("Menu Bar Shows")));
+            let display_sub = NSMenu::new(mtm);
+            let mut tag = 502;
+            for (display, label) in [
+                (NetDisplay::Speed, "Speed"),
+                (NetDisplay::DailyTotal, "Daily Total"),
+            ] {
+                let item = unsafe { make_action_item(t(label), tag, mtm) };
+                if display == self.net_display {
+                    item.setState(NSControlStateValueOn);
+                }
+                actions.insert(tag, TrayAction::SetNetDisplay(display));
+                tag += 1;
+                display_sub.addItem(&item);
+            }
+            display_sub_item.setSubmenu(Some(&display_sub));
+            menu.addItem(&display_sub_item);
+        });
+
+        menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+        // Public IP and VPN, when enabled
+        self.net_ip_items.clear();
+        if stats.network.public_ip.is_some() {
+            for _ in 0..2 {
+                let item = make_info_item("", mtm);
+                menu.addItem(&item);
+                self.net_ip_items.push(item);
+            }
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+        }
+
+        // Connections, with the listening ports in a submenu
+        self.net_connections_item = None;
+        self.net_listening_lines.clear();
+        if stats.network.connections.is_some() {
+            let item = make_info_item("", mtm);
+            menu.addItem(&item);
+            self.net_connections_item = Some(item);
+            menu.addItem(&NSMenuItem::separatorItem(mtm));
+        }
+
+        // Wi-Fi: SSID, signal, channel, rate — or a single "n/a" line
+        self.net_wifi_items.clear();
+        let wifi_lines = if stats.network.wifi.is_some() { 4 } else { 1 };
+        for _ in 0..wifi_lines {
+            let item = make_info_item("", mtm);
+            menu.addItem(&item);
+            self.net_wifi_items.push(item);
+        }
+
+        self.update_net_menu(stats);
+
+        self.add_move_items(Module::Net, &menu);
+        self.attach_module_menu(Module::Net, &menu);
+        self.net_menu = Some(menu);
+    }
+
+    fn update_net_menu(&mut self, stats: &SystemStats) {
+        let mtm = self.mtm;
+        let net = &stats.network;
+        if let Some(item) = self.net_reading_items.get(0) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: {} /s",
+                    t("Download"),
+                    format_speed(net.received_per_sec)
+                ),
+                mtm,
+            );
+        }
+        if let Some(item) = self.net_reading_items.get(1) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: {} /s",
+                    t("Upload"),
+                    format_speed(net.transmitted_per_sec)
+                ),
+                mtm,
+            );
+        }
+        if let Some(item) = self.net_reading_items.get(2) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: D {}  U {}",
+                    t("Session"),
+                    format_bytes(net.session_received_bytes),
+                    format_bytes(net.session_transmitted_bytes)
+                ),
+                mtm,
+            );
+        }
+        if let Some(item) = self.net_reading_items.get(3) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: D {}  U {}",
+                    t("Today"),
+                    format_bytes(net.today_received_bytes),
+                    format_bytes(net.today_transmitted_bytes)
+                ),
+                mtm,
+            );
+        }
+        if let Some(item) = self.net_reading_items.get(4) {
+            set_menu_item_white(
+                item,
+                &format!(
+                    "{}: D {}  U {}",
+                    t("Since boot"),
+                    format_bytes(net.total_received_bytes),
+                    format_bytes(net.total_transmitted_bytes)
+                ),
+                mtm,
+            );
+        }
+
+        // If Wi-Fi, the public IP, latency or connection lines appeared or
+        // disappeared, rebuild
+        let wifi_lines = if net.wifi.is_some() { 4 } else { 1 };
+        let ip_lines = if net.public_ip.is_some() { 2 } else { 0 };
+        if wifi_lines != self.net_wifi_items.len()
+            || ip_lines != self.net_ip_items.len()
+            || net.connections.is_some() != self.net_connections_item.is_some()
+            || self.latency_warn_ms.is_some() != self.net_latency_item.is_some()
+        {
+            self.net_menu = None;
+            self.net_reading_items.clear();
+            self.net_wifi_items.clear();
+            self.net_ip_items.clear();
+            self.net_connections_item = None;
+            self.net_latency_item = None;
+            return;
+        }
+        if let (Some(warn_ms), Some(item)) = (self.latency_warn_ms, &self.net_latency_item) {
+            let line = format!(
+                "{}: {}",
+                t("Latency"),
+                match net.latency_ms {
+                    Some(ms) => format!("{:.0} ms", ms),
+                    None => t("timeout").to_string(),
+                }
+            );
+            if net.latency_ms.is_some_and(|ms| ms > warn_ms as f64) {
+                set_menu_item_colored(item, &line, &NSColor::systemRedColor(), mtm);
+            } else {
+                set_menu_item_white(item, &line, mtm);
+            }
+        }
+        if let (Some(connections), Some(item)) = (&net.connections, &self.net_connections_item) {
+            set_menu_item_white(
+                item,
+                &tf(
+                    "Connections: {} ({} listening)",
+                    &[
+                        &connections.established.to_string(),
+                        &connections.listening.len().to_string(),
+                    ],
+                ),
+                mtm,
+            );
+            let lines: Vec<String> = connections
+                .listening
+                .iter()
+                .map(|l| format!("{}  {} ({})", l.port, l.process, l.address))
+                .collect();
+            if lines != self.net_listening_lines {
+                let sub = NSMenu::new(mtm);
+                for line in &lines {
+                    sub.addItem(&make_info_item(line, mtm));
+                }
+                if lines.is_empty() {
+                    sub.addItem(&make_info_item(t("No listening ports"), mtm));
+                }
+                item.setSubmenu(Some(&sub));
+                self.net_listening_lines = lines;
+            }
+        }
+        if let Some(public_ip) = &net.public_ip {
+            let ip_text = [
+                format!(
+                    "{}: {}",
+                    t("Public IP"),
+                    public_ip.address.as_deref().unwrap_or(t("unavailable"))
+                ),
+                format_vpn_line(&public_ip.vpn_interfaces),
+            ];
+            for (item, text) in self.net_ip_items.iter().zip(ip_text.iter()) {
+                set_menu_item_white(item, text, mtm);
+            }
+        }
+        let Some(wifi) = &net.wifi else {
+            if let Some(item) = self.net_wifi_items.first() {
+                set_menu_item_white(item, &format!("Wi-Fi: {}", t("n/a")), mtm);
+            }
+            return;
+        };
+        let wifi_text = [
+            format!("Wi-Fi: {}", wifi.ssid.as_deref().unwrap_or(t("(hidden)"))),
+            format!(
+                "{}: {} dBm  {}: {} dBm",
+                t("Signal"),
+                wifi.rssi_dbm,
+                t("Noise"),
+                wifi.noise_dbm
+            ),
+            format!("{}: {}", t("Channel"), wifi.channel),
+            format!("{}: {} Mbps", t("Tx Rate"), wifi.tx_rate_mbps),
+        ];
+        for (item, text) in self.net_wifi_items.iter().zip(wifi_text.iter()) {
+            set_menu_item_white(item, text, mtm);
+        }
+        if let Some(item) = self.net_wifi_items.get(1) {
+            if wifi.rssi_dbm < WEAK_WIFI_RSSI_DBM {
+                set_menu_item_colored(item, &wifi_text[1], &NSColor::systemRedColor(), mtm);
+            }
+        }
+    }
+
+    /// `alerting` holds the kinds of the alerts that are active, as
+    /// `AlertManager::active_kinds` reports them.
+    pub fn update(
+        &mut self,
+        stats: &SystemStats,
+        history: &HistoryBuffer,
+        config: &Config,
+        alerting: &HashSet<&'static str>,
+    ) {
+        let order = config.module_order();
+        if order != self.module_order {
+            self.module_order = order;
+            if self.items.is_some() {
+                self.recreate_items();
+            }
+        }
+        self.ensure_items();
+        if self.items.is_none() {
+            return;
+        }
+        self.check_menu_bar_space(Instant::now());
+        self.last_cpu_usage = stats.cpu.global_usage;
+
+        if self.runner.sync_config(config) {
+            self.invalidate_cpu_menu();
+        }
+
+        self.color_thresholds = config.effective_color_thresholds();
+        self.net_display = config.net_display;
+        self.latency_warn_ms = config.ping_target.as_ref().map(|_| config.latency_warn_ms);
+        let mtm = self.mtm;
+        let items = self.items.as_ref().unwrap();
+        let colors = self.color_thresholds.as_ref();
+
+        let trends = module_trends(history, config);
+        self.net_trend = trends.net;
+
+        // CPU
+        let cpu_pct = format_percent(to_total_cpu_percent(stats));
+        if let Some(frame) = self.runner.current_frame() {
+            self.apply_runner_frame(Some(frame.as_ref()));
+        }
+        let now = Instant::now();
+        if self.module_width != Some(config.module_width) {
+            self.module_width = Some(config.module_width);
+            FIXED_MODULE_WIDTH.with(|w| w.set(config.module_width));
+            for item in [
+                &items.mem,
+                &items.disk,
+                &items.net,
+                &items.temp,
+                &items.digest,
+            ] {
+                item.setLength(config.module_width.unwrap_or(MODULE_WIDTH));
+                forget_title(&**item);
+            }
+            // Re-applies the CPU width, which depends on the style
+            self.cpu_style = None;
+        }
+        let cpu_style = config.cpu_status_style;
+        if self.cpu_style != Some(cpu_style) {
+            self.cpu_style = Some(cpu_style);
+            self.cpu_flash = ThresholdFlash::default();
+            setup_cpu_item(&items.cpu, cpu_style, mtm);
+        }
+        if cpu_style != CpuStatusStyle::Text {
+            set_cpu_sparkline(&items.cpu, &history.cpu_usage, mtm);
+        }
+        if cpu_style != CpuStatusStyle::Sparkline {
+            self.cpu_flash.observe(
+                ModuleTitle {
+                    line1: cpu_pct,
+                    line2: cpu_label(stats, config),
+                    module: Module::Cpu,
+                    value: stats.cpu.global_usage,
+                    trend: trends.cpu,
+                    alert: alerting.contains("cpu"),
+                },
+                colors,
+                now,
+            );
+            self.cpu_flash.draw(&items.cpu, colors, now, mtm);
+        }
+
+        // Memory
+        let mem_pct = format_percent(stats.memory.usage_percent);
+        self.mem_flash.observe(
+            ModuleTitle {
+                line1: mem_pct,
+                line2: "MEM".to_string(),
+                module: Module::Mem,
+                value: stats.memory.usage_percent,
+                trend: trends.mem,
+                alert: alerting.contains("memory"),
+            },
+            colors,
+            now,
+        );
+        self.mem_flash.draw(&items.mem, colors, now, mtm);
+
+        // Disk
+        let disk_usage = stats.disks.first().map(|d| d.usage_percent).unwrap_or(0.0);
+        let disk_pct = stats
+            .disks
+            .first()
+            .map(|d| format_percent(d.usage_percent))
+            .unwrap_or_else(|| "--%".to_string());
+        self.disk_flash.observe(
+            ModuleTitle {
+                line1: disk_pct,
+                line2: "SSD".to_string(),
+                module: Module::Disk,
+                value: disk_usage,
+                trend: trends.disk,
+                alert: alerting.contains("disk_health"),
+            },
+            colors,
+            now,
+        );
+        self.disk_flash.draw(&items.disk, colors, now, mtm);
+
+        self.draw_net(stats);
+        let idle_bytes = config.auto_hide.net_idle_kbps * 1024;
+        let net_idle = stats.network.received_per_sec < idle_bytes
+            && stats.network.transmitted_per_sec < idle_bytes;
+        self.net_auto_hide.observe(
+            config.auto_hide.net && net_idle,
+            config.auto_hide.after_polls,
+        );
+
+        // Temperature: one sensor, or cycling through CPU/GPU/SSD
+        let temp_c = if let Some(secs) = config.temp_cycle_secs {
+            let titles: Vec<ModuleTitle> = TEMP_CYCLE_LABELS
+                .iter()
+                .filter_map(|label| {
+                    let reading = stats
+                        .temperature
+                        .readings
+                        .iter()
+                        .find(|r| r.label == *label)?;
+                    Some(ModuleTitle {
+                        line1: format_temp(Some(reading.temp_c)),
+                        line2: label.to_string(),
+                        module: Module::Temp,
+                        value: reading.temp_c,
+                        trend: trends.temp(label),
+                        alert: alerting.contains("temperature"),
+                    })
+                })
+                .collect();
+            let hottest_c = titles.iter().map(|t| t.value).fold(0.0, f32::max);
+            self.temp_cycle.set_titles(titles);
+            self.temp_cycle.interval = Some(Duration::from_secs(secs.max(1)));
+            // Flashing on every switch to a hot sensor would be noise
+            self.temp_flash = ThresholdFlash::default();
+            self.temp_cycle.draw(&items.temp, colors, mtm);
+            hottest_c
+        } else {
+            self.temp_cycle = TempCycle::default();
+            let style = config.temp_label_style;
+            let temp_reading = menu_bar_temp(stats, config);
+            let temp_val = format_temp(temp_reading.map(|r| r.temp_c));
+            let temp_c = temp_reading.map(|r| r.temp_c).unwrap_or(0.0);
+            let temp_label = match temp_reading {
+                Some(r) if style != TempLabelStyle::Static => short_temp_label(&r.label),
+                _ => "TEMP".to_string(),
+            };
+            self.temp_flash.observe(
+                ModuleTitle {
+                    line1: temp_val,
+                    line2: temp_label,
+                    module: Module::Temp,
+                    value: temp_c,
+                    trend: temp_reading.and_then(|r| trends.temp(&r.label)),
+                    alert: alerting.contains("temperature"),
+                },
+                colors,
+                now,
+            );
+            self.temp_flash.draw(&items.temp, colors, now, mtm);
+            temp_c
+        };
+        let temp_cool = temp_c < config.auto_hide.temp_cool_c;
+        self.temp_auto_hide.observe(
+            config.auto_hide.temp && temp_cool,
+            config.auto_hide.after_polls,
+        );
+
+        self.apply_module_visibility(config);
+        self.update_digest(stats, config, alerting, now);
+
+        self.history_summary =
+            format_history_summary(history, config.poll_interval().as_secs_f64());
+        self.update_menus(stats, config);
+    }
+
+    /// Show the modules `visible_modules` lists, less the auto-hidden ones
+    fn apply_module_visibility(&mut self, config: &Config) {
+        let Some(items) = &self.items else {
+            return;
+        };
+        let hidden: Vec<Module> = Module::ALL
+            .into_iter()
+            .filter(|&module| {
+                let auto_hidden = match module {
+                    Module::Net => self.net_auto_hide.hidden,
+                    Module::Temp => self.temp_auto_hide.hidden,
+                    _ => false,
+                };
+                auto_hidden || !config.module_visible(module.id())
+            })
+            .collect();
+        if self.hidden_modules.as_ref() == Some(&hidden) {
+            return;
+        }
+        for module in Module::ALL {
+            set_status_item_visible(items.get(module), !hidden.contains(&module));
+        }
+        self.hidden_modules = Some(hidden);
+    }
+
+    /// Digest item: whichever metric is closest to its alert threshold, and
+    /// a dropdown ranking them all
+    fn update_digest(
+        &mut self,
+        stats: &SystemStats,
+        config: &Config,
+        alerting: &HashSet<&'static str>,
+        now: Instant,
+    ) {
+        let Some(items) = &self.items else {
+            return;
+        };
+        let visible = config.module_visible(DIGEST_MODULE);
+        if self.digest.visible != Some(visible) {
+            self.digest.visible = Some(visible);
+            set_status_item_visible(&items.digest, visible);
+        }
+        if !visible {
+            return;
+        }
+        let ranked = rank_digest_metrics(stats, &config.alert_thresholds);
+        let mtm = self.mtm;
+        match self.digest.pick(&ranked, now) {
+            Some(metric) => {
+                let value = metric.value_text();
+                let line1 = if alerting.contains(metric.alert_kind()) {
+                    format!("{}{}", ALERT_MARK, value)
+                } else {
+                    value
+                };
+                set_module_title(
+                    &items.digest,
+                    &line1,
+                    module_label(metric.module),
+                    Some((metric.module, metric.value)),
+                    self.color_thresholds.as_ref(),
+                    None,
+                    mtm,
+                );
+            }
+            None => set_module_title(&items.digest, "--", "TOP", None, None, None, mtm),
+        }
+
+        if self.digest_menu.is_some() && self.digest_items.len() != ranked.len() {
+            self.digest_menu = None;
+        }
+        if self.digest_menu.is_none() {
+            let menu = NSMenu::new(mtm);
+            menu.setAutoenablesItems(false);
+            menu.addItem(&make_info_item(t("Closest to Alert"), mtm));
+            self.digest_items = ranked
+                .iter()
+                .map(|_| {
+                    let item = make_info_item("", mtm);
+                    menu.addItem(&item);
+                    item
+                })
+                .collect();
+            attach_menu(&items.digest, &menu);
+            self.digest_menu = Some(menu);
+        } else if self.holds_updates(&self.digest_menu) {
+            return;
+        }
+        for (metric, item) in ranked.iter().zip(&self.digest_items) {
+            let line = tf(
+                "{} {} ({}% of alert at {})",
+                &[
+                    module_label(metric.module),
+                    &metric.value_text(),
+                    &format!("{:.0}", metric.ratio() * 100.0),
+                    &metric.threshold_text(),
+                ],
+            );
+            set_menu_item_white(item, &line, mtm);
+        }
+    }
+
+    /// Between full polls: only the NET item and its menu, leaving the
+    /// per-poll state (flashes, auto-hide counts) alone.
+    pub fn update_net(&mut self, stats: &SystemStats) {
+        if self.items.is_none() {
+            return;
+        }
+        self.draw_net(stats);
+        self.ensure_net_menu(stats);
+    }
+
+    /// NET title: no label line; both lines already carry a direction arrow,
+    /// and the trend of the rates follows the second. Rates, or with the
+    /// daily total display, bytes moved today (which only ever rise, so
+    /// without a trend).
+    fn draw_net(&self, stats: &SystemStats) {
+        let Some(items) = &self.items else {
+            return;
+        };
+        let net = &stats.network;
+        let (up, down) = match self.net_display {
+            NetDisplay::Speed => (net.transmitted_per_sec, net.received_per_sec),
+            NetDisplay::DailyTotal => (net.today_transmitted_bytes, net.today_received_bytes),
+        };
+        let net_up = format!("↑{}", format_speed(up));
+        let net_dn = format!("↓{}", format_speed(down));
+        let weak_wifi = stats
+            .network
+            .wifi
+            .as_ref()
+            .filter(|w| w.rssi_dbm < WEAK_WIFI_RSSI_DBM)
+            .map(|w| wifi_signal_badness(w.rssi_dbm));
+        set_module_title(
+            &items.net,
+            &net_up,
+            &net_dn,
+            weak_wifi.map(|badness| (Module::Net, badness)),
+            self.color_thresholds.as_ref(),
+            self.net_trend
+                .filter(|_| self.net_display == NetDisplay::Speed),
+            self.mtm,
+        );
+    }
+
+    /// Build or update the dropdowns in place, leaving an open one alone
+    /// unless `open_menu_updates` is Live.
+    pub fn update_menus(&mut self, stats: &SystemStats, config: &Config) {
+        if self.items.is_none() {
+            return;
+        }
+        self.open_menu_updates = config.open_menu_updates;
+        self.ensure_temp_menu(stats, config);
+        self.ensure_cpu_menu(stats, config);
+        self.ensure_mem_menu(stats);
+        self.ensure_disk_menu(stats, config);
+        self.ensure_net_menu(stats);
+    }
+
+    /// Whether `menu` is showing and its readings wait until it closes
+    fn holds_updates(&self, menu: &Option<Retained<NSMenu>>) -> bool {
+        self.open_menu_updates != OpenMenuUpdates::Live
+            && menu
+                .as_ref()
+                .is_some_and(|menu| Retained::as_ptr(menu) as usize == OPEN_MENU.with(Cell::get))
+    }
+}
+
+// ── Title renderer ──
+
+fn to_total_cpu_percent(stats: &SystemStats) -> f32 {
+    stats.cpu.global_usage
+}
+
+/// Two-line module title: line1 (value) + line2 (label)
+/// If metric is Some, line1 is colored from that module's table in `colors`;
+/// otherwise (or with colors off) it uses label color.
+/// A trend arrow, if given, is appended to line2 in a muted color. The
+/// tooltip repeats both lines, arrow included.
+fn set_module_title(
+    item: &NSStatusItem,
+    line1: &str,
+    line2: &str,
+    metric: Option<(Module, f32)>,
+    colors: Option<&ColorThresholds>,
+    trend: Option<Trend>,
+    mtm: MainThreadMarker,
+) {
+    let line2 = match trend {
+        Some(trend) => format!("{} {}", line2, trend.arrow()),
+        None => line2.to_string(),
+    };
+    let text = format!("{}\n{}", line1, line2);
+    // The menu bar can be light while the app is dark, so ask the button
+    let dark = item.button(mtm).map_or(true, |button| unsafe {
+        appearance::is_dark(Retained::as_ptr(&button).cast())
+    });
+    let value_color = metric.and_then(|(module, value)| module_color(colors, module, value));
+    // System colors are shared instances, so the pointer identifies the color
+    let color_id = value_color
+        .as_ref()
+        .map_or(0, |c| Retained::as_ptr(c) as usize);
+    if !title_changed(item, format!("{}\0{:x}\0{}", text, color_id, dark)) {
+        return;
+    }
+    let value_color = if dark {
+        value_color
+    } else {
+        value_color.map(readable_on_light)
+    };
+    if let Some(button) = item.button(mtm) {
+        unsafe {
+            let ns_text = NSString::from_str(&text);
+            let attr_str = NSMutableAttributedString::initWithString(
+                NSMutableAttributedString::alloc(),
+                &ns_text,
+            );
+            let full_len = text.encode_utf16().count();
+            let full_range = NSRange::new(0, full_len);
+
+            MODULE_TITLE_ATTRS.with(|attrs| {
+                attr_str.addAttribute_value_range(ns_string!("NSFont"), &attrs.font, full_range);
+                attr_str.addAttribute_value_range(
+                    ns_string!("NSParagraphStyle"),
+                    &attrs.paragraph,
+                    full_range,
+                );
+                attr_str.addAttribute_value_range(
+                    ns_string!("NSBaselineOffset"),
+                    &attrs.baseline_offset,
+                    full_range,
+                );
+            });
+
+            // Colors: line1 colored (if value provided), line2 always label color
+            let color_key = ns_string!("NSColor");
+            let line1_len = line1.encode_utf16().count();
+            if line1.starts_with(ALERT_MARK) {
+                // Active alert: white on red, whatever the color thresholds say
+                let line1_range = NSRange::new(0, line1_len);
+                let red = NSColor::systemRedColor();
+                attr_str.addAttribute_value_range(
+                    ns_string!("NSBackgroundColor"),
+                    &red,
+                    line1_range,
+                );
+                attr_str.addAttribute_value_range(color_key, &NSColor::whiteColor(), line1_range);
+                let label_color = NSColor::labelColor();
+                let line2_range = NSRange::new(line1_len + 1, full_len - line1_len - 1);
+                attr_str.addAttribute_value_range(color_key, &label_color, line2_range);
+            } else if let Some(value_color) = value_color {
+                let line1_range = NSRange::new(0, line1_len);
+                attr_str.addAttribute_value_range(color_key, &value_color, line1_range);
+
+                let label_color = NSColor::labelColor();
+                let line2_range = NSRange::new(line1_len + 1, full_len - line1_len - 1);
+                attr_str.addAttribute_value_range(color_key, &label_color, line2_range);
+            } else {
+                let label_color = NSColor::labelColor();
+                attr_str.addAttribute_value_range(color_key, &label_color, full_range);
+            }
+            if let Some(trend) = trend {
+                let arrow_len = trend.arrow().encode_utf16().count();
+                let arrow_range = NSRange::new(full_len - arrow_len, arrow_len);
+                let muted = NSColor::secondaryLabelColor();
+                attr_str.addAttribute_value_range(color_key, &muted, arrow_range);
+            }
+
+            let _: () = msg_send![&button, setAttributedTitle: &*attr_str];
+            button.setToolTip(Some(&NSString::from_str(&text.replace('\n', " "))));
+            if FIXED_MODULE_WIDTH.with(Cell::get).is_none() {
+                let size: NSSize = msg_send![&*attr_str, size];
+                let mut width =
+                    (size.width + MODULE_WIDTH_PADDING).clamp(MODULE_WIDTH_MIN, MODULE_WIDTH_MAX);
+                if button.imagePosition() == NSCellImagePosition::ImageLeft {
+                    width += sparkline::SPARKLINE_WIDTH + 4.0;
+                }
+                if (width - item.length()).abs() > MODULE_WIDTH_DAMPING {
+                    item.setLength(width);
+                }
+            }
+        }
+    }
+}
+
+/// Sensors the TEMP item cycles through when `temp_cycle_secs` is set
+const TEMP_CYCLE_LABELS: [&str; 3] = ["CPU", "GPU", "SSD"];
+/// Prefix of a value line whose alert is active; drawn on a red background
+const ALERT_MARK: &str = "⚠";
+
+/// Width, image position and title for a CPU status style.
+fn setup_cpu_item(item: &NSStatusItem, style: CpuStatusStyle, mtm: MainThreadMarker) {
+    let sparkline_width = sparkline::SPARKLINE_WIDTH + 4.0;
+    let module_width = FIXED_MODULE_WIDTH.with(Cell::get).unwrap_or(MODULE_WIDTH);
+    let (length, position) = match style {
+        CpuStatusStyle::Text => (module_width, NSCellImagePosition::NoImage),
+        CpuStatusStyle::Sparkline => (sparkline_width, NSCellImagePosition::ImageOnly),
+        CpuStatusStyle::Both => (
+            module_width + sparkline_width,
+            NSCellImagePosition::ImageLeft,
+        ),
+    };
+    item.setLength(length);
+    if let Some(button) = item.button(mtm) {
+        button.setImagePosition(position);
+        if style == CpuStatusStyle::Text {
+            button.setImage(None);
+        }
+        if style == CpuStatusStyle::Sparkline {
+            button.setTitle(&NSString::from_str(""));
+        }
+    }
+    // Re-render (and, in auto width, re-measure) the title for the new layout
+    forget_title(item);
+}
+
+/// Draw the CPU history into the CPU button's image at the screen's scale.
+fn set_cpu_sparkline(item: &NSStatusItem, cpu_usage: &VecDeque<f32>, mtm: MainThreadMarker) {
+    let Some(button) = item.button(mtm) else {
+        return;
+    };
+    let scale = unsafe {
+        let window: *mut AnyObject = msg_send![&button, window];
+        if window.is_null() {
+            2.0
+        } else {
+            let scale: f64 = msg_send![window, backingScaleFactor];
+            scale
+        }
+    };
+    if let Some(image) = sparkline::render(cpu_usage, scale) {
+        button.setImage(Some(&image));
+    }
+}
+
+/// AppKit keeps a status item's button highlighted while its menu is open.
+fn menu_is_open(item: &NSStatusItem, mtm: MainThreadMarker) -> bool {
+    item.button(mtm).is_some_and(|button| unsafe {
+        let highlighted: bool = msg_send![&button, isHighlighted];
+        highlighted
+    })
+}
+
+/// Whether a status item got a place in the menu bar. One that doesn't fit
+/// keeps its button, but the button's window never shows on screen.
+fn has_room(item: &NSStatusItem, mtm: MainThreadMarker) -> bool {
+    let Some(button) = item.button(mtm) else {
+        return false;
+    };
+    unsafe {
+        // Auto-hidden items are out of the bar on purpose
+        let visible: bool = msg_send![item, isVisible];
+        if !visible {
+            return true;
+        }
+        let window: *const AnyObject = msg_send![&button, window];
+        if window.is_null() {
+            return false;
+        }
+        let occlusion: usize = msg_send![window, occlusionState];
+        occlusion & WINDOW_OCCLUSION_VISIBLE != 0
+    }
+}
+
+/// Name a module goes by on its status item
+fn module_label(module: Module) -> &'static str {
+    match module {
+        Module::Cpu => "CPU",
+        Module::Mem => "MEM",
+        Module::Disk => "SSD",
+        Module::Net => "NET",
+        Module::Temp => "TEMP",
+    }
+}
+
+fn set_status_item_visible(item: &NSStatusItem, visible: bool) {
+    unsafe {
+        let _: () = msg_send![item, setVisible: visible];
+    }
+}
+
+/// Skip attention-grabbing animation under Reduce Motion, or while another
+/// app owns the menu bar (full screen, presenting).
+fn motion_allowed() -> bool {
+    if motion::reduce_motion() {
+        return false;
+    }
+    unsafe {
+        if let Some(app_class) = AnyClass::get(c"NSApplication") {
+            let app: *mut AnyObject = msg_send![app_class, sharedApplication];
+            if !app.is_null() {
+                let options: usize = msg_send![app, currentSystemPresentationOptions];
+                if options != 0 {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Color for a module value: the color of the highest stop at or below it.
+/// None (label color) when coloring is off.
+fn module_color(
+    colors: Option<&ColorThresholds>,
+    module: Module,
+    value: f32,
+) -> Option<Retained<NSColor>> {
+    let Some(stops) = colors?.stops(module) else {
+        return Some(get_color_for_value(value));
+    };
+    stops
+        .iter()
+        .filter(|stop| value >= stop.at)
+        .max_by(|a, b| a.at.total_cmp(&b.at))
+        .map(|stop| parse_color_name(&stop.color))
+}
+
+/// Highest threshold in the module's table (its "red zone")
+fn top_threshold(colors: Option<&ColorThresholds>, module: Module) -> Option<f32> {
+    colors?
+        .stops(module)?
+        .iter()
+        .map(|stop| stop.at)
+        .reduce(f32::max)
+}
+
+/// System color for a config color name; unknown names fall back to label color.
+fn parse_color_name(name: &str) -> Retained<NSColor> {
+    match name.trim().to_lowercase().as_str() {
+        "green" => NSColor::systemGreenColor(),
+        "yellow" => NSColor::systemYellowColor(),
+        "orange" => NSColor::systemOrangeColor(),
+        "red" => NSColor::systemRedColor(),
+        "purple" => NSColor::systemPurpleColor(),
+        "pink" => NSColor::systemPinkColor(),
+        "blue" => NSColor::systemBlueColor(),
+        "teal" => NSColor::systemTealColor(),
+        "gray" | "grey" => NSColor::systemGrayColor(),
+        _ => NSColor::labelColor(),
+    }
+}
+
+/// System yellow all but disappears on a light or tinted menu bar; a darker
+/// amber keeps its meaning. The other system colors read well enough.
+fn readable_on_light(color: Retained<NSColor>) -> Retained<NSColor> {
+    if Retained::as_ptr(&color) == Retained::as_ptr(&NSColor::systemYellowColor()) {
+        NSColor::colorWithSRGBRed_green_blue_alpha(0.72, 0.5, 0.0, 1.0)
+    } else {
+        color
+    }
+}
+
+fn get_color_for_value(value: f32) -> Retained<NSColor> {
+    if value >= 80.0 {
+        NSColor::systemRedColor()
+    } else if value >= 60.0 {
+        NSColor::systemPurpleColor()
+    } else if value >= 30.0 {
+        NSColor::systemYellowColor()
+    } else {
+        NSColor::systemGreenColor()
+    }
+}
+
+/// Map RSSI onto the 0-100 color scale: -50 dBm or better → 0, -90 dBm → 100
+fn wifi_signal_badness(rssi_dbm: i32) -> f32 {
+    ((-50 - rssi_dbm) as f32 * 2.5).clamp(0.0, 100.0)
+}
@@ -0,0 +1,1314 @@
+use super::SPEED_PREVIEW;
+use crate::config::{
+    config_dir, Config, CustomRunnerSet, RunnerIconMode, RunnerIdleBehavior,
+    CUSTOM_RUNNER_CATEGORY, OTHER_RUNNER_CATEGORY,
+};
+use crate::issues;
+use crate::ui::motion;
+use objc2::rc::Retained;
+use objc2::AnyThread;
+use objc2_app_kit::{NSBundleImageExtension, NSImage};
+use objc2_foundation::{NSBundle, NSSize, NSString};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const EMBEDDED_RUN_CAT_UI_BUNDLE_RELATIVE: &str = "LocalPackage_UserInterface.bundle";
+const EMBEDDED_RUN_CAT_UI_ASSETS_RELATIVE: &str =
+    "LocalPackage_UserInterface.bundle/Contents/Resources/Assets.car";
+const EXPORTED_RUN_CAT_FRAMES_RELATIVE: &str = "runcat-frames";
+const EXPORTED_RUN_CAT_FRAMES_WHITE_RELATIVE: &str = "runcat-frames-white";
+/// How long the CPU must stay below the idle threshold before the runner sleeps
+const RUNNER_IDLE_AFTER: Duration = Duration::from_secs(3);
+
+#[derive(Clone)]
+pub struct RunnerMenuOption {
+    pub id: String,
+    pub title: String,
+}
+
+/// Where a runner's first frame file is, for lookups off the main thread
+enum FirstFrameSource {
+    /// A RunCat set's exported folders, in order of preference
+    Exported {
+        prefix: String,
+        relatives: Vec<&'static str>,
+    },
+    /// A custom set's frame files, in order
+    Files(Vec<String>),
+}
+
+/// First-frame files found in the background: (generation, runner id,
+/// file); None when the runner has no such file on disk
+type ReadyPreviews = Arc<Mutex<Vec<(u64, String, Option<PathBuf>)>>>;
+
+/// Where the runner's images come from. The app reads RunCat's bundle,
+/// frame folders and custom sets; tests hand out plain values, so rotation
+/// and frame timing run without AppKit.
+pub trait FrameProvider {
+    type Frame: Clone;
+
+    /// Runners besides the custom sets, in menu order
+    fn runner_sets(&self) -> Vec<RunnerMenuOption>;
+
+    /// Frames of `runner_id`, and whether they are already white for the
+    /// white icon mode
+    fn frames(
+        &self,
+        runner_id: &str,
+        custom_sets: &[CustomRunnerSet],
+        icon_mode: RunnerIconMode,
+    ) -> (Vec<Self::Frame>, bool);
+
+    /// Dedicated sleep image of `runner_id`, if it ships one
+    fn sleep_frame(&self, runner_id: &str, precolored_white: bool) -> Option<Self::Frame>;
+
+    /// Shown while a runner has no frames of its own
+    fn fallback_frames(&self) -> Vec<Self::Frame>;
+}
+
+/// RunCat's bundle and exported frames, custom sets and SF Symbols
+pub struct RunCatFrames {
+    bundle: Option<Retained<NSBundle>>,
+}
+
+impl RunCatFrames {
+    fn load() -> Self {
+        Self {
+            bundle: load_run_cat_bundle(),
+        }
+    }
+
+    fn load_runcat_frames(
+        &self,
+        prefix: &str,
+        icon_mode: RunnerIconMode,
+    ) -> (Vec<Retained<NSImage>>, bool) {
+        if icon_mode == RunnerIconMode::White {
+            let white_exported = load_exported_runcat_frames_from_dir(
+                prefix,
+                EXPORTED_RUN_CAT_FRAMES_WHITE_RELATIVE,
+            );
+            if !white_exported.is_empty() {
+                return (white_exported, true);
+            }
+        }
+
+        let exported =
+            load_exported_runcat_frames_from_dir(prefix, EXPORTED_RUN_CAT_FRAMES_RELATIVE);
+        if !exported.is_empty() {
+            return (exported, false);
+        }
+
+        let Some(bundle) = &self.bundle else {
+            return (Vec::new(), false);
+        };
+        let mut frames = Vec::new();
+        for idx in 0..40 {
+            let name = NSString::from_str(&format!("{}-page-{}", prefix, idx));
+            if let Some(image) = bundle.imageForResource(&name) {
+                image.setTemplate(false);
+                frames.push(image);
+            } else if !frames.is_empty() {
+                break;
+            }
+        }
+        (frames, false)
+    }
+}
+
+impl FrameProvider for RunCatFrames {
+    type Frame = Retained<NSImage>;
+
+    fn runner_sets(&self) -> Vec<RunnerMenuOption> {
+        discover_runcat_sets(self.bundle.as_ref())
+    }
+
+    fn frames(
+        &self,
+        runner_id: &str,
+        custom_sets: &[CustomRunnerSet],
+        icon_mode: RunnerIconMode,
+    ) -> (Vec<Retained<NSImage>>, bool) {
+        if let Some(prefix) = runner_id.strip_prefix("runcat:") {
+            self.load_runcat_frames(prefix, icon_mode)
+        } else if let Some(custom_id) = runner_id.strip_prefix("custom:") {
+            let set = custom_sets.iter().find(|set| set.id == custom_id);
+            (set.map(load_custom_frames).unwrap_or_default(), false)
+        } else {
+            (fallback_frames(), false)
+        }
+    }
+
+    /// A RunCat set's `<prefix>-sleep` image
+    fn sleep_frame(&self, runner_id: &str, precolored_white: bool) -> Option<Retained<NSImage>> {
+        let prefix = runner_id.strip_prefix("runcat:")?;
+        let relative = if precolored_white {
+            EXPORTED_RUN_CAT_FRAMES_WHITE_RELATIVE
+        } else {
+            EXPORTED_RUN_CAT_FRAMES_RELATIVE
+        };
+        if let Some(image) = load_exported_runcat_sleep_frame(prefix, relative) {
+            return Some(image);
+        }
+        let bundle = self.bundle.as_ref()?;
+        let image = bundle.imageForResource(&NSString::from_str(&format!("{}-sleep", prefix)))?;
+        image.setTemplate(false);
+        Some(image)
+    }
+
+    fn fallback_frames(&self) -> Vec<Retained<NSImage>> {
+        fallback_frames()
+    }
+}
+
+pub struct RunnerAnimator<P: FrameProvider = RunCatFrames> {
+    provider: P,
+    pub icon_mode: RunnerIconMode,
+    pub active_frames_precolored_white: bool,
+    configured_runner_id: String,
+    pub selected_id: String,
+    pub rotation_ids: Vec<String>,
+    rotation_index: usize,
+    /// Rotate to a random other runner instead of the next one
+    shuffle: bool,
+    /// xorshift state for shuffling, seeded from the clock
+    rng_state: u64,
+    pub display_secs: u64,
+    frame_ms: u64,
+    frame_overrides: BTreeMap<String, u64>,
+    idle_behavior: RunnerIdleBehavior,
+    pub idle_threshold: f32,
+    /// When the CPU dropped below the idle threshold
+    idle_since: Option<Instant>,
+    sleeping: bool,
+    /// Frames hold still while a menu or chart window is in use
+    paused: bool,
+    /// Animate even with Reduce Motion on
+    ignore_reduce_motion: bool,
+    /// Sleep image for the runner id it was loaded for
+    sleep_frame: Option<(String, Option<P::Frame>)>,
+    frame_index: usize,
+    frame_accumulator: f64,
+    last_step: Instant,
+    last_runner_switch: Instant,
+    active_frames: Vec<P::Frame>,
+    default_sets: Vec<RunnerMenuOption>,
+    custom_sets_snapshot: Vec<CustomRunnerSet>,
+    /// 16 pt runner menu previews by runner id
+    preview_cache: HashMap<String, Retained<NSImage>>,
+    /// Ids whose first frame is being looked up in the background
+    preview_pending: HashSet<String>,
+    preview_ready: ReadyPreviews,
+    /// Bumped when cached previews are dropped, so late lookups are ignored
+    preview_generation: u64,
+}
+
+impl<P: FrameProvider> RunnerAnimator<P> {
+    pub fn with_provider(provider: P) -> Self {
+        let default_sets = provider.runner_sets();
+        let mut runner = Self {
+            provider,
+            icon_mode: RunnerIconMode::Original,
+            active_frames_precolored_white: false,
+            configured_runner_id: "runcat:cat".to_string(),
+            selected_id: "runcat:cat".to_string(),
+            rotation_ids: vec!["runcat:cat".to_string()],
+            rotation_index: 0,
+            shuffle: false,
+            rng_state: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+                | 1,
+            display_secs: 600,
+            frame_ms: 100,
+            frame_overrides: BTreeMap::new(),
+            idle_behavior: RunnerIdleBehavior::Animate,
+            idle_threshold: 5.0,
+            idle_since: None,
+            sleeping: false,
+            paused: false,
+            ignore_reduce_motion: false,
+            sleep_frame: None,
+            frame_index: 0,
+            frame_accumulator: 0.0,
+            last_step: Instant::now(),
+            last_runner_switch: Instant::now(),
+            active_frames: Vec::new(),
+            default_sets,
+            custom_sets_snapshot: Vec::new(),
+            preview_cache: HashMap::new(),
+            preview_pending: HashSet::new(),
+            preview_ready: Arc::new(Mutex::new(Vec::new())),
+            preview_generation: 0,
+        };
+        let (frames, precolored_white) = runner.load_frames_for_id("runcat:cat", &[]);
+        runner.active_frames = frames;
+        runner.active_frames_precolored_white = precolored_white;
+        if runner.active_frames.is_empty() {
+            runner.selected_id = "fallback:runner".to_string();
+            runner.active_frames = runner.provider.fallback_frames();
+            runner.active_frames_precolored_white = false;
+        }
+        runner
+    }
+
+    pub fn sync_config(&mut self, config: &Config) -> bool {
+        let mut changed = false;
+        if self.custom_sets_snapshot != config.custom_runner_sets {
+            // Only the previews of sets that were edited or removed go stale
+            for set in &self.custom_sets_snapshot {
+                if !config.custom_runner_sets.contains(set) {
+                    self.preview_cache.remove(&format!("custom:{}", set.id));
+                }
+            }
+            self.forget_pending_previews();
+            self.custom_sets_snapshot = config.custom_runner_sets.clone();
+            changed = true;
+        }
+
+        let display_secs = config.runner_display_secs.clamp(1, 3600);
+        if self.display_secs != display_secs {
+            self.display_secs = display_secs;
+            changed = true;
+        }
+
+        // Speed is read on every step; no reload, so the runner keeps its
+        // place in the cycle
+        self.frame_ms = config.runner_frame_ms.clamp(40, 200);
+        if self.frame_overrides != config.runner_frame_overrides {
+            self.frame_overrides = config.runner_frame_overrides.clone();
+        }
+
+        if self.icon_mode != config.runner_icon_mode {
+            self.icon_mode = config.runner_icon_mode;
+            self.sleep_frame = None;
+            // RunCat previews follow the icon mode; custom ones don't
+            self.preview_cache.retain(|id, _| !id.starts_with("runcat:"));
+            self.forget_pending_previews();
+            changed = true;
+        }
+
+        if self.idle_behavior != config.runner_idle_behavior {
+            self.idle_behavior = config.runner_idle_behavior;
+            changed = true;
+        }
+        self.idle_threshold = config.runner_idle_threshold.clamp(0.0, 100.0);
+        self.shuffle = config.runner_rotation_shuffle;
+        self.ignore_reduce_motion = config.ignore_reduce_motion;
+
+        let preferred = if self.runner_id_exists(&config.runner_id) {
+            config.runner_id.clone()
+        } else if let Some(first) = self.default_sets.first() {
+            first.id.clone()
+        } else {
+            "fallback:runner".to_string()
+        };
+        let config_runner_changed = self.configured_runner_id != config.runner_id;
+        self.configured_runner_id = config.runner_id.clone();
+
+        let mut rotation_ids: Vec<String> = config
+            .runner_rotation_ids
+            .iter()
+            .filter(|id| self.runner_id_exists(id))
+            .cloned()
+            .collect();
+        if rotation_ids.is_empty() {
+            rotation_ids.push(preferred.clone());
+        }
+        if !rotation_ids.iter().any(|id| id == &preferred) {
+            rotation_ids.insert(0, preferred.clone());
+        }
+        if self.rotation_ids != rotation_ids {
+            self.rotation_ids = rotation_ids;
+            changed = true;
+        }
+
+        let mut desired = if config_runner_changed {
+            preferred
+        } else if self.rotation_ids.iter().any(|id| id == &self.selected_id) {
+            self.selected_id.clone()
+        } else {
+            self.rotation_ids
+                .first()
+                .cloned()
+                .unwrap_or_else(|| "fallback:runner".to_string())
+        };
+
+        let desired_idx = self
+            .rotation_ids
+            .iter()
+            .position(|id| id == &desired)
+            .unwrap_or(0);
+        if self.rotation_index != desired_idx {
+            self.rotation_index = desired_idx;
+            changed = true;
+        }
+        if let Some(actual) = self.rotation_ids.get(self.rotation_index) {
+            desired = actual.clone();
+        }
+
+        if self.selected_id != desired {
+            self.selected_id = desired.clone();
+            changed = true;
+        }
+
+        if changed {
+            let (frames, precolored_white) =
+                self.load_frames_for_id(&self.selected_id, &self.custom_sets_snapshot);
+            self.active_frames = frames;
+            self.active_frames_precolored_white = precolored_white;
+            if self.active_frames.is_empty() {
+                self.active_frames = self.provider.fallback_frames();
+                self.active_frames_precolored_white = false;
+            }
+            self.frame_index = 0;
+            self.frame_accumulator = 0.0;
+            let now = Instant::now();
+            self.last_step = now;
+            self.last_runner_switch = now;
+        }
+
+        if self.active_frames.is_empty() {
+            self.active_frames = self.provider.fallback_frames();
+            self.active_frames_precolored_white = false;
+            return true;
+        }
+
+        changed
+    }
+
+    pub fn menu_options(&self) -> Vec<RunnerMenuOption> {
+        let mut options = self.default_sets.clone();
+        for custom in &self.custom_sets_snapshot {
+            options.push(RunnerMenuOption {
+                id: format!("custom:{}", custom.id),
+                title: format!("Custom: {}", custom.name),
+            });
+        }
+        options
+    }
+
+    /// Lookups still running were for settings that changed; they are
+    /// requested again on the next menu build
+    fn forget_pending_previews(&mut self) {
+        self.preview_generation += 1;
+        self.preview_pending.clear();
+    }
+
+    /// Frame duration for the selected runner: its own override, else the global value.
+    pub fn effective_frame_ms(&self) -> u64 {
+        let override_ms = if let Some(custom_id) = self.selected_id.strip_prefix("custom:") {
+            self.custom_sets_snapshot
+                .iter()
+                .find(|set| set.id == custom_id)
+                .and_then(|set| set.frame_ms)
+        } else {
+            self.frame_overrides.get(&self.selected_id).copied()
+        };
+        override_ms
+            .map(|ms| ms.clamp(40, 200))
+            .unwrap_or(self.frame_ms)
+    }
+
+    pub fn current_frame(&self) -> Option<P::Frame> {
+        if self.sleeping {
+            if let Some((id, Some(image))) = &self.sleep_frame {
+                if id == &self.selected_id {
+                    return Some(image.clone());
+                }
+            }
+        }
+        self.active_frames.get(self.frame_index).cloned()
+    }
+
+    /// Idle handling for `advance`: Some(frame change) while sleeping or waking.
+    fn advance_idle(&mut self, now: Instant, cpu_usage: f32) -> Option<Option<P::Frame>> {
+        let idle =
+            self.idle_behavior == RunnerIdleBehavior::Sleep && cpu_usage < self.idle_threshold;
+        if !idle {
+            self.idle_since = None;
+            if !self.sleeping {
+                return None;
+            }
+            // Woke up: animate again from the current frame
+            self.sleeping = false;
+            self.last_step = now;
+            self.frame_accumulator = 0.0;
+            return Some(self.current_frame());
+        }
+
+        let since = *self.idle_since.get_or_insert(now);
+        if now.duration_since(since) < RUNNER_IDLE_AFTER {
+            return None;
+        }
+        self.last_step = now;
+        self.frame_accumulator = 0.0;
+        let stale = !matches!(&self.sleep_frame, Some((id, _)) if id == &self.selected_id);
+        if stale {
+            let image = self
+                .provider
+                .sleep_frame(&self.selected_id, self.active_frames_precolored_white);
+            self.sleep_frame = Some((self.selected_id.clone(), image));
+        }
+        if self.sleeping && !stale {
+            return Some(None);
+        }
+        self.sleeping = true;
+        self.frame_index = 0;
+        Some(self.current_frame())
+    }
+
+    /// Resuming starts from the held frame instead of catching up on the
+    /// frames the pause skipped
+    pub fn set_paused(&mut self, paused: bool, now: Instant) {
+        if self.paused && !paused {
+            self.last_step = now;
+            self.frame_accumulator = 0.0;
+        }
+        self.paused = paused;
+    }
+
+    pub fn advance(&mut self, now: Instant, cpu_usage: f32) -> Option<P::Frame> {
+        if self.paused {
+            return None;
+        }
+        self.rotate_runner_if_needed(now);
+
+        if self.active_frames.is_empty() {
+            let (frames, precolored_white) =
+                self.load_frames_for_id(&self.selected_id, &self.custom_sets_snapshot);
+            self.active_frames = frames;
+            self.active_frames_precolored_white = precolored_white;
+            if self.active_frames.is_empty() {
+                self.active_frames = self.provider.fallback_frames();
+                self.active_frames_precolored_white = false;
+            }
+            self.frame_index = 0;
+            self.frame_accumulator = 0.0;
+            self.last_step = now;
+            return self.current_frame();
+        }
+
+        if let Some(frame) = self.advance_idle(now, cpu_usage) {
+            return frame;
+        }
+
+        // Reduce Motion: stand still on the first frame
+        if motion::reduce_motion() && !self.ignore_reduce_motion {
+            self.last_step = now;
+            self.frame_accumulator = 0.0;
+            if self.frame_index == 0 {
+                return None;
+            }
+            self.frame_index = 0;
+            return self.current_frame();
+        }
+
+        let elapsed_ms = now.duration_since(self.last_step).as_secs_f64() * 1000.0;
+        self.last_step = now;
+
+        let cpu_ratio = (cpu_usage.clamp(0.0, 100.0) / 100.0) as f64;
+        let speed_factor = 0.35 + cpu_ratio * 3.0;
+        let frame_ms = SPEED_PREVIEW
+            .with(Cell::get)
+            .unwrap_or_else(|| self.effective_frame_ms());
+        let effective_frame_ms = (frame_ms as f64 / speed_factor).max(16.0);
+
+        self.frame_accumulator += elapsed_ms;
+
+        // 只在帧真正改变时才返回新图像
+        let old_index = self.frame_index;
+        while self.frame_accumulator >= effective_frame_ms {
+            self.frame_accumulator -= effective_frame_ms;
+            self.frame_index = (self.frame_index + 1) % self.active_frames.len();
+        }
+
+        // 防止累加器无限增长
+        if self.frame_accumulator > effective_frame_ms * 2.0 {
+            self.frame_accumulator = 0.0;
+        }
+
+        // 只在帧索引改变时才返回图像
+        if old_index != self.frame_index {
+            self.current_frame()
+        } else {
+            None
+        }
+    }
+
+    /// Until the rotation moves on; None with fewer than two runners
+    pub fn next_switch_in(&self, now: Instant) -> Option<Duration> {
+        if self.rotation_ids.len() <= 1 {
+            return None;
+        }
+        let elapsed = now.duration_since(self.last_runner_switch);
+        Some(Duration::from_secs(self.display_secs.max(1)).saturating_sub(elapsed))
+    }
+
+    fn rotate_runner_if_needed(&mut self, now: Instant) {
+        if self.rotation_ids.len() <= 1 {
+            return;
+        }
+        let elapsed = now.duration_since(self.last_runner_switch).as_secs_f64();
+        let interval = self.display_secs.max(1) as f64;
+        if elapsed < interval {
+            return;
+        }
+
+        let steps = (elapsed / interval).floor() as usize;
+        if steps == 0 {
+            return;
+        }
+
+        let len = self.rotation_ids.len();
+        let index = if self.shuffle {
+            // Any runner but the current one
+            let offset = 1 + (self.next_random() % (len as u64 - 1)) as usize;
+            (self.rotation_index + offset) % len
+        } else {
+            (self.rotation_index + steps) % len
+        };
+        self.switch_to(index, now);
+    }
+
+    /// Move one runner forward or back in rotation order. False when there
+    /// is nothing to switch to.
+    pub fn step(&mut self, forward: bool, now: Instant) -> bool {
+        let len = self.rotation_ids.len();
+        if len <= 1 {
+            return false;
+        }
+        let index = if forward {
+            (self.rotation_index + 1) % len
+        } else {
+            (self.rotation_index + len - 1) % len
+        };
+        self.switch_to(index, now);
+        true
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        x
+    }
+
+    /// Show the runner at `index` in the rotation and restart the display timer.
+    fn switch_to(&mut self, index: usize, now: Instant) {
+        self.rotation_index = index;
+        self.last_runner_switch = now;
+
+        let next_id = self.rotation_ids[self.rotation_index].clone();
+        if next_id != self.selected_id {
+            self.selected_id = next_id;
+            let (frames, precolored_white) =
+                self.load_frames_for_id(&self.selected_id, &self.custom_sets_snapshot);
+            self.active_frames = frames;
+            self.active_frames_precolored_white = precolored_white;
+            if self.active_frames.is_empty() {
+                self.active_frames = self.provider.fallback_frames();
+                self.active_frames_precolored_white = false;
+            }
+            self.frame_index = 0;
+            self.frame_accumulator = 0.0;
+        }
+    }
+
+    pub fn runner_id_exists(&self, runner_id: &str) -> bool {
+        if runner_id == "fallback:runner" {
+            return true;
+        }
+        if self.default_sets.iter().any(|set| set.id == runner_id) {
+            return true;
+        }
+        if let Some(id) = runner_id.strip_prefix("custom:") {
+            return self.custom_sets_snapshot.iter().any(|set| set.id == id);
+        }
+        false
+    }
+
+    fn load_frames_for_id(
+        &self,
+        runner_id: &str,
+        custom_sets: &[CustomRunnerSet],
+    ) -> (Vec<P::Frame>, bool) {
+        let loaded = self.provider.frames(runner_id, custom_sets, self.icon_mode);
+        if loaded.0.is_empty() {
+            issues::report(
+                "runner",
+                format!("no frames could be loaded for {}", runner_id),
+            );
+        }
+        loaded
+    }
+}
+
+impl RunnerAnimator {
+    pub fn new() -> Self {
+        Self::with_provider(RunCatFrames::load())
+    }
+
+    /// Cached previews for `options`. Missing ones are looked up on a
+    /// background thread and handed out by `take_ready_previews`.
+    pub fn preview_images(
+        &mut self,
+        options: &[RunnerMenuOption],
+    ) -> HashMap<String, Retained<NSImage>> {
+        let mut queued = Vec::new();
+        for opt in options {
+            if self.preview_cache.contains_key(&opt.id) || self.preview_pending.contains(&opt.id) {
+                continue;
+            }
+            match self.first_frame_source(&opt.id) {
+                Some(source) => {
+                    self.preview_pending.insert(opt.id.clone());
+                    queued.push((opt.id.clone(), source));
+                }
+                // Bundle assets and SF Symbols: a single cheap lookup
+                None => {
+                    if let Some(image) = self.load_first_frame_for_id(&opt.id) {
+                        self.cache_preview(&opt.id, &image);
+                    }
+                }
+            }
+        }
+        if !queued.is_empty() {
+            let ready = Arc::clone(&self.preview_ready);
+            let generation = self.preview_generation;
+            thread::spawn(move || {
+                for (id, source) in queued {
+                    let file = first_frame_file(&source);
+                    ready.lock().unwrap().push((generation, id, file));
+                }
+            });
+        }
+        options
+            .iter()
+            .filter_map(|opt| Some((opt.id.clone(), self.preview_cache.get(&opt.id)?.clone())))
+            .collect()
+    }
+
+    /// Previews whose background lookup finished since the last call
+    pub fn take_ready_previews(&mut self) -> Vec<(String, Retained<NSImage>)> {
+        if self.preview_pending.is_empty() {
+            return Vec::new();
+        }
+        let ready = std::mem::take(&mut *self.preview_ready.lock().unwrap());
+        let mut images = Vec::new();
+        for (generation, id, file) in ready {
+            if generation != self.preview_generation {
+                continue;
+            }
+            self.preview_pending.remove(&id);
+            let image = match file {
+                Some(file) => load_image_from_file(&file).inspect(|image| image.setTemplate(false)),
+                None => id
+                    .strip_prefix("runcat:")
+                    .and_then(|prefix| self.load_bundle_first_frame(prefix)),
+            };
+            if let Some(image) = image {
+                self.cache_preview(&id, &image);
+                images.push((id, image));
+            }
+        }
+        images
+    }
+
+    fn cache_preview(&mut self, runner_id: &str, image: &Retained<NSImage>) {
+        image.setSize(NSSize::new(16.0, 16.0));
+        self.preview_cache
+            .insert(runner_id.to_string(), image.clone());
+    }
+
+    /// Where the first frame file of `runner_id` may be; None for runners
+    /// that only come from the RunCat bundle or SF Symbols
+    fn first_frame_source(&self, runner_id: &str) -> Option<FirstFrameSource> {
+        if let Some(prefix) = runner_id.strip_prefix("runcat:") {
+            let relatives = if self.icon_mode == RunnerIconMode::White {
+                vec![
+                    EXPORTED_RUN_CAT_FRAMES_WHITE_RELATIVE,
+                    EXPORTED_RUN_CAT_FRAMES_RELATIVE,
+                ]
+            } else {
+                vec![EXPORTED_RUN_CAT_FRAMES_RELATIVE]
+            };
+            return Some(FirstFrameSource::Exported {
+                prefix: prefix.to_string(),
+                relatives,
+            });
+        }
+        let custom_id = runner_id.strip_prefix("custom:")?;
+        let set = self
+            .custom_sets_snapshot
+            .iter()
+            .find(|set| set.id == custom_id)?;
+        Some(FirstFrameSource::Files(set.frame_paths.clone()))
+    }
+
+    /// Like `load_frames_for_id`, but stops after the first image
+    fn load_first_frame_for_id(&self, runner_id: &str) -> Option<Retained<NSImage>> {
+        let from_file = self
+            .first_frame_source(runner_id)
+            .and_then(|source| first_frame_file(&source))
+            .and_then(|file| load_image_from_file(&file));
+        if let Some(image) = from_file {
+            image.setTemplate(false);
+            return Some(image);
+        }
+        if let Some(prefix) = runner_id.strip_prefix("runcat:") {
+            return self.load_bundle_first_frame(prefix);
+        }
+        if runner_id.starts_with("custom:") {
+            return None;
+        }
+        fallback_frames().into_iter().next()
+    }
+
+    fn load_bundle_first_frame(&self, prefix: &str) -> Option<Retained<NSImage>> {
+        let bundle = self.provider.bundle.as_ref()?;
+        let image = bundle.imageForResource(&NSString::from_str(&format!("{}-page-0", prefix)))?;
+        image.setTemplate(false);
+        Some(image)
+    }
+}
+
+/// The configured categories, then every custom set, then the runners no
+/// category lists, each narrowed to `options`. Empty categories are left out.
+pub fn runner_categories<'a>(
+    config: &Config,
+    options: &'a [RunnerMenuOption],
+) -> Vec<(String, Vec<&'a RunnerMenuOption>)> {
+    let mut categories: Vec<(String, Vec<&RunnerMenuOption>)> = config
+        .runner_categories
+        .iter()
+        .map(|category| {
+            let members = options
+                .iter()
+                .filter(|opt| category.runner_ids.contains(&opt.id))
+                .collect();
+            (category.name.clone(), members)
+        })
+        .collect();
+    let custom = options
+        .iter()
+        .filter(|opt| opt.id.starts_with("custom:"))
+        .collect();
+    categories.push((CUSTOM_RUNNER_CATEGORY.to_string(), custom));
+    let other = options
+        .iter()
+        .filter(|opt| {
+            !opt.id.starts_with("custom:")
+                && !categories
+                    .iter()
+                    .any(|(_, members)| members.iter().any(|m| m.id == opt.id))
+        })
+        .collect();
+    categories.push((OTHER_RUNNER_CATEGORY.to_string(), other));
+    categories.retain(|(_, members)| !members.is_empty());
+    categories
+}
+
+fn discover_runcat_sets(bundle: Option<&Retained<NSBundle>>) -> Vec<RunnerMenuOption> {
+    let mut prefixes = discover_runcat_prefixes_from_exported_frames();
+    let use_bundle_probe = prefixes.is_empty();
+    if use_bundle_probe {
+        prefixes = resolve_runcat_assets_car_path()
+            .map(|path| discover_runcat_prefixes_from_assets_car(&path))
+            .unwrap_or_default();
+    }
+    if prefixes.is_empty() {
+        prefixes = vec![
+            "cat".to_string(),
+            "cat-b".to_string(),
+            "cat-c".to_string(),
+            "cat-tail".to_string(),
+            "human".to_string(),
+            "engine".to_string(),
+            "steam-locomotive".to_string(),
+            "rabbit".to_string(),
+            "horse".to_string(),
+        ];
+    }
+
+    let mut options = Vec::new();
+    let bundle = if use_bundle_probe { bundle } else { None };
+    for prefix in prefixes {
+        if let Some(bundle) = bundle {
+            let probe = NSString::from_str(&format!("{}-page-0", prefix));
+            if bundle.imageForResource(&probe).is_none() {
+                continue;
+            }
+        }
+        options.push(RunnerMenuOption {
+            id: format!("runcat:{}", prefix),
+            title: format!("RunCat {}", humanize_runner_prefix(&prefix)),
+        });
+    }
+    options.sort_by(|a, b| a.title.cmp(&b.title));
+    options
+}
+
+#[derive(Deserialize)]
+struct AssetCatalogEntry {
+    #[serde(rename = "AssetType")]
+    asset_type: Option<String>,
+    #[serde(rename = "Name")]
+    name: Option<String>,
+}
+
+fn discover_runcat_prefixes_from_assets_car(assets_car: &Path) -> Vec<String> {
+    if !assets_car.exists() {
+        return Vec::new();
+    }
+
+    let output = match Command::new("assetutil").arg("-I").arg(assets_car).output() {
+        Ok(output) => output,
+        Err(e) => {
+            log::warn!("could not run assetutil: {}", e);
+            return Vec::new();
+        }
+    };
+    if !output.status.success() {
+        log::warn!(
+            "assetutil failed on {} ({})",
+            assets_car.display(),
+            output.status
+        );
+        return Vec::new();
+    }
+
+    let entries = match serde_json::from_slice::<Vec<AssetCatalogEntry>>(&output.stdout) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("unexpected assetutil output: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut prefixes = BTreeSet::new();
+    for entry in entries {
+        if entry.asset_type.as_deref() != Some("Image") {
+            continue;
+        }
+        let Some(name) = entry.name else {
+            continue;
+        };
+        let Some(prefix) = name.strip_suffix("-page-0") else {
+            continue;
+        };
+        if !prefix.is_empty() {
+            prefixes.insert(prefix.to_string());
+        }
+    }
+
+    prefixes.into_iter().collect()
+}
+
+fn humanize_runner_prefix(prefix: &str) -> String {
+    prefix
+        .split(['-', '_'])
+        .filter(|part| !part.is_empty())
+        .map(capitalize_ascii_word)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn capitalize_ascii_word(word: &str) -> String {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let mut result = String::new();
+    result.push(first.to_ascii_uppercase());
+    for c in chars {
+        result.push(c);
+    }
+    result
+}
+
+fn fallback_frames() -> Vec<Retained<NSImage>> {
+    let mut frames = Vec::new();
+    for symbol in ["figure.run", "hare.fill", "figure.walk"] {
+        if let Some(image) = NSImage::imageWithSystemSymbolName_accessibilityDescription(
+            &NSString::from_str(symbol),
+            None,
+        ) {
+            frames.push(image);
+        }
+    }
+    if frames.is_empty() {
+        if let Some(image) = NSImage::imageWithSystemSymbolName_accessibilityDescription(
+            &NSString::from_str("circle.fill"),
+            None,
+        ) {
+            frames.push(image);
+        }
+    }
+    frames
+}
+
+fn load_custom_frames(set: &CustomRunnerSet) -> Vec<Retained<NSImage>> {
+    let mut frames = Vec::new();
+    for path in &set.frame_paths {
+        if let Some(image) = load_image_from_file(Path::new(path)) {
+            image.setTemplate(false);
+            frames.push(image);
+        }
+    }
+    frames
+}
+
+fn load_image_from_file(path: &Path) -> Option<Retained<NSImage>> {
+    let ns_path = NSString::from_str(path.to_string_lossy().as_ref());
+    NSImage::initWithContentsOfFile(NSImage::alloc(), &ns_path)
+}
+
+fn executable_contents_resources_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let macos_dir = exe.parent()?;
+    let contents_dir = macos_dir.parent()?;
+    let resources_dir = contents_dir.join("Resources");
+    if resources_dir.exists() {
+        Some(resources_dir)
+    } else {
+        None
+    }
+}
+
+fn resolve_exported_runcat_frames_dir(relative: &str) -> Option<PathBuf> {
+    let resources_dir = executable_contents_resources_dir()?;
+    let exported = resources_dir.join(relative);
+    if exported.exists() {
+        Some(exported)
+    } else {
+        None
+    }
+}
+
+fn discover_runcat_prefixes_from_exported_frames() -> Vec<String> {
+    let Some(root) = resolve_exported_runcat_frames_dir(EXPORTED_RUN_CAT_FRAMES_RELATIVE) else {
+        return Vec::new();
+    };
+
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    let mut prefixes = BTreeSet::new();
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if !file_type.is_dir() {
+            continue;
+        }
+
+        let prefix = entry.file_name().to_string_lossy().to_string();
+        if prefix.is_empty() || prefix == "all-runners" || prefix == "self-made" {
+            continue;
+        }
+
+        let has_frames = fs::read_dir(entry.path())
+            .ok()
+            .map(|files| {
+                files.flatten().any(|file| {
+                    file.path()
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(is_supported_runner_image_ext)
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false);
+        if has_frames {
+            prefixes.insert(prefix);
+        }
+    }
+
+    prefixes.into_iter().collect()
+}
+
+fn load_exported_runcat_frames_from_dir(prefix: &str, relative: &str) -> Vec<Retained<NSImage>> {
+    let mut frames = Vec::new();
+    for file in exported_runcat_frame_files(prefix, relative) {
+        if let Some(image) = load_image_from_file(&file) {
+            image.setTemplate(false);
+            frames.push(image);
+        }
+    }
+    frames
+}
+
+/// Animation frames of an exported RunCat set in order, sleep image excluded
+fn exported_runcat_frame_files(prefix: &str, relative: &str) -> Vec<PathBuf> {
+    let Some(root) = resolve_exported_runcat_frames_dir(relative) else {
+        return Vec::new();
+    };
+    let dir = root.join(prefix);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(is_supported_runner_image_ext)
+                .unwrap_or(false)
+        })
+        .filter(|path| !is_sleep_frame(path))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Only touches the file system, so it may run off the main thread
+fn first_frame_file(source: &FirstFrameSource) -> Option<PathBuf> {
+    match source {
+        FirstFrameSource::Exported { prefix, relatives } => relatives.iter().find_map(|relative| {
+            exported_runcat_frame_files(prefix, relative)
+                .into_iter()
+                .next()
+        }),
+        FirstFrameSource::Files(paths) => {
+            paths.iter().map(PathBuf::from).find(|path| path.is_file())
+        }
+    }
+}
+
+/// `<prefix>-sleep.png` and friends are shown while idle, not animated
+fn is_sleep_frame(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.ends_with("-sleep"))
+}
+
+fn load_exported_runcat_sleep_frame(prefix: &str, relative: &str) -> Option<Retained<NSImage>> {
+    let dir = resolve_exported_runcat_frames_dir(relative)?.join(prefix);
+    let stem = format!("{}-sleep", prefix);
+    let path = fs::read_dir(&dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_stem().and_then(|s| s.to_str()) == Some(stem.as_str())
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(is_supported_runner_image_ext)
+        })?;
+    let image = load_image_from_file(&path)?;
+    image.setTemplate(false);
+    Some(image)
+}
+
+fn is_supported_runner_image_ext(ext: &str) -> bool {
+    matches!(
+        ext.to_ascii_lowercase().as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "tiff" | "webp" | "heic"
+    )
+}
+
+fn resolve_runcat_ui_bundle_path() -> Option<PathBuf> {
+    let resources_dir = executable_contents_resources_dir()?;
+    let embedded = resources_dir.join(EMBEDDED_RUN_CAT_UI_BUNDLE_RELATIVE);
+    if embedded.exists() {
+        Some(embedded)
+    } else {
+        None
+    }
+}
+
+fn resolve_runcat_assets_car_path() -> Option<PathBuf> {
+    let resources_dir = executable_contents_resources_dir()?;
+    let embedded = resources_dir.join(EMBEDDED_RUN_CAT_UI_ASSETS_RELATIVE);
+    if embedded.exists() {
+        Some(embedded)
+    } else {
+        None
+    }
+}
+
+fn load_run_cat_bundle() -> Option<Retained<NSBundle>> {
+    let bundle_path = resolve_runcat_ui_bundle_path()?;
+    NSBundle::bundleWithPath(&NSString::from_str(bundle_path.to_string_lossy().as_ref()))
+}
+
+pub fn custom_frames_root_dir() -> PathBuf {
+    config_dir().join("custom-runners")
+}
+
+/// Save normalized PNG `frames` under a new set ID
+pub fn write_custom_frames(frames: &[Vec<u8>]) -> std::io::Result<(String, Vec<String>)> {
+    fs::create_dir_all(custom_frames_root_dir())?;
+    let set_id = CustomRunnerSet::generate_id();
+    let target_dir = custom_frames_root_dir().join(&set_id);
+    fs::create_dir_all(&target_dir)?;
+
+    let mut copied = Vec::new();
+    for (idx, png) in frames.iter().enumerate() {
+        let target = target_dir.join(format!("{:03}.png", idx));
+        fs::write(&target, png)?;
+        copied.push(target.to_string_lossy().to_string());
+    }
+
+    Ok((set_id, copied))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "cat-0" to "cat-3" for each runner, plus "cat-sleep"
+    struct NamedFrames;
+
+    impl FrameProvider for NamedFrames {
+        type Frame = String;
+
+        fn runner_sets(&self) -> Vec<RunnerMenuOption> {
+            ["cat", "dog", "bird"]
+                .iter()
+                .map(|name| RunnerMenuOption {
+                    id: format!("runcat:{}", name),
+                    title: name.to_string(),
+                })
+                .collect()
+        }
+
+        fn frames(
+            &self,
+            runner_id: &str,
+            _custom_sets: &[CustomRunnerSet],
+            _icon_mode: RunnerIconMode,
+        ) -> (Vec<String>, bool) {
+            let Some(name) = runner_id.strip_prefix("runcat:") else {
+                return (self.fallback_frames(), false);
+            };
+            ((0..4).map(|i| format!("{}-{}", name, i)).collect(), false)
+        }
+
+        fn sleep_frame(&self, runner_id: &str, _precolored_white: bool) -> Option<String> {
+            Some(format!("{}-sleep", runner_id.strip_prefix("runcat:")?))
+        }
+
+        fn fallback_frames(&self) -> Vec<String> {
+            vec!["fallback".to_string()]
+        }
+    }
+
+    /// 100 ms frames, 10 s per runner, rotating through `rotation`
+    fn config(rotation: &[&str]) -> Config {
+        let mut config = Config::default();
+        config.runner_id = format!("runcat:{}", rotation[0]);
+        config.runner_rotation_ids = rotation
+            .iter()
+            .map(|name| format!("runcat:{}", name))
+            .collect();
+        config.runner_display_secs = 10;
+        config.runner_frame_ms = 100;
+        config.ignore_reduce_motion = true;
+        config
+    }
+
+    /// A runner whose frame clock was last advanced at the returned instant
+    fn runner(config: &Config) -> (RunnerAnimator<NamedFrames>, Instant) {
+        let mut runner = RunnerAnimator::with_provider(NamedFrames);
+        runner.sync_config(config);
+        let start = Instant::now();
+        runner.advance(start, 55.0);
+        (runner, start)
+    }
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn frames_follow_elapsed_time_and_wrap() {
+        let (mut runner, start) = runner(&config(&["cat"]));
+        // 55% CPU doubles the speed: 50 ms a frame
+        assert_eq!(runner.advance(ms(start, 40), 55.0), None);
+        assert_eq!(
+            runner.advance(ms(start, 120), 55.0).as_deref(),
+            Some("cat-2")
+        );
+        assert_eq!(
+            runner.advance(ms(start, 220), 55.0).as_deref(),
+            Some("cat-0")
+        );
+    }
+
+    #[test]
+    fn an_idle_cpu_runs_slower() {
+        let (mut runner, start) = runner(&config(&["cat"]));
+        // 0% CPU: about 286 ms a frame
+        assert_eq!(runner.advance(ms(start, 200), 0.0), None);
+        assert_eq!(
+            runner.advance(ms(start, 300), 0.0).as_deref(),
+            Some("cat-1")
+        );
+    }
+
+    #[test]
+    fn resuming_does_not_catch_up_on_the_pause() {
+        let (mut runner, start) = runner(&config(&["cat"]));
+        runner.set_paused(true, start);
+        assert_eq!(runner.advance(ms(start, 1000), 55.0), None);
+        runner.set_paused(false, ms(start, 1000));
+        assert_eq!(runner.advance(ms(start, 1040), 55.0), None);
+        assert_eq!(runner.current_frame().as_deref(), Some("cat-0"));
+    }
+
+    #[test]
+    fn rotation_moves_on_after_the_display_time() {
+        let (mut runner, start) = runner(&config(&["cat", "dog", "bird"]));
+        assert!(runner.next_switch_in(start).unwrap() <= Duration::from_secs(10));
+        runner.advance(ms(start, 9_000), 55.0);
+        assert_eq!(runner.selected_id, "runcat:cat");
+        runner.advance(ms(start, 10_000), 55.0);
+        assert_eq!(runner.selected_id, "runcat:dog");
+        // Two display times passed at once: skip ahead by two
+        runner.advance(ms(start, 30_000), 55.0);
+        assert_eq!(runner.selected_id, "runcat:cat");
+    }
+
+    #[test]
+    fn step_walks_the_rotation_both_ways() {
+        let (mut runner, start) = runner(&config(&["cat", "dog", "bird"]));
+        assert!(runner.step(false, start));
+        assert_eq!(runner.selected_id, "runcat:bird");
+        assert_eq!(runner.current_frame().as_deref(), Some("bird-0"));
+        assert!(runner.step(true, start));
+        assert_eq!(runner.selected_id, "runcat:cat");
+
+        let (mut single, start) = runner(&config(&["cat"]));
+        assert!(!single.step(true, start));
+        assert_eq!(single.next_switch_in(start), None);
+    }
+
+    #[test]
+    fn an_idle_runner_sleeps_and_wakes() {
+        let mut config = config(&["cat"]);
+        config.runner_idle_behavior = RunnerIdleBehavior::Sleep;
+        let (mut runner, start) = runner(&config);
+        runner.advance(ms(start, 1000), 1.0);
+        let asleep = start + Duration::from_secs(1) + RUNNER_IDLE_AFTER;
+        assert_eq!(runner.advance(asleep, 1.0).as_deref(), Some("cat-sleep"));
+        assert_eq!(runner.advance(asleep + Duration::from_secs(1), 1.0), None);
+        let awake = asleep + Duration::from_secs(2);
+        assert_eq!(runner.advance(awake, 50.0).as_deref(), Some("cat-0"));
+    }
+}
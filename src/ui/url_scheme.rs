@@ -0,0 +1,155 @@
+//! `msm://` URLs for Shortcuts and scripts. macOS hands them over as a
+//! GetURL Apple Event; each becomes a queued action like a menu click.
+
+use crate::ui::action::TrayAction;
+use crate::ui::chart_window::ChartMode;
+use crate::ui::tray::post_pending_event;
+use objc2::runtime::{AnyClass, AnyObject, ClassBuilder, Sel};
+use objc2::{msg_send, sel};
+use std::ffi::{c_char, CStr};
+use std::sync::Once;
+
+const SCHEME: &str = "msm://";
+/// 'GURL', both the event class and the event id
+const K_INTERNET_EVENT_CLASS: u32 = 0x4755_524C;
+const K_AE_GET_URL: u32 = 0x4755_524C;
+/// '----'
+const KEY_DIRECT_OBJECT: u32 = 0x2D2D_2D2D;
+
+/// Supported URLs and what they do, as listed under About
+pub const URL_COMMANDS: &[(&str, &str)] = &[
+    ("msm://charts/toggle", "Show or hide the charts"),
+    ("msm://charts/temp", "Show or hide the temperature charts"),
+    ("msm://charts/cores", "Show or hide the core charts"),
+    (
+        "msm://charts/pop/cpu",
+        "Pop out the CPU chart (also net, temp)",
+    ),
+    ("msm://pause", "Pause or resume the charts"),
+    ("msm://poll/5", "Poll every 5 seconds"),
+    ("msm://runner/next", "Next runner"),
+    ("msm://runner/previous", "Previous runner"),
+    ("msm://runner/shuffle", "Turn shuffle on or off"),
+    ("msm://profile/Work", "Switch to the profile named Work"),
+];
+
+static INSTALL_HANDLER: Once = Once::new();
+
+/// Apple Events arrive on the main thread, like menu actions.
+unsafe extern "C" fn handle_get_url(
+    _this: *const AnyObject,
+    _sel: Sel,
+    event: *const AnyObject,
+    _reply: *const AnyObject,
+) {
+    if event.is_null() {
+        return;
+    }
+    let descriptor: *const AnyObject =
+        msg_send![event, paramDescriptorForKeyword: KEY_DIRECT_OBJECT];
+    if descriptor.is_null() {
+        return;
+    }
+    let string: *const AnyObject = msg_send![descriptor, stringValue];
+    if string.is_null() {
+        return;
+    }
+    let utf8: *const c_char = msg_send![string, UTF8String];
+    if utf8.is_null() {
+        return;
+    }
+    let url = CStr::from_ptr(utf8).to_string_lossy();
+    match parse(&url) {
+        Some(action) => post_pending_event(action),
+        None => log::warn!("unknown URL command: {}", url),
+    }
+}
+
+/// Register for GetURL events. The bundle's Info.plist claims the scheme.
+pub fn install() {
+    INSTALL_HANDLER.call_once(|| unsafe {
+        let (Some(superclass), Some(manager_class)) = (
+            AnyClass::get(c"NSObject"),
+            AnyClass::get(c"NSAppleEventManager"),
+        ) else {
+            return;
+        };
+        let Some(mut builder) = ClassBuilder::new(c"UrlSchemeHandler", superclass) else {
+            return;
+        };
+        builder.add_method(
+            sel!(handleGetURLEvent:withReplyEvent:),
+            handle_get_url
+                as unsafe extern "C" fn(*const AnyObject, Sel, *const AnyObject, *const AnyObject),
+        );
+        let cls = builder.register();
+        // Lives as long as the app, like the menu handler
+        let handler: *const AnyObject = msg_send![cls, new];
+        let manager: *const AnyObject = msg_send![manager_class, sharedAppleEventManager];
+        if manager.is_null() {
+            log::warn!("no Apple Event manager; URL commands are off");
+            return;
+        }
+        let _: () = msg_send![
+            manager,
+            setEventHandler: handler,
+            andSelector: sel!(handleGetURLEvent:withReplyEvent:),
+            forEventClass: K_INTERNET_EVENT_CLASS,
+            andEventID: K_AE_GET_URL
+        ];
+    });
+}
+
+/// The action for an `msm://` URL; None for anything unsupported.
+pub fn parse(url: &str) -> Option<TrayAction> {
+    let scheme = url.get(..SCHEME.len())?;
+    if !scheme.eq_ignore_ascii_case(SCHEME) {
+        return None;
+    }
+    let path = url[SCHEME.len()..].trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').collect();
+    match segments.as_slice() {
+        ["charts"] | ["charts", "toggle"] => Some(TrayAction::ShowCharts(ChartMode::All)),
+        ["charts", "temp"] => Some(TrayAction::ShowCharts(ChartMode::TempOnly)),
+        ["charts", "cores"] => Some(TrayAction::ShowCharts(ChartMode::Cores)),
+        ["charts", "pop", mode] => ChartMode::from_id(mode)
+            .filter(|mode| {
+                matches!(
+                    mode,
+                    ChartMode::CpuOnly | ChartMode::NetOnly | ChartMode::TempOnly
+                )
+            })
+            .map(TrayAction::PopOutChart),
+        ["pause"] => Some(TrayAction::ToggleChartPause),
+        ["poll", secs] => secs
+            .parse::<f64>()
+            .ok()
+            .filter(|secs| secs.is_finite() && *secs > 0.0)
+            .map(TrayAction::SetPollInterval),
+        ["runner", "next"] => Some(TrayAction::NextRunner),
+        ["runner", "previous"] => Some(TrayAction::PreviousRunner),
+        ["runner", "shuffle"] => Some(TrayAction::ToggleRunnerShuffle),
+        ["profile", name] if !name.is_empty() => {
+            percent_decode(name).map(TrayAction::SwitchProfile)
+        }
+        _ => None,
+    }
+}
+
+/// "My%20Profile" → "My Profile"; None for a broken escape or non-UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
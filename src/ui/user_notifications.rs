@@ -0,0 +1,140 @@
+//! Notifications through UNUserNotificationCenter, which can carry an image
+//! and replace an earlier notification with the same id. Only the bundled
+//! app can use it, and only once the user allowed notifications; until
+//! then `post` returns false and callers fall back to `osascript`.
+
+use block2::{Block, RcBlock};
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool};
+use objc2_foundation::{ns_string, NSString};
+use std::path::Path;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+#[link(name = "UserNotifications", kind = "framework")]
+extern "C" {}
+
+/// UNAuthorizationOptionAlert
+const OPTION_ALERT: usize = 1 << 2;
+
+static REQUEST_AUTHORIZATION: Once = Once::new();
+/// The user allowed notifications; set from the authorization callback
+static AUTHORIZED: AtomicBool = AtomicBool::new(false);
+
+/// Ask once to show notifications. macOS only prompts the first time;
+/// later launches get the earlier answer. Does nothing outside the bundle.
+pub fn request_authorization() {
+    REQUEST_AUTHORIZATION.call_once(|| unsafe {
+        let Some(center) = center() else {
+            log::info!("not running as an app bundle; notifications go through osascript");
+            return;
+        };
+        let handler = RcBlock::new(|granted: Bool, _error: *mut AnyObject| {
+            AUTHORIZED.store(granted.as_bool(), Ordering::Relaxed);
+        });
+        let _: () = msg_send![
+            &center,
+            requestAuthorizationWithOptions: OPTION_ALERT,
+            completionHandler: &*handler
+        ];
+    });
+}
+
+/// Show `title` and `body`, with the PNG at `image` attached if given.
+/// Posting again with the same `id` replaces the notification, and
+/// notifications with the same `thread` are grouped. False when it could
+/// not be posted this way.
+pub fn post(id: &str, thread: &str, title: &str, body: &str, image: Option<&Path>) -> bool {
+    if !AUTHORIZED.load(Ordering::Relaxed) {
+        return false;
+    }
+    unsafe { try_post(id, thread, title, body, image).is_some() }
+}
+
+/// Take the notification posted with `id` out of Notification Center
+pub fn remove(id: &str) {
+    if !AUTHORIZED.load(Ordering::Relaxed) {
+        return;
+    }
+    unsafe {
+        let (Some(center), Some(array_class)) = (center(), AnyClass::get(c"NSArray")) else {
+            return;
+        };
+        let ids: Option<Retained<AnyObject>> =
+            msg_send![array_class, arrayWithObject: &*NSString::from_str(id)];
+        if let Some(ids) = ids {
+            let _: () = msg_send![&center, removeDeliveredNotificationsWithIdentifiers: &*ids];
+        }
+    }
+}
+
+unsafe fn try_post(
+    id: &str,
+    thread: &str,
+    title: &str,
+    body: &str,
+    image: Option<&Path>,
+) -> Option<()> {
+    let center = center()?;
+    let content_class = AnyClass::get(c"UNMutableNotificationContent")?;
+    let content: Option<Retained<AnyObject>> = msg_send![content_class, new];
+    let content = content?;
+    let id = NSString::from_str(id);
+    let _: () = msg_send![&content, setTitle: &*NSString::from_str(title)];
+    let _: () = msg_send![&content, setBody: &*NSString::from_str(body)];
+    let _: () = msg_send![&content, setThreadIdentifier: &*NSString::from_str(thread)];
+    // Without the image the text still goes out
+    if let Some(attachment) = image.and_then(|path| attachment(path)) {
+        let array_class = AnyClass::get(c"NSArray")?;
+        let attachments: Option<Retained<AnyObject>> =
+            msg_send![array_class, arrayWithObject: &*attachment];
+        let _: () = msg_send![&content, setAttachments: &*attachments?];
+    }
+    let request_class = AnyClass::get(c"UNNotificationRequest")?;
+    let request: Option<Retained<AnyObject>> = msg_send![
+        request_class,
+        requestWithIdentifier: &*id,
+        content: &*content,
+        trigger: ptr::null::<AnyObject>()
+    ];
+    let _: () = msg_send![
+        &center,
+        addNotificationRequest: &*request?,
+        withCompletionHandler: None::<&Block<dyn Fn(*mut AnyObject)>>
+    ];
+    Some(())
+}
+
+/// The image at `path`; Notification Center moves the file into its own
+/// store once the notification is posted
+unsafe fn attachment(path: &Path) -> Option<Retained<AnyObject>> {
+    let url_class = AnyClass::get(c"NSURL")?;
+    let url: Option<Retained<AnyObject>> = msg_send![
+        url_class,
+        fileURLWithPath: &*NSString::from_str(&path.to_string_lossy())
+    ];
+    let class = AnyClass::get(c"UNNotificationAttachment")?;
+    let attachment: Option<Retained<AnyObject>> = msg_send![
+        class,
+        attachmentWithIdentifier: ns_string!("sparkline"),
+        URL: &*url?,
+        options: ptr::null::<AnyObject>(),
+        error: ptr::null_mut::<*mut AnyObject>()
+    ];
+    if attachment.is_none() {
+        log::warn!("could not attach {} to a notification", path.display());
+    }
+    attachment
+}
+
+/// The app's notification center. Asking for it outside an app bundle
+/// throws, so that case is None.
+unsafe fn center() -> Option<Retained<AnyObject>> {
+    let bundle_class = AnyClass::get(c"NSBundle")?;
+    let bundle: Option<Retained<AnyObject>> = msg_send![bundle_class, mainBundle];
+    let identifier: Option<Retained<AnyObject>> = msg_send![&bundle?, bundleIdentifier];
+    let class = AnyClass::get(c"UNUserNotificationCenter").filter(|_| identifier.is_some())?;
+    msg_send![class, currentNotificationCenter]
+}
@@ -0,0 +1,68 @@
+use crate::config::config_dir;
+use crate::launch_agent;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+/// What an uninstall removed, and what it couldn't
+#[derive(Default)]
+pub struct Outcome {
+    pub removed: Vec<PathBuf>,
+    pub failed: Vec<String>,
+}
+
+/// Remove the login item and, with `delete_data`, the config folder:
+/// settings, logs, heartbeat and imported custom runner frames. The app
+/// bundle itself is left for the user to move to the Trash.
+pub fn run(delete_data: bool) -> Outcome {
+    let mut outcome = Outcome::default();
+    let plist = launch_agent::plist_path();
+    let had_plist = plist.exists();
+    match launch_agent::set_enabled(false) {
+        Ok(()) if had_plist => outcome.removed.push(plist),
+        Ok(()) => {}
+        Err(e) => outcome.failed.push(e),
+    }
+    let dir = config_dir();
+    if delete_data && dir.exists() {
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => outcome.removed.push(dir),
+            Err(e) => outcome
+                .failed
+                .push(format!("could not remove {}: {}", dir.display(), e)),
+        }
+    }
+    outcome
+}
+
+/// `--uninstall [--delete-settings]`: without the flag, asks whether to
+/// delete the config folder when run from a terminal and keeps it otherwise.
+/// Prints what was removed; exits 1 if anything could not be.
+pub fn cli(args: &[String]) -> i32 {
+    let delete_data = args.iter().any(|arg| arg == "--delete-settings")
+        || (io::stdin().is_terminal() && ask_delete_data());
+    let outcome = run(delete_data);
+    if outcome.removed.is_empty() && outcome.failed.is_empty() {
+        println!("nothing to remove");
+    }
+    for path in &outcome.removed {
+        println!("removed {}", path.display());
+    }
+    for error in &outcome.failed {
+        eprintln!("error: {}", error);
+    }
+    i32::from(!outcome.failed.is_empty())
+}
+
+fn ask_delete_data() -> bool {
+    print!(
+        "Also delete settings, logs and custom runner frames in {}? [y/N] ",
+        config_dir().display()
+    );
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}